@@ -0,0 +1,213 @@
+//!
+//! ARM build attributes reader
+//!
+//! Selects the simulated core from the `Tag_CPU_arch`, `Tag_CPU_arch_profile`
+//! and `Tag_FP_arch` attributes the toolchain records in the `.ARM.attributes`
+//! section.
+//!
+
+use goblin::elf::Elf;
+use zmu_cortex_m::core::cpu::Cpu;
+
+const FORMAT_VERSION: u8 = b'A';
+const TAG_FILE: u8 = 1;
+
+const TAG_CPU_RAW_NAME: u64 = 4;
+const TAG_CPU_NAME: u64 = 5;
+const TAG_CPU_ARCH: u64 = 6;
+const TAG_CPU_ARCH_PROFILE: u64 = 7;
+const TAG_FP_ARCH: u64 = 10;
+const TAG_COMPATIBILITY: u64 = 32;
+
+// Tag_CPU_arch values
+const CPU_ARCH_V7: u64 = 10;
+const CPU_ARCH_V6_M: u64 = 11;
+const CPU_ARCH_V6S_M: u64 = 12;
+const CPU_ARCH_V7E_M: u64 = 13;
+
+// Tag_CPU_arch_profile value of the microcontroller profile
+const PROFILE_M: u64 = b'M' as u64;
+
+fn u32_at(data: &[u8], pos: usize) -> Option<u32> {
+    let bytes = data.get(pos..pos.checked_add(4)?)?;
+    Some(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+}
+
+// Returns the value and the position after it
+fn uleb_at(data: &[u8], mut pos: usize) -> Option<(u64, usize)> {
+    let mut value = 0;
+    let mut shift = 0;
+    loop {
+        let byte = *data.get(pos)?;
+        pos += 1;
+        if shift < 64 {
+            value |= u64::from(byte & 0x7f) << shift;
+        }
+        shift += 7;
+        if byte & 0x80 == 0 {
+            return Some((value, pos));
+        }
+    }
+}
+
+// Position after the null terminated string at `pos`
+fn skip_cstr(data: &[u8], pos: usize) -> Option<usize> {
+    let length = data.get(pos..)?.iter().position(|b| *b == 0)?;
+    Some(pos + length + 1)
+}
+
+// Integer valued attributes of a file scope subsection
+fn file_attributes(data: &[u8]) -> Option<Vec<(u64, u64)>> {
+    let mut attributes = Vec::new();
+    let mut pos = 0;
+    while pos < data.len() {
+        let (tag, next) = uleb_at(data, pos)?;
+        pos = match tag {
+            TAG_CPU_RAW_NAME | TAG_CPU_NAME => skip_cstr(data, next)?,
+            TAG_COMPATIBILITY => skip_cstr(data, uleb_at(data, next)?.1)?,
+            // from 32 on, odd numbered tags have string values
+            _ if tag > TAG_COMPATIBILITY && tag % 2 == 1 => skip_cstr(data, next)?,
+            _ => {
+                let (value, next) = uleb_at(data, next)?;
+                attributes.push((tag, value));
+                next
+            }
+        };
+    }
+    Some(attributes)
+}
+
+// Attributes of the "aeabi" vendor section that apply to the whole file
+fn aeabi_file_attributes(section: &[u8]) -> Option<Vec<(u64, u64)>> {
+    if *section.first()? != FORMAT_VERSION {
+        return None;
+    }
+    let mut pos = 1;
+    while pos < section.len() {
+        let length = u32_at(section, pos)? as usize;
+        let vendor_section = section.get(pos..pos.checked_add(length)?)?;
+        let subsections = skip_cstr(vendor_section, 4)?;
+        if &vendor_section[4..subsections - 1] == b"aeabi" {
+            let mut sub = subsections;
+            while sub < vendor_section.len() {
+                let tag = vendor_section[sub];
+                let size = u32_at(vendor_section, sub + 1)? as usize;
+                let data = vendor_section.get(sub + 5..sub.checked_add(size)?)?;
+                if tag == TAG_FILE {
+                    return file_attributes(data);
+                }
+                sub += size.max(5);
+            }
+        }
+        pos += length.max(4);
+    }
+    None
+}
+
+///
+/// Core type matching the architecture the executable was built for, None
+/// if the executable does not record it
+///
+pub fn detect_cpu(elf: &Elf, buffer: &[u8]) -> Option<Cpu> {
+    let section = elf
+        .section_headers
+        .iter()
+        .find(|sh| elf.shdr_strtab.get(sh.sh_name).and_then(|n| n.ok()) == Some(".ARM.attributes"))
+        .and_then(|sh| {
+            let end = sh.sh_offset.checked_add(sh.sh_size)?;
+            buffer.get(sh.sh_offset as usize..end as usize)
+        })?;
+    cpu_from_attributes(section)
+}
+
+// Core type for the contents of an `.ARM.attributes` section
+fn cpu_from_attributes(section: &[u8]) -> Option<Cpu> {
+    let attributes = aeabi_file_attributes(section)?;
+    let attribute = |tag| {
+        attributes
+            .iter()
+            .find(|(t, _)| *t == tag)
+            .map(|(_, value)| *value)
+    };
+
+    match attribute(TAG_CPU_ARCH)? {
+        CPU_ARCH_V6_M | CPU_ARCH_V6S_M => Some(Cpu::CortexM0),
+        // plain v7 is shared with the application and real-time profiles
+        CPU_ARCH_V7 if attribute(TAG_CPU_ARCH_PROFILE) == Some(PROFILE_M) => Some(Cpu::CortexM3),
+        CPU_ARCH_V7E_M if attribute(TAG_FP_ARCH).unwrap_or(0) != 0 => Some(Cpu::CortexM4F),
+        CPU_ARCH_V7E_M => Some(Cpu::CortexM4),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // "aeabi" vendor section with a single file scope subsection of the
+    // given attribute bytes
+    fn attributes_section(attributes: &[u8]) -> Vec<u8> {
+        let mut file = vec![TAG_FILE];
+        file.extend_from_slice(&(5 + attributes.len() as u32).to_le_bytes());
+        file.extend_from_slice(attributes);
+
+        let mut vendor = Vec::new();
+        vendor.extend_from_slice(&(4 + 6 + file.len() as u32).to_le_bytes());
+        vendor.extend_from_slice(b"aeabi\0");
+        vendor.extend_from_slice(&file);
+
+        let mut section = vec![FORMAT_VERSION];
+        section.extend_from_slice(&vendor);
+        section
+    }
+
+    #[test]
+    fn test_cpu_arch() {
+        let cpu = |attributes: &[u8]| cpu_from_attributes(&attributes_section(attributes));
+
+        assert_eq!(cpu(&[6, 11]), Some(Cpu::CortexM0));
+        assert_eq!(cpu(&[6, 12]), Some(Cpu::CortexM0));
+        assert_eq!(cpu(&[6, 10, 7, b'M']), Some(Cpu::CortexM3));
+        assert_eq!(cpu(&[6, 13, 7, b'M']), Some(Cpu::CortexM4));
+        assert_eq!(cpu(&[6, 13, 7, b'M', 10, 4]), Some(Cpu::CortexM4F));
+        // v7-A and v7-R are not simulated
+        assert_eq!(cpu(&[6, 10, 7, b'A']), None);
+        assert_eq!(cpu(&[6, 10, 7, b'R']), None);
+        assert_eq!(cpu(&[6, 10]), None);
+        assert_eq!(cpu(&[10, 4]), None);
+    }
+
+    #[test]
+    fn test_string_attributes_skipped() {
+        // Tag_CPU_name "cortex-m4", Tag_compatibility 0 "", Tag_conformance "2.09"
+        let mut attributes = vec![5];
+        attributes.extend_from_slice(b"cortex-m4\0");
+        attributes.extend_from_slice(&[32, 0, 0]);
+        attributes.push(67);
+        attributes.extend_from_slice(b"2.09\0");
+        attributes.extend_from_slice(&[6, 13, 7, b'M']);
+
+        assert_eq!(
+            cpu_from_attributes(&attributes_section(&attributes)),
+            Some(Cpu::CortexM4)
+        );
+    }
+
+    #[test]
+    fn test_malformed_sections() {
+        let section = attributes_section(&[6, 11]);
+
+        assert_eq!(cpu_from_attributes(&[]), None);
+        assert_eq!(cpu_from_attributes(b"B"), None);
+        // truncated anywhere
+        for end in 1..section.len() {
+            assert_eq!(cpu_from_attributes(&section[..end]), None);
+        }
+        // unterminated ULEB128 value
+        assert_eq!(cpu_from_attributes(&attributes_section(&[6, 0x80])), None);
+        // other vendors are ignored
+        let mut other = section.clone();
+        other[5..10].copy_from_slice(b"other");
+        assert_eq!(cpu_from_attributes(&other), None);
+    }
+}
@@ -0,0 +1,756 @@
+//!
+//! defmt log frame decoding
+//!
+//! Decodes the log frames sent by firmware using the defmt logging framework
+//! (wire format version 4), with the format strings interned in the `.defmt`
+//! section of the executable, and prints them as text lines.
+//!
+
+use std::collections::HashMap;
+use std::io;
+use std::io::prelude::*;
+use std::rc::Rc;
+
+use goblin::elf::Elf;
+
+use crate::dwarf::{variable_locations, Location};
+
+// supported wire format version
+const DEFMT_VERSION: &str = "4";
+
+// limits the nesting of formatted values in corrupted frames
+const MAX_DEPTH: usize = 32;
+
+///
+/// Framing of the log frames in the byte stream
+///
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Encoding {
+    /// frames are sent as is
+    Raw,
+    /// frames are rzCOBS encoded and separated by zero bytes
+    Rzcobs,
+}
+
+///
+/// Transport used by the defmt global logger of the executable
+///
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Transport {
+    Rtt,
+    Itm,
+    Semihosting,
+}
+
+// crates implementing the global logger for each transport
+const LOGGER_CRATES: [(&str, Transport); 3] = [
+    ("defmt_rtt", Transport::Rtt),
+    ("defmt_itm", Transport::Itm),
+    ("defmt_semihosting", Transport::Semihosting),
+];
+
+struct Entry {
+    tag: String,
+    format: String,
+}
+
+///
+/// Interned strings of a defmt enabled executable
+///
+pub struct DefmtTable {
+    entries: HashMap<u16, Entry>,
+    timestamp: Option<String>,
+    encoding: Encoding,
+    locations: HashMap<u64, Location>,
+    pub transport: Option<Transport>,
+}
+
+#[derive(Debug, PartialEq)]
+enum DecodeError {
+    // more data is needed to decode the frame
+    Incomplete,
+    Malformed,
+}
+
+type DecodeResult<T> = Result<T, DecodeError>;
+
+#[derive(Clone, Debug, PartialEq)]
+enum Type {
+    Unsigned(usize),
+    Signed(usize),
+    F32,
+    F64,
+    Bool,
+    Char,
+    Str,
+    IStr,
+    Format,
+    FormatSlice,
+    FormatArray(usize),
+    FormatSequence,
+    // output of core::fmt Debug and Display
+    Text,
+    U8Slice,
+    U8Array(usize),
+    BitField(u32, u32),
+}
+
+struct Parameter {
+    index: usize,
+    ty: Type,
+    hint: Option<String>,
+}
+
+enum Fragment {
+    Literal(String),
+    Parameter(Parameter),
+}
+
+enum Value {
+    Unsigned(u128),
+    Signed(i128),
+    F32(f32),
+    F64(f64),
+    Bool(bool),
+    Char(char),
+    Str(String),
+    Bytes(Vec<u8>),
+    Formatted(String),
+}
+
+// Parse the JSON object stored in the name of an interned string symbol
+fn parse_symbol(name: &str) -> Option<HashMap<String, String>> {
+    fn parse_string(chars: &mut std::iter::Peekable<std::str::Chars>) -> Option<String> {
+        let mut s = String::new();
+        if chars.next()? != '"' {
+            return None;
+        }
+        loop {
+            match chars.next()? {
+                '"' => return Some(s),
+                '\\' => match chars.next()? {
+                    'n' => s.push('\n'),
+                    't' => s.push('\t'),
+                    'r' => s.push('\r'),
+                    'u' => {
+                        let code: String = chars.by_ref().take(4).collect();
+                        s.push(std::char::from_u32(u32::from_str_radix(&code, 16).ok()?)?);
+                    }
+                    c => s.push(c),
+                },
+                c => s.push(c),
+            }
+        }
+    }
+
+    let mut fields = HashMap::new();
+    let mut chars = name.chars().peekable();
+    if chars.next()? != '{' {
+        return None;
+    }
+    loop {
+        let key = parse_string(&mut chars)?;
+        if chars.next()? != ':' {
+            return None;
+        }
+        let value = if chars.peek() == Some(&'"') {
+            parse_string(&mut chars)?
+        } else {
+            let mut value = String::new();
+            while let Some(c) = chars.peek() {
+                if *c == ',' || *c == '}' {
+                    break;
+                }
+                value.push(chars.next()?);
+            }
+            value
+        };
+        fields.insert(key, value);
+        match chars.next()? {
+            ',' => {}
+            '}' => return Some(fields),
+            _ => return None,
+        }
+    }
+}
+
+fn parse_type(ty: &str) -> Option<Type> {
+    let array_length = |s: &str| s.trim().strip_suffix(']')?.trim().parse::<usize>().ok();
+
+    Some(match ty {
+        "u8" => Type::Unsigned(1),
+        "u16" => Type::Unsigned(2),
+        "u32" | "usize" => Type::Unsigned(4),
+        "u64" => Type::Unsigned(8),
+        "u128" => Type::Unsigned(16),
+        "i8" => Type::Signed(1),
+        "i16" => Type::Signed(2),
+        "i32" | "isize" => Type::Signed(4),
+        "i64" => Type::Signed(8),
+        "i128" => Type::Signed(16),
+        "f32" => Type::F32,
+        "f64" => Type::F64,
+        "bool" => Type::Bool,
+        "char" => Type::Char,
+        "str" => Type::Str,
+        "istr" => Type::IStr,
+        "?" => Type::Format,
+        "[?]" => Type::FormatSlice,
+        "[u8]" => Type::U8Slice,
+        "__internal_Debug" | "__internal_Display" => Type::Text,
+        "__internal_FormatSequence" => Type::FormatSequence,
+        _ => {
+            if let Some(length) = ty.strip_prefix("[u8;") {
+                Type::U8Array(array_length(length)?)
+            } else if let Some(length) = ty.strip_prefix("[?;") {
+                Type::FormatArray(array_length(length)?)
+            } else {
+                let mut range = ty.splitn(2, "..");
+                let start = range.next()?.parse::<u32>().ok()?;
+                let end = range.next()?.parse::<u32>().ok()?;
+                if start >= end || end > 128 {
+                    return None;
+                }
+                Type::BitField(start, end)
+            }
+        }
+    })
+}
+
+// Parse parameter specification "{index=type:hint}", all parts are optional
+fn parse_parameter(spec: &str, next_index: &mut usize) -> Option<Parameter> {
+    let digits = spec
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(spec.len());
+    let index = if digits > 0 {
+        spec[..digits].parse().ok()?
+    } else {
+        *next_index += 1;
+        *next_index - 1
+    };
+    let spec = &spec[digits..];
+    let (ty, hint) = match spec.find(':') {
+        Some(colon) => (&spec[..colon], Some(spec[colon + 1..].to_string())),
+        None => (spec, None),
+    };
+    let ty = match ty.strip_prefix('=') {
+        Some(ty) => parse_type(ty)?,
+        None if ty.is_empty() => Type::Format,
+        None => return None,
+    };
+    Some(Parameter { index, ty, hint })
+}
+
+fn parse_format(format: &str) -> DecodeResult<Vec<Fragment>> {
+    let mut fragments = Vec::new();
+    let mut literal = String::new();
+    let mut next_index = 0;
+    let mut chars = format.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '{' if chars.as_str().starts_with('{') => {
+                chars.next();
+                literal.push('{');
+            }
+            '}' if chars.as_str().starts_with('}') => {
+                chars.next();
+                literal.push('}');
+            }
+            '{' => {
+                let rest = chars.as_str();
+                let end = rest.find('}').ok_or(DecodeError::Malformed)?;
+                let parameter =
+                    parse_parameter(&rest[..end], &mut next_index).ok_or(DecodeError::Malformed)?;
+                chars = rest[end + 1..].chars();
+                if !literal.is_empty() {
+                    fragments.push(Fragment::Literal(literal.split_off(0)));
+                }
+                fragments.push(Fragment::Parameter(parameter));
+            }
+            c => literal.push(c),
+        }
+    }
+    if !literal.is_empty() {
+        fragments.push(Fragment::Literal(literal));
+    }
+    Ok(fragments)
+}
+
+struct Reader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn bytes(&mut self, count: usize) -> DecodeResult<&'a [u8]> {
+        let end = self.pos + count;
+        let bytes = self
+            .data
+            .get(self.pos..end)
+            .ok_or(DecodeError::Incomplete)?;
+        self.pos = end;
+        Ok(bytes)
+    }
+
+    fn uint(&mut self, size: usize) -> DecodeResult<u128> {
+        Ok(self
+            .bytes(size)?
+            .iter()
+            .rev()
+            .fold(0, |value, byte| (value << 8) | u128::from(*byte)))
+    }
+
+    fn u16(&mut self) -> DecodeResult<u16> {
+        self.uint(2).map(|v| v as u16)
+    }
+
+    fn u32(&mut self) -> DecodeResult<u32> {
+        self.uint(4).map(|v| v as u32)
+    }
+}
+
+fn ascii_escape(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .flat_map(|b| std::ascii::escape_default(*b))
+        .map(char::from)
+        .collect()
+}
+
+// Format an integer according to a display hint like "#010x"
+fn format_integer(value: i128, hint: Option<&str>) -> String {
+    let mut hint = hint.unwrap_or("");
+    let alternate = hint.starts_with('#');
+    if alternate {
+        hint = &hint[1..];
+    }
+    let mut width = 0;
+    if let Some(rest) = hint.strip_prefix('0') {
+        let digits = rest
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(rest.len());
+        width = rest[..digits].parse().unwrap_or(0);
+        hint = &rest[digits..];
+    }
+    let unsigned = value as u128;
+    match (hint, alternate) {
+        ("x", false) => format!("{:0w$x}", unsigned, w = width),
+        ("x", true) => format!("{:#0w$x}", unsigned, w = width),
+        ("X", false) => format!("{:0w$X}", unsigned, w = width),
+        ("X", true) => format!("{:#0w$X}", unsigned, w = width),
+        ("b", false) => format!("{:0w$b}", unsigned, w = width),
+        ("b", true) => format!("{:#0w$b}", unsigned, w = width),
+        ("a", _) if (0..=255).contains(&value) => ascii_escape(&[value as u8]),
+        ("us", _) => format!("{}.{:06}", value / 1_000_000, value % 1_000_000),
+        ("ms", _) => format!("{}.{:03}", value / 1_000, value % 1_000),
+        ("tus", _) | ("tms", _) | ("ts", _) => {
+            let (seconds, fraction) = match hint {
+                "tus" => (value / 1_000_000, format!(".{:06}", value % 1_000_000)),
+                "tms" => (value / 1_000, format!(".{:03}", value % 1_000)),
+                _ => (value, String::new()),
+            };
+            format!(
+                "{:02}:{:02}:{:02}{}",
+                seconds / 3600,
+                seconds / 60 % 60,
+                seconds % 60,
+                fraction
+            )
+        }
+        _ => format!("{:0w$}", value, w = width),
+    }
+}
+
+fn format_value(value: &Value, ty: &Type, hint: Option<&str>) -> String {
+    let debug = hint == Some("?");
+    match value {
+        Value::Unsigned(raw) => {
+            if let Type::BitField(start, end) = ty {
+                let width = (end - start) as usize;
+                let bits = (raw >> start) & (u128::MAX >> (128 - width));
+                if hint.is_none() {
+                    return format!("0b{:0w$b}", bits, w = width);
+                }
+                return format_integer(bits as i128, hint);
+            }
+            format_integer(*raw as i128, hint)
+        }
+        Value::Signed(value) => format_integer(*value, hint),
+        Value::F32(value) if debug => format!("{:?}", value),
+        Value::F32(value) => format!("{}", value),
+        Value::F64(value) if debug => format!("{:?}", value),
+        Value::F64(value) => format!("{}", value),
+        Value::Bool(value) => format!("{}", value),
+        Value::Char(value) if debug => format!("{:?}", value),
+        Value::Char(value) => format!("{}", value),
+        Value::Str(value) if debug => format!("{:?}", value),
+        Value::Str(value) => value.clone(),
+        Value::Bytes(bytes) if hint == Some("a") => format!("b\"{}\"", ascii_escape(bytes)),
+        Value::Bytes(bytes) => {
+            let elements: Vec<String> = bytes
+                .iter()
+                .map(|b| format_integer(i128::from(*b), hint))
+                .collect();
+            format!("[{}]", elements.join(", "))
+        }
+        Value::Formatted(text) => text.clone(),
+    }
+}
+
+// Split the format string of a derived enum into the format strings of its variants
+fn enum_variants(format: &str) -> Option<Vec<&str>> {
+    if format.contains('|') {
+        Some(format.split('|').collect())
+    } else {
+        None
+    }
+}
+
+impl DefmtTable {
+    ///
+    /// Read the interned strings from the executable, returns None if the
+    /// executable does not use defmt
+    ///
+    pub fn parse(elf: &Elf, buffer: &[u8]) -> Option<Self> {
+        let defmt_section = elf.section_headers.iter().position(|sh| {
+            elf.shdr_strtab.get(sh.sh_name).and_then(|n| n.ok()) == Some(".defmt")
+        })?;
+
+        let mut table = Self {
+            entries: HashMap::new(),
+            timestamp: None,
+            encoding: Encoding::Rzcobs,
+            locations: HashMap::new(),
+            transport: None,
+        };
+
+        for sym in &elf.syms {
+            let name = match elf.strtab.get(sym.st_name).and_then(|n| n.ok()) {
+                Some(name) => name,
+                None => continue,
+            };
+            if table.transport.is_none() {
+                table.transport = LOGGER_CRATES
+                    .iter()
+                    .find(|(krate, _)| name.contains(krate))
+                    .map(|(_, transport)| *transport);
+            }
+            if let Some(version) = name.strip_prefix("_defmt_version_ = ") {
+                if version != DEFMT_VERSION {
+                    warn!("Unsupported defmt wire format version {}", version);
+                    return None;
+                }
+            } else if let Some(encoding) = name.strip_prefix("_defmt_encoding_ = ") {
+                table.encoding = match encoding {
+                    "raw" => Encoding::Raw,
+                    "rzcobs" => Encoding::Rzcobs,
+                    _ => {
+                        warn!("Unsupported defmt encoding {}", encoding);
+                        return None;
+                    }
+                };
+            } else if sym.st_shndx == defmt_section {
+                if let Some(mut fields) = parse_symbol(name) {
+                    let tag = fields.remove("tag").unwrap_or_default();
+                    let format = fields.remove("data").unwrap_or_default();
+                    if tag == "defmt_timestamp" {
+                        table.timestamp = Some(format.clone());
+                    }
+                    table
+                        .entries
+                        .insert(sym.st_value as u16, Entry { tag, format });
+                }
+            }
+        }
+
+        table.locations = variable_locations(elf, buffer);
+        info!(
+            "defmt: {} interned strings, {:?} encoding, {:?} transport",
+            table.entries.len(),
+            table.encoding,
+            table.transport
+        );
+        Some(table)
+    }
+
+    fn entry(&self, index: u16) -> DecodeResult<&Entry> {
+        self.entries.get(&index).ok_or(DecodeError::Malformed)
+    }
+
+    // Decode the data of a value formatted with the format string of `index`
+    fn format_index(&self, index: u16, reader: &mut Reader, depth: usize) -> DecodeResult<String> {
+        let entry = self.entry(index)?;
+        if entry.tag == "defmt_derived" {
+            if let Some(variants) = enum_variants(&entry.format) {
+                let discriminant = match variants.len() {
+                    0..=255 => reader.uint(1)?,
+                    256..=65535 => reader.uint(2)?,
+                    _ => reader.uint(4)?,
+                } as usize;
+                let variant = variants.get(discriminant).ok_or(DecodeError::Malformed)?;
+                return self.format(variant, reader, depth);
+            }
+        }
+        self.format(&entry.format, reader, depth)
+    }
+
+    fn decode_value(&self, ty: &Type, reader: &mut Reader, depth: usize) -> DecodeResult<Value> {
+        Ok(match ty {
+            Type::Unsigned(size) => Value::Unsigned(reader.uint(*size)?),
+            Type::Signed(size) => {
+                let shift = 128 - size * 8;
+                Value::Signed(((reader.uint(*size)? << shift) as i128) >> shift)
+            }
+            Type::F32 => Value::F32(f32::from_bits(reader.u32()?)),
+            Type::F64 => Value::F64(f64::from_bits(reader.uint(8)? as u64)),
+            Type::Bool => Value::Bool(reader.uint(1)? != 0),
+            Type::Char => Value::Char(
+                std::char::from_u32(reader.u32()?).unwrap_or(std::char::REPLACEMENT_CHARACTER),
+            ),
+            Type::Str => {
+                let length = reader.u32()? as usize;
+                Value::Str(String::from_utf8_lossy(reader.bytes(length)?).into_owned())
+            }
+            Type::IStr => Value::Str(self.entry(reader.u16()?)?.format.clone()),
+            Type::Format => Value::Formatted(self.format_index(reader.u16()?, reader, depth)?),
+            Type::FormatSlice | Type::FormatArray(_) => {
+                let length = match ty {
+                    Type::FormatArray(length) => *length,
+                    _ => reader.u32()? as usize,
+                };
+                let index = reader.u16()?;
+                let mut elements = Vec::new();
+                for _ in 0..length {
+                    elements.push(self.format_index(index, reader, depth)?);
+                }
+                Value::Formatted(format!("[{}]", elements.join(", ")))
+            }
+            Type::FormatSequence => {
+                let mut text = String::new();
+                loop {
+                    let index = reader.u16()?;
+                    if index == 0 {
+                        break;
+                    }
+                    text.push_str(&self.format_index(index, reader, depth)?);
+                }
+                Value::Formatted(text)
+            }
+            Type::Text => {
+                let rest = &reader.data[reader.pos..];
+                let length = rest
+                    .iter()
+                    .position(|b| *b == 0xff)
+                    .ok_or(DecodeError::Incomplete)?;
+                let text = String::from_utf8_lossy(reader.bytes(length)?).into_owned();
+                reader.bytes(1)?;
+                Value::Formatted(text)
+            }
+            Type::U8Slice => {
+                let length = reader.u32()? as usize;
+                Value::Bytes(reader.bytes(length)?.to_vec())
+            }
+            Type::U8Array(length) => Value::Bytes(reader.bytes(*length)?.to_vec()),
+            Type::BitField(start, end) => {
+                // only the bytes containing the bitfields are sent
+                let lowest_byte = start / 8;
+                let size = match (end - 1) / 8 - lowest_byte + 1 {
+                    1 => 1,
+                    2 => 2,
+                    3..=4 => 4,
+                    5..=8 => 8,
+                    _ => 16,
+                };
+                Value::Unsigned(reader.uint(size)? << (lowest_byte * 8))
+            }
+        })
+    }
+
+    // Decode the arguments of `format` and format them
+    fn format(&self, format: &str, reader: &mut Reader, depth: usize) -> DecodeResult<String> {
+        if depth > MAX_DEPTH {
+            return Err(DecodeError::Malformed);
+        }
+        let fragments = parse_format(format)?;
+
+        // arguments are sent once each, in index order
+        let mut types: Vec<Option<Type>> = Vec::new();
+        for fragment in &fragments {
+            if let Fragment::Parameter(parameter) = fragment {
+                if types.len() <= parameter.index {
+                    types.resize(parameter.index + 1, None);
+                }
+                types[parameter.index] = match (&types[parameter.index], &parameter.ty) {
+                    (Some(Type::BitField(a, b)), Type::BitField(c, d)) => {
+                        Some(Type::BitField(*a.min(c), *b.max(d)))
+                    }
+                    (Some(ty), _) => Some(ty.clone()),
+                    (None, ty) => Some(ty.clone()),
+                };
+            }
+        }
+        let mut values = Vec::new();
+        for ty in &types {
+            let ty = ty.as_ref().ok_or(DecodeError::Malformed)?;
+            values.push(self.decode_value(ty, reader, depth + 1)?);
+        }
+
+        let mut text = String::new();
+        for fragment in &fragments {
+            match fragment {
+                Fragment::Literal(literal) => text.push_str(literal),
+                Fragment::Parameter(parameter) => text.push_str(&format_value(
+                    &values[parameter.index],
+                    &parameter.ty,
+                    parameter.hint.as_deref(),
+                )),
+            }
+        }
+        Ok(text)
+    }
+
+    // Decode a log frame, returns the log line and the frame size
+    fn decode_frame(&self, frame: &[u8]) -> DecodeResult<(String, usize)> {
+        let mut reader = Reader {
+            data: frame,
+            pos: 0,
+        };
+        let index = reader.u16()?;
+        let entry = self.entry(index)?;
+        let level = match entry.tag.as_str() {
+            "defmt_trace" => Some("TRACE"),
+            "defmt_debug" => Some("DEBUG"),
+            "defmt_info" => Some("INFO "),
+            "defmt_warn" => Some("WARN "),
+            "defmt_error" => Some("ERROR"),
+            "defmt_println" => None,
+            _ => return Err(DecodeError::Malformed),
+        };
+
+        let mut line = String::new();
+        if let Some(timestamp) = &self.timestamp {
+            line.push_str(&self.format(timestamp, &mut reader, 0)?);
+            line.push(' ');
+        }
+        if let Some(level) = level {
+            line.push_str(level);
+            line.push(' ');
+        }
+        line.push_str(&self.format(&entry.format, &mut reader, 0)?);
+        if let Some(location) = self.locations.get(&u64::from(index)) {
+            line.push_str(&format!("\n└─ {}:{}", location.file, location.line));
+        }
+        Ok((line, reader.pos))
+    }
+}
+
+// Decode a rzCOBS encoded frame, without the zero delimiter
+fn rzcobs_decode(data: &[u8]) -> Option<Vec<u8>> {
+    // decoding is done from the end of the frame
+    let mut decoded = Vec::new();
+    let mut bytes = data.iter().rev().cloned();
+    while let Some(code) = bytes.next() {
+        match code {
+            0 => return None,
+            0x01..=0x7f => {
+                // a group of 7 bytes, bits set for zero bytes
+                for bit in (0..7).rev() {
+                    if code & (1 << bit) != 0 {
+                        decoded.push(0);
+                    } else {
+                        decoded.push(bytes.next()?);
+                    }
+                }
+            }
+            0x80..=0xfe => {
+                // run of non zero bytes followed by a zero
+                decoded.push(0);
+                for _ in 0..(code & 0x7f) + 7 {
+                    decoded.push(bytes.next()?);
+                }
+            }
+            0xff => {
+                for _ in 0..134 {
+                    decoded.push(bytes.next()?);
+                }
+            }
+        }
+    }
+    decoded.reverse();
+    Some(decoded)
+}
+
+///
+/// Writer that decodes defmt log frames and writes them as text lines
+///
+pub struct DefmtDecoder {
+    table: Rc<DefmtTable>,
+    buffer: Vec<u8>,
+    output: Box<dyn io::Write + 'static>,
+}
+
+impl DefmtDecoder {
+    pub fn new(table: Rc<DefmtTable>, output: Box<dyn io::Write + 'static>) -> Self {
+        Self {
+            table,
+            buffer: Vec::new(),
+            output,
+        }
+    }
+
+    fn output_frame(&mut self, frame: &[u8]) -> io::Result<usize> {
+        match self.table.decode_frame(frame) {
+            Ok((line, size)) => {
+                writeln!(self.output, "{}", line)?;
+                self.output.flush()?;
+                Ok(size)
+            }
+            Err(DecodeError::Incomplete) => Ok(0),
+            Err(DecodeError::Malformed) => {
+                warn!("malformed defmt frame {:02x?}", frame);
+                Ok(frame.len())
+            }
+        }
+    }
+}
+
+impl Write for DefmtDecoder {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self.table.encoding {
+            Encoding::Rzcobs => {
+                for byte in buf {
+                    if *byte != 0 {
+                        self.buffer.push(*byte);
+                    } else if !self.buffer.is_empty() {
+                        let encoded = self.buffer.split_off(0);
+                        match rzcobs_decode(&encoded) {
+                            Some(frame) => {
+                                // trailing zeros of the frame are padding
+                                if self.output_frame(&frame)? == 0 {
+                                    warn!("truncated defmt frame {:02x?}", frame);
+                                }
+                            }
+                            None => warn!("malformed defmt frame {:02x?}", encoded),
+                        }
+                    }
+                }
+            }
+            Encoding::Raw => {
+                self.buffer.extend_from_slice(buf);
+                loop {
+                    let frame = self.buffer.clone();
+                    let size = self.output_frame(&frame)?;
+                    if size == 0 {
+                        break;
+                    }
+                    self.buffer.drain(..size);
+                }
+            }
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.output.flush()
+    }
+}
@@ -0,0 +1,565 @@
+//!
+//! Minimal DWARF reader
+//!
+//! Only resolves the declaration file and line of static variables, which
+//! is what is needed to show the source location of defmt log statements.
+//! Supports DWARF versions 2 to 5 with 32-bit offsets.
+//!
+
+use std::collections::HashMap;
+
+use goblin::elf::Elf;
+
+// tags
+const DW_TAG_COMPILE_UNIT: u64 = 0x11;
+const DW_TAG_VARIABLE: u64 = 0x34;
+
+// attributes
+const DW_AT_LOCATION: u64 = 0x02;
+const DW_AT_STMT_LIST: u64 = 0x10;
+const DW_AT_DECL_FILE: u64 = 0x3a;
+const DW_AT_DECL_LINE: u64 = 0x3b;
+
+// forms
+const DW_FORM_ADDR: u64 = 0x01;
+const DW_FORM_BLOCK2: u64 = 0x03;
+const DW_FORM_BLOCK4: u64 = 0x04;
+const DW_FORM_DATA2: u64 = 0x05;
+const DW_FORM_DATA4: u64 = 0x06;
+const DW_FORM_DATA8: u64 = 0x07;
+const DW_FORM_STRING: u64 = 0x08;
+const DW_FORM_BLOCK: u64 = 0x09;
+const DW_FORM_BLOCK1: u64 = 0x0a;
+const DW_FORM_DATA1: u64 = 0x0b;
+const DW_FORM_FLAG: u64 = 0x0c;
+const DW_FORM_SDATA: u64 = 0x0d;
+const DW_FORM_STRP: u64 = 0x0e;
+const DW_FORM_UDATA: u64 = 0x0f;
+const DW_FORM_REF_ADDR: u64 = 0x10;
+const DW_FORM_REF1: u64 = 0x11;
+const DW_FORM_REF2: u64 = 0x12;
+const DW_FORM_REF4: u64 = 0x13;
+const DW_FORM_REF8: u64 = 0x14;
+const DW_FORM_REF_UDATA: u64 = 0x15;
+const DW_FORM_INDIRECT: u64 = 0x16;
+const DW_FORM_SEC_OFFSET: u64 = 0x17;
+const DW_FORM_EXPRLOC: u64 = 0x18;
+const DW_FORM_FLAG_PRESENT: u64 = 0x19;
+const DW_FORM_STRX: u64 = 0x1a;
+const DW_FORM_ADDRX: u64 = 0x1b;
+const DW_FORM_REF_SUP4: u64 = 0x1c;
+const DW_FORM_STRP_SUP: u64 = 0x1d;
+const DW_FORM_DATA16: u64 = 0x1e;
+const DW_FORM_LINE_STRP: u64 = 0x1f;
+const DW_FORM_REF_SIG8: u64 = 0x20;
+const DW_FORM_IMPLICIT_CONST: u64 = 0x21;
+const DW_FORM_LOCLISTX: u64 = 0x22;
+const DW_FORM_RNGLISTX: u64 = 0x23;
+const DW_FORM_REF_SUP8: u64 = 0x24;
+const DW_FORM_STRX1: u64 = 0x25;
+const DW_FORM_STRX2: u64 = 0x26;
+const DW_FORM_STRX3: u64 = 0x27;
+const DW_FORM_STRX4: u64 = 0x28;
+const DW_FORM_ADDRX1: u64 = 0x29;
+const DW_FORM_ADDRX2: u64 = 0x2a;
+const DW_FORM_ADDRX3: u64 = 0x2b;
+const DW_FORM_ADDRX4: u64 = 0x2c;
+
+// line number program content types
+const DW_LNCT_PATH: u64 = 0x1;
+const DW_LNCT_DIRECTORY_INDEX: u64 = 0x2;
+
+// location expression operation
+const DW_OP_ADDR: u8 = 0x03;
+
+///
+/// Source location of a declaration
+///
+#[derive(Clone, Debug, PartialEq)]
+pub struct Location {
+    pub file: String,
+    pub line: u64,
+}
+
+struct Reader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn new(data: &'a [u8], pos: usize) -> Self {
+        Self { data, pos }
+    }
+
+    fn bytes(&mut self, count: usize) -> Option<&'a [u8]> {
+        let end = self.pos.checked_add(count)?;
+        let bytes = self.data.get(self.pos..end)?;
+        self.pos = end;
+        Some(bytes)
+    }
+
+    fn uint(&mut self, size: usize) -> Option<u64> {
+        let bytes = self.bytes(size)?;
+        Some(
+            bytes
+                .iter()
+                .rev()
+                .fold(0, |value, byte| (value << 8) | u64::from(*byte)),
+        )
+    }
+
+    fn u8(&mut self) -> Option<u8> {
+        self.uint(1).map(|v| v as u8)
+    }
+
+    fn u16(&mut self) -> Option<u16> {
+        self.uint(2).map(|v| v as u16)
+    }
+
+    fn u32(&mut self) -> Option<u32> {
+        self.uint(4).map(|v| v as u32)
+    }
+
+    fn uleb(&mut self) -> Option<u64> {
+        let mut value = 0;
+        let mut shift = 0;
+        loop {
+            let byte = self.u8()?;
+            if shift < 64 {
+                value |= u64::from(byte & 0x7f) << shift;
+            }
+            shift += 7;
+            if byte & 0x80 == 0 {
+                return Some(value);
+            }
+        }
+    }
+
+    fn sleb(&mut self) -> Option<i64> {
+        let mut value = 0i64;
+        let mut shift = 0;
+        loop {
+            let byte = self.u8()?;
+            if shift < 64 {
+                value |= i64::from(byte & 0x7f) << shift;
+            }
+            shift += 7;
+            if byte & 0x80 == 0 {
+                if shift < 64 && byte & 0x40 != 0 {
+                    value |= -1 << shift;
+                }
+                return Some(value);
+            }
+        }
+    }
+
+    fn cstr(&mut self) -> Option<&'a str> {
+        let length = self.data.get(self.pos..)?.iter().position(|b| *b == 0)?;
+        let s = std::str::from_utf8(self.bytes(length)?).ok()?;
+        self.pos += 1;
+        Some(s)
+    }
+}
+
+fn cstr_at(section: &[u8], offset: u64) -> Option<String> {
+    Reader::new(section, offset as usize)
+        .cstr()
+        .map(|s| s.to_string())
+}
+
+#[derive(Clone, Copy)]
+struct AttributeSpec {
+    name: u64,
+    form: u64,
+    implicit_const: i64,
+}
+
+struct Abbreviation {
+    tag: u64,
+    attributes: Vec<AttributeSpec>,
+}
+
+fn parse_abbreviations(section: &[u8], offset: usize) -> Option<HashMap<u64, Abbreviation>> {
+    let mut reader = Reader::new(section, offset);
+    let mut abbreviations = HashMap::new();
+    loop {
+        let code = reader.uleb()?;
+        if code == 0 {
+            return Some(abbreviations);
+        }
+        let tag = reader.uleb()?;
+        reader.u8()?; // children flag, nesting needs no tracking here
+        let mut attributes = Vec::new();
+        loop {
+            let name = reader.uleb()?;
+            let form = reader.uleb()?;
+            if name == 0 && form == 0 {
+                break;
+            }
+            let implicit_const = if form == DW_FORM_IMPLICIT_CONST {
+                reader.sleb()?
+            } else {
+                0
+            };
+            attributes.push(AttributeSpec {
+                name,
+                form,
+                implicit_const,
+            });
+        }
+        abbreviations.insert(code, Abbreviation { tag, attributes });
+    }
+}
+
+enum Value<'a> {
+    Unsigned(u64),
+    Block(&'a [u8]),
+    String(String),
+    // forms that are not needed for locations
+    Other,
+}
+
+impl<'a> Value<'a> {
+    fn as_u64(&self) -> Option<u64> {
+        match self {
+            Value::Unsigned(value) => Some(*value),
+            _ => None,
+        }
+    }
+}
+
+struct Sections<'a> {
+    info: &'a [u8],
+    abbrev: &'a [u8],
+    line: &'a [u8],
+    str: &'a [u8],
+    line_str: &'a [u8],
+}
+
+struct Unit {
+    version: u16,
+    address_size: usize,
+}
+
+fn read_value<'a>(
+    reader: &mut Reader<'a>,
+    sections: &Sections<'a>,
+    unit: &Unit,
+    form: u64,
+    implicit_const: i64,
+) -> Option<Value<'a>> {
+    let value = match form {
+        DW_FORM_ADDR => Value::Unsigned(reader.uint(unit.address_size)?),
+        DW_FORM_DATA1 | DW_FORM_REF1 | DW_FORM_FLAG => Value::Unsigned(reader.uint(1)?),
+        DW_FORM_DATA2 | DW_FORM_REF2 => Value::Unsigned(reader.uint(2)?),
+        DW_FORM_DATA4 | DW_FORM_REF4 | DW_FORM_SEC_OFFSET | DW_FORM_REF_SUP4 => {
+            Value::Unsigned(reader.uint(4)?)
+        }
+        DW_FORM_DATA8 | DW_FORM_REF8 | DW_FORM_REF_SIG8 | DW_FORM_REF_SUP8 => {
+            Value::Unsigned(reader.uint(8)?)
+        }
+        DW_FORM_UDATA | DW_FORM_REF_UDATA => Value::Unsigned(reader.uleb()?),
+        DW_FORM_SDATA => Value::Unsigned(reader.sleb()? as u64),
+        DW_FORM_IMPLICIT_CONST => Value::Unsigned(implicit_const as u64),
+        DW_FORM_FLAG_PRESENT => Value::Unsigned(1),
+        DW_FORM_REF_ADDR => {
+            let size = if unit.version == 2 {
+                unit.address_size
+            } else {
+                4
+            };
+            reader.uint(size)?;
+            Value::Other
+        }
+        DW_FORM_BLOCK1 => {
+            let length = reader.uint(1)? as usize;
+            Value::Block(reader.bytes(length)?)
+        }
+        DW_FORM_BLOCK2 => {
+            let length = reader.uint(2)? as usize;
+            Value::Block(reader.bytes(length)?)
+        }
+        DW_FORM_BLOCK4 => {
+            let length = reader.uint(4)? as usize;
+            Value::Block(reader.bytes(length)?)
+        }
+        DW_FORM_BLOCK | DW_FORM_EXPRLOC => {
+            let length = reader.uleb()? as usize;
+            Value::Block(reader.bytes(length)?)
+        }
+        DW_FORM_DATA16 => {
+            reader.bytes(16)?;
+            Value::Other
+        }
+        DW_FORM_STRING => Value::String(reader.cstr()?.to_string()),
+        DW_FORM_STRP => Value::String(cstr_at(sections.str, reader.uint(4)?)?),
+        DW_FORM_LINE_STRP => Value::String(cstr_at(sections.line_str, reader.uint(4)?)?),
+        DW_FORM_STRP_SUP => {
+            reader.uint(4)?;
+            Value::Other
+        }
+        DW_FORM_STRX | DW_FORM_ADDRX | DW_FORM_LOCLISTX | DW_FORM_RNGLISTX => {
+            reader.uleb()?;
+            Value::Other
+        }
+        DW_FORM_STRX1 | DW_FORM_ADDRX1 => {
+            reader.uint(1)?;
+            Value::Other
+        }
+        DW_FORM_STRX2 | DW_FORM_ADDRX2 => {
+            reader.uint(2)?;
+            Value::Other
+        }
+        DW_FORM_STRX3 | DW_FORM_ADDRX3 => {
+            reader.uint(3)?;
+            Value::Other
+        }
+        DW_FORM_STRX4 | DW_FORM_ADDRX4 => {
+            reader.uint(4)?;
+            Value::Other
+        }
+        DW_FORM_INDIRECT => {
+            let form = reader.uleb()?;
+            return read_value(reader, sections, unit, form, implicit_const);
+        }
+        _ => return None,
+    };
+    Some(value)
+}
+
+fn join_path(directory: &str, file: &str) -> String {
+    if directory.is_empty() || file.starts_with('/') {
+        file.to_string()
+    } else {
+        format!("{}/{}", directory, file)
+    }
+}
+
+// Read a DWARF 5 directory or file name table
+fn read_entry_table<'a>(
+    reader: &mut Reader<'a>,
+    sections: &Sections<'a>,
+    unit: &Unit,
+) -> Option<Vec<(String, u64)>> {
+    let format_count = reader.u8()?;
+    let mut formats = Vec::new();
+    for _ in 0..format_count {
+        formats.push((reader.uleb()?, reader.uleb()?));
+    }
+    let count = reader.uleb()?;
+    let mut entries = Vec::new();
+    for _ in 0..count {
+        let mut path = String::new();
+        let mut directory = 0;
+        for (content_type, form) in &formats {
+            let value = read_value(reader, sections, unit, *form, 0)?;
+            match (*content_type, value) {
+                (DW_LNCT_PATH, Value::String(s)) => path = s,
+                (DW_LNCT_DIRECTORY_INDEX, Value::Unsigned(index)) => directory = index,
+                _ => {}
+            }
+        }
+        entries.push((path, directory));
+    }
+    Some(entries)
+}
+
+//
+// Read the file name table of the line number program at `offset`.
+// Returns the file names indexed as DW_AT_decl_file expects them.
+//
+fn parse_file_names(sections: &Sections, offset: usize) -> Option<Vec<String>> {
+    let mut reader = Reader::new(sections.line, offset);
+    let unit_length = reader.u32()?;
+    if unit_length == 0xffff_ffff {
+        return None;
+    }
+    let version = reader.u16()?;
+    let mut address_size = 4;
+    if version >= 5 {
+        address_size = reader.u8()? as usize;
+        reader.u8()?; // segment selector size
+    }
+    reader.u32()?; // header length
+    reader.u8()?; // minimum instruction length
+    if version >= 4 {
+        reader.u8()?; // maximum operations per instruction
+    }
+    reader.u8()?; // default is_stmt
+    reader.u8()?; // line base
+    reader.u8()?; // line range
+    let opcode_base = reader.u8()?;
+    reader.bytes(usize::from(opcode_base.saturating_sub(1)))?;
+
+    if version >= 5 {
+        let unit = Unit {
+            version,
+            address_size,
+        };
+        let directories = read_entry_table(&mut reader, sections, &unit)?;
+        let files = read_entry_table(&mut reader, sections, &unit)?;
+        return Some(
+            files
+                .iter()
+                .map(
+                    |(file, directory)| match directories.get(*directory as usize) {
+                        // directory 0 is the compilation directory
+                        Some((path, _)) if *directory != 0 => join_path(path, file),
+                        _ => file.clone(),
+                    },
+                )
+                .collect(),
+        );
+    }
+
+    // index 0 is the compilation directory
+    let mut directories = vec![String::new()];
+    loop {
+        let directory = reader.cstr()?;
+        if directory.is_empty() {
+            break;
+        }
+        directories.push(directory.to_string());
+    }
+    // file indexes start from 1
+    let mut files = vec![String::new()];
+    loop {
+        let file = reader.cstr()?;
+        if file.is_empty() {
+            break;
+        }
+        let directory = reader.uleb()?;
+        reader.uleb()?; // modification time
+        reader.uleb()?; // length
+        let directory = directories
+            .get(directory as usize)
+            .map_or("", |d| d.as_str());
+        files.push(join_path(directory, file));
+    }
+    Some(files)
+}
+
+fn parse_unit(
+    sections: &Sections,
+    offset: usize,
+    locations: &mut HashMap<u64, Location>,
+) -> Option<usize> {
+    let mut reader = Reader::new(sections.info, offset);
+    let unit_length = reader.u32()?;
+    if unit_length == 0xffff_ffff {
+        // 64-bit DWARF is not supported
+        return None;
+    }
+    let end = reader.pos + unit_length as usize;
+    let version = reader.u16()?;
+    let (abbrev_offset, address_size) = if version >= 5 {
+        let unit_type = reader.u8()?;
+        let address_size = reader.u8()? as usize;
+        let abbrev_offset = reader.u32()?;
+        match unit_type {
+            // skeleton and split compile units
+            0x04 | 0x05 => {
+                reader.bytes(8)?;
+            }
+            // type units
+            0x02 | 0x06 => {
+                reader.bytes(12)?;
+            }
+            _ => {}
+        }
+        (abbrev_offset, address_size)
+    } else {
+        let abbrev_offset = reader.u32()?;
+        (abbrev_offset, reader.u8()? as usize)
+    };
+    let unit = Unit {
+        version,
+        address_size,
+    };
+
+    let abbreviations = parse_abbreviations(sections.abbrev, abbrev_offset as usize)?;
+    let mut files = Vec::new();
+
+    while reader.pos < end {
+        let code = reader.uleb()?;
+        if code == 0 {
+            continue;
+        }
+        let abbreviation = abbreviations.get(&code)?;
+
+        let mut address = None;
+        let mut file = None;
+        let mut line = None;
+        let mut stmt_list = None;
+        for spec in &abbreviation.attributes {
+            let value = read_value(&mut reader, sections, &unit, spec.form, spec.implicit_const)?;
+            match spec.name {
+                DW_AT_LOCATION => {
+                    if let Value::Block(expression) = value {
+                        if expression.len() == 1 + address_size && expression[0] == DW_OP_ADDR {
+                            address = Reader::new(expression, 1).uint(address_size);
+                        }
+                    }
+                }
+                DW_AT_DECL_FILE => file = value.as_u64(),
+                DW_AT_DECL_LINE => line = value.as_u64(),
+                DW_AT_STMT_LIST => stmt_list = value.as_u64(),
+                _ => {}
+            }
+        }
+
+        match abbreviation.tag {
+            DW_TAG_COMPILE_UNIT => {
+                if let Some(offset) = stmt_list {
+                    files = parse_file_names(sections, offset as usize).unwrap_or_default();
+                }
+            }
+            DW_TAG_VARIABLE => {
+                if let (Some(address), Some(file), Some(line)) = (address, file, line) {
+                    if let Some(file) = files.get(file as usize) {
+                        locations.insert(
+                            address,
+                            Location {
+                                file: file.clone(),
+                                line,
+                            },
+                        );
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    Some(end)
+}
+
+///
+/// Collect the declaration locations of all statically allocated variables,
+/// keyed by their address
+///
+pub fn variable_locations(elf: &Elf, buffer: &[u8]) -> HashMap<u64, Location> {
+    let section = |name: &str| -> &[u8] {
+        elf.section_headers
+            .iter()
+            .find(|sh| elf.shdr_strtab.get(sh.sh_name).and_then(|n| n.ok()) == Some(name))
+            .and_then(|sh| buffer.get(sh.sh_offset as usize..(sh.sh_offset + sh.sh_size) as usize))
+            .unwrap_or(&[])
+    };
+    let sections = Sections {
+        info: section(".debug_info"),
+        abbrev: section(".debug_abbrev"),
+        line: section(".debug_line"),
+        str: section(".debug_str"),
+        line_str: section(".debug_line_str"),
+    };
+
+    let mut locations = HashMap::new();
+    let mut offset = 0;
+    while offset < sections.info.len() {
+        match parse_unit(&sections, offset, &mut locations) {
+            Some(next) => offset = next,
+            None => break,
+        }
+    }
+    locations
+}
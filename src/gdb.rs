@@ -0,0 +1,978 @@
+//!
+//! GDB remote serial protocol server
+//!
+//! Lets a debugger such as `arm-none-eabi-gdb` attach to the simulated
+//! processor with `target remote :<port>`.
+//!
+
+use std::collections::HashSet;
+use std::io;
+use std::io::prelude::*;
+use std::net::{TcpListener, TcpStream};
+use std::time::Instant;
+
+use zmu_cortex_m::bus::Bus;
+use zmu_cortex_m::core::bits::Bits;
+use zmu_cortex_m::core::fpu::FPSCR_WRITE_MASK;
+use zmu_cortex_m::core::lockup::Lockup;
+use zmu_cortex_m::core::register::{BaseReg, Reg};
+use zmu_cortex_m::core::reset::Reset;
+use zmu_cortex_m::executor::Executor;
+use zmu_cortex_m::peripheral::dcb::{
+    DebugControlBlock, DFSR_BKPT, DHCSR_C_DEBUGEN, DHCSR_C_HALT, DHCSR_C_STEP, DHCSR_DBGKEY,
+};
+use zmu_cortex_m::peripheral::fpb::{FlashPatchBreakpoint, FpbMatch};
+use zmu_cortex_m::system::simulation::SimulationStatistics;
+use zmu_cortex_m::Processor;
+
+use crate::errors::*;
+
+const SIGINT: u8 = 2;
+const SIGTRAP: u8 = 5;
+const SIGSEGV: u8 = 11;
+
+// number of simulation steps between checks for a break request from gdb
+const INTERRUPT_POLL_INTERVAL: u64 = 0x4000;
+
+// largest memory block transferred in a single packet
+const MAX_MEMORY_BLOCK: usize = 0x800;
+
+// register numbering follows the order of the target description below:
+// r0-r12, sp, lr, pc, xpsr, msp, psp and with the FPU d0-d15, fpscr
+const REG_XPSR: usize = 16;
+const REG_MSP: usize = 17;
+const REG_PSP: usize = 18;
+const REG_D0: usize = 19;
+const REG_FPSCR: usize = 35;
+
+const TARGET_XML_CORE: &str = r#"<?xml version="1.0"?>
+<!DOCTYPE target SYSTEM "gdb-target.dtd">
+<target version="1.0">
+<architecture>arm</architecture>
+<feature name="org.gnu.gdb.arm.m-profile">
+<reg name="r0" bitsize="32" regnum="0"/>
+<reg name="r1" bitsize="32"/>
+<reg name="r2" bitsize="32"/>
+<reg name="r3" bitsize="32"/>
+<reg name="r4" bitsize="32"/>
+<reg name="r5" bitsize="32"/>
+<reg name="r6" bitsize="32"/>
+<reg name="r7" bitsize="32"/>
+<reg name="r8" bitsize="32"/>
+<reg name="r9" bitsize="32"/>
+<reg name="r10" bitsize="32"/>
+<reg name="r11" bitsize="32"/>
+<reg name="r12" bitsize="32"/>
+<reg name="sp" bitsize="32" type="data_ptr"/>
+<reg name="lr" bitsize="32"/>
+<reg name="pc" bitsize="32" type="code_ptr"/>
+<reg name="xpsr" bitsize="32"/>
+</feature>
+<feature name="org.gnu.gdb.arm.m-system">
+<reg name="msp" bitsize="32" type="data_ptr"/>
+<reg name="psp" bitsize="32" type="data_ptr"/>
+</feature>"#;
+
+// described only for cores with the floating point extension
+const TARGET_XML_FPU: &str = r#"<feature name="org.gnu.gdb.arm.vfp">
+<reg name="d0" bitsize="64" type="ieee_double"/>
+<reg name="d1" bitsize="64" type="ieee_double"/>
+<reg name="d2" bitsize="64" type="ieee_double"/>
+<reg name="d3" bitsize="64" type="ieee_double"/>
+<reg name="d4" bitsize="64" type="ieee_double"/>
+<reg name="d5" bitsize="64" type="ieee_double"/>
+<reg name="d6" bitsize="64" type="ieee_double"/>
+<reg name="d7" bitsize="64" type="ieee_double"/>
+<reg name="d8" bitsize="64" type="ieee_double"/>
+<reg name="d9" bitsize="64" type="ieee_double"/>
+<reg name="d10" bitsize="64" type="ieee_double"/>
+<reg name="d11" bitsize="64" type="ieee_double"/>
+<reg name="d12" bitsize="64" type="ieee_double"/>
+<reg name="d13" bitsize="64" type="ieee_double"/>
+<reg name="d14" bitsize="64" type="ieee_double"/>
+<reg name="d15" bitsize="64" type="ieee_double"/>
+<reg name="fpscr" bitsize="32" type="int" group="float"/>
+</feature>"#;
+
+const TARGET_XML_END: &str = "</target>";
+
+enum Packet {
+    Command(Vec<u8>),
+    Interrupt,
+}
+
+enum StopReason {
+    Trap,
+    Interrupted,
+    // locked up or stopped without an exit status, still inspectable
+    Fault,
+    Exited(u8),
+}
+
+struct Connection {
+    stream: TcpStream,
+}
+
+impl Connection {
+    fn read_byte(&mut self) -> io::Result<u8> {
+        let mut byte = [0_u8];
+        self.stream.read_exact(&mut byte)?;
+        Ok(byte[0])
+    }
+
+    fn read_packet(&mut self) -> io::Result<Packet> {
+        loop {
+            match self.read_byte()? {
+                b'$' => {
+                    let mut raw = Vec::new();
+                    loop {
+                        let byte = self.read_byte()?;
+                        // an escaped '#' is packet data
+                        if byte == b'#' && raw.last() != Some(&b'}') {
+                            break;
+                        }
+                        raw.push(byte);
+                    }
+                    let checksum = [self.read_byte()?, self.read_byte()?];
+
+                    if let Some(data) = decode_packet(&raw, &checksum) {
+                        self.stream.write_all(b"+")?;
+                        return Ok(Packet::Command(data));
+                    }
+                    warn!("gdb packet checksum mismatch, requesting retransmit");
+                    self.stream.write_all(b"-")?;
+                }
+                0x03 => return Ok(Packet::Interrupt),
+                // acknowledgements from gdb
+                _ => {}
+            }
+        }
+    }
+
+    fn write_packet(&mut self, data: &str) -> io::Result<()> {
+        self.stream.write_all(encode_packet(data).as_bytes())?;
+        self.stream.flush()
+    }
+
+    // Check without blocking if gdb has requested the target to stop.
+    fn poll_interrupt(&mut self) -> io::Result<bool> {
+        let mut byte = [0_u8];
+        self.stream.set_nonblocking(true)?;
+        let result = match self.stream.read(&mut byte) {
+            Ok(0) => Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "gdb closed the connection",
+            )),
+            Ok(_) => Ok(byte[0] == 0x03),
+            Err(ref e) if e.kind() == io::ErrorKind::WouldBlock => Ok(false),
+            Err(e) => Err(e),
+        };
+        self.stream.set_nonblocking(false)?;
+        result
+    }
+}
+
+fn checksum(data: &[u8]) -> u8 {
+    data.iter().fold(0, |sum, byte| sum.wrapping_add(*byte))
+}
+
+// Frame a reply as "$data#checksum"
+fn encode_packet(data: &str) -> String {
+    format!("${}#{:02x}", data, checksum(data.as_bytes()))
+}
+
+// Verify the checksum of the raw packet data between '$' and '#' and
+// remove the escaping, None if the checksum does not match.
+fn decode_packet(raw: &[u8], checksum_digits: &[u8]) -> Option<Vec<u8>> {
+    if parse_hex(checksum_digits)? != u32::from(checksum(raw)) {
+        return None;
+    }
+    let mut data = Vec::with_capacity(raw.len());
+    let mut escaped = false;
+    for byte in raw {
+        if escaped {
+            data.push(byte ^ 0x20);
+            escaped = false;
+        } else if *byte == b'}' {
+            escaped = true;
+        } else {
+            data.push(*byte);
+        }
+    }
+    Some(data)
+}
+
+// Target description matching the registers of the simulated core
+fn target_xml(processor: &Processor) -> String {
+    let mut xml = TARGET_XML_CORE.to_string();
+    if processor.get_cpu().has_fpu() {
+        xml.push_str(TARGET_XML_FPU);
+    }
+    xml.push_str(TARGET_XML_END);
+    xml
+}
+
+// Number of the last register in the target description
+fn last_register(processor: &Processor) -> usize {
+    if processor.get_cpu().has_fpu() {
+        REG_FPSCR
+    } else {
+        REG_PSP
+    }
+}
+
+// Single step flag of the first action of "vCont;action[:thread]..."
+fn vcont_single_step(actions: &[u8]) -> Option<bool> {
+    match actions.first()? {
+        b'c' | b'C' => Some(false),
+        b's' | b'S' => Some(true),
+        _ => None,
+    }
+}
+
+fn push_hex_u32(out: &mut String, value: u32) {
+    for byte in &value.to_le_bytes() {
+        out.push_str(&format!("{:02x}", byte));
+    }
+}
+
+fn decode_hex(text: &[u8]) -> Option<Vec<u8>> {
+    if !text.len().is_multiple_of(2) {
+        return None;
+    }
+    text.chunks(2)
+        .map(|pair| {
+            std::str::from_utf8(pair)
+                .ok()
+                .and_then(|digits| u8::from_str_radix(digits, 16).ok())
+        })
+        .collect()
+}
+
+fn parse_hex(text: &[u8]) -> Option<u32> {
+    std::str::from_utf8(text)
+        .ok()
+        .and_then(|digits| u32::from_str_radix(digits, 16).ok())
+}
+
+fn le_u32(bytes: &[u8]) -> u32 {
+    bytes
+        .iter()
+        .take(4)
+        .enumerate()
+        .fold(0, |value, (i, byte)| value | u32::from(*byte) << (8 * i))
+}
+
+// Split "addr,length" style arguments.
+fn parse_address_length(args: &[u8]) -> Option<(u32, usize)> {
+    let mut parts = args.splitn(2, |c| *c == b',');
+    let address = parse_hex(parts.next()?)?;
+    let length = parse_hex(parts.next()?)?;
+    Some((address, length as usize))
+}
+
+fn register_size(n: usize) -> usize {
+    if (REG_D0..REG_FPSCR).contains(&n) {
+        8
+    } else {
+        4
+    }
+}
+
+fn read_register(processor: &mut Processor, n: usize, out: &mut String) -> bool {
+    if n > last_register(processor) {
+        return false;
+    }
+    match n {
+        0..=12 => push_hex_u32(out, processor.r0_12[n]),
+        13 => push_hex_u32(out, processor.get_r(Reg::SP)),
+        14 => push_hex_u32(out, processor.lr),
+        15 => push_hex_u32(out, processor.get_pc()),
+        REG_XPSR => push_hex_u32(out, processor.psr.value),
+        REG_MSP => push_hex_u32(out, processor.get_msp()),
+        REG_PSP => push_hex_u32(out, processor.get_psp()),
+        REG_D0..=34 => {
+            let index = (n - REG_D0) * 2;
+            push_hex_u32(out, processor.fp_regs[index]);
+            push_hex_u32(out, processor.fp_regs[index + 1]);
+        }
+        REG_FPSCR => push_hex_u32(out, processor.fpscr),
+        _ => return false,
+    }
+    true
+}
+
+fn write_register(processor: &mut Processor, n: usize, bytes: &[u8]) -> bool {
+    if n > last_register(processor) || bytes.len() != register_size(n) {
+        return false;
+    }
+    let value = le_u32(bytes);
+    match n {
+        0..=12 => processor.r0_12[n] = value,
+        13 => processor.set_r(Reg::SP, value),
+        14 => processor.lr = value,
+        15 => processor.branch_write_pc(value),
+        REG_XPSR => processor.psr.value = value,
+        REG_MSP => processor.set_msp(value),
+        REG_PSP => processor.set_psp(value),
+        REG_D0..=34 => {
+            let index = (n - REG_D0) * 2;
+            processor.fp_regs[index] = value;
+            processor.fp_regs[index + 1] = le_u32(&bytes[4..]);
+        }
+        REG_FPSCR => processor.fpscr = value & FPSCR_WRITE_MASK,
+        _ => return false,
+    }
+    true
+}
+
+//
+// Program a free FPB code comparator with a breakpoint at `address`,
+// the way a hardware debug probe would.
+//
+fn insert_hw_breakpoint(processor: &mut Processor, address: u32) -> bool {
+    let ctrl = processor.read_fp_ctrl();
+    let num_code = (ctrl.get_bits(12..15) << 4 | ctrl.get_bits(4..8)) as usize;
+    let revision2 = ctrl.get_bits(28..32) == 1;
+
+    let (word, replace) = if revision2 {
+        (address & 0xffff_fffe, 0)
+    } else if address < 0x2000_0000 {
+        let replace = if address & 2 == 0 { 0b01 } else { 0b10 };
+        (address & 0x1fff_fffc, replace)
+    } else {
+        return false;
+    };
+
+    let mut free = None;
+    for n in 0..num_code {
+        let comp = processor.read_fp_comp(n);
+        if comp & 1 == 0 {
+            free = free.or(Some(n));
+        } else if !revision2 && comp.get_bits(30..32) != 0 && comp & 0x1fff_fffc == word {
+            // other halfword of the same word is already a breakpoint
+            processor.write_fp_comp(n, comp | replace << 30);
+            return true;
+        }
+    }
+
+    match free {
+        Some(n) => {
+            processor.write_fp_comp(n, word | replace << 30 | 1);
+            // FP_CTRL: KEY | ENABLE
+            processor.write_fp_ctrl(0b11);
+            true
+        }
+        None => false,
+    }
+}
+
+fn remove_hw_breakpoint(processor: &mut Processor, address: u32) {
+    let ctrl = processor.read_fp_ctrl();
+    let num_code = (ctrl.get_bits(12..15) << 4 | ctrl.get_bits(4..8)) as usize;
+    let revision2 = ctrl.get_bits(28..32) == 1;
+
+    for n in 0..num_code {
+        let comp = processor.read_fp_comp(n);
+        if comp & 1 == 0 {
+            continue;
+        }
+        if revision2 {
+            if comp & 0xffff_fffe == address & 0xffff_fffe {
+                processor.write_fp_comp(n, 0);
+            }
+        } else if comp.get_bits(30..32) != 0 && comp & 0x1fff_fffc == address & 0x1fff_fffc {
+            let replace = comp.get_bits(30..32) & !(if address & 2 == 0 { 0b01 } else { 0b10 });
+            let value = if replace == 0 {
+                0
+            } else {
+                comp & 0x3fff_ffff | replace << 30
+            };
+            processor.write_fp_comp(n, value);
+        }
+    }
+}
+
+fn stop_reply(reason: &StopReason) -> String {
+    match reason {
+        StopReason::Trap => format!("S{:02x}", SIGTRAP),
+        StopReason::Interrupted => format!("S{:02x}", SIGINT),
+        StopReason::Fault => format!("T{:02x}", SIGSEGV),
+        StopReason::Exited(status) => format!("W{:02x}", status),
+    }
+}
+
+struct GdbServer {
+    connection: Connection,
+    breakpoints: HashSet<u32>,
+}
+
+impl GdbServer {
+    //
+    // Leave debug state and run the processor until it halts again, gdb
+    // requests a stop or the simulation terminates. With `single_step` the
+    // core halts after executing one instruction.
+    //
+    fn resume(&mut self, processor: &mut Processor, single_step: bool) -> io::Result<StopReason> {
+        let step = if single_step { DHCSR_C_STEP } else { 0 };
+        processor.dap_write_dhcsr(DHCSR_DBGKEY | DHCSR_C_DEBUGEN | step);
+
+        let mut first = true;
+        let mut count: u64 = 0;
+
+        loop {
+            if processor.locked_up() {
+                return Ok(StopReason::Fault);
+            }
+            if processor.state & 1 == 0 {
+                // the exit code is limited to 8 bits
+                return Ok(match processor.exit_status {
+                    Some(status) => StopReason::Exited(status.code() as u8),
+                    None => StopReason::Fault,
+                });
+            }
+            if processor.debug_halted() {
+                return Ok(StopReason::Trap);
+            }
+
+            if processor.state == 0b11 {
+                //running, sleeping
+                processor.step_sleep();
+            } else {
+                let pc = processor.get_pc();
+                if !first && self.breakpoints.contains(&pc) {
+                    processor.debug_halt(DFSR_BKPT);
+                    return Ok(StopReason::Trap);
+                }
+                processor.last_pc = pc;
+                if first && processor.fpb_match_fetch(pc) == FpbMatch::Breakpoint {
+                    // step over the hardware breakpoint the core is halted on
+                    let ctrl = processor.read_fp_ctrl();
+                    processor.write_fp_ctrl(0b10);
+                    processor.step();
+                    processor.write_fp_ctrl(ctrl | 0b10);
+                } else {
+                    processor.step();
+                }
+            }
+            first = false;
+
+            count += 1;
+            if count.is_multiple_of(INTERRUPT_POLL_INTERVAL) && self.connection.poll_interrupt()? {
+                processor.dap_write_dhcsr(DHCSR_DBGKEY | DHCSR_C_DEBUGEN | DHCSR_C_HALT);
+                return Ok(StopReason::Interrupted);
+            }
+        }
+    }
+
+    // Word aligned words are accessed with 32 bit transfers so that
+    // memory mapped registers can be inspected and modified.
+    fn read_memory(processor: &mut Processor, args: &[u8]) -> String {
+        let (address, length) = match parse_address_length(args) {
+            Some(range) => range,
+            None => return "E01".to_string(),
+        };
+        let length = length.min(MAX_MEMORY_BLOCK);
+
+        let mut reply = String::new();
+        let mut offset = 0;
+        while offset < length {
+            let current = address.wrapping_add(offset as u32);
+            if current.is_multiple_of(4) && length - offset >= 4 {
+                match processor.read32(current) {
+                    Ok(word) => push_hex_u32(&mut reply, word),
+                    Err(_) => break,
+                }
+                offset += 4;
+            } else {
+                match processor.read8(current) {
+                    Ok(byte) => reply.push_str(&format!("{:02x}", byte)),
+                    Err(_) => break,
+                }
+                offset += 1;
+            }
+        }
+        if reply.is_empty() && length > 0 {
+            return "E14".to_string();
+        }
+        reply
+    }
+
+    fn write_memory(processor: &mut Processor, address: u32, data: &[u8]) -> String {
+        let mut offset = 0;
+        while offset < data.len() {
+            let current = address.wrapping_add(offset as u32);
+            let result = if current.is_multiple_of(4) && data.len() - offset >= 4 {
+                offset += 4;
+                processor.write32(current, le_u32(&data[offset - 4..offset]))
+            } else {
+                offset += 1;
+                processor.write8(current, data[offset - 1])
+            };
+            if result.is_err() {
+                return "E14".to_string();
+            }
+        }
+        "OK".to_string()
+    }
+
+    fn breakpoint(&mut self, processor: &mut Processor, insert: bool, args: &[u8]) -> String {
+        let mut parts = args.split(|c| *c == b',');
+        let kind = parts.next();
+        let address = parts.next().and_then(parse_hex);
+
+        match (kind, address) {
+            // software breakpoints never modify the code in memory
+            (Some(b"0"), Some(address)) => {
+                if insert {
+                    self.breakpoints.insert(address & 0xffff_fffe);
+                } else {
+                    self.breakpoints.remove(&(address & 0xffff_fffe));
+                }
+                "OK".to_string()
+            }
+            // hardware breakpoints use the FPB comparators
+            (Some(b"1"), Some(address)) => {
+                if !insert {
+                    remove_hw_breakpoint(processor, address);
+                    "OK".to_string()
+                } else if insert_hw_breakpoint(processor, address) {
+                    "OK".to_string()
+                } else {
+                    "E01".to_string()
+                }
+            }
+            // watchpoints are not supported
+            _ => String::new(),
+        }
+    }
+
+    fn query(processor: &mut Processor, packet: &[u8]) -> String {
+        if packet.starts_with(b"qSupported") {
+            format!(
+                "PacketSize={:x};qXfer:features:read+",
+                MAX_MEMORY_BLOCK * 2 + 16
+            )
+        } else if packet.starts_with(b"qXfer:features:read:target.xml:") {
+            let args = &packet[b"qXfer:features:read:target.xml:".len()..];
+            match parse_address_length(args) {
+                Some((offset, length)) => {
+                    let xml = target_xml(processor);
+                    let offset = (offset as usize).min(xml.len());
+                    let end = offset.saturating_add(length).min(xml.len());
+                    let marker = if end == xml.len() { 'l' } else { 'm' };
+                    format!("{}{}", marker, &xml[offset..end])
+                }
+                None => "E01".to_string(),
+            }
+        } else if packet == b"qAttached" {
+            "1".to_string()
+        } else if packet == b"qfThreadInfo" {
+            "m1".to_string()
+        } else if packet == b"qsThreadInfo" {
+            "l".to_string()
+        } else if packet == b"qC" {
+            "QC1".to_string()
+        } else if packet.starts_with(b"qRcmd,") {
+            match decode_hex(&packet[b"qRcmd,".len()..]) {
+                Some(ref command) if command == b"reset" => match processor.reset() {
+                    Ok(_) => "OK".to_string(),
+                    Err(_) => "E01".to_string(),
+                },
+                _ => "E01".to_string(),
+            }
+        } else {
+            String::new()
+        }
+    }
+
+    fn serve(&mut self, processor: &mut Processor) -> io::Result<()> {
+        loop {
+            let packet = match self.connection.read_packet()? {
+                Packet::Command(packet) => packet,
+                // already halted
+                Packet::Interrupt => continue,
+            };
+            if packet.is_empty() {
+                self.connection.write_packet("")?;
+                continue;
+            }
+            let args = &packet[1..];
+
+            let reply = match packet[0] {
+                b'?' if processor.locked_up() => stop_reply(&StopReason::Fault),
+                b'?' => stop_reply(&StopReason::Trap),
+                b'g' => {
+                    let mut reply = String::new();
+                    for n in 0..=last_register(processor) {
+                        read_register(processor, n, &mut reply);
+                    }
+                    reply
+                }
+                b'G' => match decode_hex(args) {
+                    Some(bytes) => {
+                        let mut offset = 0;
+                        for n in 0..=last_register(processor) {
+                            let size = register_size(n);
+                            if offset + size > bytes.len() {
+                                break;
+                            }
+                            write_register(processor, n, &bytes[offset..offset + size]);
+                            offset += size;
+                        }
+                        "OK".to_string()
+                    }
+                    None => "E01".to_string(),
+                },
+                b'p' => {
+                    let mut reply = String::new();
+                    match parse_hex(args) {
+                        Some(n) if read_register(processor, n as usize, &mut reply) => reply,
+                        _ => "E01".to_string(),
+                    }
+                }
+                b'P' => {
+                    let mut parts = args.splitn(2, |c| *c == b'=');
+                    let n = parts.next().and_then(parse_hex);
+                    let bytes = parts.next().and_then(decode_hex);
+                    match (n, bytes) {
+                        (Some(n), Some(bytes)) if write_register(processor, n as usize, &bytes) => {
+                            "OK".to_string()
+                        }
+                        _ => "E01".to_string(),
+                    }
+                }
+                b'm' => Self::read_memory(processor, args),
+                b'M' | b'X' => {
+                    let mut parts = args.splitn(2, |c| *c == b':');
+                    let range = parts.next().and_then(parse_address_length);
+                    let data = parts.next().and_then(|data| {
+                        if packet[0] == b'M' {
+                            decode_hex(data)
+                        } else {
+                            Some(data.to_vec())
+                        }
+                    });
+                    match (range, data) {
+                        (Some((address, length)), Some(data)) if data.len() == length => {
+                            Self::write_memory(processor, address, &data)
+                        }
+                        _ => "E01".to_string(),
+                    }
+                }
+                b'Z' => self.breakpoint(processor, true, args),
+                b'z' => self.breakpoint(processor, false, args),
+                b'c' | b's' => {
+                    if let Some(address) = parse_hex(args) {
+                        processor.branch_write_pc(address);
+                    }
+                    stop_reply(&self.resume(processor, packet[0] == b's')?)
+                }
+                b'v' if packet == b"vCont?" => "vCont;c;C;s;S".to_string(),
+                b'v' if packet.starts_with(b"vCont;") => {
+                    match vcont_single_step(&packet[b"vCont;".len()..]) {
+                        Some(single_step) => stop_reply(&self.resume(processor, single_step)?),
+                        None => "E01".to_string(),
+                    }
+                }
+                b'H' => "OK".to_string(),
+                b'T' => "OK".to_string(),
+                b'q' => Self::query(processor, &packet),
+                b'D' => {
+                    self.connection.write_packet("OK")?;
+                    info!("GDB detached, continuing simulation");
+                    processor.dap_write_dhcsr(DHCSR_DBGKEY);
+                    while processor.state & 1 == 1 {
+                        if processor.state == 0b11 {
+                            processor.step_sleep();
+                        } else {
+                            processor.step();
+                        }
+                    }
+                    return Ok(());
+                }
+                b'k' => return Ok(()),
+                b'v' if packet.starts_with(b"vKill") => {
+                    self.connection.write_packet("OK")?;
+                    return Ok(());
+                }
+                _ => String::new(),
+            };
+
+            self.connection.write_packet(&reply)?;
+            // the program has exited, the exit was reported above
+            if processor.exit_status.is_some() && processor.state & 1 == 0 {
+                return Ok(());
+            }
+        }
+    }
+}
+
+///
+/// Wait for gdb to connect on the given local port and serve debug requests
+/// until the debugger kills the target or the simulation terminates.
+///
+pub fn run_gdb_server(processor: &mut Processor, port: u16) -> Result<SimulationStatistics> {
+    let listener =
+        TcpListener::bind(("127.0.0.1", port)).chain_err(|| "unable to open gdb server port")?;
+    info!("Waiting for GDB connection on port {}", port);
+
+    let (stream, peer) = listener
+        .accept()
+        .chain_err(|| "failed to accept gdb connection")?;
+    info!("GDB connected from {}", peer);
+    stream
+        .set_nodelay(true)
+        .chain_err(|| "failed to configure gdb connection")?;
+
+    let mut server = GdbServer {
+        connection: Connection { stream },
+        breakpoints: HashSet::new(),
+    };
+
+    let start = Instant::now();
+    processor.state.set_bit(0, true); // running
+    processor.dap_write_dhcsr(DHCSR_DBGKEY | DHCSR_C_DEBUGEN | DHCSR_C_HALT);
+    server
+        .serve(processor)
+        .chain_err(|| "gdb connection failed")?;
+    let end = Instant::now();
+
+    Ok(SimulationStatistics {
+        instruction_count: processor.instruction_count,
+        cycle_count: processor.cycle_count,
+        sleep_cycle_count: processor.sleep_cycle_count,
+        deep_sleep_cycle_count: processor.deep_sleep_cycle_count,
+        duration: end.duration_since(start),
+        exit_status: processor.exit_status,
+        lockup_count: processor.lockup_count,
+        lockup: if processor.locked_up() {
+            processor.lockup_snapshot
+        } else {
+            None
+        },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+    use zmu_cortex_m::core::cpu::Cpu;
+    use zmu_cortex_m::core::register::Epsr;
+    use zmu_cortex_m::semihosting::{ExitStatus, SysExceptionReason};
+
+    fn halted_processor(cpu: Cpu) -> Processor {
+        let mut processor = Processor::new();
+        processor.cpu(cpu);
+        processor.cache_instructions();
+        processor.reset().unwrap();
+        // the blank reset vector does not select thumb state
+        processor.psr.set_t(true);
+        processor.state = 1;
+        processor.dap_write_dhcsr(DHCSR_DBGKEY | DHCSR_C_DEBUGEN | DHCSR_C_HALT);
+        processor
+    }
+
+    // Data of the next reply packet, skipping the acknowledgements
+    fn read_reply(stream: &mut TcpStream) -> String {
+        let mut byte = [0_u8];
+        loop {
+            stream.read_exact(&mut byte).unwrap();
+            if byte[0] == b'$' {
+                break;
+            }
+        }
+        let mut raw = Vec::new();
+        loop {
+            stream.read_exact(&mut byte).unwrap();
+            if byte[0] == b'#' {
+                break;
+            }
+            raw.push(byte[0]);
+        }
+        let mut digits = [0_u8; 2];
+        stream.read_exact(&mut digits).unwrap();
+        String::from_utf8(decode_packet(&raw, &digits).unwrap()).unwrap()
+    }
+
+    // Serve the packets from a client connected over the loopback interface,
+    // returns the replies
+    fn exchange(processor: &mut Processor, packets: &[&str]) -> Vec<String> {
+        let listener = TcpListener::bind(("127.0.0.1", 0)).unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let packets: Vec<String> = packets.iter().map(|packet| encode_packet(packet)).collect();
+
+        let client = thread::spawn(move || {
+            let mut stream = TcpStream::connect(("127.0.0.1", port)).unwrap();
+            let mut replies = Vec::new();
+            for packet in packets {
+                stream.write_all(packet.as_bytes()).unwrap();
+                replies.push(read_reply(&mut stream));
+            }
+            // the server may already have closed the connection
+            let _ = stream.write_all(encode_packet("k").as_bytes());
+            replies
+        });
+
+        let (stream, _) = listener.accept().unwrap();
+        let mut server = GdbServer {
+            connection: Connection { stream },
+            breakpoints: HashSet::new(),
+        };
+        server.serve(processor).unwrap();
+        client.join().unwrap()
+    }
+
+    #[test]
+    fn test_packet_checksum() {
+        // Arrange & Act & Assert
+        assert_eq!(encode_packet("OK"), "$OK#9a");
+        assert_eq!(encode_packet(""), "$#00");
+        assert_eq!(decode_packet(b"OK", b"9a"), Some(b"OK".to_vec()));
+        assert_eq!(decode_packet(b"OK", b"9A"), Some(b"OK".to_vec()));
+        assert_eq!(decode_packet(b"OK", b"9b"), None);
+        assert_eq!(decode_packet(b"OK", b"zz"), None);
+        // "}]" is an escaped '}', the checksum covers the escaped form
+        assert_eq!(decode_packet(b"X}]", b"32"), Some(b"X}".to_vec()));
+    }
+
+    #[test]
+    fn test_memory_packets() {
+        // Arrange
+        let mut processor = halted_processor(Cpu::CortexM4);
+
+        // Act
+        let replies = exchange(
+            &mut processor,
+            &[
+                "M20000000,4:78563412",
+                "m20000000,4",
+                "m20000001,2",
+                "M20000000,2:11",
+                "mzz",
+            ],
+        );
+
+        // Assert
+        assert_eq!(replies, ["OK", "78563412", "5634", "E01", "E01"]);
+        assert_eq!(processor.read32(0x2000_0000).unwrap(), 0x1234_5678);
+    }
+
+    #[test]
+    fn test_hardware_breakpoint_packets() {
+        // Arrange
+        let mut processor = halted_processor(Cpu::CortexM4);
+
+        // Act
+        let replies = exchange(&mut processor, &["Z1,100,2"]);
+
+        // Assert
+        assert_eq!(replies, ["OK"]);
+        assert_eq!(processor.fpb_match_fetch(0x100), FpbMatch::Breakpoint);
+        assert_eq!(processor.fpb_match_fetch(0x102), FpbMatch::None);
+
+        // Act
+        let replies = exchange(&mut processor, &["z1,100,2"]);
+
+        // Assert
+        assert_eq!(replies, ["OK"]);
+        assert_eq!(processor.fpb_match_fetch(0x100), FpbMatch::None);
+    }
+
+    #[test]
+    fn test_vcont() {
+        // Arrange
+        let mut processor = halted_processor(Cpu::CortexM4);
+        let pc = processor.get_pc();
+
+        // Act
+        let replies = exchange(&mut processor, &["vCont?", "vCont;s:1", "vCont;x"]);
+
+        // Assert
+        assert_eq!(replies, ["vCont;c;C;s;S", "S05", "E01"]);
+        assert_eq!(processor.get_pc(), pc + 2);
+        assert_eq!(processor.instruction_count, 1);
+    }
+
+    #[test]
+    fn test_exit_status() {
+        // Arrange
+        let mut processor = halted_processor(Cpu::CortexM4);
+        processor.state = 0;
+        processor.exit_status = Some(ExitStatus {
+            reason: SysExceptionReason::ADPStoppedApplicationExit,
+            subcode: 3,
+        });
+
+        // Act
+        let replies = exchange(&mut processor, &["c"]);
+
+        // Assert
+        assert_eq!(replies, ["W03"]);
+        assert_eq!(stop_reply(&StopReason::Trap), "S05");
+    }
+
+    #[test]
+    fn test_exit_status_truncated() {
+        // Arrange
+        let mut processor = halted_processor(Cpu::CortexM4);
+        processor.state = 0;
+        processor.exit_status = Some(ExitStatus {
+            reason: SysExceptionReason::ADPStoppedApplicationExit,
+            subcode: 256,
+        });
+
+        // Act
+        let replies = exchange(&mut processor, &["c"]);
+
+        // Assert
+        assert_eq!(replies, ["W01"]);
+    }
+
+    #[test]
+    fn test_lockup_stop_reply() {
+        // Arrange
+        let mut code = [0; 0x100];
+        code[0..4].copy_from_slice(&0x2000_0400_u32.to_le_bytes());
+        code[4..8].copy_from_slice(&0x0000_0081_u32.to_le_bytes()); // reset
+        code[12..16].copy_from_slice(&0x0000_0081_u32.to_le_bytes()); // HardFault
+        code[0x80..0x82].copy_from_slice(&0xde00_u16.to_le_bytes()); // udf
+        let mut processor = Processor::new();
+        processor.cpu(Cpu::CortexM4);
+        processor.flash_memory(code.len(), &code);
+        processor.cache_instructions();
+        processor.reset().unwrap();
+        processor.state = 1;
+        processor.dap_write_dhcsr(DHCSR_DBGKEY | DHCSR_C_DEBUGEN | DHCSR_C_HALT);
+
+        // Act
+        let replies = exchange(&mut processor, &["c", "?", "p0f", "p0d", "c"]);
+
+        // Assert
+        assert_eq!(replies, ["T0b", "T0b", "feffffef", "e0030020", "T0b"]);
+        assert!(processor.locked_up());
+        assert_eq!(processor.lockup_snapshot.unwrap().pc, 0x80);
+    }
+
+    #[test]
+    fn test_target_description() {
+        // Arrange
+        let mut m4f = halted_processor(Cpu::CortexM4F);
+        let mut m3 = halted_processor(Cpu::CortexM3);
+
+        // Act
+        let m4f_replies = exchange(&mut m4f, &["g", "p13"]);
+        let m3_replies = exchange(&mut m3, &["g", "p13", "P13=0000000000000000"]);
+
+        // Assert
+        assert!(target_xml(&m4f).contains("org.gnu.gdb.arm.vfp"));
+        assert!(!target_xml(&m3).contains("org.gnu.gdb.arm.vfp"));
+        assert!(target_xml(&m3).ends_with("</target>"));
+        // 19 core registers, 16 double and fpscr
+        assert_eq!(m4f_replies[0].len(), 19 * 8 + 16 * 16 + 8);
+        assert_eq!(m4f_replies[1].len(), 16);
+        assert_eq!(m3_replies, [&m3_replies[0][..], "E01", "E01"]);
+        assert_eq!(m3_replies[0].len(), 19 * 8);
+    }
+}
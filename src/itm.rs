@@ -0,0 +1,136 @@
+//!
+//! ITM stimulus port decoder
+//!
+//! Demultiplexes the instrumentation packets of an ITM trace stream
+//! and writes the payload bytes of each stimulus port to its own writer.
+//! The stream is expected to be in the SWO format, with the TPIU
+//! formatter bypassed.
+//!
+
+use std::collections::HashMap;
+use std::io;
+use std::io::prelude::*;
+
+enum State {
+    // expecting a packet header
+    Header,
+    // inside a synchronization packet
+    Sync,
+    // payload bytes of a source packet
+    Source {
+        port: u8,
+        instrumentation: bool,
+        remaining: usize,
+    },
+    // continuation bytes of a timestamp or extension packet
+    Continuation,
+}
+
+///
+/// Writer that decodes ITM packets and forwards the instrumentation
+/// payloads of the selected stimulus ports. Data for other ports,
+/// hardware source packets and protocol packets are discarded.
+///
+pub struct ItmDecoder {
+    ports: HashMap<u8, Box<dyn io::Write + 'static>>,
+    state: State,
+    payload: Vec<u8>,
+}
+
+impl ItmDecoder {
+    pub fn new() -> Self {
+        Self {
+            ports: HashMap::new(),
+            state: State::Header,
+            payload: Vec::with_capacity(4),
+        }
+    }
+
+    ///
+    /// Write decoded payload of stimulus `port` to `output`
+    ///
+    pub fn add_port(&mut self, port: u8, output: Box<dyn io::Write + 'static>) {
+        self.ports.insert(port, output);
+    }
+
+    fn decode_header(&mut self, header: u8) -> State {
+        match header & 0b11 {
+            0 if header == 0 => State::Sync,
+            // overflow, local timestamp, global timestamp and extension
+            // packets are followed by continuation bytes if bit 7 is set
+            0 if header & 0x80 != 0 => State::Continuation,
+            0 => State::Header,
+            size => {
+                self.payload.clear();
+                State::Source {
+                    port: header >> 3,
+                    instrumentation: header & 0b100 == 0,
+                    remaining: 1 << (size - 1),
+                }
+            }
+        }
+    }
+
+    fn output_payload(&mut self, port: u8) -> io::Result<()> {
+        if let Some(output) = self.ports.get_mut(&port) {
+            output.write_all(&self.payload)?;
+            output.flush()?;
+        }
+        Ok(())
+    }
+
+    fn decode(&mut self, byte: u8) -> io::Result<()> {
+        self.state = match self.state {
+            State::Header => self.decode_header(byte),
+            State::Sync => match byte {
+                0 => State::Sync,
+                0x80 => State::Header,
+                // not a valid synchronization, treat as start of next packet
+                _ => self.decode_header(byte),
+            },
+            State::Source {
+                port,
+                instrumentation,
+                remaining,
+            } => {
+                self.payload.push(byte);
+                if remaining > 1 {
+                    State::Source {
+                        port,
+                        instrumentation,
+                        remaining: remaining - 1,
+                    }
+                } else {
+                    if instrumentation {
+                        self.output_payload(port)?;
+                    }
+                    State::Header
+                }
+            }
+            State::Continuation => {
+                if byte & 0x80 != 0 {
+                    State::Continuation
+                } else {
+                    State::Header
+                }
+            }
+        };
+        Ok(())
+    }
+}
+
+impl Write for ItmDecoder {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        for byte in buf {
+            self.decode(*byte)?;
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        for output in self.ports.values_mut() {
+            output.flush()?;
+        }
+        Ok(())
+    }
+}
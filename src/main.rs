@@ -20,43 +20,238 @@ use goblin::Object;
 use std::fs::File;
 use std::io;
 use std::io::prelude::*;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::sync::mpsc::{channel, Receiver};
+use std::thread;
 use std::time::Instant;
 
+mod attributes;
+mod defmt;
+mod dwarf;
+mod gdb;
+mod itm;
 mod semihost;
+mod swo;
 mod trace;
 
+use crate::attributes::detect_cpu;
+use crate::defmt::{DefmtDecoder, DefmtTable, Transport};
+use crate::gdb::run_gdb_server;
+use crate::itm::ItmDecoder;
 use crate::semihost::get_semihost_func;
+use crate::swo::{SwoServer, Tee};
 use crate::trace::format_trace_entry;
 
 use std::cmp;
 use std::collections::HashMap;
 use tabwriter::TabWriter;
+use zmu_cortex_m::core::cpu::Cpu;
+use zmu_cortex_m::core::lockup::LockupPolicy;
 use zmu_cortex_m::memory::map::MemoryMapConfig;
+use zmu_cortex_m::rtt::Rtt;
+use zmu_cortex_m::semihosting::FAULT_TRAP_EXIT_CODE;
 use zmu_cortex_m::Processor;
 
 use zmu_cortex_m::system::simulation::simulate_trace;
-use zmu_cortex_m::system::simulation::{simulate, SimulationError};
+use zmu_cortex_m::system::simulation::{
+    make_processor, simulate, SimulationConfig, SimulationError,
+};
 
 mod errors {
     // Create the Error, ErrorKind, ResultExt, and Result types
-    error_chain! {}
+    error_chain! {
+        errors {
+            FaultTrap {
+                description("fault trap")
+                display("simulation stopped by a fault trap")
+            }
+            UndefinedInstruction(opcode: String, pc: u32, symbol: String) {
+                description("undefined instruction")
+                display("undefined instruction {} at 0x{:08x} ({})", opcode, pc, symbol)
+            }
+            Lockup(pc: u32, symbol: String, registers: String) {
+                description("processor lockup")
+                display("processor locked up at 0x{:08x} ({})\n{}", pc, symbol, registers)
+            }
+        }
+    }
 }
 
 use crate::errors::*;
-use error_chain::State;
 
 impl From<SimulationError> for errors::Error {
-    fn from(_error: SimulationError) -> Self {
-        errors::Error(ErrorKind::Msg("trap".to_string()), State::default())
+    fn from(error: SimulationError) -> Self {
+        match error {
+            SimulationError::FaultTrap => ErrorKind::FaultTrap.into(),
+            SimulationError::UndefinedInstruction { pc, opcode } => {
+                ErrorKind::UndefinedInstruction(opcode.to_string(), pc, "unknown".to_string())
+                    .into()
+            }
+        }
+    }
+}
+
+// process exit code for zmu errors, fault traps use the reserved
+// FAULT_TRAP_EXIT_CODE that the firmware exit status never maps to
+const ERROR_EXIT_CODE: i32 = 1;
+
+// core simulated when the executable does not tell its architecture
+const DEFAULT_CPU: Cpu = Cpu::CortexM4F;
+
+type ChannelOutput = (u32, Box<dyn io::Write + 'static>);
+
+fn find_symbol(elf: &goblin::elf::Elf, name: &str) -> Option<u32> {
+    elf.syms
+        .iter()
+        .find(|sym| elf.strtab.get(sym.st_name).and_then(|n| n.ok()) == Some(name))
+        .map(|sym| sym.st_value as u32)
+}
+
+fn symbol_at(elf: &goblin::elf::Elf, address: u32) -> Option<String> {
+    elf.syms
+        .iter()
+        .filter(|sym| sym.st_type() == goblin::elf::sym::STT_FUNC)
+        .find(|sym| {
+            let start = sym.st_value as u32 & 0xffff_fffe;
+            address >= start && u64::from(address - start) < sym.st_size
+        })
+        .and_then(|sym| elf.strtab.get(sym.st_name).and_then(|n| n.ok()))
+        .map(str::to_string)
+}
+
+// Undefined instructions are reported with the function they were found in
+fn simulation_error(elf: &goblin::elf::Elf, error: SimulationError) -> Error {
+    match error {
+        SimulationError::UndefinedInstruction { pc, opcode } => ErrorKind::UndefinedInstruction(
+            opcode.to_string(),
+            pc,
+            symbol_at(elf, pc).unwrap_or_else(|| "unknown".to_string()),
+        )
+        .into(),
+        _ => error.into(),
+    }
+}
+
+// Heap and stack parameters for SYS_HEAPINFO. Heap grows from the end of
+// the program data and the stack down from the initial stack pointer, both
+// share the memory in between. Zero values are unknown to the simulator.
+fn heap_info(elf: &goblin::elf::Elf, flash_mem: &[u8]) -> [u32; 4] {
+    let heap_base = ["__sheap", "__end__", "end", "_end"]
+        .iter()
+        .find_map(|name| find_symbol(elf, name))
+        .unwrap_or(0);
+    let stack_base = match flash_mem.get(0..4) {
+        Some(sp) => u32::from_le_bytes([sp[0], sp[1], sp[2], sp[3]]),
+        None => 0,
+    };
+    [heap_base, stack_base, stack_base, heap_base]
+}
+
+// Command line seen by the firmware, arguments with spaces are quoted
+fn semihost_cmdline<'a>(executable: &str, args: impl Iterator<Item = &'a str>) -> String {
+    let mut cmdline = executable.to_string();
+    for arg in args {
+        cmdline.push(' ');
+        if arg.contains(char::is_whitespace) {
+            cmdline.push_str(&format!("\"{}\"", arg));
+        } else {
+            cmdline.push_str(arg);
+        }
+    }
+    cmdline
+}
+
+// Load the defmt interned strings, if the executable uses defmt
+fn load_defmt_table(buffer: &[u8]) -> Option<Rc<DefmtTable>> {
+    match Object::parse(buffer) {
+        Ok(Object::Elf(elf)) => DefmtTable::parse(&elf, buffer).map(Rc::new),
+        _ => None,
+    }
+}
+
+// Output to stdout, with defmt log frames decoded when the table is given
+fn stdout_output(defmt: Option<&Rc<DefmtTable>>) -> Box<dyn io::Write + 'static> {
+    match defmt {
+        Some(table) => Box::new(DefmtDecoder::new(table.clone(), Box::new(io::stdout()))),
+        None => Box::new(io::stdout()),
     }
 }
 
-fn run_bin(
-    buffer: &[u8],
+// Forward stdin to the simulation without blocking it
+fn spawn_stdin_reader() -> Receiver<u8> {
+    let (sender, receiver) = channel();
+    thread::spawn(move || {
+        let mut buffer = [0; 256];
+        while let Ok(count) = io::stdin().read(&mut buffer) {
+            if count == 0 || buffer[..count].iter().any(|b| sender.send(*b).is_err()) {
+                break;
+            }
+        }
+    });
+    receiver
+}
+
+fn configure_rtt(
+    elf: &goblin::elf::Elf,
+    rtt_channels: Vec<ChannelOutput>,
+    defmt: Option<&Rc<DefmtTable>>,
+) -> Option<Rtt> {
+    let control_block = match find_symbol(elf, "_SEGGER_RTT") {
+        Some(address) => address,
+        None => {
+            if !rtt_channels.is_empty() {
+                warn!("RTT channels requested, but no _SEGGER_RTT symbol found");
+            }
+            return None;
+        }
+    };
+    info!("RTT control block at 0x{:08x}", control_block);
+
+    let mut rtt = Rtt::new(control_block);
+    if rtt_channels.is_empty() {
+        rtt.up_channel(0, stdout_output(defmt));
+    }
+    for (channel, output) in rtt_channels {
+        rtt.up_channel(channel, output);
+    }
+    // semihosting reads stdin as well, so take it only for targets with a down-buffer
+    rtt.down_channel(Box::new(spawn_stdin_reader));
+    Some(rtt)
+}
+
+// Options of the run command
+struct RunOptions {
     trace: bool,
-    option_trace_start: Option<u64>,
+    trace_start: Option<u64>,
     itm_file: Option<Box<dyn io::Write + 'static>>,
-) -> Result<()> {
+    rtt_channels: Vec<ChannelOutput>,
+    defmt: Option<Rc<DefmtTable>>,
+    semihost_root: Option<PathBuf>,
+    semihost_cmdline: String,
+    gdb_port: Option<u16>,
+    strict_decode: bool,
+    lockup_policy: LockupPolicy,
+    fpb_revision: u32,
+    // detected from the executable if not given
+    cpu: Option<Cpu>,
+}
+
+fn run_bin(buffer: &[u8], options: RunOptions) -> Result<i32> {
+    let RunOptions {
+        trace,
+        trace_start,
+        itm_file,
+        rtt_channels,
+        defmt,
+        semihost_root,
+        semihost_cmdline,
+        gdb_port,
+        strict_decode,
+        lockup_policy,
+        fpb_revision,
+        cpu,
+    } = options;
     let res = Object::parse(buffer).unwrap();
 
     let elf = match res {
@@ -68,6 +263,16 @@ fn run_bin(
 
     debug!("Detected ELF file.");
 
+    // the core is selected from the build attributes unless given
+    let cpu = match cpu.or_else(|| detect_cpu(&elf, buffer)) {
+        Some(cpu) => cpu,
+        None => {
+            warn!("Unable to detect the cpu, using {}", DEFAULT_CPU);
+            DEFAULT_CPU
+        }
+    };
+    info!("Simulating {}", cpu);
+
     // auto detection of required flash size:
     // loop 1: determine lower bound and upper bound
 
@@ -95,7 +300,7 @@ fn run_bin(
     }
 
     let flash_start_address = min_address as u32;
-    let flash_size = (max_address - min_address) as usize;
+    let flash_size = max_address - min_address;
     info!(
         "Auto configuring flash: address space is 0x{:x}..0x{:x}, size= {} bytes",
         flash_start_address, max_address, flash_size
@@ -116,16 +321,50 @@ fn run_bin(
         }
     }
 
-    let trace_start = option_trace_start.unwrap_or(0);
-    let semihost_func = Box::new(get_semihost_func(Instant::now()));
+    let trace_start = trace_start.unwrap_or(0);
+    let defmt_transport = |transport| {
+        defmt
+            .as_ref()
+            .filter(|table| table.transport == Some(transport))
+    };
+    let rtt = configure_rtt(&elf, rtt_channels, defmt_transport(Transport::Rtt));
+    let semihost_func = Box::new(get_semihost_func(
+        Instant::now(),
+        stdout_output(defmt_transport(Transport::Semihosting)),
+        semihost_root,
+        semihost_cmdline,
+        heap_info(&elf, &flash_mem),
+    ));
+    let config = SimulationConfig {
+        map: if flash_start_address != 0 {
+            Some(MemoryMapConfig::new(flash_start_address, 0, flash_size))
+        } else {
+            None
+        },
+        semihost_func: Some(semihost_func),
+        itm_file,
+        rtt,
+        strict_decode,
+        lockup_policy,
+        fpb_revision,
+        ..SimulationConfig::new(cpu, flash_size)
+    };
 
-    let statistics = if trace {
+    let statistics = if let Some(port) = gdb_port {
+        debug!("Starting simulation under gdb.");
+        let config = SimulationConfig {
+            strict_decode: false,
+            ..config
+        };
+        let mut processor = make_processor(&flash_mem, config)?;
+        run_gdb_server(&mut processor, port)?
+    } else if trace {
         debug!("Configuring tracing.");
 
         let mut symboltable = HashMap::new();
         let mut trace_stdout = TabWriter::new(io::stdout()).minwidth(16).padding(1);
 
-        for sym in elf.syms {
+        for sym in elf.syms.iter() {
             if sym.st_type() != goblin::elf::sym::STT_FILE {
                 if let Some(maybe_name) = elf.strtab.get(sym.st_name) {
                     let name = maybe_name.unwrap_or("unknown");
@@ -150,31 +389,11 @@ fn run_bin(
         };
         debug!("Starting simulation with trace.");
 
-        simulate_trace(
-            &flash_mem,
-            tracefunc,
-            semihost_func,
-            itm_file,
-            if flash_start_address != 0 {
-                Some(MemoryMapConfig::new(flash_start_address, 0, flash_size))
-            } else {
-                None
-            },
-            flash_size,
-        )?
+        simulate_trace(&flash_mem, tracefunc, config)
+            .map_err(|error| simulation_error(&elf, error))?
     } else {
         debug!("Starting simulation.");
-        simulate(
-            &flash_mem,
-            semihost_func,
-            itm_file,
-            if flash_start_address != 0 {
-                Some(MemoryMapConfig::new(flash_start_address, 0, flash_size))
-            } else {
-                None
-            },
-            flash_size,
-        )?
+        simulate(&flash_mem, config).map_err(|error| simulation_error(&elf, error))?
     };
 
     let duration_in_secs = statistics.duration.as_secs() as f64
@@ -193,7 +412,37 @@ fn run_bin(
         cycles_per_sec,
         cycles_per_sec / 1_000_000.0,
     );
-    Ok(())
+
+    if let Some(lockup) = statistics.lockup {
+        bail!(ErrorKind::Lockup(
+            lockup.pc,
+            symbol_at(&elf, lockup.pc).unwrap_or_else(|| "unknown".to_string()),
+            lockup.to_string(),
+        ));
+    }
+    if statistics.sleep_cycle_count > 0 {
+        info!(
+            "Processor slept {} cycles, {} of them in deep sleep",
+            statistics.sleep_cycle_count, statistics.deep_sleep_cycle_count
+        );
+    }
+    if statistics.lockup_count > 0 {
+        info!(
+            "Processor locked up {} times and was reset",
+            statistics.lockup_count
+        );
+    }
+
+    Ok(match statistics.exit_status {
+        Some(exit_status) => {
+            info!(
+                "Program exited: {:?}, subcode {}",
+                exit_status.reason, exit_status.subcode
+            );
+            exit_status.code()
+        }
+        None => 0,
+    })
 }
 
 fn open_itm_file(filename: &str) -> Option<Box<dyn io::Write + 'static>> {
@@ -205,7 +454,47 @@ fn open_itm_file(filename: &str) -> Option<Box<dyn io::Write + 'static>> {
     }
 }
 
-fn run(args: &ArgMatches) -> Result<()> {
+// Parse "<n>[=file]" where n < count, output goes to stdout if no file is given.
+// defmt log frames on channel 0 are decoded when written to stdout.
+fn open_channel_output(
+    spec: &str,
+    count: u32,
+    kind: &str,
+    defmt: Option<&Rc<DefmtTable>>,
+) -> Result<ChannelOutput> {
+    let (channel, filename) = match spec.find('=') {
+        Some(index) => (&spec[..index], Some(&spec[index + 1..])),
+        None => (spec, None),
+    };
+    let channel = channel
+        .parse::<u32>()
+        .ok()
+        .filter(|channel| *channel < count)
+        .chain_err(|| format!("invalid {} '{}'", kind, spec))?;
+    let output: Box<dyn io::Write + 'static> = match filename {
+        Some(filename) => Box::new(
+            File::create(filename)
+                .chain_err(|| format!("unable to create {} file '{}'", kind, filename))?,
+        ),
+        None if channel == 0 => stdout_output(defmt),
+        None => Box::new(io::stdout()),
+    };
+    Ok((channel, output))
+}
+
+fn open_itm_decoder<'a>(
+    port_specs: impl Iterator<Item = &'a str>,
+    defmt: Option<&Rc<DefmtTable>>,
+) -> Result<ItmDecoder> {
+    let mut decoder = ItmDecoder::new();
+    for spec in port_specs {
+        let (port, output) = open_channel_output(spec, 32, "itm port", defmt)?;
+        decoder.add_port(port as u8, output);
+    }
+    Ok(decoder)
+}
+
+fn run(args: &ArgMatches) -> Result<i32> {
     match args.subcommand() {
         ("run", Some(run_matches)) => {
             let filename = run_matches
@@ -221,30 +510,113 @@ fn run(args: &ArgMatches) -> Result<()> {
                 None => None,
             };
 
-            let itm_output = match run_matches.value_of("itm") {
-                Some(filename) => open_itm_file(filename),
-                None => None,
-            };
-
             let buffer = {
                 let mut v = Vec::new();
-                let mut f = File::open(&filename).chain_err(|| "unable to open file")?;
+                let mut f = File::open(filename).chain_err(|| "unable to open file")?;
                 f.read_to_end(&mut v).chain_err(|| "failed to read file")?;
                 v
             };
 
-            run_bin(
-                &buffer,
-                run_matches.is_present("trace"),
+            let defmt = load_defmt_table(&buffer);
+            let defmt_transport = |transport| {
+                defmt
+                    .as_ref()
+                    .filter(|table| table.transport == Some(transport))
+            };
+
+            let itm_file = match run_matches.value_of("itm") {
+                Some(filename) => open_itm_file(filename),
+                None => None,
+            };
+
+            let swo_server = match run_matches.value_of("swo-tcp") {
+                Some(port) => Some(SwoServer::bind(
+                    port.parse::<u16>().chain_err(|| "invalid swo port")?,
+                )?),
+                None => None,
+            };
+
+            let itm_decoder = match run_matches.values_of("itm-port") {
+                Some(port_specs) => Some(open_itm_decoder(
+                    port_specs,
+                    defmt_transport(Transport::Itm),
+                )?),
+                None => None,
+            };
+
+            let mut itm_outputs: Vec<Box<dyn io::Write + 'static>> = Vec::new();
+            if let Some(file) = itm_file {
+                itm_outputs.push(file);
+            }
+            if let Some(server) = swo_server {
+                itm_outputs.push(Box::new(server));
+            }
+            if let Some(decoder) = itm_decoder {
+                itm_outputs.push(Box::new(decoder));
+            }
+            let itm_output = itm_outputs
+                .into_iter()
+                .reduce(|a, b| Box::new(Tee(a, b)) as Box<dyn io::Write + 'static>);
+
+            let mut rtt_channels = Vec::new();
+            if let Some(channel_specs) = run_matches.values_of("rtt-channel") {
+                for spec in channel_specs {
+                    rtt_channels.push(open_channel_output(
+                        spec,
+                        32,
+                        "rtt channel",
+                        defmt_transport(Transport::Rtt),
+                    )?);
+                }
+            }
+
+            // file names are checked against the canonical root, so that
+            // symbolic links can not be used to leave the sandbox
+            let semihost_root = match run_matches.value_of("semihost-root") {
+                Some(dir) => Some(
+                    Path::new(dir)
+                        .canonicalize()
+                        .chain_err(|| format!("invalid semihost root '{}'", dir))?,
+                ),
+                None => None,
+            };
+
+            let gdb_port = match run_matches.value_of("gdb") {
+                Some(port) => Some(port.parse::<u16>().chain_err(|| "invalid gdb port")?),
+                None => None,
+            };
+
+            let options = RunOptions {
+                trace: run_matches.is_present("trace"),
                 trace_start,
-                itm_output,
-            )?;
+                itm_file: itm_output,
+                rtt_channels,
+                defmt,
+                semihost_root,
+                semihost_cmdline: semihost_cmdline(
+                    filename,
+                    run_matches.values_of("ARGS").into_iter().flatten(),
+                ),
+                gdb_port,
+                strict_decode: run_matches.is_present("strict-decode"),
+                lockup_policy: match run_matches.value_of("lockup") {
+                    Some("reset") => LockupPolicy::Reset,
+                    _ => LockupPolicy::Stop,
+                },
+                fpb_revision: match run_matches.value_of("fpb-revision") {
+                    Some("2") => 2,
+                    _ => 1,
+                },
+                cpu: match run_matches.value_of("cpu") {
+                    Some(cpu) => Some(cpu.parse::<Cpu>()?),
+                    None => None,
+                },
+            };
+            run_bin(&buffer, options)
         }
         ("", None) => bail!("No sub command found"),
         _ => unreachable!(), // If all subcommands are defined above, anything else is unreachabe!()
     }
-
-    Ok(())
 }
 
 fn main() {
@@ -261,6 +633,7 @@ fn main() {
         .subcommand(
             SubCommand::with_name("run")
                 .about("Load and run <EXECUTABLE>")
+                .setting(AppSettings::TrailingVarArg)
                 .arg(
                     Arg::with_name("trace")
                         .short("t")
@@ -279,6 +652,85 @@ fn main() {
                         .help("Name of file to which itm trace data is written to. ")
                         .takes_value(true),
                 )
+                .arg(
+                    Arg::with_name("itm-port")
+                        .long("itm-port")
+                        .value_name("n[=file]")
+                        .help("Decode itm stimulus port <n> and write its data to stdout or <file>")
+                        .takes_value(true)
+                        .multiple(true)
+                        .number_of_values(1),
+                )
+                .arg(
+                    Arg::with_name("rtt-channel")
+                        .long("rtt-channel")
+                        .value_name("n[=file]")
+                        .help(
+                            "Write RTT up-channel <n> to stdout or <file>. Channel 0 is written to stdout by default",
+                        )
+                        .takes_value(true)
+                        .multiple(true)
+                        .number_of_values(1),
+                )
+                .arg(
+                    Arg::with_name("swo-tcp")
+                        .long("swo-tcp")
+                        .value_name("port")
+                        .help(
+                            "Serve the SWO trace output to clients connecting to local TCP <port>",
+                        )
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::with_name("semihost-root")
+                        .long("semihost-root")
+                        .value_name("dir")
+                        .help("Allow semihosting file access to files under <dir>")
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::with_name("gdb")
+                        .long("gdb")
+                        .value_name("port")
+                        .help("Halt at reset and wait for a GDB remote connection on <port>")
+                        .conflicts_with("trace")
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::with_name("strict-decode")
+                        .long("strict-decode")
+                        .help("Stop with an error on undefined instructions instead of raising a fault")
+                        .conflicts_with("gdb"),
+                )
+                .arg(
+                    Arg::with_name("lockup")
+                        .long("lockup")
+                        .value_name("policy")
+                        .help("Stop the simulation or reset the system when the processor locks up")
+                        .possible_values(&["stop", "reset"])
+                        .default_value("stop"),
+                )
+                .arg(
+                    Arg::with_name("fpb-revision")
+                        .long("fpb-revision")
+                        .value_name("revision")
+                        .help("Architecture revision of the Flash Patch and Breakpoint unit")
+                        .possible_values(&["1", "2"])
+                        .default_value("1"),
+                )
+                .arg(
+                    Arg::with_name("cpu")
+                        .long("cpu")
+                        .value_name("cpu")
+                        .help("Simulated core, detected from the executable if not given")
+                        .possible_values(&[
+                            "cortex-m0",
+                            "cortex-m0plus",
+                            "cortex-m3",
+                            "cortex-m4",
+                            "cortex-m4f",
+                        ]),
+                )
                 .arg(
                     Arg::with_name("EXECUTABLE")
                         .index(1)
@@ -290,7 +742,8 @@ fn main() {
                         .required(false)
                         .help("List of free arguments to pass to runtime as parameters")
                         .index(2)
-                        .multiple(true),
+                        .multiple(true)
+                        .allow_hyphen_values(true),
                 ),
         )
         .get_matches();
@@ -303,17 +756,26 @@ fn main() {
         .init()
         .unwrap();
 
-    if let Err(ref e) = run(&args) {
-        error!("error: {}", e);
+    match run(&args) {
+        Ok(0) => {}
+        Ok(code) => ::std::process::exit(code),
+        Err(ref e) => {
+            error!("error: {}", e);
 
-        for e in e.iter().skip(1) {
-            error!("caused by: {}", e);
-        }
+            for e in e.iter().skip(1) {
+                error!("caused by: {}", e);
+            }
 
-        if let Some(backtrace) = e.backtrace() {
-            error!("backtrace: {:?}", backtrace);
-        }
+            if let Some(backtrace) = e.backtrace() {
+                error!("backtrace: {:?}", backtrace);
+            }
 
-        ::std::process::exit(1);
+            ::std::process::exit(match e.kind() {
+                ErrorKind::FaultTrap
+                | ErrorKind::UndefinedInstruction(..)
+                | ErrorKind::Lockup(..) => FAULT_TRAP_EXIT_CODE,
+                _ => ERROR_EXIT_CODE,
+            });
+        }
     }
 }
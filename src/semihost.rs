@@ -1,7 +1,11 @@
 use std::cmp::min;
+use std::collections::HashMap;
+use std::fs::{self, File, OpenOptions};
 use std::io;
 use std::io::prelude::*;
-use std::time::Instant;
+use std::io::SeekFrom;
+use std::path::{Component, Path, PathBuf};
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
 
 use zmu_cortex_m::semihosting::{SemihostingCommand, SemihostingResponse, SysExceptionReason};
 
@@ -19,8 +23,213 @@ const SEMIHOST_FEATURES_HANDLE: u32 = 4;
 */
 static FEATURE_DATA: [u8; 5] = [0x53, 0x48, 0x46, 0x42, 3];
 
-pub fn get_semihost_func(start: Instant) -> impl FnMut(&SemihostingCommand) -> SemihostingResponse {
+// SYS_ELAPSED counts microseconds of host time
+const TICK_FREQUENCY: u32 = 1_000_000;
+
+// first handle given out for host files
+const FIRST_FILE_HANDLE: u32 = 5;
+
+// errno values reported for failures without a host error code
+const EINVAL: i32 = 22;
+const EBADF: i32 = 9;
+const EACCES: i32 = 13;
+const ENOSYS: i32 = 88; // newlib value, the target C library interprets errno
+
+///
+/// Host files opened by the target, confined to a sandbox directory
+///
+struct HostFiles {
+    root: Option<PathBuf>,
+    files: HashMap<u32, File>,
+    next_handle: u32,
+    errno: i32,
+}
+
+impl HostFiles {
+    fn new(root: Option<PathBuf>) -> Self {
+        Self {
+            root,
+            files: HashMap::new(),
+            next_handle: FIRST_FILE_HANDLE,
+            errno: 0,
+        }
+    }
+
+    // Record the error for SYS_ERRNO and return it as a host error code
+    fn error(&mut self, error: &io::Error) -> i32 {
+        self.errno = error.raw_os_error().unwrap_or(EINVAL);
+        self.errno
+    }
+
+    fn fail(&mut self, errno: i32) -> i32 {
+        self.errno = errno;
+        -1
+    }
+
+    // Map a target path to a host path inside the sandbox, absolute paths
+    // are relative to the sandbox root and ".." may not leave it
+    fn resolve(&self, name: &str) -> Option<PathBuf> {
+        let root = self.root.as_ref()?;
+        let mut relative = PathBuf::new();
+        for component in Path::new(name).components() {
+            match component {
+                Component::Normal(part) => relative.push(part),
+                Component::ParentDir => {
+                    if !relative.pop() {
+                        return None;
+                    }
+                }
+                Component::CurDir | Component::RootDir | Component::Prefix(_) => {}
+            }
+        }
+        if relative.as_os_str().is_empty() {
+            return None;
+        }
+
+        // symbolic links inside the sandbox may not point out of it
+        let path = root.join(&relative);
+        let parent = path.parent()?.canonicalize().ok()?;
+        if !parent.starts_with(root) {
+            return None;
+        }
+        match fs::symlink_metadata(&path) {
+            // a dangling link would be followed when creating the file
+            Ok(metadata) if metadata.file_type().is_symlink() => {
+                let target = path.canonicalize().ok()?;
+                if target.starts_with(root) {
+                    Some(path)
+                } else {
+                    None
+                }
+            }
+            _ => Some(path),
+        }
+    }
+
+    // Open a file with an ISO C fopen() mode, 0 = "r" .. 11 = "a+b"
+    fn open(&mut self, name: &str, mode: u32) -> Result<u32, i32> {
+        let path = match self.resolve(name) {
+            Some(path) => path,
+            None => return Err(self.fail(EACCES)),
+        };
+        let mut options = OpenOptions::new();
+        match mode >> 1 {
+            0 => options.read(true),
+            1 => options.read(true).write(true),
+            2 => options.write(true).create(true).truncate(true),
+            3 => options.read(true).write(true).create(true).truncate(true),
+            4 => options.append(true).create(true),
+            5 => options.read(true).append(true).create(true),
+            _ => return Err(self.fail(EINVAL)),
+        };
+        match options.open(&path) {
+            Ok(file) => {
+                let handle = self.next_handle;
+                self.next_handle += 1;
+                self.files.insert(handle, file);
+                Ok(handle)
+            }
+            Err(e) => {
+                self.error(&e);
+                Err(-1)
+            }
+        }
+    }
+
+    fn file(&mut self, handle: u32) -> Result<&mut File, i32> {
+        if !self.files.contains_key(&handle) {
+            return Err(self.fail(EBADF));
+        }
+        Ok(self.files.get_mut(&handle).unwrap())
+    }
+
+    fn close(&mut self, handle: u32) -> bool {
+        if self.files.remove(&handle).is_none() {
+            self.fail(EBADF);
+            return false;
+        }
+        true
+    }
+
+    fn read(&mut self, handle: u32, len: u32) -> Result<Vec<u8>, i32> {
+        let mut data = Vec::new();
+        let result = self.file(handle)?.take(u64::from(len)).read_to_end(&mut data);
+        match result {
+            Ok(_) => Ok(data),
+            Err(e) => {
+                self.error(&e);
+                Err(-1)
+            }
+        }
+    }
+
+    fn write(&mut self, handle: u32, data: &[u8]) -> Result<u32, i32> {
+        let result = self.file(handle).map_err(|_| data.len() as i32)?.write_all(data);
+        match result {
+            Ok(()) => Ok(0),
+            Err(e) => {
+                self.error(&e);
+                Err(data.len() as i32)
+            }
+        }
+    }
+
+    fn seek(&mut self, handle: u32, position: u32) -> bool {
+        let result = match self.file(handle) {
+            Ok(file) => file.seek(SeekFrom::Start(u64::from(position))),
+            Err(_) => return false,
+        };
+        match result {
+            Ok(_) => true,
+            Err(e) => {
+                self.error(&e);
+                false
+            }
+        }
+    }
+
+    fn len(&mut self, handle: u32) -> Result<u32, i32> {
+        let result = self.file(handle)?.metadata();
+        match result {
+            Ok(metadata) => Ok(metadata.len() as u32),
+            Err(e) => {
+                self.error(&e);
+                Err(-1)
+            }
+        }
+    }
+
+    fn remove(&mut self, name: &str) -> Result<(), i32> {
+        let path = self.resolve(name).ok_or_else(|| self.fail(EACCES))?;
+        fs::remove_file(path).map_err(|e| self.error(&e))
+    }
+
+    fn rename(&mut self, from: &str, to: &str) -> Result<(), i32> {
+        let from = self.resolve(from).ok_or_else(|| self.fail(EACCES))?;
+        let to = self.resolve(to).ok_or_else(|| self.fail(EACCES))?;
+        fs::rename(from, to).map_err(|e| self.error(&e))
+    }
+}
+
+// Debug events can be resumed, all other reasons terminate the program
+fn stops_execution(reason: &SysExceptionReason) -> bool {
+    !matches!(
+        reason,
+        SysExceptionReason::ADPStoppedBreakPoint
+            | SysExceptionReason::ADPStoppedWatchPoint
+            | SysExceptionReason::ADPStoppedStepComplete
+    )
+}
+
+pub fn get_semihost_func(
+    start: Instant,
+    mut stdout: Box<dyn io::Write + 'static>,
+    root: Option<PathBuf>,
+    cmdline: String,
+    heap_info: [u32; 4],
+) -> impl FnMut(&SemihostingCommand) -> SemihostingResponse {
     let mut semihost_features_position: u32 = 0;
+    let mut files = HostFiles::new(root);
 
     move |semihost_cmd: &SemihostingCommand| -> SemihostingResponse {
         match semihost_cmd {
@@ -46,7 +255,9 @@ pub fn get_semihost_func(start: Instant) -> impl FnMut(&SemihostingCommand) -> S
                         result: Ok(SEMIHOST_FEATURES_HANDLE),
                     }
                 } else {
-                    SemihostingResponse::SysOpen { result: Err(-1) }
+                    SemihostingResponse::SysOpen {
+                        result: files.open(name, *mode),
+                    }
                 }
             }
             SemihostingCommand::SysClose { handle } => {
@@ -55,7 +266,9 @@ pub fn get_semihost_func(start: Instant) -> impl FnMut(&SemihostingCommand) -> S
                     semihost_features_position = 0;
                 }
 
-                SemihostingResponse::SysClose { success: true }
+                SemihostingResponse::SysClose {
+                    success: *handle < FIRST_FILE_HANDLE || files.close(*handle),
+                }
             }
             SemihostingCommand::SysFlen { handle } => {
                 // println!("filelen for handle '{}'", handle);
@@ -65,7 +278,9 @@ pub fn get_semihost_func(start: Instant) -> impl FnMut(&SemihostingCommand) -> S
                 } else if *handle == SEMIHOST_FEATURES_HANDLE {
                     SemihostingResponse::SysFlen { result: Ok(5) }
                 } else {
-                    SemihostingResponse::SysFlen { result: Err(-1) }
+                    SemihostingResponse::SysFlen {
+                        result: files.len(*handle),
+                    }
                 }
             }
             SemihostingCommand::SysIstty { handle } => {
@@ -79,18 +294,24 @@ pub fn get_semihost_func(start: Instant) -> impl FnMut(&SemihostingCommand) -> S
                 } else if *handle == SEMIHOST_FEATURES_HANDLE {
                     SemihostingResponse::SysIstty { result: Ok(0) }
                 } else {
-                    SemihostingResponse::SysIstty { result: Err(-1) }
+                    SemihostingResponse::SysIstty {
+                        result: files.file(*handle).map(|_| 0),
+                    }
                 }
             }
             SemihostingCommand::SysWrite { handle, ref data } => {
                 // println!("write: handle={}, data={:?}", handle, data);
                 if *handle == TT_HANDLE_STDOUT {
-                    let text = &**data;
-                    print!("{}", String::from_utf8_lossy(text));
-                    io::stdout().flush().expect("Could not flush stdout");
+                    stdout.write_all(data).expect("Could not write stdout");
+                    stdout.flush().expect("Could not flush stdout");
+                    SemihostingResponse::SysWrite { result: Ok(0) }
+                } else if *handle == TT_HANDLE_STDERR {
+                    io::stderr().write_all(data).expect("Could not write stderr");
                     SemihostingResponse::SysWrite { result: Ok(0) }
                 } else {
-                    SemihostingResponse::SysWrite { result: Err(-1) }
+                    SemihostingResponse::SysWrite {
+                        result: files.write(*handle, data),
+                    }
                 }
             }
             SemihostingCommand::SysRead {
@@ -123,7 +344,11 @@ pub fn get_semihost_func(start: Instant) -> impl FnMut(&SemihostingCommand) -> S
                         result: Ok((*memoryptr, data, diff)),
                     }
                 } else {
-                    SemihostingResponse::SysRead { result: Err(-1) }
+                    SemihostingResponse::SysRead {
+                        result: files
+                            .read(*handle, *len)
+                            .map(|data| (*memoryptr, data.clone(), *len - data.len() as u32)),
+                    }
                 }
             }
             SemihostingCommand::SysSeek { handle, position } => {
@@ -137,10 +362,31 @@ pub fn get_semihost_func(start: Instant) -> impl FnMut(&SemihostingCommand) -> S
                         SemihostingResponse::SysSeek { success: false }
                     }
                 } else {
-                    SemihostingResponse::SysSeek { success: false }
+                    SemihostingResponse::SysSeek {
+                        success: files.seek(*handle, *position),
+                    }
+                }
+            }
+            SemihostingCommand::SysTmpnam { memoryptr, id, len } => {
+                let mut name = format!("tmp{:03}", id).into_bytes();
+                name.push(0);
+                if *id > 255 || name.len() > *len as usize {
+                    SemihostingResponse::SysTmpnam {
+                        result: Err(files.fail(EINVAL)),
+                    }
+                } else {
+                    SemihostingResponse::SysTmpnam {
+                        result: Ok((*memoryptr, name)),
+                    }
                 }
             }
-            SemihostingCommand::SysClock { .. } => {
+            SemihostingCommand::SysRemove { name } => SemihostingResponse::SysRemove {
+                result: files.remove(name),
+            },
+            SemihostingCommand::SysRename { from, to } => SemihostingResponse::SysRename {
+                result: files.rename(from, to),
+            },
+            SemihostingCommand::SysClock => {
                 // println!("sysclock");
                 let elapsed = start.elapsed();
                 let in_cs =
@@ -152,15 +398,10 @@ pub fn get_semihost_func(start: Instant) -> impl FnMut(&SemihostingCommand) -> S
             }
             SemihostingCommand::SysException { ref reason } => {
                 // println!("sysexception {:?}", reason);
-                let stop = match reason {
-                    SysExceptionReason::ADPStoppedApplicationExit
-                    | SysExceptionReason::ADPStopped => true,
-                    _ => false,
-                };
 
                 SemihostingResponse::SysException {
                     success: true,
-                    stop,
+                    stop: stops_execution(reason),
                 }
             }
             SemihostingCommand::SysExitExtended { ref reason, .. } => {
@@ -168,14 +409,198 @@ pub fn get_semihost_func(start: Instant) -> impl FnMut(&SemihostingCommand) -> S
 
                 SemihostingResponse::SysExitExtended {
                     success: true,
-                    stop: reason == &SysExceptionReason::ADPStoppedApplicationExit,
+                    stop: stops_execution(reason),
                 }
             }
-            SemihostingCommand::SysErrno { .. } => {
+            SemihostingCommand::SysErrno => {
                 // println!("syserrno");
 
-                SemihostingResponse::SysErrno { result: 0 }
+                SemihostingResponse::SysErrno {
+                    result: files.errno as u32,
+                }
+            }
+            SemihostingCommand::SysWritec { c } => {
+                stdout.write_all(&[*c]).expect("Could not write stdout");
+                stdout.flush().expect("Could not flush stdout");
+                SemihostingResponse::SysWritec
+            }
+            SemihostingCommand::SysWrite0 { ref data } => {
+                stdout.write_all(data).expect("Could not write stdout");
+                stdout.flush().expect("Could not flush stdout");
+                SemihostingResponse::SysWrite0
+            }
+            SemihostingCommand::SysReadc => {
+                let mut c = [0; 1];
+                match io::stdin().read(&mut c) {
+                    Ok(1) => SemihostingResponse::SysReadc {
+                        result: Ok(u32::from(c[0])),
+                    },
+                    _ => SemihostingResponse::SysReadc { result: Err(-1) },
+                }
+            }
+            SemihostingCommand::SysIserror { status } => SemihostingResponse::SysIserror {
+                result: ((*status as i32) < 0) as u32,
+            },
+            SemihostingCommand::SysTime => {
+                let seconds = SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .map(|d| d.as_secs())
+                    .unwrap_or(0);
+
+                SemihostingResponse::SysTime {
+                    result: Ok(seconds as u32),
+                }
+            }
+            SemihostingCommand::SysElapsed { memoryptr } => SemihostingResponse::SysElapsed {
+                result: Ok((*memoryptr, start.elapsed().as_micros() as u64)),
+            },
+            SemihostingCommand::SysTickfreq => SemihostingResponse::SysTickfreq {
+                result: Ok(TICK_FREQUENCY),
+            },
+            SemihostingCommand::SysHeapinfo { memoryptr } => SemihostingResponse::SysHeapinfo {
+                result: Ok((*memoryptr, heap_info)),
+            },
+            SemihostingCommand::SysGetCmdline { memoryptr, len } => {
+                let mut data = cmdline.clone().into_bytes();
+                data.push(0);
+                if data.len() > *len as usize {
+                    SemihostingResponse::SysGetCmdline { result: Err(-1) }
+                } else {
+                    SemihostingResponse::SysGetCmdline {
+                        result: Ok((*memoryptr, data)),
+                    }
+                }
+            }
+            SemihostingCommand::SysUnsupported { operation } => {
+                warn!("Unsupported semihosting operation 0x{:02x}", operation);
+                files.fail(ENOSYS);
+                SemihostingResponse::SysUnsupported
             }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+
+    // Fresh sandbox directory for a test, and a directory outside of it
+    fn sandbox(name: &str) -> (PathBuf, PathBuf) {
+        let base = env::temp_dir().join(format!("zmu-semihost-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&base);
+        fs::create_dir_all(base.join("root")).unwrap();
+        fs::create_dir_all(base.join("outside")).unwrap();
+        let base = base.canonicalize().unwrap();
+        (base.join("root"), base.join("outside"))
+    }
+
+    #[test]
+    fn test_resolve_parent_dir() {
+        // Arrange
+        let (root, _) = sandbox("parent");
+        fs::create_dir(root.join("a")).unwrap();
+        let files = HostFiles::new(Some(root.clone()));
+
+        // Act & Assert
+        assert_eq!(files.resolve("a/../b"), Some(root.join("b")));
+        assert_eq!(files.resolve("./a/b"), Some(root.join("a/b")));
+        assert_eq!(files.resolve(".."), None);
+        assert_eq!(files.resolve("../outside/x"), None);
+        assert_eq!(files.resolve("a/../../x"), None);
+        assert_eq!(files.resolve("a/.."), None);
+    }
+
+    #[test]
+    fn test_resolve_absolute() {
+        // Arrange
+        let (root, _) = sandbox("absolute");
+        fs::create_dir(root.join("etc")).unwrap();
+        let files = HostFiles::new(Some(root.clone()));
+
+        // Act & Assert
+        assert_eq!(files.resolve("/etc/passwd"), Some(root.join("etc/passwd")));
+        assert_eq!(files.resolve("/f"), Some(root.join("f")));
+        assert_eq!(files.resolve("/"), None);
+        assert_eq!(files.resolve("/missing/f"), None);
+    }
+
+    #[test]
+    fn test_resolve_without_root() {
+        let files = HostFiles::new(None);
+        assert_eq!(files.resolve("f"), None);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_resolve_symlink_escape() {
+        use std::os::unix::fs::symlink;
+
+        // Arrange
+        let (root, outside) = sandbox("symlink");
+        fs::write(root.join("inside"), b"data").unwrap();
+        symlink(outside.join("x"), root.join("dangling")).unwrap();
+        symlink(outside.join("."), root.join("dir")).unwrap();
+        symlink(root.join("inside"), root.join("link")).unwrap();
+        symlink(root.join("missing"), root.join("dangling_inside")).unwrap();
+        let mut files = HostFiles::new(Some(root.clone()));
+
+        // Act & Assert
+        assert_eq!(files.resolve("dangling"), None);
+        assert_eq!(files.resolve("dir/x"), None);
+        assert_eq!(files.resolve("dangling_inside"), None);
+        assert_eq!(files.resolve("link"), Some(root.join("link")));
+
+        // "w" and "a" must not create the link target outside the sandbox
+        assert_eq!(files.open("dangling", 4), Err(-1));
+        assert_eq!(files.errno, EACCES);
+        assert_eq!(files.open("dangling", 8), Err(-1));
+        assert!(!outside.join("x").exists());
+    }
+
+    #[test]
+    fn test_file_access() {
+        // Arrange
+        let (root, _) = sandbox("access");
+        let mut files = HostFiles::new(Some(root.clone()));
+
+        // Act
+        let handle = files.open("/f.txt", 6).unwrap(); // "w+"
+        assert_eq!(files.write(handle, b"hello"), Ok(0));
+        assert!(files.seek(handle, 1));
+
+        // Assert
+        assert_eq!(handle, FIRST_FILE_HANDLE);
+        assert_eq!(files.len(handle), Ok(5));
+        assert_eq!(files.read(handle, 10), Ok(b"ello".to_vec()));
+        assert!(files.close(handle));
+        assert!(!files.close(handle));
+        assert_eq!(files.errno, EBADF);
+        assert_eq!(fs::read(root.join("f.txt")).unwrap(), b"hello");
+    }
+
+    #[test]
+    fn test_unsupported_operation() {
+        // Arrange
+        let mut semihost = get_semihost_func(
+            Instant::now(),
+            Box::new(io::sink()),
+            None,
+            String::new(),
+            [0; 4],
+        );
+
+        // Act
+        let response = semihost(&SemihostingCommand::SysUnsupported { operation: 0x12 });
+        let errno = semihost(&SemihostingCommand::SysErrno);
+
+        // Assert
+        assert_eq!(response, SemihostingResponse::SysUnsupported);
+        assert_eq!(
+            errno,
+            SemihostingResponse::SysErrno {
+                result: ENOSYS as u32
+            }
+        );
+    }
+}
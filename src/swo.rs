@@ -0,0 +1,78 @@
+//!
+//! SWO trace streaming over TCP
+//!
+//! Serves the trace port output on a local TCP port, so that live viewers
+//! can connect and disconnect while the simulation runs.
+//!
+
+use std::io;
+use std::io::prelude::*;
+use std::net::{TcpListener, TcpStream};
+
+use crate::errors::*;
+
+///
+/// Writer that sends everything written to it to all connected clients.
+/// Data written while no client is connected is discarded.
+///
+pub struct SwoServer {
+    listener: TcpListener,
+    clients: Vec<TcpStream>,
+}
+
+impl SwoServer {
+    pub fn bind(port: u16) -> Result<Self> {
+        let listener =
+            TcpListener::bind(("127.0.0.1", port)).chain_err(|| "unable to open swo port")?;
+        listener
+            .set_nonblocking(true)
+            .chain_err(|| "unable to configure swo port")?;
+        info!("Serving SWO trace on port {}", port);
+        Ok(Self {
+            listener,
+            clients: Vec::new(),
+        })
+    }
+
+    fn accept_clients(&mut self) {
+        while let Ok((stream, peer)) = self.listener.accept() {
+            if stream.set_nonblocking(false).is_ok() {
+                let _ = stream.set_nodelay(true);
+                info!("SWO client connected from {}", peer);
+                self.clients.push(stream);
+            }
+        }
+    }
+}
+
+impl Write for SwoServer {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.accept_clients();
+        // drop clients that have disconnected
+        self.clients
+            .retain_mut(|client| client.write_all(buf).is_ok());
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+///
+/// Writer that duplicates the output to two writers
+///
+pub struct Tee<A: Write, B: Write>(pub A, pub B);
+
+impl<A: Write, B: Write> Write for Tee<A, B> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.write_all(buf)?;
+        self.1.write_all(buf)?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.0.flush()?;
+        self.1.flush()
+    }
+}
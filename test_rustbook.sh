@@ -33,7 +33,7 @@ cd ../..
 
 for i in "${arr[@]}"
 do
-   echo "./target/release/zmu-armv7m run tests/rustbook/target/thumbv7m-none-eabi/debug/examples/$i"
-   timeout 1s ./target/release/zmu-armv7m run tests/rustbook/target/thumbv7m-none-eabi/debug/examples/$i || true
+   echo "./target/release/zmu run tests/rustbook/target/thumbv7m-none-eabi/debug/examples/$i"
+   timeout 1s ./target/release/zmu run tests/rustbook/target/thumbv7m-none-eabi/debug/examples/$i || true
    echo ""
 done
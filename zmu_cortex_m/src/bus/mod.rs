@@ -5,12 +5,17 @@
 use crate::Processor;
 
 use crate::core::fault::Fault;
+use crate::core::fpu::{FPCCR_WRITE_MASK, FPDSCR_WRITE_MASK};
+use crate::core::monitor::Monitor;
 use crate::memory::map::MapMemory;
-use crate::peripheral::dwt::Dwt;
+use crate::peripheral::dcb::DebugControlBlock;
+use crate::peripheral::dwt::{dwt_num_comparators, Dwt};
+use crate::peripheral::fpb::FlashPatchBreakpoint;
 use crate::peripheral::itm::InstrumentationTraceMacrocell;
 use crate::peripheral::nvic::NVIC;
 use crate::peripheral::scb::SystemControlBlock;
 use crate::peripheral::systick::SysTick;
+use crate::peripheral::tpiu::TracePortInterface;
 
 ///
 /// Trait for reading and writing via a memory bus.
@@ -45,20 +50,30 @@ pub trait Bus {
     fn in_range(&self, addr: u32) -> bool;
 }
 
-impl Bus for Processor {
-    fn read8(&self, bus_addr: u32) -> Result<u8, Fault> {
+impl Processor {
+    fn bus_read8(&self, bus_addr: u32) -> Result<u8, Fault> {
+        if self.fpb_enabled {
+            if let Some(remapped) = self.fpb_remap_literal(bus_addr) {
+                return self.bus_read8(remapped);
+            }
+        }
         let addr = self.map_address(bus_addr);
 
         let result = match addr {
-            0xE000_E400..=0xE000_E5EC => {
-                self.nvic_read_ipr_u8(((addr - 0xE000_E400) >> 2) as usize)
+            0xE000_E400..=0xE000_E5EF => self.nvic_read_ipr_u8((addr - 0xE000_E400) as usize),
+            0xE000_ED18..=0xE000_ED1B if self.cpu.armv7m() => {
+                self.read_shpr1_u8((addr - 0xE000_ED18) as usize)
+            }
+            0xE000_ED1C..=0xE000_ED1F if self.cpu.armv7m() => {
+                self.read_shpr2_u8((addr - 0xE000_ED1C) as usize)
+            }
+            0xE000_ED20..=0xE000_ED23 if self.cpu.armv7m() => {
+                self.read_shpr3_u8((addr - 0xE000_ED20) as usize)
+            }
+            // MMFSR, BFSR and UFSR sub-registers of CFSR
+            0xE000_ED28..=0xE000_ED2B if self.cpu.armv7m() => {
+                (self.cfsr >> ((addr & 0b11) * 8)) as u8
             }
-            #[cfg(any(armv7m, armv7em))]
-            0xE000_ED18..=0xE000_ED1B => self.read_shpr1_u8((addr - 0xE000_ED18) as usize),
-            #[cfg(any(armv7m, armv7em))]
-            0xE000_ED1C..=0xE000_ED1F => self.read_shpr2_u8((addr - 0xE000_ED1C) as usize),
-            #[cfg(any(armv7m, armv7em))]
-            0xE000_ED20..=0xE000_ED23 => self.read_shpr3_u8((addr - 0xE000_ED20) as usize),
 
             _ => {
                 if self.sram.in_range(addr) {
@@ -68,29 +83,34 @@ impl Bus for Processor {
                 } else if self.device.in_range(addr) {
                     return self.device.read8(addr);
                 } else {
-                    return Err(Fault::DAccViol);
+                    return Err(Fault::Preciserr);
                 }
             }
         };
         Ok(result)
     }
 
-    fn read16(&self, bus_addr: u32) -> Result<u16, Fault> {
+    fn bus_read16(&self, bus_addr: u32) -> Result<u16, Fault> {
+        if self.fpb_enabled {
+            if let Some(remapped) = self.fpb_remap_literal(bus_addr) {
+                return self.bus_read16(remapped);
+            }
+        }
         let addr = self.map_address(bus_addr);
         match addr {
-            #[cfg(any(armv7m, armv7em))]
-            0xE000_ED18..=0xE000_ED1B => {
+            0xE000_ED18..=0xE000_ED1B if self.cpu.armv7m() => {
                 Ok(self.read_shpr1_u16(((addr - 0xE000_ED18) >> 1) as usize))
             }
-            #[cfg(any(armv7m, armv7em))]
-            0xE000_ED1C..=0xE000_ED1F => {
+            0xE000_ED1C..=0xE000_ED1F if self.cpu.armv7m() => {
                 Ok(self.read_shpr2_u16(((addr - 0xE000_ED1C) >> 1) as usize))
             }
-            #[cfg(any(armv7m, armv7em))]
-            0xE000_ED20..=0xE000_ED23 => {
+            0xE000_ED20..=0xE000_ED23 if self.cpu.armv7m() => {
                 Ok(self.read_shpr3_u16(((addr - 0xE000_ED20) >> 1) as usize))
             }
-            0xE000_E400..=0xE000_E5EC => {
+            0xE000_ED28 | 0xE000_ED2A if self.cpu.armv7m() => {
+                Ok((self.cfsr >> ((addr & 0b10) * 8)) as u16)
+            }
+            0xE000_E400..=0xE000_E5EE => {
                 Ok(self.nvic_read_ipr_u16(((addr - 0xE000_E400) >> 1) as usize))
             }
 
@@ -102,19 +122,44 @@ impl Bus for Processor {
                 } else if self.device.in_range(addr) {
                     self.device.read16(addr)
                 } else {
-                    Err(Fault::DAccViol)
+                    Err(Fault::Preciserr)
                 }
             }
         }
     }
 
-    fn read32(&mut self, bus_addr: u32) -> Result<u32, Fault> {
+    fn bus_read32(&mut self, bus_addr: u32) -> Result<u32, Fault> {
+        if self.fpb_enabled {
+            if let Some(remapped) = self.fpb_remap_literal(bus_addr) {
+                return self.bus_read32(remapped);
+            }
+        }
         let addr = self.map_address(bus_addr);
 
         let result = match addr {
-            0xE000_0000 => self.read_stim0(),
+            0xE000_0000..=0xE000_007C => self.read_stim(((addr - 0xE000_0000) >> 2) as u8),
+            0xE000_0E00 => self.itm_read_ter(),
+            0xE000_0E40 => self.itm_read_tpr(),
+            0xE000_0E80 => self.itm_read_tcr(),
+            0xE000_0FB4 => self.itm_read_lsr(),
+
+            // TPIU
+            0xE004_0000 => self.tpiu_read_sspsr(),
+            0xE004_0004 => self.tpiu_read_cspsr(),
+            0xE004_0010 => self.tpiu_read_acpr(),
+            0xE004_00F0 => self.tpiu_read_sppr(),
+            0xE004_0300 => self.tpiu_read_ffsr(),
+            0xE004_0304 => self.tpiu_read_ffcr(),
+            // DEVID: NRZ and Manchester SWO supported
+            0xE004_0FC8 => 0xCA1,
 
             0xE000_1004 => self.dwt_cyccnt,
+            0xE000_1008 if self.cpu.armv7m() => self.dwt_cpicnt,
+            0xE000_100C if self.cpu.armv7m() => self.dwt_exccnt,
+            0xE000_1010 if self.cpu.armv7m() => self.dwt_sleepcnt,
+            0xE000_1014 if self.cpu.armv7m() => self.dwt_lsucnt,
+            0xE000_1018 if self.cpu.armv7m() => self.dwt_foldcnt,
+            0xE000_101C => self.dwt_read_pcsr(),
 
             0xE000_E004 => self.ictr,
             0xE000_E008 => self.actlr,
@@ -122,25 +167,22 @@ impl Bus for Processor {
             0xE000_E014 => self.syst_read_rvr(),
             0xE000_E018 => self.syst_read_cvr(),
             0xE000_E01C => self.syst_read_calib(),
-            0xE000_E100..=0xE000_E13C => self.nvic_read_iser(((addr - 0xE000_E100) >> 5) as usize),
-            0xE000_E180..=0xE000_E1BC => self.nvic_read_icer(((addr - 0xE000_E180) >> 5) as usize),
-            0xE000_E200..=0xE000_E23C => self.nvic_read_ispr(((addr - 0xE000_E200) >> 5) as usize),
-            0xE000_E280..=0xE000_E2BC => self.nvic_read_icpr(((addr - 0xE000_E280) >> 5) as usize),
-            0xE000_E300..=0xE000_E33C => self.nvic_read_iabr(((addr - 0xE000_E300) >> 5) as usize),
+            0xE000_E100..=0xE000_E13C => self.nvic_read_iser(((addr - 0xE000_E100) >> 2) as usize),
+            0xE000_E180..=0xE000_E1BC => self.nvic_read_icer(((addr - 0xE000_E180) >> 2) as usize),
+            0xE000_E200..=0xE000_E23C => self.nvic_read_ispr(((addr - 0xE000_E200) >> 2) as usize),
+            0xE000_E280..=0xE000_E2BC => self.nvic_read_icpr(((addr - 0xE000_E280) >> 2) as usize),
+            0xE000_E300..=0xE000_E33C => self.nvic_read_iabr(((addr - 0xE000_E300) >> 2) as usize),
             0xE000_E400..=0xE000_E5EC => self.nvic_read_ipr(((addr - 0xE000_E400) >> 2) as usize),
 
             0xE000_ED00 => self.cpuid,
             0xE000_ED04 => self.read_icsr(),
             0xE000_ED08 => self.read_vtor(),
-            0xE000_ED0C => self.aircr,
+            0xE000_ED0C => self.read_aircr(),
             0xE000_ED10 => self.read_scr(),
             0xE000_ED14 => self.ccr,
-            #[cfg(any(armv7m, armv7em))]
-            0xE000_ED18 => self.read_shpr1(),
-            #[cfg(any(armv7m, armv7em))]
-            0xE000_ED1C => self.read_shpr2(),
-            #[cfg(any(armv7m, armv7em))]
-            0xE000_ED20 => self.read_shpr3(),
+            0xE000_ED18 if self.cpu.armv7m() => self.read_shpr1(),
+            0xE000_ED1C if self.cpu.armv7m() => self.read_shpr2(),
+            0xE000_ED20 if self.cpu.armv7m() => self.read_shpr3(),
             0xE000_ED24 => self.shcsr,
             0xE000_ED28 => self.cfsr,
             0xE000_ED2C => self.hfsr,
@@ -159,10 +201,28 @@ impl Bus for Processor {
             0xE000_EF44 => self.mvfr1,
             0xE000_EF48 => self.mvfr2,
 
+            0xE000_EDF0 => self.read_dhcsr(),
+            0xE000_EDF8 => self.read_dcrdr(),
             0xE000_EDFC => self.read_demcr(),
 
             // DWT
             0xE000_1000 => self.dwt_ctrl,
+            0xE000_1020..=0xE000_105C
+                if (addr - 0xE000_1020) >> 4 < dwt_num_comparators(self.cpu) as u32 =>
+            {
+                let n = ((addr - 0xE000_1020) >> 4) as usize;
+                match addr & 0xf {
+                    0x0 => self.dwt_read_comp(n),
+                    0x4 => self.dwt_read_mask(n),
+                    0x8 => self.dwt_read_function(n),
+                    _ => 0,
+                }
+            }
+
+            // FPB
+            0xE000_2000 => self.read_fp_ctrl(),
+            0xE000_2004 => self.read_fp_remap(),
+            0xE000_2008..=0xE000_2024 => self.read_fp_comp(((addr - 0xE000_2008) >> 2) as usize),
             _ => {
                 if self.sram.in_range(addr) {
                     self.sram.read32(addr)?
@@ -171,14 +231,14 @@ impl Bus for Processor {
                 } else if self.device.in_range(addr) {
                     self.device.read32(addr)?
                 } else {
-                    return Err(Fault::DAccViol);
+                    return Err(Fault::Preciserr);
                 }
             }
         };
         Ok(result)
     }
 
-    fn write32(&mut self, addr: u32, value: u32) -> Result<(), Fault> {
+    fn bus_write32(&mut self, addr: u32, value: u32) -> Result<(), Fault> {
         match addr {
             0xE000_0000..=0xE000_007C => {
                 self.write_stim_u32(((addr - 0xE000_0000) >> 2) as u8, value)
@@ -186,42 +246,89 @@ impl Bus for Processor {
 
             0xE000_1000 => self.dwt_write_ctrl(value),
             0xE000_1004 => self.dwt_write_cyccnt(value),
+            0xE000_1008 if self.cpu.armv7m() => self.dwt_write_cpicnt(value),
+            0xE000_100C if self.cpu.armv7m() => self.dwt_write_exccnt(value),
+            0xE000_1010 if self.cpu.armv7m() => self.dwt_write_sleepcnt(value),
+            0xE000_1014 if self.cpu.armv7m() => self.dwt_write_lsucnt(value),
+            0xE000_1018 if self.cpu.armv7m() => self.dwt_write_foldcnt(value),
+            0xE000_1020..=0xE000_105C
+                if (addr - 0xE000_1020) >> 4 < dwt_num_comparators(self.cpu) as u32 =>
+            {
+                let n = ((addr - 0xE000_1020) >> 4) as usize;
+                match addr & 0xf {
+                    0x0 => self.dwt_write_comp(n, value),
+                    0x4 => self.dwt_write_mask(n, value),
+                    0x8 => self.dwt_write_function(n, value),
+                    _ => {}
+                }
+            }
 
-            0xE000_1FB0 => self.itm_write_lar_u32(value),
+            0xE000_0E00 => self.itm_write_ter(value),
+            0xE000_0E40 => self.itm_write_tpr(value),
+            0xE000_0E80 => self.itm_write_tcr(value),
+            0xE000_0FB0 => self.itm_write_lar_u32(value),
+            // DWT lock access, DWT registers are always accessible
+            0xE000_1FB0 => {}
+
+            0xE004_0004 => self.tpiu_write_cspsr(value),
+            0xE004_0010 => self.tpiu_write_acpr(value),
+            0xE004_00F0 => self.tpiu_write_sppr(value),
+            0xE004_0304 => self.tpiu_write_ffcr(value),
+
+            0xE000_2000 => self.write_fp_ctrl(value),
+            0xE000_2004 => self.write_fp_remap(value),
+            0xE000_2008..=0xE000_2024 => {
+                self.write_fp_comp(((addr - 0xE000_2008) >> 2) as usize, value)
+            }
 
             0xE000_ED04 => self.write_icsr(value),
             0xE000_ED08 => self.write_vtor(value),
+            0xE000_ED0C => self.write_aircr(value),
             0xE000_ED10 => self.write_scr(value),
-            #[cfg(any(armv7m, armv7em))]
-            0xE000_ED18 => self.write_shpr1(value),
-            #[cfg(any(armv7m, armv7em))]
-            0xE000_ED1C => self.write_shpr2(value),
-            #[cfg(any(armv7m, armv7em))]
-            0xE000_ED20 => self.write_shpr3(value),
-
+            0xE000_ED18 if self.cpu.armv7m() => self.write_shpr1(value),
+            0xE000_ED1C if self.cpu.armv7m() => self.write_shpr2(value),
+            0xE000_ED20 if self.cpu.armv7m() => self.write_shpr3(value),
+
+            0xE000_ED14 if self.cpu.armv7m() => self.write_ccr(value),
+            0xE000_ED24 if self.cpu.armv7m() => self.write_shcsr(value),
+            0xE000_ED28 if self.cpu.armv7m() => self.write_cfsr(value),
+            0xE000_ED2C if self.cpu.armv7m() => self.write_hfsr(value),
+            0xE000_ED30 => self.write_dfsr(value),
+            0xE000_ED34 if self.cpu.armv7m() => self.mmfar = value,
+            0xE000_ED38 if self.cpu.armv7m() => self.bfar = value,
+
+            // only the FPU coprocessors CP10 and CP11 are implemented
+            0xE000_ED88 if self.cpu.has_fpu() => self.cpacr = value & 0x00f0_0000,
+
+            0xE000_EF34 if self.cpu.has_fpu() => self.fpccr = value & FPCCR_WRITE_MASK,
+            0xE000_EF38 if self.cpu.has_fpu() => self.fpcar = value & 0xffff_fff8,
+            0xE000_EF3C if self.cpu.has_fpu() => self.fpdscr = value & FPDSCR_WRITE_MASK,
+
+            0xE000_EDF0 => self.write_dhcsr(value),
+            0xE000_EDF4 => self.write_dcrsr(value),
+            0xE000_EDF8 => self.write_dcrdr(value),
             0xE000_EDFC => self.write_demcr(value),
 
             0xE000_E010 => self.syst_write_csr(value),
             0xE000_E014 => self.syst_write_rvr(value),
             0xE000_E018 => self.syst_write_cvr(value),
             0xE000_E100..=0xE000_E13C => {
-                self.nvic_write_iser(((addr - 0xE000_E100) >> 5) as usize, value)
+                self.nvic_write_iser(((addr - 0xE000_E100) >> 2) as usize, value)
             }
             0xE000_E180..=0xE000_E1BC => {
-                self.nvic_write_icer(((addr - 0xE000_E180) >> 5) as usize, value)
+                self.nvic_write_icer(((addr - 0xE000_E180) >> 2) as usize, value)
             }
             0xE000_E200..=0xE000_E23C => {
-                self.nvic_write_ispr(((addr - 0xE000_E200) >> 5) as usize, value)
+                self.nvic_write_ispr(((addr - 0xE000_E200) >> 2) as usize, value)
             }
             0xE000_E280..=0xE000_E2BC => {
-                self.nvic_write_icpr(((addr - 0xE000_E280) >> 5) as usize, value)
+                self.nvic_write_icpr(((addr - 0xE000_E280) >> 2) as usize, value)
             }
             0xE000_E400..=0xE000_E5EC => {
                 self.nvic_write_ipr(((addr - 0xE000_E400) >> 2) as usize, value)
             }
 
-            #[cfg(any(armv7m, armv7em))]
-            0xE000_EF00 => self.write_stir(value),
+            0xE000_EF00 if self.cpu.armv7m() => self.write_stir(value),
             _ => {
                 if self.sram.in_range(addr) {
                     return self.sram.write32(addr, value);
@@ -230,31 +337,31 @@ impl Bus for Processor {
                 } else if self.device.in_range(addr) {
                     return self.device.write32(addr, value);
                 } else {
-                    return Err(Fault::DAccViol);
+                    return Err(Fault::Preciserr);
                 }
             }
         }
         Ok(())
     }
 
-    fn write16(&mut self, addr: u32, value: u16) -> Result<(), Fault> {
+    fn bus_write16(&mut self, addr: u32, value: u16) -> Result<(), Fault> {
         match addr {
             0xE000_0000..=0xE000_007C => {
                 self.write_stim_u16(((addr - 0xE000_0000) >> 2) as u8, value)
             }
-            #[cfg(any(armv7m, armv7em))]
-            0xE000_ED18..=0xE000_ED1B => {
+            0xE000_ED18..=0xE000_ED1B if self.cpu.armv7m() => {
                 self.write_shpr1_u16(((addr - 0xE000_ED18) >> 1) as usize, value)
             }
-            #[cfg(any(armv7m, armv7em))]
-            0xE000_ED1C..=0xE000_ED1F => {
+            0xE000_ED1C..=0xE000_ED1F if self.cpu.armv7m() => {
                 self.write_shpr2_u16(((addr - 0xE000_ED1C) >> 1) as usize, value)
             }
-            #[cfg(any(armv7m, armv7em))]
-            0xE000_ED20..=0xE000_ED23 => {
+            0xE000_ED20..=0xE000_ED23 if self.cpu.armv7m() => {
                 self.write_shpr3_u16(((addr - 0xE000_ED20) >> 1) as usize, value)
             }
-            0xE000_E400..=0xE000_E5EC => {
+            0xE000_ED28 | 0xE000_ED2A if self.cpu.armv7m() => {
+                self.write_cfsr(u32::from(value) << ((addr & 0b10) * 8))
+            }
+            0xE000_E400..=0xE000_E5EE => {
                 self.nvic_write_ipr_u16(((addr - 0xE000_E400) >> 1) as usize, value)
             }
             _ => {
@@ -265,27 +372,33 @@ impl Bus for Processor {
                 } else if self.device.in_range(addr) {
                     return self.device.write16(addr, value);
                 } else {
-                    return Err(Fault::DAccViol);
+                    return Err(Fault::Preciserr);
                 }
             }
         }
         Ok(())
     }
 
-    fn write8(&mut self, addr: u32, value: u8) -> Result<(), Fault> {
+    fn bus_write8(&mut self, addr: u32, value: u8) -> Result<(), Fault> {
         match addr {
             0xE000_0000..=0xE000_007C => {
                 self.write_stim_u8(((addr - 0xE000_0000) >> 2) as u8, value)
             }
-            0xE000_E400..=0xE000_E5EC => {
+            0xE000_E400..=0xE000_E5EF => {
                 self.nvic_write_ipr_u8((addr - 0xE000_E400) as usize, value)
             }
-            #[cfg(any(armv7m, armv7em))]
-            0xE000_ED18..=0xE000_ED1B => self.write_shpr1_u8((addr - 0xE000_ED18) as usize, value),
-            #[cfg(any(armv7m, armv7em))]
-            0xE000_ED1C..=0xE000_ED1F => self.write_shpr2_u8((addr - 0xE000_ED1C) as usize, value),
-            #[cfg(any(armv7m, armv7em))]
-            0xE000_ED20..=0xE000_ED23 => self.write_shpr3_u8((addr - 0xE000_ED20) as usize, value),
+            0xE000_ED18..=0xE000_ED1B if self.cpu.armv7m() => {
+                self.write_shpr1_u8((addr - 0xE000_ED18) as usize, value)
+            }
+            0xE000_ED1C..=0xE000_ED1F if self.cpu.armv7m() => {
+                self.write_shpr2_u8((addr - 0xE000_ED1C) as usize, value)
+            }
+            0xE000_ED20..=0xE000_ED23 if self.cpu.armv7m() => {
+                self.write_shpr3_u8((addr - 0xE000_ED20) as usize, value)
+            }
+            0xE000_ED28..=0xE000_ED2B if self.cpu.armv7m() => {
+                self.write_cfsr(u32::from(value) << ((addr & 0b11) * 8))
+            }
 
             _ => {
                 if self.sram.in_range(addr) {
@@ -295,12 +408,79 @@ impl Bus for Processor {
                 } else if self.device.in_range(addr) {
                     return self.device.write8(addr, value);
                 } else {
-                    return Err(Fault::DAccViol);
+                    return Err(Fault::Preciserr);
                 }
             }
         }
         Ok(())
     }
+}
+
+impl Processor {
+    // remember the address of a failed access for the fault address registers
+    fn bus_error(&self, addr: u32, fault: Fault) -> Fault {
+        self.fault_address.set(addr);
+        fault
+    }
+}
+
+impl Bus for Processor {
+    fn read8(&self, addr: u32) -> Result<u8, Fault> {
+        let value = self
+            .bus_read8(addr)
+            .map_err(|fault| self.bus_error(addr, fault))?;
+        if self.dwt_watch_enabled {
+            self.dwt_data_access(addr, 1, u32::from(value), false);
+        }
+        Ok(value)
+    }
+
+    fn read16(&self, addr: u32) -> Result<u16, Fault> {
+        let value = self
+            .bus_read16(addr)
+            .map_err(|fault| self.bus_error(addr, fault))?;
+        if self.dwt_watch_enabled {
+            self.dwt_data_access(addr, 2, u32::from(value), false);
+        }
+        Ok(value)
+    }
+
+    fn read32(&mut self, addr: u32) -> Result<u32, Fault> {
+        let value = self
+            .bus_read32(addr)
+            .map_err(|fault| self.bus_error(addr, fault))?;
+        if self.dwt_watch_enabled {
+            self.dwt_data_access(addr, 4, value, false);
+        }
+        Ok(value)
+    }
+
+    fn write32(&mut self, addr: u32, value: u32) -> Result<(), Fault> {
+        if self.dwt_watch_enabled {
+            self.dwt_data_access(addr, 4, value, true);
+        }
+        self.monitor_store(addr, 4);
+        self.bus_write32(addr, value)
+            .map_err(|fault| self.bus_error(addr, fault))
+    }
+
+    fn write16(&mut self, addr: u32, value: u16) -> Result<(), Fault> {
+        if self.dwt_watch_enabled {
+            self.dwt_data_access(addr, 2, u32::from(value), true);
+        }
+        self.monitor_store(addr, 2);
+        self.bus_write16(addr, value)
+            .map_err(|fault| self.bus_error(addr, fault))
+    }
+
+    fn write8(&mut self, addr: u32, value: u8) -> Result<(), Fault> {
+        if self.dwt_watch_enabled {
+            self.dwt_data_access(addr, 1, u32::from(value), true);
+        }
+        self.monitor_store(addr, 1);
+        self.bus_write8(addr, value)
+            .map_err(|fault| self.bus_error(addr, fault))
+    }
 
     #[allow(unused)]
     fn in_range(&self, addr: u32) -> bool {
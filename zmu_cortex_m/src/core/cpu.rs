@@ -0,0 +1,147 @@
+//!
+//! Simulated processor core type
+//!
+//! The core type selects the architecture profile at runtime: which
+//! instructions, registers and exception handling features are present.
+//!
+
+use std::fmt;
+use std::str::FromStr;
+
+///
+/// Architecture profile implemented by a core
+///
+#[derive(PartialEq, Debug, Copy, Clone)]
+pub enum Architecture {
+    /// ARMv6-M
+    ArmV6M,
+    /// ARMv7-M
+    ArmV7M,
+    /// ARMv7E-M, ARMv7-M with the DSP extension
+    ArmV7EM,
+}
+
+///
+/// Simulated Cortex-M core
+///
+#[derive(PartialEq, Debug, Copy, Clone)]
+pub enum Cpu {
+    /// Cortex-M0
+    CortexM0,
+    /// Cortex-M0+
+    CortexM0Plus,
+    /// Cortex-M3
+    CortexM3,
+    /// Cortex-M4 without floating point unit
+    CortexM4,
+    /// Cortex-M4 with single precision floating point unit
+    CortexM4F,
+}
+
+impl Cpu {
+    ///
+    /// Architecture profile of the core
+    ///
+    pub fn architecture(self) -> Architecture {
+        match self {
+            Cpu::CortexM0 | Cpu::CortexM0Plus => Architecture::ArmV6M,
+            Cpu::CortexM3 => Architecture::ArmV7M,
+            Cpu::CortexM4 | Cpu::CortexM4F => Architecture::ArmV7EM,
+        }
+    }
+
+    ///
+    /// true if the core implements the ARMv6-M profile
+    ///
+    #[inline(always)]
+    pub fn armv6m(self) -> bool {
+        self.architecture() == Architecture::ArmV6M
+    }
+
+    ///
+    /// true if the core implements the ARMv7-M features,
+    /// which are also part of ARMv7E-M
+    ///
+    #[inline(always)]
+    pub fn armv7m(self) -> bool {
+        !self.armv6m()
+    }
+
+    ///
+    /// true if the core has the floating point extension
+    ///
+    #[inline(always)]
+    pub fn has_fpu(self) -> bool {
+        self == Cpu::CortexM4F
+    }
+
+    ///
+    /// Reset value of the CPUID register
+    ///
+    pub fn cpuid(self) -> u32 {
+        match self {
+            Cpu::CortexM0 => 0x410C_C200,
+            Cpu::CortexM0Plus => 0x410C_C601,
+            Cpu::CortexM3 => 0x412F_C231,
+            Cpu::CortexM4 | Cpu::CortexM4F => 0x410F_C241,
+        }
+    }
+}
+
+impl FromStr for Cpu {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "cortex-m0" => Ok(Cpu::CortexM0),
+            "cortex-m0plus" => Ok(Cpu::CortexM0Plus),
+            "cortex-m3" => Ok(Cpu::CortexM3),
+            "cortex-m4" => Ok(Cpu::CortexM4),
+            "cortex-m4f" => Ok(Cpu::CortexM4F),
+            _ => Err(format!("unknown cpu '{}'", s)),
+        }
+    }
+}
+
+impl fmt::Display for Cpu {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Cpu::CortexM0 => "cortex-m0",
+            Cpu::CortexM0Plus => "cortex-m0plus",
+            Cpu::CortexM3 => "cortex-m3",
+            Cpu::CortexM4 => "cortex-m4",
+            Cpu::CortexM4F => "cortex-m4f",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cpu_names() {
+        for cpu in &[
+            Cpu::CortexM0,
+            Cpu::CortexM0Plus,
+            Cpu::CortexM3,
+            Cpu::CortexM4,
+            Cpu::CortexM4F,
+        ] {
+            assert_eq!(cpu.to_string().parse::<Cpu>(), Ok(*cpu));
+        }
+        assert!("cortex-m7".parse::<Cpu>().is_err());
+    }
+
+    #[test]
+    fn test_cpu_features() {
+        assert!(Cpu::CortexM0Plus.armv6m());
+        assert!(!Cpu::CortexM0Plus.armv7m());
+        assert!(Cpu::CortexM3.armv7m());
+        assert!(!Cpu::CortexM3.has_fpu());
+        assert_eq!(Cpu::CortexM4.architecture(), Architecture::ArmV7EM);
+        assert!(!Cpu::CortexM4.has_fpu());
+        assert!(Cpu::CortexM4F.has_fpu());
+    }
+}
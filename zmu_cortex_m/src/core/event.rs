@@ -0,0 +1,141 @@
+//!
+//! Event register for the WFE and SEV instructions
+//!
+//! The event register is set by SEV, on exception entry and return and,
+//! when SCR.SEVONPEND is set, by interrupts becoming pending. WFE clears a
+//! set event register, otherwise the processor sleeps until an event.
+//!
+
+use crate::core::bits::Bits;
+use crate::peripheral::scb::SCR_SEVONPEND;
+use crate::Processor;
+
+///
+/// Trait for the event register and waiting for events
+///
+pub trait EventRegister {
+    ///
+    /// Set the event register
+    ///
+    fn set_event(&mut self);
+
+    ///
+    /// Set the event register if SCR.SEVONPEND is set, called when
+    /// interrupts have become pending.
+    ///
+    fn set_event_on_pend(&mut self);
+
+    ///
+    /// Clear the event register if it is set, otherwise put the processor
+    /// to sleep until an event.
+    ///
+    fn wait_for_event(&mut self);
+
+    ///
+    /// Wake up a processor waiting for an event if the event register is set
+    ///
+    fn check_event(&mut self);
+}
+
+impl EventRegister for Processor {
+    fn set_event(&mut self) {
+        self.event_register = true;
+    }
+
+    fn set_event_on_pend(&mut self) {
+        if self.scr & SCR_SEVONPEND != 0 {
+            self.event_register = true;
+        }
+    }
+
+    fn wait_for_event(&mut self) {
+        if self.event_register {
+            self.event_register = false;
+        } else {
+            self.waiting_for_event = true;
+            self.state.set_bit(1, true); // sleeping == true
+        }
+    }
+
+    #[inline(always)]
+    fn check_event(&mut self) {
+        if self.waiting_for_event && self.event_register {
+            self.event_register = false;
+            self.waiting_for_event = false;
+            self.state.set_bit(1, false); // sleeping == false
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bus::Bus;
+    use crate::peripheral::nvic::NVIC;
+
+    #[test]
+    fn test_wfe_consumes_event() {
+        // Arrange
+        let mut processor = Processor::new();
+        processor.state = 1;
+        processor.set_event();
+
+        // Act
+        processor.wait_for_event();
+
+        // Assert
+        assert!(!processor.state.get_bit(1));
+        assert!(!processor.event_register);
+
+        // Act: no event left, sleep
+        processor.wait_for_event();
+
+        // Assert
+        assert!(processor.state.get_bit(1));
+    }
+
+    #[test]
+    fn test_sev_wakes_up() {
+        // Arrange
+        let mut processor = Processor::new();
+        processor.state = 1;
+        processor.wait_for_event();
+        processor.check_event();
+        assert!(processor.state.get_bit(1));
+
+        // Act
+        processor.set_event();
+        processor.check_event();
+
+        // Assert
+        assert!(!processor.state.get_bit(1));
+        assert!(!processor.event_register);
+    }
+
+    #[test]
+    fn test_sevonpend() {
+        // Arrange
+        let mut processor = Processor::new();
+        processor.state = 1;
+        processor.wait_for_event();
+
+        // Act: pending a disabled interrupt is not an event without SEVONPEND
+        processor.nvic_write_ispr(0, 1 << 3);
+        processor.check_event();
+
+        // Assert
+        assert!(processor.state.get_bit(1));
+
+        // Act
+        processor.write32(0xE000_ED10, SCR_SEVONPEND).unwrap();
+        processor.nvic_write_ispr(0, 1 << 3); // already pending
+        processor.check_event();
+        assert!(processor.state.get_bit(1));
+        processor.nvic_write_ispr(0, 1 << 4);
+        processor.check_event();
+
+        // Assert
+        assert!(!processor.state.get_bit(1));
+        assert_eq!(processor.read32(0xE000_ED10).unwrap(), SCR_SEVONPEND);
+    }
+}
@@ -5,13 +5,27 @@
 
 use crate::bus::Bus;
 use crate::core::bits::Bits;
+use crate::core::event::EventRegister;
 use crate::core::fault::Fault;
+use crate::core::fpu::{
+    FPCCR_BFRDY, FPCCR_HFRDY, FPCCR_LSPACT, FPCCR_LSPEN, FPCCR_MMRDY, FPCCR_MONRDY,
+    FPCCR_THREAD, FPCCR_USER,
+};
+use crate::core::lockup::Lockup;
+use crate::core::monitor::Monitor;
 use crate::core::register::{BaseReg, Ipsr, Reg};
 use crate::core::reset::Reset;
+use crate::peripheral::dcb::{DebugControlBlock, DHCSR_C_DEBUGEN, DHCSR_C_MASKINTS};
+use crate::peripheral::dwt::{Dwt, EXCEPTION_TRACE_ENTERED, EXCEPTION_TRACE_EXITED, EXCEPTION_TRACE_RETURNED};
 use crate::peripheral::nvic::NVIC;
+use crate::peripheral::scb::{CCR_NONBASETHRDENA, CCR_STKALIGN, SCR_SLEEPONEXIT};
 use crate::Processor;
 use crate::ProcessorMode;
 
+// overhead of stacking and unstacking, used for the DWT exception counter
+const EXCEPTION_ENTRY_CYCLES: u32 = 12;
+const EXCEPTION_RETURN_CYCLES: u32 = 10;
+
 #[derive(Debug, Eq, Ord, PartialEq, PartialOrd, Copy, Clone)]
 ///
 /// Status information for an exception
@@ -69,6 +83,21 @@ pub trait ExceptionHandling {
     ///
     fn exception_entry(&mut self, exception: Exception, return_address: u32) -> Result<(), Fault>;
 
+    ///
+    /// Enter the handler for a synchronous fault.
+    ///
+    /// The fault is recorded to the fault status and address registers. Configurable faults
+    /// that are disabled or cannot preempt the current execution are escalated to HardFault.
+    /// A fault that HardFault cannot preempt locks up the processor.
+    ///
+    fn fault_entry(&mut self, fault: Fault, return_address: u32);
+
+    ///
+    /// Pend the SVCall exception of a supervisor call. An SVC executed at a
+    /// priority that does not allow SVCall to be taken escalates to HardFault.
+    ///
+    fn supervisor_call(&mut self);
+
     ///
     /// Return from an exception.
     ///
@@ -84,6 +113,11 @@ pub trait ExceptionHandling {
     ///
     fn exception_active(&self, exception: Exception) -> bool;
 
+    ///
+    /// Check if exception is pending
+    ///
+    fn exception_pending(&self, exception: Exception) -> bool;
+
     ///
     /// Set priority of an exception. Smaller priority number has higher urgency.
     ///
@@ -99,6 +133,12 @@ pub trait ExceptionHandling {
     ///
     fn exceptions_reset(&mut self);
 
+    ///
+    /// Save the floating point context to the stack space reserved by lazy state
+    /// preservation (FPCCR.LSPACT), before the registers are used by a new context.
+    ///
+    fn preserve_fp_state(&mut self) -> Result<(), Fault>;
+
     ///
     ///
     ///
@@ -107,16 +147,23 @@ pub trait ExceptionHandling {
 
 trait ExceptionHandlingHelpers {
     fn exception_taken(&mut self, exception: Exception) -> Result<(), Fault>;
+    fn handler_entry(&mut self, exception: Exception);
+    fn fault_status(&mut self, fault: Fault) -> Exception;
+    fn fault_exception(&mut self, fault: Fault) -> Option<Exception>;
+    fn fault_pending(&mut self, fault: Fault);
+    fn fault_tail_chain(&mut self, fault: Fault, exc_return: u32);
     fn deactivate(&mut self, returning_exception_number: usize);
     fn invalid_exception_return(
         &mut self,
         returning_exception_number: usize,
         exc_return: u32,
     ) -> Result<(), Fault>;
-    fn return_address(&self, exception_type: Exception, return_address: u32) -> u32;
-    fn push_stack(&mut self, exception_type: Exception, return_address: u32) -> Result<(), Fault>;
+    fn push_stack(&mut self, return_address: u32) -> Result<(), Fault>;
     fn pop_stack(&mut self, frameptr: u32, exc_return: u32) -> Result<(), Fault>;
+    fn update_fpccr(&mut self, frameptr: u32);
     fn exception_active_bit_count(&self) -> usize;
+    fn set_exception_active(&mut self, exception_number: usize, active: bool);
+    fn group_priority(&self, priority: i16) -> i16;
 }
 
 #[derive(PartialEq, Debug, Copy, Clone)]
@@ -166,12 +213,51 @@ pub enum Exception {
     },
 }
 
+// Configurable fault handling a fault and the fault status bit in CFSR,
+// None for faults that are always reported as HardFault
+fn configurable_fault(fault: Fault) -> Option<(Exception, usize)> {
+    match fault {
+        Fault::IAccViol => Some((Exception::MemoryManagementFault, 0)),
+        Fault::DAccViol => Some((Exception::MemoryManagementFault, 1)),
+        Fault::Munstkerr => Some((Exception::MemoryManagementFault, 3)),
+        Fault::Mstkerr => Some((Exception::MemoryManagementFault, 4)),
+        Fault::Ibuserr => Some((Exception::BusFault, 8)),
+        Fault::Preciserr => Some((Exception::BusFault, 9)),
+        Fault::Unstkerr => Some((Exception::BusFault, 11)),
+        Fault::Stkerr => Some((Exception::BusFault, 12)),
+        Fault::UndefInstr => Some((Exception::UsageFault, 16)),
+        Fault::Invstate => Some((Exception::UsageFault, 17)),
+        Fault::InvPc => Some((Exception::UsageFault, 18)),
+        Fault::NoCp => Some((Exception::UsageFault, 19)),
+        Fault::Unaligned => Some((Exception::UsageFault, 24)),
+        Fault::DivByZero => Some((Exception::UsageFault, 25)),
+        Fault::VectorTable | Fault::Forced | Fault::DebugEvent => None,
+    }
+}
+
+// exception numbers of the bits set in an exception bitmap, in ascending order
+fn bitmap_exceptions(bitmap: &[u32]) -> impl Iterator<Item = usize> + '_ {
+    bitmap.iter().enumerate().flat_map(|(index, &word)| {
+        let mut bits = word;
+        std::iter::from_fn(move || {
+            if bits == 0 {
+                return None;
+            }
+            let bit = bits.trailing_zeros() as usize;
+            bits &= bits - 1;
+            Some(index * 32 + bit)
+        })
+    })
+}
+
 impl ExceptionHandlingHelpers for Processor {
     fn exception_taken(&mut self, exception: Exception) -> Result<(), Fault> {
         self.control.sp_sel = false;
         self.mode = ProcessorMode::HandlerMode;
         self.psr.set_isr_number(exception.into());
-        self.exceptions.get_mut(&exception.into()).unwrap().active = true;
+        self.set_exception_active(exception.into(), true);
+        // the handler starts without a floating point context
+        self.control.fpca = false;
 
         self.execution_priority = self.get_execution_priority();
 
@@ -184,18 +270,99 @@ impl ExceptionHandlingHelpers for Processor {
         Ok(())
     }
 
-    fn deactivate(&mut self, returning_exception_number: usize) {
-        self.exceptions
-            .get_mut(&returning_exception_number)
-            .unwrap()
-            .active = false;
-
-        #[cfg(any(armv7m, armv7em))]
-        {
-            if self.psr.get_isr_number() != 0b10 {
-                self.faultmask = false;
+    // Enter the handler of an exception whose context is already stacked. If
+    // the vector cannot be read, HardFault takes over the stacked context.
+    fn handler_entry(&mut self, exception: Exception) {
+        if self.exception_taken(exception).is_err() {
+            self.set_exception_active(exception.into(), false);
+            self.execution_priority = self.get_execution_priority();
+            self.fault_status(Fault::VectorTable);
+
+            if exception != Exception::HardFault
+                && self.get_exception_priority(Exception::HardFault) < self.execution_priority
+            {
+                self.handler_entry(Exception::HardFault);
+            } else {
+                self.lockup();
             }
         }
+    }
+
+    fn fault_status(&mut self, fault: Fault) -> Exception {
+        if self.cpu.armv7m() {
+            if let Some((exception, bit)) = configurable_fault(fault) {
+                self.cfsr.set_bit(bit, true);
+                match fault {
+                    Fault::DAccViol => {
+                        self.mmfar = self.fault_address.get();
+                        self.cfsr.set_bit(7, true); // MMARVALID
+                    }
+                    Fault::Preciserr => {
+                        self.bfar = self.fault_address.get();
+                        self.cfsr.set_bit(15, true); // BFARVALID
+                    }
+                    _ => (),
+                }
+                return exception;
+            }
+        }
+        match fault {
+            Fault::VectorTable => self.hfsr.set_bit(1, true),
+            Fault::Forced => self.hfsr.set_bit(30, true),
+            Fault::DebugEvent => self.hfsr.set_bit(31, true),
+            _ => (),
+        }
+        Exception::HardFault
+    }
+
+    fn fault_exception(&mut self, fault: Fault) -> Option<Exception> {
+        let exception = self.fault_status(fault);
+
+        if self.cpu.armv7m() {
+            let enabled = match exception {
+                Exception::MemoryManagementFault => self.shcsr.get_bit(16),
+                Exception::BusFault => self.shcsr.get_bit(17),
+                Exception::UsageFault => self.shcsr.get_bit(18),
+                _ => false,
+            };
+            if enabled && self.get_exception_priority(exception) < self.execution_priority {
+                return Some(exception);
+            }
+            if exception != Exception::HardFault {
+                self.hfsr.set_bit(30, true); // FORCED
+            }
+        }
+
+        if self.get_exception_priority(Exception::HardFault) < self.execution_priority {
+            Some(Exception::HardFault)
+        } else {
+            self.lockup();
+            None
+        }
+    }
+
+    // derived fault that is taken after the exception being entered
+    fn fault_pending(&mut self, fault: Fault) {
+        if let Some(exception) = self.fault_exception(fault) {
+            self.set_exception_pending(exception);
+        }
+    }
+
+    // fault on exception return, the fault handler returns to the context
+    // that is still stacked
+    fn fault_tail_chain(&mut self, fault: Fault, exc_return: u32) {
+        self.set_r(Reg::LR, (0b1111 << 28) + exc_return);
+        if let Some(exception) = self.fault_exception(fault) {
+            self.handler_entry(exception);
+        }
+    }
+
+    fn deactivate(&mut self, returning_exception_number: usize) {
+        self.set_exception_active(returning_exception_number, false);
+
+        if self.psr.get_isr_number() != 0b10 {
+            self.faultmask = false;
+        }
         self.execution_priority = self.get_execution_priority();
     }
 
@@ -205,49 +372,49 @@ impl ExceptionHandlingHelpers for Processor {
         exc_return: u32,
     ) -> Result<(), Fault> {
         self.deactivate(returning_exception_number);
-        //ufsr.invpc = true;
-        self.set_r(Reg::LR, (0b1111 << 28) + exc_return);
-        self.exception_taken(Exception::UsageFault)
+        self.fault_tail_chain(Fault::InvPc, exc_return);
+        Ok(())
+    }
+
+    // group priority part of ```priority```, the subpriority bits below the
+    // AIRCR.PRIGROUP split do not take part in preemption
+    fn group_priority(&self, priority: i16) -> i16 {
+        // the fixed negative priorities are not affected by grouping
+        if priority <= 0 {
+            return priority;
+        }
+        let groupvalue = 2 << self.aircr.get_bits(8..11);
+        priority - priority % groupvalue
     }
 
     fn exception_active_bit_count(&self) -> usize {
-        self.exceptions
+        self.exceptions_active
             .iter()
-            .filter(|&(_, exp)| exp.active)
-            .fold(0, |acc, _| acc + 1)
-    }
-    fn return_address(&self, exception_type: Exception, return_address: u32) -> u32 {
-        match exception_type {
-            Exception::NMI
-            | Exception::HardFault
-            | Exception::MemoryManagementFault
-            | Exception::BusFault
-            | Exception::SVCall
-            | Exception::DebugMonitor
-            | Exception::PendSV
-            | Exception::SysTick
-            | Exception::Interrupt { .. } => return_address,
-            Exception::UsageFault => return_address - 4,
-            _ => todo!("unsupported exception"),
-        }
-    }
-    fn push_stack(&mut self, exception_type: Exception, return_address: u32) -> Result<(), Fault> {
-        const FRAME_SIZE: u32 = 0x20;
-
-        //TODO FP extensions
-        //TODO forcealign
+            .map(|word| word.count_ones() as usize)
+            .sum()
+    }
+
+    fn set_exception_active(&mut self, exception_number: usize, active: bool) {
+        self.exceptions.get_mut(&exception_number).unwrap().active = active;
+        self.exceptions_active[exception_number / 32].set_bit(exception_number % 32, active);
+    }
+    fn push_stack(&mut self, return_address: u32) -> Result<(), Fault> {
+        // extended frame with S0-S15 and FPSCR when a floating point context is active
+        let fp_context = self.control.fpca;
+        let frame_size: u32 = if fp_context { 0x68 } else { 0x20 };
+
         // forces 8 byte alignment on the stack
-        let forcealign = true;
+        let forcealign = self.ccr & CCR_STKALIGN != 0;
         let spmask = ((forcealign as u32) << 2) ^ 0xFFFF_FFFF;
 
         let (frameptr, frameptralign) =
             if self.control.sp_sel && self.mode == ProcessorMode::ThreadMode {
                 let align = (self.psp.get_bit(2) & forcealign) as u32;
-                self.set_psp((self.psp.wrapping_sub(FRAME_SIZE)) & spmask);
+                self.set_psp((self.psp.wrapping_sub(frame_size)) & spmask);
                 (self.psp, align)
             } else {
-                let align = self.msp.get_bit(2) as u32;
-                self.set_msp((self.msp.wrapping_sub(FRAME_SIZE)) & spmask);
+                let align = (self.msp.get_bit(2) & forcealign) as u32;
+                self.set_msp((self.msp.wrapping_sub(frame_size)) & spmask);
                 (self.msp, align)
             };
 
@@ -258,18 +425,35 @@ impl ExceptionHandlingHelpers for Processor {
         let r12 = self.get_r(Reg::R12);
         let lr = self.get_r(Reg::LR);
 
-        let ret_addr = self.return_address(exception_type, return_address);
-
-        self.write32(frameptr, r0)?;
-        self.write32(frameptr.wrapping_add(0x4), r1)?;
-        self.write32(frameptr.wrapping_add(0x8), r2)?;
-        self.write32(frameptr.wrapping_add(0xc), r3)?;
-        self.write32(frameptr.wrapping_add(0x10), r12)?;
-        self.write32(frameptr.wrapping_add(0x14), lr)?;
-        self.write32(frameptr.wrapping_add(0x18), ret_addr)?;
         let xpsr = (self.psr.value & 0b1111_1111_1111_1111_1111_1101_1111_1111)
             | (frameptralign << 9) as u32;
-        self.write32(frameptr.wrapping_add(0x1c), xpsr)?;
+        let frame = [r0, r1, r2, r3, r12, lr, return_address, xpsr];
+
+        // the whole frame is written even if a part of it faults
+        let mut result = Ok(());
+        for (i, value) in frame.iter().enumerate() {
+            if self.write32(frameptr.wrapping_add(4 * i as u32), *value).is_err() {
+                result = Err(Fault::Stkerr);
+            }
+        }
+
+        if fp_context {
+            if self.fpccr & FPCCR_LSPEN == 0 {
+                for i in 0..16 {
+                    let value = self.fp_regs[i];
+                    if self.write32(frameptr.wrapping_add(0x20 + 4 * i as u32), value).is_err() {
+                        result = Err(Fault::Stkerr);
+                    }
+                }
+                if self.write32(frameptr.wrapping_add(0x60), self.fpscr).is_err() {
+                    result = Err(Fault::Stkerr);
+                }
+            } else {
+                // only reserve the space, the registers are saved when the
+                // handler executes its first floating point instruction
+                self.update_fpccr(frameptr);
+            }
+        }
 
         if self.mode == ProcessorMode::HandlerMode {
             self.lr = 0xFFFF_FFF1;
@@ -278,48 +462,98 @@ impl ExceptionHandlingHelpers for Processor {
         } else {
             self.lr = 0xFFFF_FFF9;
         }
-        Ok(())
+        // EXC_RETURN bit 4 clear marks the extended frame
+        self.lr.set_bit(4, !fp_context);
+        result
+    }
+
+    fn update_fpccr(&mut self, frameptr: u32) {
+        self.fpcar = frameptr.wrapping_add(0x20) & 0xffff_fff8;
+        self.fpccr |= FPCCR_LSPACT;
+
+        let privileged = self.mode == ProcessorMode::HandlerMode || !self.control.n_priv;
+        // readiness of the fault handlers that lazy preservation may need to pend
+        let ready = |enabled: bool, exception: Exception| {
+            enabled && self.get_exception_priority(exception) < self.execution_priority
+        };
+        let flags = [
+            (FPCCR_USER, !privileged),
+            (FPCCR_THREAD, self.mode == ProcessorMode::ThreadMode),
+            (FPCCR_HFRDY, self.execution_priority > -1),
+            (
+                FPCCR_MMRDY,
+                ready(self.shcsr.get_bit(16), Exception::MemoryManagementFault),
+            ),
+            (
+                FPCCR_BFRDY,
+                ready(self.shcsr.get_bit(17), Exception::BusFault),
+            ),
+            (
+                FPCCR_MONRDY,
+                ready(self.demcr.get_bit(16), Exception::DebugMonitor),
+            ),
+        ];
+        for (flag, value) in flags.iter() {
+            if *value {
+                self.fpccr |= flag;
+            } else {
+                self.fpccr &= !flag;
+            }
+        }
     }
 
     fn pop_stack(&mut self, frameptr: u32, exc_return: u32) -> Result<(), Fault> {
-        //TODO: fp extensions
+        let fp_context = !exc_return.get_bit(4);
+        let frame_size: u32 = if fp_context { 0x68 } else { 0x20 };
 
-        const FRAME_SIZE: u32 = 0x20;
+        let forcealign = self.ccr & CCR_STKALIGN != 0;
 
-        //let forcealign = ccr.stkalign;
-        let forcealign = true;
+        // nothing is restored if the frame cannot be read
+        let mut frame = [0u32; 8];
+        for (i, value) in frame.iter_mut().enumerate() {
+            *value = self
+                .read32(frameptr.wrapping_add(4 * i as u32))
+                .map_err(|_| Fault::Unstkerr)?;
+        }
+        let [r0, r1, r2, r3, r12, lr, pc, psr] = frame;
 
-        let r0 = self.read32(frameptr)?;
         self.set_r(Reg::R0, r0);
-        let r1 = self.read32(frameptr.wrapping_add(0x4))?;
         self.set_r(Reg::R1, r1);
-        let r2 = self.read32(frameptr.wrapping_add(0x8))?;
         self.set_r(Reg::R2, r2);
-        let r3 = self.read32(frameptr.wrapping_add(0xc))?;
         self.set_r(Reg::R3, r3);
-        let r12 = self.read32(frameptr.wrapping_add(0x10))?;
         self.set_r(Reg::R12, r12);
-        let lr = self.read32(frameptr.wrapping_add(0x14))?;
         self.set_r(Reg::LR, lr);
-        let pc = self.read32(frameptr.wrapping_add(0x18))?;
-        let psr = self.read32(frameptr.wrapping_add(0x1c))?;
-
         self.branch_write_pc(pc);
 
+        if fp_context {
+            if self.fpccr & FPCCR_LSPACT != 0 {
+                // the handler did not touch the registers, they are still valid
+                self.fpccr &= !FPCCR_LSPACT;
+            } else {
+                for i in 0..16 {
+                    self.fp_regs[i] = self
+                        .read32(frameptr.wrapping_add(0x20 + 4 * i as u32))
+                        .map_err(|_| Fault::Unstkerr)?;
+                }
+                self.fpscr = self
+                    .read32(frameptr.wrapping_add(0x60))
+                    .map_err(|_| Fault::Unstkerr)?;
+            }
+        }
+        self.control.fpca = fp_context;
+
         let spmask = ((psr.get_bit(9) && forcealign) as u32) << 2;
 
         match exc_return.get_bits(0..4) {
             0b0001 | 0b1001 => {
                 let msp = self.get_msp();
-                self.set_msp((msp.wrapping_add(FRAME_SIZE)) | spmask);
+                self.set_msp((msp.wrapping_add(frame_size)) | spmask);
             }
             0b1101 => {
                 let psp = self.get_psp();
-                self.set_psp((psp.wrapping_add(FRAME_SIZE)) | spmask);
-            }
-            _ => {
-                todo!("wrong exc return");
+                self.set_psp((psp.wrapping_add(frame_size)) | spmask);
             }
+            _ => return Err(Fault::InvPc),
         }
         self.psr.value.set_bits(27..32, psr.get_bits(27..32));
         self.psr.value.set_bits(0..9, psr.get_bits(0..9));
@@ -331,6 +565,8 @@ impl ExceptionHandlingHelpers for Processor {
 
 impl ExceptionHandling for Processor {
     fn exceptions_reset(&mut self) {
+        self.exceptions_pending = [0; 16];
+        self.exceptions_active = [0; 16];
         for exception in self.exceptions.values_mut() {
             exception.pending = false;
             exception.active = false;
@@ -340,11 +576,29 @@ impl ExceptionHandling for Processor {
             }
         }
     }
+    fn preserve_fp_state(&mut self) -> Result<(), Fault> {
+        if self.fpccr & FPCCR_LSPACT != 0 {
+            let start = self.fpcar;
+            for i in 0..16 {
+                let value = self.fp_regs[i];
+                self.write32(start.wrapping_add(4 * i as u32), value)?;
+            }
+            self.write32(start.wrapping_add(0x40), self.fpscr)?;
+            self.fpccr &= !FPCCR_LSPACT;
+        }
+        Ok(())
+    }
+
     fn exception_active(&self, exception: Exception) -> bool {
         self.exceptions[&usize::from(exception)].active
     }
 
+    fn exception_pending(&self, exception: Exception) -> bool {
+        self.exceptions[&usize::from(exception)].pending
+    }
+
     fn set_exception_priority(&mut self, exception: Exception, priority: u8) {
+        let priority = priority & self.nvic_priority_mask();
         self.exceptions.get_mut(&exception.into()).unwrap().priority = i16::from(priority);
     }
 
@@ -355,29 +609,19 @@ impl ExceptionHandling for Processor {
     fn get_execution_priority(&self) -> i16 {
         let mut highestpri: i16 = 256;
         let mut boostedpri: i16 = 256;
-        let subgroupshift = self.aircr.get_bits(8..11);
-        let groupvalue = 2 << subgroupshift;
-
-        for (_, exp) in self.exceptions.iter().filter(|&(_, e)| e.active) {
-            if exp.priority < highestpri {
-                highestpri = exp.priority;
-                let subgroupvalue = highestpri % groupvalue;
-                highestpri -= subgroupvalue;
-            }
+
+        for exception_number in bitmap_exceptions(&self.exceptions_active) {
+            let priority = self.exceptions[&exception_number].priority;
+            highestpri = highestpri.min(self.group_priority(priority));
         }
         if self.basepri != 0 {
-            boostedpri = i16::from(self.basepri);
-            let subgroupvalue = boostedpri % groupvalue;
-            boostedpri -= subgroupvalue;
+            boostedpri = self.group_priority(i16::from(self.basepri));
         }
         if self.primask {
             boostedpri = 0;
         }
-        #[cfg(any(armv7m, armv7em))]
-        {
-            if self.faultmask {
-                boostedpri = -1;
-            }
+        if self.faultmask {
+            boostedpri = -1;
         }
 
         if boostedpri < highestpri {
@@ -388,42 +632,47 @@ impl ExceptionHandling for Processor {
     }
 
     fn set_exception_pending(&mut self, exception: Exception) {
-        let mut exp = self.exceptions.get_mut(&exception.into()).unwrap();
+        let exception_number = usize::from(exception);
+        let exp = self.exceptions.get_mut(&exception_number).unwrap();
 
         if !exp.pending {
             exp.pending = true;
             self.pending_exception_count += 1;
+            self.exceptions_pending[exception_number / 32].set_bit(exception_number % 32, true);
         }
     }
 
     fn get_pending_exception(&self) -> Option<Exception> {
         if self.pending_exception_count > 0 {
-            let mut possible_exceptions: Vec<ExceptionState> = self
-                .exceptions
-                .iter()
-                .filter(|&(_, e)| e.pending && e.priority < self.execution_priority)
-                .map(|(&_, &e)| e)
-                .collect();
-
-            if !possible_exceptions.is_empty() {
-                possible_exceptions.sort_by(|a, b| {
-                    if a.priority == b.priority {
-                        a.exception_number.cmp(&b.exception_number)
-                    } else {
-                        a.priority.cmp(&b.priority)
-                    }
-                });
-                return Some(possible_exceptions[0].exception_number.into());
+            // PendSV, SysTick and external interrupts can be masked by the debugger
+            let maskints = self.dhcsr & (DHCSR_C_DEBUGEN | DHCSR_C_MASKINTS)
+                == (DHCSR_C_DEBUGEN | DHCSR_C_MASKINTS);
+            let mut selected: Option<&ExceptionState> = None;
+            for exception_number in bitmap_exceptions(&self.exceptions_pending) {
+                let e = &self.exceptions[&exception_number];
+                if self.group_priority(e.priority) >= self.execution_priority
+                    || (maskints && exception_number >= Exception::PendSV.into())
+                {
+                    continue;
+                }
+                // the priority value orders by group priority and then by
+                // subpriority, ties go to the lowest exception number
+                if selected.map_or(true, |s| e.priority < s.priority) {
+                    selected = Some(e);
+                }
             }
+            return selected.map(|e| e.exception_number.into());
         }
         None
     }
 
     fn clear_pending_exception(&mut self, exception: Exception) {
-        let exp = self.exceptions.get_mut(&exception.into()).unwrap();
+        let exception_number = usize::from(exception);
+        let exp = self.exceptions.get_mut(&exception_number).unwrap();
         if exp.pending {
             exp.pending = false;
             self.pending_exception_count -= 1;
+            self.exceptions_pending[exception_number / 32].set_bit(exception_number % 32, false);
         }
     }
 
@@ -434,8 +683,34 @@ impl ExceptionHandling for Processor {
             if let Exception::Interrupt { n } = exception {
                 self.nvic_unpend_interrupt(n);
             }
-            self.push_stack(exception, return_address)?;
-            self.exception_taken(exception)
+            self.clear_exclusive_local();
+            self.set_event();
+            let stacking = self.push_stack(return_address);
+            self.handler_entry(exception);
+            self.dwt_exception_overhead(EXCEPTION_ENTRY_CYCLES);
+            self.dwt_exception_trace(exception.into(), EXCEPTION_TRACE_ENTERED);
+            self.debug_vector_catch(exception);
+            // a stacking fault is taken after the handler has been entered
+            if let Err(fault) = stacking {
+                self.fault_pending(fault);
+            }
+            Ok(())
+        }
+    }
+
+    fn fault_entry(&mut self, fault: Fault, return_address: u32) {
+        if let Some(exception) = self.fault_exception(fault) {
+            if self.exception_entry(exception, return_address).is_err() {
+                self.lockup();
+            }
+        }
+    }
+
+    fn supervisor_call(&mut self) {
+        if self.get_exception_priority(Exception::SVCall) < self.execution_priority {
+            self.set_exception_pending(Exception::SVCall);
+        } else {
+            self.fault_pending(Fault::Forced);
         }
     }
 
@@ -444,6 +719,12 @@ impl ExceptionHandling for Processor {
 
         let returning_exception_number = self.psr.get_isr_number();
         let nested_activation = self.exception_active_bit_count();
+        self.clear_exclusive_local();
+
+        // the extended frame only exists with the floating point extension
+        if !self.cpu.has_fpu() && !exc_return.get_bit(4) {
+            return self.invalid_exception_return(returning_exception_number, exc_return);
+        }
 
         if self.exceptions[&returning_exception_number].active {
             let frameptr;
@@ -456,9 +737,7 @@ impl ExceptionHandling for Processor {
                 }
                 0b1001 => {
                     // returning to thread using main stack
-                    if nested_activation == 0
-                    /*&& !self.ccr.nonbasethreadena*/
-                    {
+                    if nested_activation != 1 && self.ccr & CCR_NONBASETHRDENA == 0 {
                         return self
                             .invalid_exception_return(returning_exception_number, exc_return);
                     } else {
@@ -469,9 +748,7 @@ impl ExceptionHandling for Processor {
                 }
                 0b1101 => {
                     // returning to thread using process stack
-                    if nested_activation == 0
-                    /*&& !self.ccr.nonbasethreadena*/
-                    {
+                    if nested_activation != 1 && self.ccr & CCR_NONBASETHRDENA == 0 {
                         return self
                             .invalid_exception_return(returning_exception_number, exc_return);
                     } else {
@@ -486,24 +763,28 @@ impl ExceptionHandling for Processor {
             }
 
             self.deactivate(returning_exception_number);
-            self.pop_stack(frameptr, exc_return)?;
-            if self.mode == ProcessorMode::HandlerMode && self.psr.get_isr_number() == 0 {
-                //ufsr.invpc = true;
-                self.push_stack(Exception::UsageFault, exc_return)?; // to negate pop_stack
-                self.set_r(Reg::LR, (0b1111 << 28) + exc_return);
-                return self.exception_taken(Exception::UsageFault);
+            self.set_event();
+            if let Err(fault) = self.pop_stack(frameptr, exc_return) {
+                self.fault_tail_chain(fault, exc_return);
+                return Ok(());
             }
-
-            if self.mode == ProcessorMode::ThreadMode && self.psr.get_isr_number() != 0 {
-                //ufsr.invpc = true;
-                self.push_stack(Exception::UsageFault, exc_return)?; // to negate pop_stack
-                self.set_r(Reg::LR, (0b1111 << 28) + exc_return);
-                return self.exception_taken(Exception::UsageFault);
+            self.dwt_exception_overhead(EXCEPTION_RETURN_CYCLES);
+            self.dwt_exception_trace(returning_exception_number, EXCEPTION_TRACE_EXITED);
+            let returned_to = self.psr.get_isr_number();
+            self.dwt_exception_trace(returned_to, EXCEPTION_TRACE_RETURNED);
+            // integrity check of the restored IPSR against the return mode
+            if (self.mode == ProcessorMode::HandlerMode) == (self.psr.get_isr_number() == 0) {
+                let stacking = self.push_stack(exc_return); // to negate pop_stack
+                self.fault_tail_chain(Fault::InvPc, exc_return);
+                if let Err(fault) = stacking {
+                    self.fault_pending(fault);
+                }
+                return Ok(());
             }
 
             if self.mode == ProcessorMode::ThreadMode
                 && nested_activation == 1 // deactivate() reduced one
-                && self.scr.get_bit(1)
+                && self.scr & SCR_SLEEPONEXIT != 0
             {
                 self.state.set_bit(1, true); // sleeping = true
             }
@@ -518,11 +799,12 @@ impl ExceptionHandling for Processor {
     fn check_exceptions(&mut self) {
         if let Some(exception) = self.get_pending_exception() {
             self.state.set_bit(1, false); // sleeping == false
+            self.waiting_for_event = false;
             self.clear_pending_exception(exception);
             let pc = self.get_pc();
-            // TODO: handle failure to enter exception
-            self.exception_entry(exception, pc)
-                .expect("error handling on exception entry not implemented");
+            if self.exception_entry(exception, pc).is_err() {
+                self.lockup();
+            }
         }
     }
 }
@@ -577,12 +859,11 @@ impl From<usize> for Exception {
 mod tests {
     use super::*;
     use crate::bus::Bus;
-    #[cfg(any(armv7m, armv7em))]
+    use crate::core::cpu::Cpu;
     use crate::core::exception::Exception;
     use crate::core::exception::ExceptionHandling;
-    #[cfg(any(armv7m, armv7em))]
     use crate::core::instruction::Instruction;
-    #[cfg(any(armv7m, armv7em))]
+    use crate::core::lockup::LOCKUP_ADDRESS;
     use crate::executor::Executor;
 
     #[test]
@@ -606,7 +887,7 @@ mod tests {
             core.psr.value = 0xffff_ffff;
 
             // act
-            core.push_stack(Exception::HardFault, 99).unwrap();
+            core.push_stack(99).unwrap();
 
             assert_eq!(core.msp, STACK_START - 32);
             core.get_r(Reg::LR)
@@ -681,7 +962,6 @@ mod tests {
         );
     }
 
-    #[cfg(any(armv7m, armv7em))]
     #[test]
     fn test_faultmask_priority() {
         // Arrange
@@ -722,4 +1002,246 @@ mod tests {
         // Assert
         assert_eq!(processor.nvic_read_ispr(0), 0);
     }
+
+    #[test]
+    fn test_priority_grouping() {
+        // Arrange
+        let mut processor = Processor::new();
+        processor.reset().unwrap();
+        processor.set_msp(0x2000_1000);
+        // PRIGROUP 5: group priority in bits [7:6], subpriority in bits [5:0]
+        processor.write32(0xE000_ED0C, 0x05FA_0500).unwrap();
+        processor.nvic_write_iser(0, 0b1111);
+        processor.nvic_write_ipr(0, 0x2030_7040);
+        processor
+            .exception_entry(Exception::Interrupt { n: 0 }, 0)
+            .unwrap();
+
+        // Act: same group priority does not preempt
+        processor.nvic_write_ispr(0, 0b10);
+
+        // Assert
+        assert_eq!(processor.read32(0xE000_ED0C).unwrap(), 0xFA05_0500);
+        assert_eq!(processor.execution_priority, 0x40);
+        assert_eq!(processor.get_pending_exception(), None);
+
+        // Act: higher group priority preempts, subpriority orders the pending ones
+        processor.nvic_write_ispr(0, 0b1100);
+
+        // Assert
+        assert_eq!(
+            processor.get_pending_exception(),
+            Some(Exception::Interrupt { n: 3 })
+        );
+
+        // Act: write without the key is ignored
+        processor.write32(0xE000_ED0C, 0x0000_0700).unwrap();
+
+        // Assert
+        assert_eq!(processor.read32(0xE000_ED0C).unwrap(), 0xFA05_0500);
+    }
+
+    #[test]
+    fn test_pending_exception_order() {
+        // Arrange
+        let mut processor = Processor::new();
+        processor.reset().unwrap();
+        processor.nvic_write_iser(0, 1 << 7);
+        processor.nvic_write_iser(3, 1 << 1);
+        processor.nvic_write_iser(15, 1 << 0);
+        processor.nvic_write_ipr_u8(7, 0x40);
+        processor.nvic_write_ipr_u8(97, 0x40);
+        processor.nvic_write_ipr_u8(480, 0x20);
+        processor.execution_priority = processor.get_execution_priority();
+
+        // Act
+        processor.nvic_write_ispr(15, 1 << 0);
+        processor.nvic_write_ispr(3, 1 << 1);
+        processor.nvic_write_ispr(0, 1 << 7);
+
+        // Assert: lowest priority value first, then lowest exception number
+        assert_eq!(processor.pending_exception_count, 3);
+        assert_eq!(
+            processor.get_pending_exception(),
+            Some(Exception::Interrupt { n: 480 })
+        );
+        processor.clear_pending_exception(Exception::Interrupt { n: 480 });
+        assert_eq!(
+            processor.get_pending_exception(),
+            Some(Exception::Interrupt { n: 7 })
+        );
+        processor.clear_pending_exception(Exception::Interrupt { n: 7 });
+        assert_eq!(
+            processor.get_pending_exception(),
+            Some(Exception::Interrupt { n: 97 })
+        );
+        processor.clear_pending_exception(Exception::Interrupt { n: 97 });
+        assert_eq!(processor.get_pending_exception(), None);
+        assert_eq!(processor.pending_exception_count, 0);
+    }
+
+    #[test]
+    fn test_priority_bits() {
+        // Arrange
+        let mut processor = Processor::new();
+        processor.nvic(32, 3);
+
+        // Act
+        processor.set_exception_priority(Exception::SysTick, 0xff);
+        processor.nvic_write_ipr_u8(1, 0x3f);
+
+        // Assert
+        assert_eq!(processor.get_exception_priority(Exception::SysTick), 0xe0);
+        assert_eq!(processor.nvic_read_ipr_u8(1), 0x20);
+
+        // Act: armv6m implements two bits and no priority grouping
+        processor.cpu(Cpu::CortexM0);
+        processor.nvic_write_ipr_u8(1, 0xff);
+        processor.write32(0xE000_ED0C, 0x05FA_0500).unwrap();
+
+        // Assert
+        assert_eq!(processor.nvic_read_ipr_u8(1), 0xc0);
+        assert_eq!(processor.read32(0xE000_ED0C).unwrap(), 0xFA05_0000);
+    }
+
+    #[test]
+    fn test_fault_escalated_by_priority() {
+        // Arrange
+        let mut core = Processor::new();
+        core.set_msp(0x2000_1000);
+        core.shcsr.set_bit(18, true); // USGFAULTENA
+        core.set_exception_priority(Exception::UsageFault, 0x80);
+        core.execution_priority = 0x40;
+
+        // Act
+        core.fault_entry(Fault::Unaligned, 0x100);
+
+        // Assert
+        assert!(core.cfsr.get_bit(24)); // UNALIGNED
+        assert!(core.hfsr.get_bit(30)); // FORCED
+        assert_eq!(core.psr.get_isr_number(), Exception::HardFault.into());
+    }
+
+    #[test]
+    fn test_fault_in_hardfault_locks_up() {
+        // Arrange
+        let mut core = Processor::new();
+        core.set_msp(0x2000_1000);
+        core.state = 1;
+        core.exception_entry(Exception::HardFault, 0x100).unwrap();
+
+        // Act
+        core.fault_entry(Fault::UndefInstr, 0x200);
+
+        // Assert
+        assert!(core.locked_up());
+        assert!(!core.state.get_bit(0));
+        assert_eq!(core.get_pc(), LOCKUP_ADDRESS);
+        assert_eq!(core.msp, 0x2000_1000 - 0x20);
+    }
+
+    #[test]
+    fn test_stacking_fault() {
+        // Arrange
+        let mut core = Processor::new();
+        core.set_msp(0x3000_0100);
+        core.shcsr.set_bit(17, true); // BUSFAULTENA
+        core.set_exception_priority(Exception::SysTick, 0x80);
+        core.execution_priority = core.get_execution_priority();
+
+        // Act
+        core.exception_entry(Exception::SysTick, 0x100).unwrap();
+
+        // Assert
+        assert!(core.cfsr.get_bit(12)); // STKERR
+        assert!(!core.cfsr.get_bit(15)); // BFARVALID
+        assert!(core.exception_active(Exception::SysTick));
+        assert!(core.exception_pending(Exception::BusFault));
+        assert_eq!(core.get_pending_exception(), Some(Exception::BusFault));
+    }
+
+    #[test]
+    fn test_vector_table_fault_locks_up() {
+        // Arrange
+        let mut core = Processor::new();
+        core.set_msp(0x2000_1000);
+        core.vtor = 0x3000_0000;
+        core.state = 1;
+
+        // Act
+        core.exception_entry(Exception::SysTick, 0x100).unwrap();
+
+        // Assert
+        assert!(core.hfsr.get_bit(1)); // VECTTBL
+        assert!(core.locked_up());
+        assert!(!core.exception_active(Exception::SysTick));
+    }
+
+    #[test]
+    fn test_push_stack_fp_context() {
+        const STACK_START: u32 = 0x2000_0100;
+        let mut core = Processor::new();
+
+        // arrange
+        core.control.fpca = true;
+        core.fpccr = 0;
+        core.set_msp(STACK_START);
+        for i in 0..16 {
+            core.fp_regs[i] = 0x100 + i as u32;
+        }
+        core.fpscr = 0x1234_0000;
+
+        // act
+        core.push_stack(99).unwrap();
+
+        // assert
+        assert_eq!(core.msp, STACK_START - 0x68);
+        assert_eq!(core.read32(STACK_START - 0x68 + 0x18).unwrap(), 99);
+        assert_eq!(core.read32(STACK_START - 0x68 + 0x20).unwrap(), 0x100);
+        assert_eq!(core.read32(STACK_START - 0x68 + 0x5c).unwrap(), 0x10f);
+        assert_eq!(core.read32(STACK_START - 0x68 + 0x60).unwrap(), 0x1234_0000);
+        assert_eq!(core.get_r(Reg::LR), 0xffff_ffe9);
+    }
+
+    #[test]
+    fn test_lazy_fp_state_preservation() {
+        const STACK_START: u32 = 0x2000_0100;
+        let mut core = Processor::new();
+
+        // arrange
+        core.control.fpca = true;
+        core.set_msp(STACK_START);
+        core.fp_regs[0] = 0xaaaa_5555;
+        core.fpscr = 0x1234_0000;
+
+        // act: only the space is reserved on exception entry
+        core.exception_entry(Exception::SysTick, 0x100).unwrap();
+
+        // assert
+        let frameptr = STACK_START - 0x68;
+        assert_eq!(core.msp, frameptr);
+        assert_ne!(core.fpccr & FPCCR_LSPACT, 0);
+        assert_eq!(core.fpcar, frameptr + 0x20);
+        assert!(!core.control.fpca);
+        assert_ne!(core.read32(frameptr + 0x20).unwrap(), 0xaaaa_5555);
+
+        // act: the handler uses the floating point unit
+        core.preserve_fp_state().unwrap();
+
+        // assert
+        assert_eq!(core.fpccr & FPCCR_LSPACT, 0);
+        assert_eq!(core.read32(frameptr + 0x20).unwrap(), 0xaaaa_5555);
+        assert_eq!(core.read32(frameptr + 0x60).unwrap(), 0x1234_0000);
+
+        // act: return restores the saved context
+        core.fp_regs[0] = 0;
+        core.fpscr = 0;
+        core.exception_return(0xffff_ffe9).unwrap();
+
+        // assert
+        assert_eq!(core.msp, STACK_START);
+        assert_eq!(core.fp_regs[0], 0xaaaa_5555);
+        assert_eq!(core.fpscr, 0x1234_0000);
+        assert!(core.control.fpca);
+    }
 }
@@ -13,40 +13,67 @@ pub enum Fault {
     ///
     VectorTable,
     ///
-    ///
+    /// Configurable fault escalated to a HardFault
     ///
     Forced,
     ///
+    /// Debug event that neither halted the core nor raised DebugMonitor
     ///
+    DebugEvent,
     ///
-    IAccViol,
+    /// Instruction fetch from a location that does not permit execution
     ///
+    IAccViol,
     ///
+    /// Data access to a location that does not permit the access
     ///
     DAccViol,
     ///
-    ///
+    /// Memory access violation while stacking for an exception entry
     ///
     Mstkerr,
     ///
+    /// Memory access violation while unstacking for an exception return
     ///
+    Munstkerr,
     ///
-    Msunskerr,
+    /// Bus error on instruction fetch
     ///
+    Ibuserr,
     ///
+    /// Bus error on a data access, the faulting address is known
+    ///
+    Preciserr,
+    ///
+    /// Bus error while stacking for an exception entry
     ///
     Stkerr,
     ///
+    /// Bus error while unstacking for an exception return
+    ///
+    Unstkerr,
+    ///
     /// Unknown instruction was tried to be executed.
     ///
     UndefInstr,
     ///
+    /// Instruction executed with an invalid EPSR.T or IT state
+    ///
     Invstate,
     ///
+    /// Invalid EXC_RETURN value or integrity check failure on exception return
+    ///
     InvPc,
     ///
+    /// Unaligned access that is not permitted
+    ///
     Unaligned,
     ///
+    /// Divide by zero with CCR.DIV_0_TRP set
     ///
     DivByZero,
+    ///
+    /// Coprocessor instruction while the coprocessor is disabled or not present
+    ///
+    NoCp,
 }
@@ -0,0 +1,1003 @@
+//!
+//! Single precision floating point operations of the FPv4-SP extension
+//!
+//! The operations follow the pseudocode of the ARMv7-M architecture reference
+//! manual. Results are calculated exactly with integer arithmetic and rounded
+//! once, so that the rounding modes, flush-to-zero and default NaN modes and
+//! the cumulative exception flags of FPSCR behave like on the real hardware.
+//!
+
+use crate::core::bits::Bits;
+use std::cmp::Ordering;
+
+/// FPSCR: Negative condition flag
+pub const FPSCR_N: u32 = 1 << 31;
+/// FPSCR: Zero condition flag
+pub const FPSCR_Z: u32 = 1 << 30;
+/// FPSCR: Carry condition flag
+pub const FPSCR_C: u32 = 1 << 29;
+/// FPSCR: Overflow condition flag
+pub const FPSCR_V: u32 = 1 << 28;
+/// FPSCR: Alternative half-precision format
+pub const FPSCR_AHP: u32 = 1 << 26;
+/// FPSCR: Default NaN mode
+pub const FPSCR_DN: u32 = 1 << 25;
+/// FPSCR: Flush-to-zero mode
+pub const FPSCR_FZ: u32 = 1 << 24;
+/// FPSCR: Input denormal cumulative exception flag
+pub const FPSCR_IDC: u32 = 1 << 7;
+/// FPSCR: Inexact cumulative exception flag
+pub const FPSCR_IXC: u32 = 1 << 4;
+/// FPSCR: Underflow cumulative exception flag
+pub const FPSCR_UFC: u32 = 1 << 3;
+/// FPSCR: Overflow cumulative exception flag
+pub const FPSCR_OFC: u32 = 1 << 2;
+/// FPSCR: Division by zero cumulative exception flag
+pub const FPSCR_DZC: u32 = 1 << 1;
+/// FPSCR: Invalid operation cumulative exception flag
+pub const FPSCR_IOC: u32 = 1;
+/// Implemented FPSCR bits, the rest are reserved and read as zero
+pub const FPSCR_WRITE_MASK: u32 = 0xf7c0_009f;
+
+/// FPCCR: Automatic state preservation enable, sets CONTROL.FPCA on FP instructions
+pub const FPCCR_ASPEN: u32 = 1 << 31;
+/// FPCCR: Lazy state preservation enable
+pub const FPCCR_LSPEN: u32 = 1 << 30;
+/// FPCCR: DebugMonitor could be pended when the lazy state was allocated
+pub const FPCCR_MONRDY: u32 = 1 << 8;
+/// FPCCR: BusFault could be pended when the lazy state was allocated
+pub const FPCCR_BFRDY: u32 = 1 << 6;
+/// FPCCR: MemManage could be pended when the lazy state was allocated
+pub const FPCCR_MMRDY: u32 = 1 << 5;
+/// FPCCR: HardFault could be pended when the lazy state was allocated
+pub const FPCCR_HFRDY: u32 = 1 << 4;
+/// FPCCR: Lazy state was allocated in Thread mode
+pub const FPCCR_THREAD: u32 = 1 << 3;
+/// FPCCR: Lazy state was allocated by unprivileged code
+pub const FPCCR_USER: u32 = 1 << 1;
+/// FPCCR: Lazy state preservation is active, space is reserved on the stack
+pub const FPCCR_LSPACT: u32 = 1;
+/// Implemented FPCCR bits
+pub const FPCCR_WRITE_MASK: u32 = 0xc000_017b;
+/// Implemented FPDSCR bits: default AHP, DN, FZ and rounding mode
+pub const FPDSCR_WRITE_MASK: u32 = 0x07c0_0000;
+
+const DEFAULT_NAN: u32 = 0x7fc0_0000;
+const HALF_DEFAULT_NAN: u16 = 0x7e00;
+
+#[derive(PartialEq, Debug, Copy, Clone)]
+enum FPRounding {
+    TieEven,
+    PosInf,
+    NegInf,
+    Zero,
+}
+
+fn rounding_mode(fpscr: u32) -> FPRounding {
+    match fpscr.get_bits(22..24) {
+        0b00 => FPRounding::TieEven,
+        0b01 => FPRounding::PosInf,
+        0b10 => FPRounding::NegInf,
+        _ => FPRounding::Zero,
+    }
+}
+
+#[derive(PartialEq, Debug, Copy, Clone)]
+enum FPType {
+    Zero,
+    Nonzero,
+    Infinity,
+    QNaN,
+    SNaN,
+}
+
+///
+/// Unpacked operand. Finite values are (-1)^sign * mant * 2^exp.
+///
+#[derive(Debug, Copy, Clone)]
+struct FPValue {
+    fptype: FPType,
+    sign: bool,
+    exp: i32,
+    mant: u128,
+    bits: u32,
+}
+
+impl FPValue {
+    fn finite(sign: bool, exp: i32, mant: u128) -> Self {
+        Self {
+            fptype: if mant == 0 {
+                FPType::Zero
+            } else {
+                FPType::Nonzero
+            },
+            sign,
+            exp,
+            mant,
+            bits: 0,
+        }
+    }
+
+    fn is_nan(&self) -> bool {
+        self.fptype == FPType::QNaN || self.fptype == FPType::SNaN
+    }
+
+    fn is_inf(&self) -> bool {
+        self.fptype == FPType::Infinity
+    }
+
+    fn is_zero(&self) -> bool {
+        self.fptype == FPType::Zero
+    }
+
+    fn to_f64(&self) -> f64 {
+        let magnitude = match self.fptype {
+            FPType::Infinity => f64::INFINITY,
+            _ => self.mant as f64 * 2f64.powi(self.exp),
+        };
+        if self.sign {
+            -magnitude
+        } else {
+            magnitude
+        }
+    }
+}
+
+#[derive(PartialEq, Debug, Copy, Clone)]
+struct FPFormat {
+    exp_bits: u32,
+    frac_bits: u32,
+}
+
+const SINGLE: FPFormat = FPFormat {
+    exp_bits: 8,
+    frac_bits: 23,
+};
+
+const HALF: FPFormat = FPFormat {
+    exp_bits: 5,
+    frac_bits: 10,
+};
+
+///
+/// Size of the bits shifted out when rounding, relative to half of the
+/// least significant bit kept.
+///
+#[derive(PartialEq, Debug, Copy, Clone)]
+enum RoundingError {
+    Zero,
+    BelowHalf,
+    Half,
+    AboveHalf,
+}
+
+fn shift_right(mant: u128, shift: i32) -> (u128, RoundingError) {
+    if shift <= 0 {
+        (mant << -shift, RoundingError::Zero)
+    } else if shift > 127 {
+        let error = if mant == 0 {
+            RoundingError::Zero
+        } else {
+            RoundingError::BelowHalf
+        };
+        (0, error)
+    } else {
+        let remainder = mant & ((1 << shift) - 1);
+        let half = 1 << (shift - 1);
+        let error = if remainder == 0 {
+            RoundingError::Zero
+        } else {
+            match remainder.cmp(&half) {
+                Ordering::Less => RoundingError::BelowHalf,
+                Ordering::Equal => RoundingError::Half,
+                Ordering::Greater => RoundingError::AboveHalf,
+            }
+        };
+        (mant >> shift, error)
+    }
+}
+
+fn msb(mant: u128) -> i32 {
+    127 - mant.leading_zeros() as i32
+}
+
+fn unpack(op: u32, fpscr: &mut u32) -> FPValue {
+    let sign = op.get_bit(31);
+    let exp = op.get_bits(23..31);
+    let frac = op.get_bits(0..23);
+
+    let (fptype, exp, mant) = if exp == 0 {
+        if frac == 0 || *fpscr & FPSCR_FZ != 0 {
+            if frac != 0 {
+                *fpscr |= FPSCR_IDC;
+            }
+            (FPType::Zero, 0, 0)
+        } else {
+            (FPType::Nonzero, -149, frac)
+        }
+    } else if exp == 0xff {
+        if frac == 0 {
+            (FPType::Infinity, 0, 0)
+        } else if frac.get_bit(22) {
+            (FPType::QNaN, 0, 0)
+        } else {
+            (FPType::SNaN, 0, 0)
+        }
+    } else {
+        (FPType::Nonzero, exp as i32 - 150, frac | (1 << 23))
+    };
+
+    FPValue {
+        fptype,
+        sign,
+        exp,
+        mant: u128::from(mant),
+        bits: op,
+    }
+}
+
+fn unpack_half(op: u16, fpscr: u32) -> FPValue {
+    let sign = op.get_bit(15);
+    let exp = op.get_bits(10..15);
+    let frac = op.get_bits(0..10);
+
+    let (fptype, exp, mant) = if exp == 0 {
+        if frac == 0 {
+            (FPType::Zero, 0, 0)
+        } else {
+            (FPType::Nonzero, -24, frac)
+        }
+    } else if exp == 0x1f && fpscr & FPSCR_AHP == 0 {
+        if frac == 0 {
+            (FPType::Infinity, 0, 0)
+        } else if frac.get_bit(9) {
+            (FPType::QNaN, 0, 0)
+        } else {
+            (FPType::SNaN, 0, 0)
+        }
+    } else {
+        (FPType::Nonzero, i32::from(exp) - 25, frac | (1 << 10))
+    };
+
+    FPValue {
+        fptype,
+        sign,
+        exp,
+        mant: u128::from(mant),
+        bits: u32::from(op),
+    }
+}
+
+fn zero(sign: bool) -> u32 {
+    (sign as u32) << 31
+}
+
+fn infinity(sign: bool) -> u32 {
+    zero(sign) | 0x7f80_0000
+}
+
+fn invalid_operation(fpscr: &mut u32) -> u32 {
+    *fpscr |= FPSCR_IOC;
+    DEFAULT_NAN
+}
+
+fn process_nan(op: &FPValue, fpscr: &mut u32) -> u32 {
+    let result = if op.fptype == FPType::SNaN {
+        *fpscr |= FPSCR_IOC;
+        op.bits | (1 << 22)
+    } else {
+        op.bits
+    };
+    if *fpscr & FPSCR_DN != 0 {
+        DEFAULT_NAN
+    } else {
+        result
+    }
+}
+
+///
+/// Signaling NaNs take priority over quiet NaNs, the first operand over the
+/// later ones.
+///
+fn process_nans(ops: &[FPValue], fpscr: &mut u32) -> Option<u32> {
+    ops.iter()
+        .find(|op| op.fptype == FPType::SNaN)
+        .or_else(|| ops.iter().find(|op| op.fptype == FPType::QNaN))
+        .map(|op| process_nan(op, fpscr))
+}
+
+///
+/// Round a nonzero finite value to the given format
+///
+fn round(value: FPValue, fpscr: &mut u32, format: FPFormat) -> u32 {
+    let e = format.exp_bits as i32;
+    let f = format.frac_bits as i32;
+    let bias = (1 << (e - 1)) - 1;
+    let minimum_exp = 1 - bias;
+    let sign = value.sign;
+    let sign_bit = (sign as u32) << (e + f);
+    let mode = rounding_mode(*fpscr);
+    let exponent = value.exp + msb(value.mant);
+
+    if format == SINGLE && *fpscr & FPSCR_FZ != 0 && exponent < minimum_exp {
+        *fpscr |= FPSCR_UFC;
+        return sign_bit;
+    }
+
+    let (mut biased_exp, lsb_exp) = if exponent < minimum_exp {
+        (0, minimum_exp - f)
+    } else {
+        (exponent + bias, exponent - f)
+    };
+    let (mut int_mant, error) = shift_right(value.mant, lsb_exp - value.exp);
+
+    if biased_exp == 0 && error != RoundingError::Zero {
+        *fpscr |= FPSCR_UFC;
+    }
+
+    let round_up = match mode {
+        FPRounding::TieEven => {
+            error == RoundingError::AboveHalf || (error == RoundingError::Half && int_mant & 1 == 1)
+        }
+        FPRounding::PosInf => error != RoundingError::Zero && !sign,
+        FPRounding::NegInf => error != RoundingError::Zero && sign,
+        FPRounding::Zero => false,
+    };
+
+    if round_up {
+        int_mant += 1;
+        if biased_exp == 0 && int_mant == 1 << f {
+            biased_exp = 1;
+        }
+        if int_mant == 1 << (f + 1) {
+            biased_exp += 1;
+            int_mant >>= 1;
+        }
+    }
+
+    let frac = (int_mant as u32).get_bits(0..f as usize);
+    let max_exp = (1 << e) - 1;
+    let ieee = format == SINGLE || *fpscr & FPSCR_AHP == 0;
+
+    let (result, inexact) = if ieee && biased_exp >= max_exp {
+        *fpscr |= FPSCR_OFC;
+        let overflow_to_inf = match mode {
+            FPRounding::TieEven => true,
+            FPRounding::PosInf => !sign,
+            FPRounding::NegInf => sign,
+            FPRounding::Zero => false,
+        };
+        let magnitude = if overflow_to_inf {
+            (max_exp as u32) << f
+        } else {
+            ((max_exp as u32) << f) - 1
+        };
+        (sign_bit | magnitude, true)
+    } else if !ieee && biased_exp > max_exp {
+        *fpscr |= FPSCR_IOC;
+        (sign_bit | ((1 << (e + f)) - 1), false)
+    } else {
+        (
+            sign_bit | ((biased_exp as u32) << f) | frac,
+            error != RoundingError::Zero,
+        )
+    };
+
+    if inexact {
+        *fpscr |= FPSCR_IXC;
+    }
+    result
+}
+
+///
+/// Round an exact result, which is +0 or -0 depending on the rounding mode
+/// when the operands cancel each other out.
+///
+fn round_or_zero(value: FPValue, fpscr: &mut u32) -> u32 {
+    if value.mant == 0 {
+        zero(rounding_mode(*fpscr) == FPRounding::NegInf)
+    } else {
+        round(value, fpscr, SINGLE)
+    }
+}
+
+///
+/// Exact sum of two finite values. Bits far below the precision of the larger
+/// operand are folded into a sticky bit.
+///
+fn add_exact(a: FPValue, b: FPValue) -> FPValue {
+    if a.mant == 0 {
+        return b;
+    }
+    if b.mant == 0 {
+        return a;
+    }
+
+    let normalize = |v: FPValue| {
+        let shift = 100 - msb(v.mant);
+        (v.exp - shift, v.mant << shift)
+    };
+    let (exp_a, mant_a) = normalize(a);
+    let (exp_b, mant_b) = normalize(b);
+    let ((hi, exp_hi, mant_hi), (lo, mant_lo)) = if exp_a >= exp_b {
+        ((a, exp_a, mant_a), (b, shift_sticky(mant_b, exp_a - exp_b)))
+    } else {
+        ((b, exp_b, mant_b), (a, shift_sticky(mant_a, exp_b - exp_a)))
+    };
+
+    if hi.sign == lo.sign {
+        FPValue::finite(hi.sign, exp_hi, mant_hi + mant_lo)
+    } else if mant_hi >= mant_lo {
+        FPValue::finite(hi.sign, exp_hi, mant_hi - mant_lo)
+    } else {
+        FPValue::finite(lo.sign, exp_hi, mant_lo - mant_hi)
+    }
+}
+
+fn shift_sticky(mant: u128, shift: i32) -> u128 {
+    if shift > 127 {
+        1
+    } else {
+        (mant >> shift) | u128::from(mant & ((1 << shift) - 1) != 0)
+    }
+}
+
+fn add_sub(op1: u32, op2: u32, negate: bool, fpscr: &mut u32) -> u32 {
+    let a = unpack(op1, fpscr);
+    let mut b = unpack(op2, fpscr);
+    if let Some(nan) = process_nans(&[a, b], fpscr) {
+        return nan;
+    }
+    b.sign ^= negate;
+
+    if a.is_inf() && b.is_inf() && a.sign != b.sign {
+        invalid_operation(fpscr)
+    } else if a.is_inf() || b.is_inf() {
+        infinity(if a.is_inf() { a.sign } else { b.sign })
+    } else if a.is_zero() && b.is_zero() && a.sign == b.sign {
+        zero(a.sign)
+    } else {
+        round_or_zero(add_exact(a, b), fpscr)
+    }
+}
+
+///
+/// Floating point addition
+///
+pub fn fp_add(op1: u32, op2: u32, fpscr: &mut u32) -> u32 {
+    add_sub(op1, op2, false, fpscr)
+}
+
+///
+/// Floating point subtraction
+///
+pub fn fp_sub(op1: u32, op2: u32, fpscr: &mut u32) -> u32 {
+    add_sub(op1, op2, true, fpscr)
+}
+
+///
+/// Floating point multiplication
+///
+pub fn fp_mul(op1: u32, op2: u32, fpscr: &mut u32) -> u32 {
+    let a = unpack(op1, fpscr);
+    let b = unpack(op2, fpscr);
+    if let Some(nan) = process_nans(&[a, b], fpscr) {
+        return nan;
+    }
+    let sign = a.sign ^ b.sign;
+
+    if (a.is_inf() && b.is_zero()) || (a.is_zero() && b.is_inf()) {
+        invalid_operation(fpscr)
+    } else if a.is_inf() || b.is_inf() {
+        infinity(sign)
+    } else if a.is_zero() || b.is_zero() {
+        zero(sign)
+    } else {
+        round(
+            FPValue::finite(sign, a.exp + b.exp, a.mant * b.mant),
+            fpscr,
+            SINGLE,
+        )
+    }
+}
+
+///
+/// Floating point division
+///
+pub fn fp_div(op1: u32, op2: u32, fpscr: &mut u32) -> u32 {
+    let a = unpack(op1, fpscr);
+    let b = unpack(op2, fpscr);
+    if let Some(nan) = process_nans(&[a, b], fpscr) {
+        return nan;
+    }
+    let sign = a.sign ^ b.sign;
+
+    if (a.is_inf() && b.is_inf()) || (a.is_zero() && b.is_zero()) {
+        invalid_operation(fpscr)
+    } else if a.is_inf() || b.is_zero() {
+        if !a.is_inf() {
+            *fpscr |= FPSCR_DZC;
+        }
+        infinity(sign)
+    } else if a.is_zero() || b.is_inf() {
+        zero(sign)
+    } else {
+        let dividend = a.mant << 100;
+        let quotient = dividend / b.mant;
+        let sticky = u128::from(dividend % b.mant != 0);
+        round(
+            FPValue::finite(sign, a.exp - b.exp - 101, (quotient << 1) | sticky),
+            fpscr,
+            SINGLE,
+        )
+    }
+}
+
+fn isqrt(n: u128) -> (u128, bool) {
+    let mut remainder = n;
+    let mut root = 0;
+    let mut bit = 1 << 126;
+    while bit > n {
+        bit >>= 2;
+    }
+    while bit != 0 {
+        if remainder >= root + bit {
+            remainder -= root + bit;
+            root = (root >> 1) + bit;
+        } else {
+            root >>= 1;
+        }
+        bit >>= 2;
+    }
+    (root, remainder != 0)
+}
+
+///
+/// Floating point square root
+///
+pub fn fp_sqrt(op: u32, fpscr: &mut u32) -> u32 {
+    let a = unpack(op, fpscr);
+    if let Some(nan) = process_nans(&[a], fpscr) {
+        return nan;
+    }
+
+    if a.is_zero() {
+        zero(a.sign)
+    } else if a.sign {
+        invalid_operation(fpscr)
+    } else if a.is_inf() {
+        infinity(false)
+    } else {
+        let mut shift = 120 - msb(a.mant);
+        if (a.exp - shift) % 2 != 0 {
+            shift += 1;
+        }
+        let (root, inexact) = isqrt(a.mant << shift);
+        round(
+            FPValue::finite(
+                false,
+                (a.exp - shift) / 2 - 1,
+                (root << 1) | u128::from(inexact),
+            ),
+            fpscr,
+            SINGLE,
+        )
+    }
+}
+
+///
+/// Fused multiply-add, addend + op1 * op2 with a single rounding
+///
+pub fn fp_mul_add(addend: u32, op1: u32, op2: u32, fpscr: &mut u32) -> u32 {
+    let a = unpack(addend, fpscr);
+    let b = unpack(op1, fpscr);
+    let c = unpack(op2, fpscr);
+    let invalid_product = (b.is_inf() && c.is_zero()) || (b.is_zero() && c.is_inf());
+
+    if let Some(nan) = process_nans(&[a, b, c], fpscr) {
+        if a.fptype == FPType::QNaN && invalid_product {
+            return invalid_operation(fpscr);
+        }
+        return nan;
+    }
+
+    let sign_p = b.sign ^ c.sign;
+    let inf_p = b.is_inf() || c.is_inf();
+    let zero_p = b.is_zero() || c.is_zero();
+
+    if invalid_product || (a.is_inf() && inf_p && a.sign != sign_p) {
+        invalid_operation(fpscr)
+    } else if a.is_inf() || inf_p {
+        infinity(if a.is_inf() { a.sign } else { sign_p })
+    } else if a.is_zero() && zero_p && a.sign == sign_p {
+        zero(a.sign)
+    } else {
+        let product = FPValue::finite(sign_p, b.exp + c.exp, b.mant * c.mant);
+        round_or_zero(add_exact(a, product), fpscr)
+    }
+}
+
+///
+/// Floating point absolute value, no exceptions
+///
+pub fn fp_abs(op: u32) -> u32 {
+    op & 0x7fff_ffff
+}
+
+///
+/// Floating point negation, no exceptions
+///
+pub fn fp_neg(op: u32) -> u32 {
+    op ^ 0x8000_0000
+}
+
+///
+/// Compare two values, returns the resulting NZCV flags in bits 31..28.
+/// VCMPE also raises invalid operation for quiet NaN operands.
+///
+pub fn fp_compare(op1: u32, op2: u32, quiet_nan_exc: bool, fpscr: &mut u32) -> u32 {
+    let a = unpack(op1, fpscr);
+    let b = unpack(op2, fpscr);
+
+    if a.is_nan() || b.is_nan() {
+        if a.fptype == FPType::SNaN || b.fptype == FPType::SNaN || quiet_nan_exc {
+            *fpscr |= FPSCR_IOC;
+        }
+        return FPSCR_C | FPSCR_V;
+    }
+
+    match a.to_f64().partial_cmp(&b.to_f64()) {
+        Some(Ordering::Less) => FPSCR_N,
+        Some(Ordering::Greater) => FPSCR_C,
+        _ => FPSCR_Z | FPSCR_C,
+    }
+}
+
+///
+/// Convert to a signed or unsigned fixed point number of `size` bits with
+/// `frac_bits` fraction bits. Integer conversions have no fraction bits.
+/// The result is sign or zero extended to 32 bits.
+///
+pub fn fp_to_fixed(
+    op: u32,
+    size: u32,
+    frac_bits: u32,
+    unsigned: bool,
+    round_zero: bool,
+    fpscr: &mut u32,
+) -> u32 {
+    let a = unpack(op, fpscr);
+    if a.is_nan() {
+        *fpscr |= FPSCR_IOC;
+        return 0;
+    }
+
+    let mode = if round_zero {
+        FPRounding::Zero
+    } else {
+        rounding_mode(*fpscr)
+    };
+    let exp = a.exp + frac_bits as i32;
+
+    let (magnitude, error, overflow) = if a.is_inf() || exp > 64 {
+        (0, RoundingError::Zero, true)
+    } else {
+        let (int_part, error) = shift_right(a.mant, -exp);
+        (int_part, error, false)
+    };
+
+    let round_up = match mode {
+        FPRounding::TieEven => {
+            error == RoundingError::AboveHalf
+                || (error == RoundingError::Half && magnitude & 1 == 1)
+        }
+        FPRounding::PosInf => error != RoundingError::Zero && !a.sign,
+        FPRounding::NegInf => error != RoundingError::Zero && a.sign,
+        FPRounding::Zero => false,
+    };
+    let magnitude = (magnitude + u128::from(round_up)) as i128;
+    let int_result = if a.sign { -magnitude } else { magnitude };
+
+    let (min, max) = if unsigned {
+        (0, (1i128 << size) - 1)
+    } else {
+        (-(1i128 << (size - 1)), (1i128 << (size - 1)) - 1)
+    };
+
+    let result = if overflow || int_result < min || int_result > max {
+        *fpscr |= FPSCR_IOC;
+        if a.sign {
+            min
+        } else {
+            max
+        }
+    } else {
+        if error != RoundingError::Zero {
+            *fpscr |= FPSCR_IXC;
+        }
+        int_result
+    };
+    result as u32
+}
+
+///
+/// Convert a signed or unsigned fixed point number held in the low `size`
+/// bits of the operand to a floating point value
+///
+pub fn fixed_to_fp(op: u32, size: u32, frac_bits: u32, unsigned: bool, fpscr: &mut u32) -> u32 {
+    let int_operand = if unsigned {
+        i64::from(op.get_bits(0..size as usize))
+    } else {
+        i64::from(((op << (32 - size)) as i32) >> (32 - size))
+    };
+
+    if int_operand == 0 {
+        zero(false)
+    } else {
+        round(
+            FPValue::finite(
+                int_operand < 0,
+                -(frac_bits as i32),
+                u128::from(int_operand.unsigned_abs()),
+            ),
+            fpscr,
+            SINGLE,
+        )
+    }
+}
+
+///
+/// Convert a half precision value to single precision
+///
+pub fn fp_half_to_single(op: u16, fpscr: &mut u32) -> u32 {
+    let a = unpack_half(op, *fpscr);
+
+    if a.is_nan() {
+        if a.fptype == FPType::SNaN {
+            *fpscr |= FPSCR_IOC;
+        }
+        if *fpscr & FPSCR_DN != 0 {
+            DEFAULT_NAN
+        } else {
+            zero(a.sign) | 0x7fc0_0000 | (u32::from(op).get_bits(0..9) << 13)
+        }
+    } else if a.is_inf() {
+        infinity(a.sign)
+    } else if a.is_zero() {
+        zero(a.sign)
+    } else {
+        round(a, fpscr, SINGLE)
+    }
+}
+
+///
+/// Convert a single precision value to half precision, using the
+/// alternative half precision format when FPSCR.AHP is set
+///
+pub fn fp_single_to_half(op: u32, fpscr: &mut u32) -> u16 {
+    let a = unpack(op, fpscr);
+    let sign = (a.sign as u16) << 15;
+    let ahp = *fpscr & FPSCR_AHP != 0;
+
+    if a.is_nan() {
+        if a.fptype == FPType::SNaN || ahp {
+            *fpscr |= FPSCR_IOC;
+        }
+        if ahp {
+            sign
+        } else if *fpscr & FPSCR_DN != 0 {
+            HALF_DEFAULT_NAN
+        } else {
+            sign | 0x7e00 | op.get_bits(13..22) as u16
+        }
+    } else if a.is_inf() {
+        if ahp {
+            *fpscr |= FPSCR_IOC;
+            sign | 0x7fff
+        } else {
+            sign | 0x7c00
+        }
+    } else if a.is_zero() {
+        sign
+    } else {
+        round(a, fpscr, HALF) as u16
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ONE: u32 = 0x3f80_0000;
+    const TWO: u32 = 0x4000_0000;
+    const THREE: u32 = 0x4040_0000;
+    const MAX_NORMAL: u32 = 0x7f7f_ffff;
+    const MIN_NORMAL: u32 = 0x0080_0000;
+
+    fn rmode(mode: u32) -> u32 {
+        mode << 22
+    }
+
+    #[test]
+    fn test_fp_add_sub() {
+        let mut fpscr = 0;
+        assert_eq!(fp_add(ONE, TWO, &mut fpscr), THREE);
+        assert_eq!(fp_sub(ONE, THREE, &mut fpscr), 0xc000_0000);
+        assert_eq!(fpscr, 0);
+
+        // exact cancellation gives +0, or -0 when rounding towards minus infinity
+        assert_eq!(fp_sub(ONE, ONE, &mut fpscr), 0);
+        let mut fpscr = rmode(0b10);
+        assert_eq!(fp_sub(ONE, ONE, &mut fpscr), 0x8000_0000);
+
+        // 1 + 2^-24 is halfway between 1 and the next value
+        let mut fpscr = 0;
+        assert_eq!(fp_add(ONE, 0x3380_0000, &mut fpscr), ONE);
+        assert_eq!(fpscr, FPSCR_IXC);
+        let mut fpscr = rmode(0b01);
+        assert_eq!(fp_add(ONE, 0x3380_0000, &mut fpscr), ONE + 1);
+
+        // tiny addend only affects directed rounding
+        let mut fpscr = rmode(0b11);
+        assert_eq!(fp_sub(ONE, 0x0000_0001, &mut fpscr), 0x3f7f_ffff);
+    }
+
+    #[test]
+    fn test_fp_overflow() {
+        let mut fpscr = 0;
+        assert_eq!(fp_add(MAX_NORMAL, MAX_NORMAL, &mut fpscr), 0x7f80_0000);
+        assert_eq!(fpscr, FPSCR_OFC | FPSCR_IXC);
+
+        let mut fpscr = rmode(0b11);
+        assert_eq!(fp_mul(MAX_NORMAL, TWO, &mut fpscr), MAX_NORMAL);
+        assert_eq!(fpscr & FPSCR_OFC, FPSCR_OFC);
+    }
+
+    #[test]
+    fn test_fp_denormals() {
+        // MIN_NORMAL / 2 is exactly representable as a denormal
+        let mut fpscr = 0;
+        assert_eq!(fp_div(MIN_NORMAL, TWO, &mut fpscr), 0x0040_0000);
+        assert_eq!(fpscr, 0);
+
+        // smallest denormal / 2 underflows to zero
+        assert_eq!(fp_div(1, TWO, &mut fpscr), 0);
+        assert_eq!(fpscr, FPSCR_UFC | FPSCR_IXC);
+
+        // flush-to-zero treats denormal inputs and results as zero
+        let mut fpscr = FPSCR_FZ;
+        assert_eq!(fp_add(0x0040_0000, ONE, &mut fpscr), ONE);
+        assert_eq!(fpscr, FPSCR_FZ | FPSCR_IDC);
+        let mut fpscr = FPSCR_FZ;
+        assert_eq!(fp_div(MIN_NORMAL, TWO, &mut fpscr), 0);
+        assert_eq!(fpscr, FPSCR_FZ | FPSCR_UFC);
+    }
+
+    #[test]
+    fn test_fp_nans() {
+        let mut fpscr = 0;
+        // quiet NaN is propagated without exceptions
+        assert_eq!(fp_add(0x7fc0_1234, ONE, &mut fpscr), 0x7fc0_1234);
+        assert_eq!(fpscr, 0);
+        // signaling NaN is quieted and has priority
+        assert_eq!(fp_mul(0x7fc0_0001, 0xff80_0002, &mut fpscr), 0xffc0_0002);
+        assert_eq!(fpscr, FPSCR_IOC);
+        // default NaN mode
+        let mut fpscr = FPSCR_DN;
+        assert_eq!(fp_add(0x7fc0_1234, ONE, &mut fpscr), DEFAULT_NAN);
+        // invalid operations
+        let mut fpscr = 0;
+        assert_eq!(fp_mul(0x7f80_0000, 0, &mut fpscr), DEFAULT_NAN);
+        assert_eq!(fp_sqrt(0xbf80_0000, &mut fpscr), DEFAULT_NAN);
+        assert_eq!(fpscr, FPSCR_IOC);
+    }
+
+    #[test]
+    fn test_fp_div_sqrt() {
+        let mut fpscr = 0;
+        assert_eq!(fp_div(ONE, THREE, &mut fpscr), 0x3eaa_aaab);
+        assert_eq!(fpscr, FPSCR_IXC);
+
+        let mut fpscr = 0;
+        assert_eq!(fp_div(ONE, 0x8000_0000, &mut fpscr), 0xff80_0000);
+        assert_eq!(fpscr, FPSCR_DZC);
+
+        let mut fpscr = 0;
+        assert_eq!(fp_sqrt(0x4080_0000, &mut fpscr), TWO);
+        assert_eq!(fpscr, 0);
+        assert_eq!(fp_sqrt(TWO, &mut fpscr), 2f32.sqrt().to_bits());
+        assert_eq!(fp_sqrt(0x8000_0000, &mut fpscr), 0x8000_0000);
+    }
+
+    #[test]
+    fn test_fp_mul_add_is_fused() {
+        // (1 + 2^-23) * (1 - 2^-23) - 1 = -2^-46, which is lost when rounded twice
+        let mut fpscr = 0;
+        let a = ONE + 1;
+        let b = 0x3f7f_fffe;
+        assert_eq!(fp_mul_add(0xbf80_0000, a, b, &mut fpscr), 0xa880_0000);
+        assert_eq!(fp_add(0xbf80_0000, fp_mul(a, b, &mut fpscr), &mut fpscr), 0);
+    }
+
+    #[test]
+    fn test_fp_compare() {
+        let mut fpscr = 0;
+        assert_eq!(fp_compare(ONE, TWO, false, &mut fpscr), FPSCR_N);
+        assert_eq!(fp_compare(TWO, ONE, false, &mut fpscr), FPSCR_C);
+        assert_eq!(
+            fp_compare(0, 0x8000_0000, false, &mut fpscr),
+            FPSCR_Z | FPSCR_C
+        );
+        assert_eq!(fpscr, 0);
+        assert_eq!(
+            fp_compare(DEFAULT_NAN, ONE, false, &mut fpscr),
+            FPSCR_C | FPSCR_V
+        );
+        assert_eq!(fpscr, 0);
+        assert_eq!(
+            fp_compare(DEFAULT_NAN, ONE, true, &mut fpscr),
+            FPSCR_C | FPSCR_V
+        );
+        assert_eq!(fpscr, FPSCR_IOC);
+    }
+
+    #[test]
+    fn test_fp_to_fixed() {
+        let mut fpscr = 0;
+        // 2.5 rounds to even, or towards zero
+        assert_eq!(fp_to_fixed(0x4020_0000, 32, 0, false, false, &mut fpscr), 2);
+        assert_eq!(
+            fp_to_fixed(0xc020_0000, 32, 0, false, true, &mut fpscr),
+            -2i32 as u32
+        );
+        assert_eq!(fpscr, FPSCR_IXC);
+
+        // saturation
+        let mut fpscr = 0;
+        assert_eq!(fp_to_fixed(0xbf80_0000, 32, 0, true, true, &mut fpscr), 0);
+        assert_eq!(
+            fp_to_fixed(0x4f80_0000, 32, 0, false, true, &mut fpscr),
+            0x7fff_ffff
+        );
+        assert_eq!(
+            fp_to_fixed(0xff80_0000, 16, 0, false, true, &mut fpscr),
+            0xffff_8000
+        );
+        assert_eq!(fpscr, FPSCR_IOC);
+
+        // 1.5 in Q16.16 and back
+        let mut fpscr = 0;
+        assert_eq!(
+            fp_to_fixed(0x3fc0_0000, 32, 16, false, true, &mut fpscr),
+            0x18000
+        );
+        assert_eq!(fixed_to_fp(0x18000, 32, 16, false, &mut fpscr), 0x3fc0_0000);
+        assert_eq!(fixed_to_fp(0xffff, 16, 0, false, &mut fpscr), 0xbf80_0000);
+        assert_eq!(
+            fixed_to_fp(0xffff_ffff, 32, 0, true, &mut fpscr),
+            0x4f80_0000
+        );
+        assert_eq!(fpscr, FPSCR_IXC);
+    }
+
+    #[test]
+    fn test_fp_half_precision() {
+        let mut fpscr = 0;
+        assert_eq!(fp_single_to_half(ONE, &mut fpscr), 0x3c00);
+        assert_eq!(fp_half_to_single(0x3c00, &mut fpscr), ONE);
+        assert_eq!(fp_half_to_single(0x0001, &mut fpscr), 0x3380_0000);
+        assert_eq!(fp_single_to_half(0x7fc0_0000, &mut fpscr), 0x7e00);
+        assert_eq!(fpscr, 0);
+
+        // 65520 overflows to infinity in IEEE format
+        assert_eq!(fp_single_to_half(0x477f_f000, &mut fpscr), 0x7c00);
+        assert_eq!(fpscr, FPSCR_OFC | FPSCR_IXC);
+
+        // exponent 31 is a normal value in the alternative format
+        let mut fpscr = FPSCR_AHP;
+        assert_eq!(fp_single_to_half(0x477f_f000, &mut fpscr), 0x7c00);
+        assert_eq!(fp_half_to_single(0x7c00, &mut fpscr), 0x4780_0000);
+        assert_eq!(fp_single_to_half(0x7f80_0000, &mut fpscr), 0x7fff);
+        assert_eq!(fpscr, FPSCR_AHP | FPSCR_IOC | FPSCR_IXC);
+    }
+}
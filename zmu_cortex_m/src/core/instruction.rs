@@ -3,7 +3,7 @@
 //!
 
 use crate::core::condition::Condition;
-use crate::core::register::{ExtensionReg, Reg};
+use crate::core::register::{DoubleReg, ExtensionReg, Reg, SingleReg};
 use crate::core::thumb::ThumbCode;
 use enum_set::EnumSet;
 
@@ -299,6 +299,120 @@ pub struct VLoadAndStoreParams {
     pub imm32: u32,
 }
 
+#[allow(missing_docs)]
+#[derive(PartialEq, Debug, Copy, Clone)]
+pub struct VLoadAndStoreMultipleParams {
+    pub rn: Reg,
+    pub dd: ExtensionReg,
+    pub regs: u8,
+    pub add: bool,
+    pub wback: bool,
+}
+
+#[allow(missing_docs)]
+#[derive(PartialEq, Debug, Copy, Clone)]
+pub struct VPushPopParams {
+    pub dd: ExtensionReg,
+    pub regs: u8,
+}
+
+#[allow(missing_docs)]
+#[derive(PartialEq, Debug, Copy, Clone)]
+pub struct VReg3Params {
+    pub sd: SingleReg,
+    pub sn: SingleReg,
+    pub sm: SingleReg,
+}
+
+#[allow(missing_docs)]
+#[derive(PartialEq, Debug, Copy, Clone)]
+pub struct VReg2Params {
+    pub sd: SingleReg,
+    pub sm: SingleReg,
+}
+
+#[allow(missing_docs)]
+#[derive(PartialEq, Debug, Copy, Clone)]
+pub struct VMovImmParams {
+    pub sd: SingleReg,
+    pub imm32: u32,
+}
+
+#[allow(missing_docs)]
+#[derive(PartialEq, Debug, Copy, Clone)]
+pub struct VCmpParams {
+    pub sd: SingleReg,
+    /// None when comparing with zero
+    pub sm: Option<SingleReg>,
+    pub quiet_nan_exc: bool,
+}
+
+#[allow(missing_docs)]
+#[derive(PartialEq, Debug, Copy, Clone)]
+pub struct VCvtIntParams {
+    pub sd: SingleReg,
+    pub sm: SingleReg,
+    pub to_integer: bool,
+    pub signed: bool,
+    pub round_zero: bool,
+}
+
+#[allow(missing_docs)]
+#[derive(PartialEq, Debug, Copy, Clone)]
+pub struct VCvtFixedParams {
+    pub sd: SingleReg,
+    pub to_fixed: bool,
+    pub unsigned: bool,
+    pub size: u8,
+    pub frac_bits: u8,
+}
+
+#[allow(missing_docs)]
+#[derive(PartialEq, Debug, Copy, Clone)]
+pub struct VCvtHalfParams {
+    pub sd: SingleReg,
+    pub sm: SingleReg,
+    pub to_half: bool,
+    pub top: bool,
+}
+
+#[allow(missing_docs)]
+#[derive(PartialEq, Debug, Copy, Clone)]
+pub struct VMovCrSpParams {
+    pub rt: Reg,
+    pub sn: SingleReg,
+    pub to_arm_register: bool,
+}
+
+#[allow(missing_docs)]
+#[derive(PartialEq, Debug, Copy, Clone)]
+pub struct VMovCr2Sp2Params {
+    pub rt: Reg,
+    pub rt2: Reg,
+    /// first of the two consecutive registers
+    pub sm: SingleReg,
+    pub to_arm_registers: bool,
+}
+
+#[allow(missing_docs)]
+#[derive(PartialEq, Debug, Copy, Clone)]
+pub struct VMovCr2DpParams {
+    pub rt: Reg,
+    pub rt2: Reg,
+    pub dm: DoubleReg,
+    pub to_arm_registers: bool,
+}
+
+#[allow(missing_docs)]
+#[derive(PartialEq, Debug, Copy, Clone)]
+pub struct VMovCrScalarParams {
+    pub rt: Reg,
+    pub dd: DoubleReg,
+    /// index of the 32-bit word in the doubleword
+    pub x: u8,
+    pub to_arm_register: bool,
+}
+
 #[allow(missing_docs)]
 #[derive(PartialEq, Debug, Copy, Clone)]
 pub struct Reg3RdRtRnImm32Params {
@@ -393,6 +507,112 @@ pub struct BfiParams {
     pub width: usize,
 }
 
+#[allow(missing_docs)]
+#[derive(PartialEq, Debug, Copy, Clone)]
+pub struct Reg3SwapParams {
+    pub rd: Reg,
+    pub rn: Reg,
+    pub rm: Reg,
+    pub m_swap: bool,
+}
+
+#[allow(missing_docs)]
+#[derive(PartialEq, Debug, Copy, Clone)]
+pub struct Reg4SwapParams {
+    pub rd: Reg,
+    pub rn: Reg,
+    pub rm: Reg,
+    pub ra: Reg,
+    pub m_swap: bool,
+}
+
+#[allow(missing_docs)]
+#[derive(PartialEq, Debug, Copy, Clone)]
+pub struct Reg643232SwapParams {
+    pub rdlo: Reg,
+    pub rdhi: Reg,
+    pub rn: Reg,
+    pub rm: Reg,
+    pub m_swap: bool,
+}
+
+#[allow(missing_docs)]
+#[derive(PartialEq, Debug, Copy, Clone)]
+pub struct Reg643232HighParams {
+    pub rdlo: Reg,
+    pub rdhi: Reg,
+    pub rn: Reg,
+    pub rm: Reg,
+    pub n_high: bool,
+    pub m_high: bool,
+}
+
+#[allow(missing_docs)]
+#[derive(PartialEq, Debug, Copy, Clone)]
+pub struct Reg3WordHighParams {
+    pub rd: Reg,
+    pub rn: Reg,
+    pub rm: Reg,
+    pub m_high: bool,
+}
+
+#[allow(missing_docs)]
+#[derive(PartialEq, Debug, Copy, Clone)]
+pub struct Reg4WordHighParams {
+    pub rd: Reg,
+    pub rn: Reg,
+    pub rm: Reg,
+    pub ra: Reg,
+    pub m_high: bool,
+}
+
+#[allow(missing_docs)]
+#[derive(PartialEq, Debug, Copy, Clone)]
+pub struct Reg3RoundParams {
+    pub rd: Reg,
+    pub rn: Reg,
+    pub rm: Reg,
+    pub round: bool,
+}
+
+#[allow(missing_docs)]
+#[derive(PartialEq, Debug, Copy, Clone)]
+pub struct Reg4RoundParams {
+    pub rd: Reg,
+    pub rn: Reg,
+    pub rm: Reg,
+    pub ra: Reg,
+    pub round: bool,
+}
+
+#[allow(missing_docs)]
+#[derive(PartialEq, Debug, Copy, Clone)]
+pub struct Reg3ShiftNoSetFlagsParams {
+    pub rd: Reg,
+    pub rn: Reg,
+    pub rm: Reg,
+    pub shift_t: SRType,
+    pub shift_n: u8,
+}
+
+#[allow(missing_docs)]
+#[derive(PartialEq, Debug, Copy, Clone)]
+pub struct SaturateParams {
+    pub rd: Reg,
+    pub rn: Reg,
+    pub saturate_to: u8,
+    pub shift_t: SRType,
+    pub shift_n: u8,
+}
+
+#[allow(missing_docs)]
+#[derive(PartialEq, Debug, Copy, Clone)]
+pub struct Saturate16Params {
+    pub rd: Reg,
+    pub rn: Reg,
+    pub saturate_to: u8,
+}
+
 #[allow(non_camel_case_types, missing_docs)]
 #[derive(PartialEq, Debug, Copy, Clone)]
 ///
@@ -714,6 +934,15 @@ pub enum Instruction {
     },
     // --------------------------------------------
     //
+    // Group: Unsigned Multiply instructions (ARMv7-M DSP extension)
+    //
+    // --------------------------------------------
+    /// Unsigned Multiply Accumulate Accumulate Long
+    UMAAL {
+        params: Reg643232Params,
+    },
+    // --------------------------------------------
+    //
     // Group: Signed Multiply instructions (ARMv7-M DSP extension)
     //
     // --------------------------------------------
@@ -727,43 +956,116 @@ pub enum Instruction {
     SMLA {
         params: Reg4HighParams,
     },
-
-    //SMLAL second variant?
-    //SMLALD
-    //SMLAW
-    //SMLSD
-    //SMLSLD
-    //SMMLA
-    //SMMLS
-    //SMMUL
-    //SMUAD
+    /// Signed multiply and Accumulate Long, halfwords
+    /// variants: SMLALTT, SMLALBB, SMLALTB, SMLALBT
+    SMLAL_hw {
+        params: Reg643232HighParams,
+    },
+    /// Signed Multiply Accumulate Dual
+    /// variants: SMLAD, SMLADX
+    SMLAD {
+        params: Reg4SwapParams,
+    },
+    /// Signed Multiply Accumulate Long Dual
+    /// variants: SMLALD, SMLALDX
+    SMLALD {
+        params: Reg643232SwapParams,
+    },
+    /// Signed Multiply Accumulate, word by halfword
+    /// variants: SMLAWB, SMLAWT
+    SMLAW {
+        params: Reg4WordHighParams,
+    },
+    /// Signed Multiply Subtract Dual
+    /// variants: SMLSD, SMLSDX
+    SMLSD {
+        params: Reg4SwapParams,
+    },
+    /// Signed Multiply Subtract Long Dual
+    /// variants: SMLSLD, SMLSLDX
+    SMLSLD {
+        params: Reg643232SwapParams,
+    },
+    /// Signed Most Significant Word Multiply Accumulate
+    /// variants: SMMLA, SMMLAR
+    SMMLA {
+        params: Reg4RoundParams,
+    },
+    /// Signed Most Significant Word Multiply Subtract
+    /// variants: SMMLS, SMMLSR
+    SMMLS {
+        params: Reg4RoundParams,
+    },
+    /// Signed Most Significant Word Multiply
+    /// variants: SMMUL, SMMULR
+    SMMUL {
+        params: Reg3RoundParams,
+    },
+    /// Signed Dual Multiply Add
+    /// variants: SMUAD, SMUADX
+    SMUAD {
+        params: Reg3SwapParams,
+    },
+    /// Signed Multiply, word by halfword
+    /// variants: SMULWB, SMULWT
+    SMULW {
+        params: Reg3WordHighParams,
+    },
+    /// Signed Dual Multiply Subtract
+    /// variants: SMUSD, SMUSDX
+    SMUSD {
+        params: Reg3SwapParams,
+    },
 
     // --------------------------------------------
     //
     // Group: Saturating instructions (ARMv7-M base arch)
     //
     // --------------------------------------------
-
-    //SSAT
-    //USAT
+    /// Signed Saturate
+    SSAT {
+        params: SaturateParams,
+    },
+    /// Unsigned Saturate
+    USAT {
+        params: SaturateParams,
+    },
 
     // --------------------------------------------
     //
     // Group: Unsigned Saturating instructions (ARMv7-M DSP extensions)
     //
     // --------------------------------------------
-    //USAT16
-    //SSAT16
+    /// Unsigned Saturate 16
+    USAT16 {
+        params: Saturate16Params,
+    },
+    /// Signed Saturate 16
+    SSAT16 {
+        params: Saturate16Params,
+    },
 
     // --------------------------------------------
     //
     // Group: Saturating add/sub (ARMv7-M DSP extensions)
     //
     // --------------------------------------------
-    //QADD
-    //QSUB
-    //QDADD
-    //QDSUB
+    /// Saturating Add
+    QADD {
+        params: Reg3NoSetFlagsParams,
+    },
+    /// Saturating Subtract
+    QSUB {
+        params: Reg3NoSetFlagsParams,
+    },
+    /// Saturating Double and Add
+    QDADD {
+        params: Reg3NoSetFlagsParams,
+    },
+    /// Saturating Double and Subtract
+    QDSUB {
+        params: Reg3NoSetFlagsParams,
+    },
 
     // --------------------------------------------
     //
@@ -795,17 +1097,43 @@ pub enum Instruction {
     // Group: Packing and unpacking instructions (DSP extensions)
     //
     // --------------------------------------------
-    //PKHBT, PKHTB
-    //SXTAB
-    //SXTAB16
-    //SXTAH
-    //SXTB16
+    /// Pack Halfword
+    /// variants: PKHBT, PKHTB
+    PKH {
+        params: Reg3ShiftNoSetFlagsParams,
+    },
+    /// Signed Extend and Add Byte
+    SXTAB {
+        params: Reg3UsizeParams,
+    },
+    /// Signed Extend and Add Byte 16
+    SXTAB16 {
+        params: Reg3UsizeParams,
+    },
+    /// Signed Extend and Add Halfword
+    SXTAH {
+        params: Reg3UsizeParams,
+    },
+    /// Signed Extend Byte 16
+    SXTB16 {
+        params: Reg2UsizeParams,
+    },
+    /// Unsigned Extend and Add Byte
     UXTAB {
         params: Reg3UsizeParams,
     },
-    //UXTAB16
-    //UXTAH
-    //UXTB16
+    /// Unsigned Extend and Add Byte 16
+    UXTAB16 {
+        params: Reg3UsizeParams,
+    },
+    /// Unsigned Extend and Add Halfword
+    UXTAH {
+        params: Reg3UsizeParams,
+    },
+    /// Unsigned Extend Byte 16
+    UXTB16 {
+        params: Reg2UsizeParams,
+    },
 
     // --------------------------------------------
     //
@@ -826,93 +1154,232 @@ pub enum Instruction {
     // Group: Parallel add / sub (DSP extension)
     //
     // --------------------------------------------
-    //SADD16, QADD16, SHADD16, UADD16, UQADD16, UHADD16
-    //SASX, QASX, SHASX, UASX, UQASX, UHSX
-    //SSAX, QSAX, SHSAX, USAX, UQSAX, UHSAX
-    //SSUB16, QSUB16, SHSUB16, USUB16, UQSUB16, UHSUB16
-    //SADD8, QADD8, SHADD8, UADD8, UQADD8, UHADD8
-    //SSUB8, QSUB8, SHSUB8, USUB8, UQSUB8, UHSUB8
-    UADD8 {
+    /// Signed Add 16
+    SADD16 {
         params: Reg3NoSetFlagsParams,
     },
-
-    // --------------------------------------------
-    //
-    // Group: Miscellaneous data-processing instructions
-    //
-    // --------------------------------------------
-    /// Bit Field Clear
-    BFC {
-        params: BfcParams,
+    /// Saturating Add 16
+    QADD16 {
+        params: Reg3NoSetFlagsParams,
     },
-    /// Bit Field Insert
-    BFI {
-        params: BfiParams,
+    /// Signed Halving Add 16
+    SHADD16 {
+        params: Reg3NoSetFlagsParams,
     },
-    /// Count Leading Zeros
-    CLZ {
-        params: Reg2RdRmParams,
+    /// Unsigned Add 16
+    UADD16 {
+        params: Reg3NoSetFlagsParams,
     },
-    /// Move Top
-    MOVT {
-        params: MovtParams,
+    /// Unsigned Saturating Add 16
+    UQADD16 {
+        params: Reg3NoSetFlagsParams,
     },
-    // RBIT
-    /// Byte-reverse word
-    REV {
-        params: Reg2RdRmParams,
-        thumb32: bool,
+    /// Unsigned Halving Add 16
+    UHADD16 {
+        params: Reg3NoSetFlagsParams,
     },
-
-    /// Byte-reverse packed half-word
-    REV16 {
-        params: Reg2RdRmParams,
-        thumb32: bool,
+    /// Signed Add and Subtract with Exchange
+    SASX {
+        params: Reg3NoSetFlagsParams,
     },
-
-    /// Byte-reverse signed half-word
-    REVSH {
-        params: Reg2RdRmParams,
-        thumb32: bool,
+    /// Saturating Add and Subtract with Exchange
+    QASX {
+        params: Reg3NoSetFlagsParams,
     },
-
-    //SBFX - signed bit field extract
-    /// Unsigned bit field extract
-    UBFX {
-        params: UbfxParams,
+    /// Signed Halving Add and Subtract with Exchange
+    SHASX {
+        params: Reg3NoSetFlagsParams,
     },
-
-    // --------------------------------------------
-    //
-    // Group: Miscellaneous data-processing instructions (DSP extensions)
-    //
-    // --------------------------------------------
-    /// Select bytes using GE flags
-    SEL {
+    /// Unsigned Add and Subtract with Exchange
+    UASX {
         params: Reg3NoSetFlagsParams,
     },
-    //USAD8
-    //USADA8
-
-    // --------------------------------------------
-    //
-    // Group: Status register access instructions
-    //
-    // --------------------------------------------
-    /// Move to Register from Special Register
-    MRS {
-        params: MrsParams,
+    /// Unsigned Saturating Add and Subtract with Exchange
+    UQASX {
+        params: Reg3NoSetFlagsParams,
     },
-    /// Move to Special Register from ARM Register
-    MSR_reg {
-        params: MsrParams,
+    /// Unsigned Halving Add and Subtract with Exchange
+    UHASX {
+        params: Reg3NoSetFlagsParams,
+    },
+    /// Signed Subtract and Add with Exchange
+    SSAX {
+        params: Reg3NoSetFlagsParams,
+    },
+    /// Saturating Subtract and Add with Exchange
+    QSAX {
+        params: Reg3NoSetFlagsParams,
+    },
+    /// Signed Halving Subtract and Add with Exchange
+    SHSAX {
+        params: Reg3NoSetFlagsParams,
+    },
+    /// Unsigned Subtract and Add with Exchange
+    USAX {
+        params: Reg3NoSetFlagsParams,
+    },
+    /// Unsigned Saturating Subtract and Add with Exchange
+    UQSAX {
+        params: Reg3NoSetFlagsParams,
+    },
+    /// Unsigned Halving Subtract and Add with Exchange
+    UHSAX {
+        params: Reg3NoSetFlagsParams,
+    },
+    /// Signed Subtract 16
+    SSUB16 {
+        params: Reg3NoSetFlagsParams,
+    },
+    /// Saturating Subtract 16
+    QSUB16 {
+        params: Reg3NoSetFlagsParams,
+    },
+    /// Signed Halving Subtract 16
+    SHSUB16 {
+        params: Reg3NoSetFlagsParams,
+    },
+    /// Unsigned Subtract 16
+    USUB16 {
+        params: Reg3NoSetFlagsParams,
+    },
+    /// Unsigned Saturating Subtract 16
+    UQSUB16 {
+        params: Reg3NoSetFlagsParams,
+    },
+    /// Unsigned Halving Subtract 16
+    UHSUB16 {
+        params: Reg3NoSetFlagsParams,
+    },
+    /// Signed Add 8
+    SADD8 {
+        params: Reg3NoSetFlagsParams,
+    },
+    /// Saturating Add 8
+    QADD8 {
+        params: Reg3NoSetFlagsParams,
+    },
+    /// Signed Halving Add 8
+    SHADD8 {
+        params: Reg3NoSetFlagsParams,
+    },
+    /// Unsigned Add 8
+    UADD8 {
+        params: Reg3NoSetFlagsParams,
+    },
+    /// Unsigned Saturating Add 8
+    UQADD8 {
+        params: Reg3NoSetFlagsParams,
+    },
+    /// Unsigned Halving Add 8
+    UHADD8 {
+        params: Reg3NoSetFlagsParams,
+    },
+    /// Signed Subtract 8
+    SSUB8 {
+        params: Reg3NoSetFlagsParams,
+    },
+    /// Saturating Subtract 8
+    QSUB8 {
+        params: Reg3NoSetFlagsParams,
+    },
+    /// Signed Halving Subtract 8
+    SHSUB8 {
+        params: Reg3NoSetFlagsParams,
+    },
+    /// Unsigned Subtract 8
+    USUB8 {
+        params: Reg3NoSetFlagsParams,
+    },
+    /// Unsigned Saturating Subtract 8
+    UQSUB8 {
+        params: Reg3NoSetFlagsParams,
+    },
+    /// Unsigned Halving Subtract 8
+    UHSUB8 {
+        params: Reg3NoSetFlagsParams,
+    },
+
+    // --------------------------------------------
+    //
+    // Group: Miscellaneous data-processing instructions
+    //
+    // --------------------------------------------
+    /// Bit Field Clear
+    BFC {
+        params: BfcParams,
+    },
+    /// Bit Field Insert
+    BFI {
+        params: BfiParams,
+    },
+    /// Count Leading Zeros
+    CLZ {
+        params: Reg2RdRmParams,
+    },
+    /// Move Top
+    MOVT {
+        params: MovtParams,
+    },
+    // RBIT
+    /// Byte-reverse word
+    REV {
+        params: Reg2RdRmParams,
+        thumb32: bool,
+    },
+
+    /// Byte-reverse packed half-word
+    REV16 {
+        params: Reg2RdRmParams,
+        thumb32: bool,
+    },
+
+    /// Byte-reverse signed half-word
+    REVSH {
+        params: Reg2RdRmParams,
+        thumb32: bool,
+    },
+
+    //SBFX - signed bit field extract
+    /// Unsigned bit field extract
+    UBFX {
+        params: UbfxParams,
+    },
+
+    // --------------------------------------------
+    //
+    // Group: Miscellaneous data-processing instructions (DSP extensions)
+    //
+    // --------------------------------------------
+    /// Select bytes using GE flags
+    SEL {
+        params: Reg3NoSetFlagsParams,
+    },
+    /// Unsigned Sum of Absolute Differences
+    USAD8 {
+        params: Reg3NoSetFlagsParams,
+    },
+    /// Unsigned Sum of Absolute Differences and Accumulate
+    USADA8 {
+        params: Reg4NoSetFlagsParams,
+    },
+
+    // --------------------------------------------
+    //
+    // Group: Status register access instructions
+    //
+    // --------------------------------------------
+    /// Move to Register from Special Register
+    MRS {
+        params: MrsParams,
+    },
+    /// Move to Special Register from ARM Register
+    MSR_reg {
+        params: MsrParams,
     },
     /// Change Processor State
     CPS {
         im: bool,
-        #[cfg(any(armv7m, armv7em))]
         affect_pri: bool,
-        #[cfg(any(armv7m, armv7em))]
         affect_fault: bool,
     },
 
@@ -1061,7 +1528,8 @@ pub enum Instruction {
     // Group: Miscellaneous
     //
     // --------------------------------------------
-    //CLREX
+    /// Clear Exclusive
+    CLREX,
     //DBG
     /// Data Memory Barrier
     DMB,
@@ -1188,45 +1656,146 @@ pub enum Instruction {
     VSTR {
         params: VLoadAndStoreParams,
     },
-    // VLDM
-    // VPOP
-    // VPUSH
-    // VSTM
+    /// FP Load multiple
+    VLDM {
+        params: VLoadAndStoreMultipleParams,
+    },
+    /// FP Pop registers from the stack
+    VPOP {
+        params: VPushPopParams,
+    },
+    /// FP Push registers to the stack
+    VPUSH {
+        params: VPushPopParams,
+    },
+    /// FP Store multiple
+    VSTM {
+        params: VLoadAndStoreMultipleParams,
+    },
 
     // --------------------------------------------
     //
     // Group: Floating-point register transfer instructions
     //
     // --------------------------------------------
-
-    // VMOV
-    //VMRS
-    //VMRS
+    /// Move between ARM core register and single precision register
+    VMOV_cr_sp {
+        params: VMovCrSpParams,
+    },
+    /// Move between two ARM core registers and two single precision registers
+    VMOV_cr2_sp2 {
+        params: VMovCr2Sp2Params,
+    },
+    /// Move between two ARM core registers and a doubleword register
+    VMOV_cr2_dp {
+        params: VMovCr2DpParams,
+    },
+    /// Move between ARM core register and a half of a doubleword register
+    VMOV_cr_scalar {
+        params: VMovCrScalarParams,
+    },
+    /// Move FPSCR to ARM core register or to APSR flags
+    VMRS {
+        rt: Reg,
+    },
+    /// Move ARM core register to FPSCR
+    VMSR {
+        rt: Reg,
+    },
 
     // --------------------------------------------
     //
     // Group: Floating-point data-processing instructions
     //
     // --------------------------------------------
-    // VABS
-    //VADD
-    //VCMP
-    //VCVT
-    //VDIV
-    //VFMA
-    //VFNMA
-    //VMAXNM
-    //VMLA
-    //VMOV
-    //VMOV
-    //VMUL
-    //VNEG
-    //VNMLA
-    //VRINTA
-    //VRINTZ
-    //VSEL
-    //VSQRT
-    //VSUB
+    /// FP Absolute value
+    VABS {
+        params: VReg2Params,
+    },
+    /// FP Add
+    VADD {
+        params: VReg3Params,
+    },
+    /// FP Compare, optionally with exception on quiet NaNs (VCMPE)
+    VCMP {
+        params: VCmpParams,
+    },
+    /// FP Convert between floating point and integer
+    VCVT_int {
+        params: VCvtIntParams,
+    },
+    /// FP Convert between floating point and fixed point
+    VCVT_fixed {
+        params: VCvtFixedParams,
+    },
+    /// FP Convert between half and single precision (VCVTB, VCVTT)
+    VCVT_half {
+        params: VCvtHalfParams,
+    },
+    /// FP Divide
+    VDIV {
+        params: VReg3Params,
+    },
+    /// FP Fused multiply accumulate
+    VFMA {
+        params: VReg3Params,
+    },
+    /// FP Fused multiply subtract
+    VFMS {
+        params: VReg3Params,
+    },
+    /// FP Fused negate multiply accumulate
+    VFNMA {
+        params: VReg3Params,
+    },
+    /// FP Fused negate multiply subtract
+    VFNMS {
+        params: VReg3Params,
+    },
+    /// FP Multiply accumulate
+    VMLA {
+        params: VReg3Params,
+    },
+    /// FP Multiply subtract
+    VMLS {
+        params: VReg3Params,
+    },
+    /// FP Move immediate
+    VMOV_imm {
+        params: VMovImmParams,
+    },
+    /// FP Move register
+    VMOV_reg {
+        params: VReg2Params,
+    },
+    /// FP Multiply
+    VMUL {
+        params: VReg3Params,
+    },
+    /// FP Negate
+    VNEG {
+        params: VReg2Params,
+    },
+    /// FP Negate multiply accumulate
+    VNMLA {
+        params: VReg3Params,
+    },
+    /// FP Negate multiply subtract
+    VNMLS {
+        params: VReg3Params,
+    },
+    /// FP Negate multiply
+    VNMUL {
+        params: VReg3Params,
+    },
+    /// FP Square root
+    VSQRT {
+        params: VReg2Params,
+    },
+    /// FP Subtract
+    VSUB {
+        params: VReg3Params,
+    },
 }
 
 use std::fmt;
@@ -1329,6 +1898,22 @@ fn format_adressing_mode2(
     }
 }
 
+fn vreglist_to_str(dd: ExtensionReg, regs: u8) -> String {
+    if regs > 1 {
+        let last = match dd {
+            ExtensionReg::Single { reg } => ExtensionReg::Single {
+                reg: SingleReg::from(usize::from(reg) as u8 + regs - 1),
+            },
+            ExtensionReg::Double { reg } => ExtensionReg::Double {
+                reg: DoubleReg::from((usize::from(reg) / 2) as u8 + regs - 1),
+            },
+        };
+        format!("{{{}-{}}}", dd, last)
+    } else {
+        format!("{{{}}}", dd)
+    }
+}
+
 fn setflags_to_str(setflags: SetFlags) -> &'static str {
     match setflags {
         SetFlags::True => "s",
@@ -1573,9 +2158,6 @@ impl fmt::Display for Instruction {
                 }
             ),
 
-            #[cfg(any(armv6m))]
-            Self::CPS { im } => write!(f, "cps{} i", if im { "ID" } else { "IE" }),
-            #[cfg(any(armv7m, armv7em))]
             Self::CPS {
                 im,
                 affect_pri,
@@ -1587,6 +2169,7 @@ impl fmt::Display for Instruction {
                 if affect_pri { "i" } else { "" },
                 if affect_fault { "f" } else { "" }
             ),
+            Self::CLREX => write!(f, "clrex"),
             Self::DMB => write!(f, "dmb"),
             Self::DSB => write!(f, "dsb"),
             Self::EOR_reg { params, thumb32 } => write!(
@@ -2086,10 +2669,360 @@ impl fmt::Display for Instruction {
                 imm32, ref opcode, ..
             } => write!(f, "udf {} (opcode = {})", imm32, opcode),
 
+            Self::SEL { params } => write!(f, "sel {}, {}, {}", params.rd, params.rn, params.rm),
+            Self::SADD16 { params } => {
+                write!(f, "sadd16 {}, {}, {}", params.rd, params.rn, params.rm)
+            }
+            Self::QADD16 { params } => {
+                write!(f, "qadd16 {}, {}, {}", params.rd, params.rn, params.rm)
+            }
+            Self::SHADD16 { params } => {
+                write!(f, "shadd16 {}, {}, {}", params.rd, params.rn, params.rm)
+            }
+            Self::UADD16 { params } => {
+                write!(f, "uadd16 {}, {}, {}", params.rd, params.rn, params.rm)
+            }
+            Self::UQADD16 { params } => {
+                write!(f, "uqadd16 {}, {}, {}", params.rd, params.rn, params.rm)
+            }
+            Self::UHADD16 { params } => {
+                write!(f, "uhadd16 {}, {}, {}", params.rd, params.rn, params.rm)
+            }
+            Self::SASX { params } => write!(f, "sasx {}, {}, {}", params.rd, params.rn, params.rm),
+            Self::QASX { params } => write!(f, "qasx {}, {}, {}", params.rd, params.rn, params.rm),
+            Self::SHASX { params } => {
+                write!(f, "shasx {}, {}, {}", params.rd, params.rn, params.rm)
+            }
+            Self::UASX { params } => write!(f, "uasx {}, {}, {}", params.rd, params.rn, params.rm),
+            Self::UQASX { params } => {
+                write!(f, "uqasx {}, {}, {}", params.rd, params.rn, params.rm)
+            }
+            Self::UHASX { params } => {
+                write!(f, "uhasx {}, {}, {}", params.rd, params.rn, params.rm)
+            }
+            Self::SSAX { params } => write!(f, "ssax {}, {}, {}", params.rd, params.rn, params.rm),
+            Self::QSAX { params } => write!(f, "qsax {}, {}, {}", params.rd, params.rn, params.rm),
+            Self::SHSAX { params } => {
+                write!(f, "shsax {}, {}, {}", params.rd, params.rn, params.rm)
+            }
+            Self::USAX { params } => write!(f, "usax {}, {}, {}", params.rd, params.rn, params.rm),
+            Self::UQSAX { params } => {
+                write!(f, "uqsax {}, {}, {}", params.rd, params.rn, params.rm)
+            }
+            Self::UHSAX { params } => {
+                write!(f, "uhsax {}, {}, {}", params.rd, params.rn, params.rm)
+            }
+            Self::SSUB16 { params } => {
+                write!(f, "ssub16 {}, {}, {}", params.rd, params.rn, params.rm)
+            }
+            Self::QSUB16 { params } => {
+                write!(f, "qsub16 {}, {}, {}", params.rd, params.rn, params.rm)
+            }
+            Self::SHSUB16 { params } => {
+                write!(f, "shsub16 {}, {}, {}", params.rd, params.rn, params.rm)
+            }
+            Self::USUB16 { params } => {
+                write!(f, "usub16 {}, {}, {}", params.rd, params.rn, params.rm)
+            }
+            Self::UQSUB16 { params } => {
+                write!(f, "uqsub16 {}, {}, {}", params.rd, params.rn, params.rm)
+            }
+            Self::UHSUB16 { params } => {
+                write!(f, "uhsub16 {}, {}, {}", params.rd, params.rn, params.rm)
+            }
+            Self::SADD8 { params } => {
+                write!(f, "sadd8 {}, {}, {}", params.rd, params.rn, params.rm)
+            }
+            Self::QADD8 { params } => {
+                write!(f, "qadd8 {}, {}, {}", params.rd, params.rn, params.rm)
+            }
+            Self::SHADD8 { params } => {
+                write!(f, "shadd8 {}, {}, {}", params.rd, params.rn, params.rm)
+            }
             Self::UADD8 { params } => {
                 write!(f, "uadd8 {}, {}, {}", params.rd, params.rn, params.rm)
             }
-            Self::SEL { params } => write!(f, "sel {}, {}, {}", params.rd, params.rn, params.rm),
+            Self::UQADD8 { params } => {
+                write!(f, "uqadd8 {}, {}, {}", params.rd, params.rn, params.rm)
+            }
+            Self::UHADD8 { params } => {
+                write!(f, "uhadd8 {}, {}, {}", params.rd, params.rn, params.rm)
+            }
+            Self::SSUB8 { params } => {
+                write!(f, "ssub8 {}, {}, {}", params.rd, params.rn, params.rm)
+            }
+            Self::QSUB8 { params } => {
+                write!(f, "qsub8 {}, {}, {}", params.rd, params.rn, params.rm)
+            }
+            Self::SHSUB8 { params } => {
+                write!(f, "shsub8 {}, {}, {}", params.rd, params.rn, params.rm)
+            }
+            Self::USUB8 { params } => {
+                write!(f, "usub8 {}, {}, {}", params.rd, params.rn, params.rm)
+            }
+            Self::UQSUB8 { params } => {
+                write!(f, "uqsub8 {}, {}, {}", params.rd, params.rn, params.rm)
+            }
+            Self::UHSUB8 { params } => {
+                write!(f, "uhsub8 {}, {}, {}", params.rd, params.rn, params.rm)
+            }
+            Self::QADD { params } => write!(f, "qadd {}, {}, {}", params.rd, params.rm, params.rn),
+            Self::QSUB { params } => write!(f, "qsub {}, {}, {}", params.rd, params.rm, params.rn),
+            Self::QDADD { params } => {
+                write!(f, "qdadd {}, {}, {}", params.rd, params.rm, params.rn)
+            }
+            Self::QDSUB { params } => {
+                write!(f, "qdsub {}, {}, {}", params.rd, params.rm, params.rn)
+            }
+            Self::SMLAD { params } => write!(
+                f,
+                "smlad{} {}, {}, {}, {}",
+                if params.m_swap { "x" } else { "" },
+                params.rd,
+                params.rn,
+                params.rm,
+                params.ra
+            ),
+            Self::SMLSD { params } => write!(
+                f,
+                "smlsd{} {}, {}, {}, {}",
+                if params.m_swap { "x" } else { "" },
+                params.rd,
+                params.rn,
+                params.rm,
+                params.ra
+            ),
+            Self::SMUAD { params } => write!(
+                f,
+                "smuad{} {}, {}, {}",
+                if params.m_swap { "x" } else { "" },
+                params.rd,
+                params.rn,
+                params.rm
+            ),
+            Self::SMUSD { params } => write!(
+                f,
+                "smusd{} {}, {}, {}",
+                if params.m_swap { "x" } else { "" },
+                params.rd,
+                params.rn,
+                params.rm
+            ),
+            Self::SMLALD { params } => write!(
+                f,
+                "smlald{} {}, {}, {}, {}",
+                if params.m_swap { "x" } else { "" },
+                params.rdlo,
+                params.rdhi,
+                params.rn,
+                params.rm
+            ),
+            Self::SMLSLD { params } => write!(
+                f,
+                "smlsld{} {}, {}, {}, {}",
+                if params.m_swap { "x" } else { "" },
+                params.rdlo,
+                params.rdhi,
+                params.rn,
+                params.rm
+            ),
+            Self::SMMLA { params } => write!(
+                f,
+                "smmla{} {}, {}, {}, {}",
+                if params.round { "r" } else { "" },
+                params.rd,
+                params.rn,
+                params.rm,
+                params.ra
+            ),
+            Self::SMMLS { params } => write!(
+                f,
+                "smmls{} {}, {}, {}, {}",
+                if params.round { "r" } else { "" },
+                params.rd,
+                params.rn,
+                params.rm,
+                params.ra
+            ),
+            Self::SMMUL { params } => write!(
+                f,
+                "smmul{} {}, {}, {}",
+                if params.round { "r" } else { "" },
+                params.rd,
+                params.rn,
+                params.rm
+            ),
+            Self::SMLAW { params } => write!(
+                f,
+                "smlaw{} {}, {}, {}, {}",
+                if params.m_high { "T" } else { "B" },
+                params.rd,
+                params.rn,
+                params.rm,
+                params.ra
+            ),
+            Self::SMULW { params } => write!(
+                f,
+                "smulw{} {}, {}, {}",
+                if params.m_high { "T" } else { "B" },
+                params.rd,
+                params.rn,
+                params.rm
+            ),
+            Self::SMLAL_hw { params } => write!(
+                f,
+                "smlal{}{} {}, {}, {}, {}",
+                if params.n_high { "T" } else { "B" },
+                if params.m_high { "T" } else { "B" },
+                params.rdlo,
+                params.rdhi,
+                params.rn,
+                params.rm
+            ),
+            Self::UMAAL { params } => write!(
+                f,
+                "umaal {}, {}, {}, {}",
+                params.rdlo, params.rdhi, params.rn, params.rm
+            ),
+            Self::SSAT { params } => write!(
+                f,
+                "ssat {}, #{}, {}{}",
+                params.rd,
+                params.saturate_to,
+                params.rn,
+                if params.shift_n > 0 {
+                    format!(", {:?} {}", params.shift_t, params.shift_n)
+                } else {
+                    "".to_string()
+                }
+            ),
+            Self::USAT { params } => write!(
+                f,
+                "usat {}, #{}, {}{}",
+                params.rd,
+                params.saturate_to,
+                params.rn,
+                if params.shift_n > 0 {
+                    format!(", {:?} {}", params.shift_t, params.shift_n)
+                } else {
+                    "".to_string()
+                }
+            ),
+            Self::SSAT16 { params } => write!(
+                f,
+                "ssat16 {}, #{}, {}",
+                params.rd, params.saturate_to, params.rn
+            ),
+            Self::USAT16 { params } => write!(
+                f,
+                "usat16 {}, #{}, {}",
+                params.rd, params.saturate_to, params.rn
+            ),
+            Self::PKH { params } => write!(
+                f,
+                "pkh{} {}, {}, {}{}",
+                if params.shift_t == SRType::ASR {
+                    "tb"
+                } else {
+                    "bt"
+                },
+                params.rd,
+                params.rn,
+                params.rm,
+                if params.shift_n > 0 {
+                    format!(", {:?} {}", params.shift_t, params.shift_n)
+                } else {
+                    "".to_string()
+                }
+            ),
+            Self::SXTAB { params } => write!(
+                f,
+                "sxtab {}, {}, {}{}",
+                params.rd,
+                params.rn,
+                params.rm,
+                if params.rotation > 0 {
+                    format!(", ror #{}", params.rotation)
+                } else {
+                    "".to_string()
+                }
+            ),
+            Self::SXTAB16 { params } => write!(
+                f,
+                "sxtab16 {}, {}, {}{}",
+                params.rd,
+                params.rn,
+                params.rm,
+                if params.rotation > 0 {
+                    format!(", ror #{}", params.rotation)
+                } else {
+                    "".to_string()
+                }
+            ),
+            Self::SXTAH { params } => write!(
+                f,
+                "sxtah {}, {}, {}{}",
+                params.rd,
+                params.rn,
+                params.rm,
+                if params.rotation > 0 {
+                    format!(", ror #{}", params.rotation)
+                } else {
+                    "".to_string()
+                }
+            ),
+            Self::UXTAB16 { params } => write!(
+                f,
+                "uxtab16 {}, {}, {}{}",
+                params.rd,
+                params.rn,
+                params.rm,
+                if params.rotation > 0 {
+                    format!(", ror #{}", params.rotation)
+                } else {
+                    "".to_string()
+                }
+            ),
+            Self::UXTAH { params } => write!(
+                f,
+                "uxtah {}, {}, {}{}",
+                params.rd,
+                params.rn,
+                params.rm,
+                if params.rotation > 0 {
+                    format!(", ror #{}", params.rotation)
+                } else {
+                    "".to_string()
+                }
+            ),
+            Self::SXTB16 { params } => write!(
+                f,
+                "sxtb16 {}, {}{}",
+                params.rd,
+                params.rm,
+                if params.rotation > 0 {
+                    format!(", ror #{}", params.rotation)
+                } else {
+                    "".to_string()
+                }
+            ),
+            Self::UXTB16 { params } => write!(
+                f,
+                "uxtb16 {}, {}{}",
+                params.rd,
+                params.rm,
+                if params.rotation > 0 {
+                    format!(", ror #{}", params.rotation)
+                } else {
+                    "".to_string()
+                }
+            ),
+            Self::USAD8 { params } => write!(f, "usad8 {}, {}, {}", params.rd, params.rn, params.rm),
+            Self::USADA8 { params } => write!(
+                f,
+                "usada8 {}, {}, {}, {}",
+                params.rd, params.rn, params.rm, params.ra
+            ),
             // ARMv7-M
             Self::UDIV { params } => write!(f, "udiv {}, {}, {}", params.rd, params.rn, params.rm),
             Self::SDIV { params } => write!(f, "sdiv {}, {}, {}", params.rd, params.rn, params.rm),
@@ -2174,6 +3107,170 @@ impl fmt::Display for Instruction {
             ),
             Self::VLDR { params } => write!(f, "vldr {}, {}", params.dd, params.rn),
             Self::VSTR { params } => write!(f, "vstr {}, {}", params.dd, params.rn),
+            Self::VLDM { params } => write!(
+                f,
+                "vldm{} {}{}, {}",
+                if params.add { "ia" } else { "db" },
+                params.rn,
+                if params.wback { "!" } else { "" },
+                vreglist_to_str(params.dd, params.regs)
+            ),
+            Self::VSTM { params } => write!(
+                f,
+                "vstm{} {}{}, {}",
+                if params.add { "ia" } else { "db" },
+                params.rn,
+                if params.wback { "!" } else { "" },
+                vreglist_to_str(params.dd, params.regs)
+            ),
+            Self::VPUSH { params } => {
+                write!(f, "vpush {}", vreglist_to_str(params.dd, params.regs))
+            }
+            Self::VPOP { params } => write!(f, "vpop {}", vreglist_to_str(params.dd, params.regs)),
+            Self::VMOV_cr_sp { params } => {
+                if params.to_arm_register {
+                    write!(f, "vmov {}, {}", params.rt, params.sn)
+                } else {
+                    write!(f, "vmov {}, {}", params.sn, params.rt)
+                }
+            }
+            Self::VMOV_cr2_sp2 { params } => {
+                let sm1 = SingleReg::from(usize::from(params.sm) as u8 + 1);
+                if params.to_arm_registers {
+                    write!(
+                        f,
+                        "vmov {}, {}, {}, {}",
+                        params.rt, params.rt2, params.sm, sm1
+                    )
+                } else {
+                    write!(
+                        f,
+                        "vmov {}, {}, {}, {}",
+                        params.sm, sm1, params.rt, params.rt2
+                    )
+                }
+            }
+            Self::VMOV_cr2_dp { params } => {
+                if params.to_arm_registers {
+                    write!(f, "vmov {}, {}, {}", params.rt, params.rt2, params.dm)
+                } else {
+                    write!(f, "vmov {}, {}, {}", params.dm, params.rt, params.rt2)
+                }
+            }
+            Self::VMOV_cr_scalar { params } => {
+                if params.to_arm_register {
+                    write!(f, "vmov.32 {}, {}[{}]", params.rt, params.dd, params.x)
+                } else {
+                    write!(f, "vmov.32 {}[{}], {}", params.dd, params.x, params.rt)
+                }
+            }
+            Self::VMRS { rt } => {
+                if rt == Reg::PC {
+                    write!(f, "vmrs APSR_nzcv, fpscr")
+                } else {
+                    write!(f, "vmrs {}, fpscr", rt)
+                }
+            }
+            Self::VMSR { rt } => write!(f, "vmsr fpscr, {}", rt),
+            Self::VABS { params } => write!(f, "vabs.f32 {}, {}", params.sd, params.sm),
+            Self::VADD { params } => {
+                write!(f, "vadd.f32 {}, {}, {}", params.sd, params.sn, params.sm)
+            }
+            Self::VCMP { params } => write!(
+                f,
+                "vcmp{}.f32 {}, {}",
+                if params.quiet_nan_exc { "e" } else { "" },
+                params.sd,
+                match params.sm {
+                    Some(sm) => format!("{}", sm),
+                    None => "#0.0".to_string(),
+                }
+            ),
+            Self::VCVT_int { params } => {
+                let int_type = if params.signed { "s32" } else { "u32" };
+                if params.to_integer {
+                    write!(
+                        f,
+                        "vcvt{}.{}.f32 {}, {}",
+                        if params.round_zero { "" } else { "r" },
+                        int_type,
+                        params.sd,
+                        params.sm
+                    )
+                } else {
+                    write!(f, "vcvt.f32.{} {}, {}", int_type, params.sd, params.sm)
+                }
+            }
+            Self::VCVT_fixed { params } => {
+                let fixed_type =
+                    format!("{}{}", if params.unsigned { "u" } else { "s" }, params.size);
+                if params.to_fixed {
+                    write!(
+                        f,
+                        "vcvt.{}.f32 {}, {}, #{}",
+                        fixed_type, params.sd, params.sd, params.frac_bits
+                    )
+                } else {
+                    write!(
+                        f,
+                        "vcvt.f32.{} {}, {}, #{}",
+                        fixed_type, params.sd, params.sd, params.frac_bits
+                    )
+                }
+            }
+            Self::VCVT_half { params } => write!(
+                f,
+                "vcvt{}.{} {}, {}",
+                if params.top { "t" } else { "b" },
+                if params.to_half { "f16.f32" } else { "f32.f16" },
+                params.sd,
+                params.sm
+            ),
+            Self::VDIV { params } => {
+                write!(f, "vdiv.f32 {}, {}, {}", params.sd, params.sn, params.sm)
+            }
+            Self::VFMA { params } => {
+                write!(f, "vfma.f32 {}, {}, {}", params.sd, params.sn, params.sm)
+            }
+            Self::VFMS { params } => {
+                write!(f, "vfms.f32 {}, {}, {}", params.sd, params.sn, params.sm)
+            }
+            Self::VFNMA { params } => {
+                write!(f, "vfnma.f32 {}, {}, {}", params.sd, params.sn, params.sm)
+            }
+            Self::VFNMS { params } => {
+                write!(f, "vfnms.f32 {}, {}, {}", params.sd, params.sn, params.sm)
+            }
+            Self::VMLA { params } => {
+                write!(f, "vmla.f32 {}, {}, {}", params.sd, params.sn, params.sm)
+            }
+            Self::VMLS { params } => {
+                write!(f, "vmls.f32 {}, {}, {}", params.sd, params.sn, params.sm)
+            }
+            Self::VMOV_imm { params } => write!(
+                f,
+                "vmov.f32 {}, #{}",
+                params.sd,
+                f32::from_bits(params.imm32)
+            ),
+            Self::VMOV_reg { params } => write!(f, "vmov.f32 {}, {}", params.sd, params.sm),
+            Self::VMUL { params } => {
+                write!(f, "vmul.f32 {}, {}, {}", params.sd, params.sn, params.sm)
+            }
+            Self::VNEG { params } => write!(f, "vneg.f32 {}, {}", params.sd, params.sm),
+            Self::VNMLA { params } => {
+                write!(f, "vnmla.f32 {}, {}, {}", params.sd, params.sn, params.sm)
+            }
+            Self::VNMLS { params } => {
+                write!(f, "vnmls.f32 {}, {}, {}", params.sd, params.sn, params.sm)
+            }
+            Self::VNMUL { params } => {
+                write!(f, "vnmul.f32 {}, {}, {}", params.sd, params.sn, params.sm)
+            }
+            Self::VSQRT { params } => write!(f, "vsqrt.f32 {}, {}", params.sd, params.sm),
+            Self::VSUB { params } => {
+                write!(f, "vsub.f32 {}, {}, {}", params.sd, params.sn, params.sm)
+            }
 
             Self::WFE { .. } => write!(f, "wfe"),
             Self::WFI { .. } => write!(f, "wfi"),
@@ -2258,7 +3355,7 @@ pub fn instruction_size(instruction: &Instruction) -> usize {
         Instruction::CBZ { .. } => 2,
         Instruction::CBNZ { .. } => 2,
         //CDP
-        //CLREX
+        Instruction::CLREX => 4,
         Instruction::CLZ { .. } => 4,
         Instruction::CMN_imm { .. } => 4,
         Instruction::CMN_reg { thumb32, .. } => isize_t(*thumb32),
@@ -2329,7 +3426,7 @@ pub fn instruction_size(instruction: &Instruction) -> usize {
         Instruction::ORR_imm { .. } => 4,
         Instruction::ORR_reg { thumb32, .. } => isize_t(*thumb32),
 
-        //PKHBT, PKHTB
+        Instruction::PKH { .. } => 4,
         Instruction::PLD_imm { .. } => 4,
         Instruction::PLD_lit { .. } => 4,
         Instruction::PLD_reg { .. } => 4,
@@ -2338,16 +3435,16 @@ pub fn instruction_size(instruction: &Instruction) -> usize {
         Instruction::POP { thumb32, .. } => isize_t(*thumb32),
         Instruction::PUSH { thumb32, .. } => isize_t(*thumb32),
 
-        //QADD16
-        //QADD8
-        //QASX
-        //QSAX
-        //QADD
-        //QSUB
-        //QDADD
-        //QDSUB
-        //QSUB16
-        //QSUB8
+        Instruction::QADD16 { .. } => 4,
+        Instruction::QADD8 { .. } => 4,
+        Instruction::QASX { .. } => 4,
+        Instruction::QSAX { .. } => 4,
+        Instruction::QADD { .. } => 4,
+        Instruction::QSUB { .. } => 4,
+        Instruction::QDADD { .. } => 4,
+        Instruction::QDSUB { .. } => 4,
+        Instruction::QSUB16 { .. } => 4,
+        Instruction::QSUB8 { .. } => 4,
 
         //RBIT
         Instruction::REV { thumb32, .. } => isize_t(*thumb32),
@@ -2358,42 +3455,42 @@ pub fn instruction_size(instruction: &Instruction) -> usize {
         Instruction::RRX { .. } => 4,
         Instruction::RSB_imm { thumb32, .. } => isize_t(*thumb32),
         Instruction::RSB_reg { thumb32, .. } => 4,
-        //SADD16
-        //SADD8
-        //SASX
+        Instruction::SADD16 { .. } => 4,
+        Instruction::SADD8 { .. } => 4,
+        Instruction::SASX { .. } => 4,
         Instruction::SBC_imm { .. } => 4,
         Instruction::SBC_reg { thumb32, .. } => isize_t(*thumb32),
         //SBFX
         Instruction::SDIV { .. } => 4,
         Instruction::SEL { .. } => 4,
         Instruction::SEV { thumb32, .. } => isize_t(*thumb32),
-        //SHADD16
-        //SHADD8
-        //SHASX
-        //SHSAX
-        //SHSUB16
-        //SHSUB8
+        Instruction::SHADD16 { .. } => 4,
+        Instruction::SHADD8 { .. } => 4,
+        Instruction::SHASX { .. } => 4,
+        Instruction::SHSAX { .. } => 4,
+        Instruction::SHSUB16 { .. } => 4,
+        Instruction::SHSUB8 { .. } => 4,
         Instruction::SMLA { .. } => 4,
-        //SMLAD
+        Instruction::SMLAD { .. } => 4,
         Instruction::SMLAL { .. } => 4,
-        //SMLAL second variant?
-        //SMLALD
-        //SMLAW
-        //SMLSD
-        //SMLSLD
-        //SMMLA
-        //SMMLS
-        //SMMUL
-        //SMUAD
+        Instruction::SMLAL_hw { .. } => 4,
+        Instruction::SMLALD { .. } => 4,
+        Instruction::SMLAW { .. } => 4,
+        Instruction::SMLSD { .. } => 4,
+        Instruction::SMLSLD { .. } => 4,
+        Instruction::SMMLA { .. } => 4,
+        Instruction::SMMLS { .. } => 4,
+        Instruction::SMMUL { .. } => 4,
+        Instruction::SMUAD { .. } => 4,
         Instruction::SMUL { .. } => 4,
         Instruction::SMULL { .. } => 4,
-        //SMULW
-        //SMUSD
-        //SSAT
-        //SSAT16
-        //SSAX
-        //SSUB16
-        //SSUB8
+        Instruction::SMULW { .. } => 4,
+        Instruction::SMUSD { .. } => 4,
+        Instruction::SSAT { .. } => 4,
+        Instruction::SSAT16 { .. } => 4,
+        Instruction::SSAX { .. } => 4,
+        Instruction::SSUB16 { .. } => 4,
+        Instruction::SSUB8 { .. } => 4,
         //STC, STC2
         Instruction::STM { thumb32, .. } => isize_t(*thumb32),
         Instruction::STMDB { .. } => 4,
@@ -2413,11 +3510,11 @@ pub fn instruction_size(instruction: &Instruction) -> usize {
         Instruction::SUB_imm { thumb32, .. } => isize_t(*thumb32),
         Instruction::SUB_reg { thumb32, .. } => isize_t(*thumb32),
         Instruction::SVC { .. } => 2,
-        //SXTAB
-        //SXTAB16
-        //SXTAH
+        Instruction::SXTAB { .. } => 4,
+        Instruction::SXTAB16 { .. } => 4,
+        Instruction::SXTAH { .. } => 4,
         Instruction::SXTB { thumb32, .. } => isize_t(*thumb32),
-        //SXTB16
+        Instruction::SXTB16 { .. } => 4,
         Instruction::SXTH { thumb32, .. } => isize_t(*thumb32),
 
         Instruction::TBB { .. } => 4,
@@ -2428,74 +3525,80 @@ pub fn instruction_size(instruction: &Instruction) -> usize {
         Instruction::TST_reg { thumb32, .. } => isize_t(*thumb32),
 
         Instruction::UADD8 { .. } => 4,
-        //UADD16
-        //UASX
+        Instruction::UADD16 { .. } => 4,
+        Instruction::UASX { .. } => 4,
         Instruction::UBFX { .. } => 4,
         Instruction::UDF { thumb32, .. } => isize_t(*thumb32),
         Instruction::UDIV { .. } => 4,
-        //UHADD16
-        //UHADD8
-        //UHASX
-        //UHSAX
-        //UHSUB16
-        //UHSUB8
-        //UMAAL
+        Instruction::UHADD16 { .. } => 4,
+        Instruction::UHADD8 { .. } => 4,
+        Instruction::UHASX { .. } => 4,
+        Instruction::UHSAX { .. } => 4,
+        Instruction::UHSUB16 { .. } => 4,
+        Instruction::UHSUB8 { .. } => 4,
+        Instruction::UMAAL { .. } => 4,
         Instruction::UMLAL { .. } => 4,
         Instruction::UMULL { .. } => 4,
-        //UQADD16
-        //UQADD8
-        //UQASX
-        //UQSAX
-        //UQSUB16
-        //UQSUB8
-        //USAD8
-        //USADA8
-        //USAT
-        //USAT16
-        //USAX
-        //USUB16
-        //USUB8
+        Instruction::UQADD16 { .. } => 4,
+        Instruction::UQADD8 { .. } => 4,
+        Instruction::UQASX { .. } => 4,
+        Instruction::UQSAX { .. } => 4,
+        Instruction::UQSUB16 { .. } => 4,
+        Instruction::UQSUB8 { .. } => 4,
+        Instruction::USAD8 { .. } => 4,
+        Instruction::USADA8 { .. } => 4,
+        Instruction::USAT { .. } => 4,
+        Instruction::USAT16 { .. } => 4,
+        Instruction::USAX { .. } => 4,
+        Instruction::USUB16 { .. } => 4,
+        Instruction::USUB8 { .. } => 4,
         Instruction::UXTAB { .. } => 4,
-        //UXTAB16
-        //UXTAH
+        Instruction::UXTAB16 { .. } => 4,
+        Instruction::UXTAH { .. } => 4,
         Instruction::UXTB { thumb32, .. } => isize_t(*thumb32),
+        Instruction::UXTB16 { .. } => 4,
         Instruction::UXTH { thumb32, .. } => isize_t(*thumb32),
 
-        //VABS
-        //VADD
-        //VCMP
+        Instruction::VABS { .. } => 4,
+        Instruction::VADD { .. } => 4,
+        Instruction::VCMP { .. } => 4,
         //VCVTX
-        //VCVT
-        //VCVTB
-        //VCVTT
-        //VDIV
-        //VFMA
-        //VFMS
-        //VFNMA
-        //VFNMS
-        //VLDM
+        Instruction::VCVT_int { .. } => 4,
+        Instruction::VCVT_fixed { .. } => 4,
+        Instruction::VCVT_half { .. } => 4,
+        Instruction::VDIV { .. } => 4,
+        Instruction::VFMA { .. } => 4,
+        Instruction::VFMS { .. } => 4,
+        Instruction::VFNMA { .. } => 4,
+        Instruction::VFNMS { .. } => 4,
+        Instruction::VLDM { .. } => 4,
         //VMAXNM
         //VMINNM
-        //VMLA
-        //VMLS
-        //VMOV_imm
-        //VMON_reg
+        Instruction::VMLA { .. } => 4,
+        Instruction::VMLS { .. } => 4,
+        Instruction::VMOV_imm { .. } => 4,
+        Instruction::VMOV_reg { .. } => 4,
+        Instruction::VMOV_cr_sp { .. } => 4,
+        Instruction::VMOV_cr2_sp2 { .. } => 4,
+        Instruction::VMOV_cr2_dp { .. } => 4,
+        Instruction::VMOV_cr_scalar { .. } => 4,
         //VMOVX
-        //VMRS
-        //VMSR
-        //VMUL
-        //VNEG
-        //VNMLA,VNMLS, VNMUL
-        //VPOP
-        //VPUSH
+        Instruction::VMRS { .. } => 4,
+        Instruction::VMSR { .. } => 4,
+        Instruction::VMUL { .. } => 4,
+        Instruction::VNEG { .. } => 4,
+        Instruction::VNMLA { .. } => 4,
+        Instruction::VNMLS { .. } => 4,
+        Instruction::VNMUL { .. } => 4,
+        Instruction::VPOP { .. } => 4,
+        Instruction::VPUSH { .. } => 4,
         //VRINTA, VRINTN, VRINTP, VRiNTM
         //VRINTX,
         //VRINTZ, VRINTR
         //VSEL
-        //VSQRT
-        //VSTM
-        //VSTR
-        //VSUB
+        Instruction::VSQRT { .. } => 4,
+        Instruction::VSTM { .. } => 4,
+        Instruction::VSUB { .. } => 4,
         Instruction::WFE { thumb32, .. } => isize_t(*thumb32),
         Instruction::WFI { thumb32, .. } => isize_t(*thumb32),
         Instruction::YIELD { thumb32, .. } => isize_t(*thumb32),
@@ -0,0 +1,188 @@
+//!
+//! Processor lockup state
+//!
+//! The processor locks up when a fault happens in a context where it can
+//! not be handled, for example in the `HardFault` or NMI handler. A locked up
+//! core executes from address 0xEFFFFFFE until it is reset.
+//!
+
+use crate::core::bits::Bits;
+use crate::core::register::{BaseReg, Reg};
+use crate::core::reset::Reset;
+use crate::Processor;
+use std::fmt;
+
+/// Program counter value of a locked up processor
+pub const LOCKUP_ADDRESS: u32 = 0xEFFF_FFFE;
+
+///
+/// Action taken when the processor enters lockup state
+///
+#[derive(PartialEq, Debug, Copy, Clone)]
+pub enum LockupPolicy {
+    ///
+    /// Stop the simulation
+    ///
+    Stop,
+    ///
+    /// Reset the system and continue, like a watchdog would do
+    ///
+    Reset,
+}
+
+///
+/// Register state of the processor at the time it entered lockup state
+///
+#[derive(PartialEq, Debug, Copy, Clone)]
+pub struct LockupSnapshot {
+    /// address of the instruction that was executing when the lockup happened
+    pub pc: u32,
+    /// general purpose registers r0-r12
+    pub r0_12: [u32; 13],
+    /// current stack pointer
+    pub sp: u32,
+    /// link register
+    pub lr: u32,
+    /// combined program status register
+    pub xpsr: u32,
+    /// configurable fault status register
+    pub cfsr: u32,
+    /// hardfault status register
+    pub hfsr: u32,
+}
+
+impl fmt::Display for LockupSnapshot {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "PC:{:08X} XPSR:{:08X} CFSR:{:08X} HFSR:{:08X}",
+            self.pc, self.xpsr, self.cfsr, self.hfsr
+        )?;
+        for (i, r) in self.r0_12.iter().enumerate() {
+            write!(f, " R{}:{:08X}", i, r)?;
+        }
+        write!(f, " SP:{:08X} LR:{:08X}", self.sp, self.lr)
+    }
+}
+
+///
+/// Trait for entering and leaving the lockup state
+///
+pub trait Lockup {
+    ///
+    /// Enter lockup state after a fault that could not be handled
+    ///
+    fn lockup(&mut self);
+
+    ///
+    /// Check if the processor is in lockup state
+    ///
+    fn locked_up(&self) -> bool;
+
+    ///
+    /// Reset the system if the processor is in lockup state and the lockup
+    /// policy asks for it.
+    ///
+    fn check_lockup(&mut self);
+}
+
+impl Lockup for Processor {
+    fn lockup(&mut self) {
+        self.lockup_snapshot = Some(LockupSnapshot {
+            pc: self.get_pc(),
+            r0_12: self.r0_12,
+            sp: self.get_r(Reg::SP),
+            lr: self.get_r(Reg::LR),
+            xpsr: self.psr.value,
+            cfsr: self.cfsr,
+            hfsr: self.hfsr,
+        });
+        self.lockup_count += 1;
+        self.set_pc(LOCKUP_ADDRESS);
+        self.state.set_bit(3, true); // lockup
+        if self.lockup_policy == LockupPolicy::Stop {
+            self.state.set_bit(0, false); // stop the simulation
+        }
+    }
+
+    fn locked_up(&self) -> bool {
+        self.state.get_bit(3)
+    }
+
+    #[inline(always)]
+    fn check_lockup(&mut self) {
+        if self.locked_up() && self.lockup_policy == LockupPolicy::Reset {
+            // without a readable vector table there is nothing to restart
+            if self.system_reset().is_err() {
+                self.state.set_bit(0, false);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bus::Bus;
+    use crate::core::register::Epsr;
+
+    fn locked_processor(policy: LockupPolicy) -> Processor {
+        let mut code = [0; 0x100];
+        code[0..4].copy_from_slice(&0x2000_0400_u32.to_le_bytes());
+        code[4..8].copy_from_slice(&0x0000_0081_u32.to_le_bytes());
+
+        let mut processor = Processor::new();
+        processor.flash_memory(code.len(), &code);
+        processor.reset().unwrap();
+        processor.lockup_policy = policy;
+        processor.state = 1;
+        processor.set_r(Reg::R3, 0x1234);
+        processor.set_pc(0x90);
+        processor.cfsr = 1 << 16; // UNDEFINSTR
+        processor.hfsr = 1 << 30; // FORCED
+        processor.lockup();
+        processor
+    }
+
+    #[test]
+    fn test_lockup_stop() {
+        // Arrange & Act
+        let mut processor = locked_processor(LockupPolicy::Stop);
+        processor.check_lockup();
+
+        // Assert
+        assert!(processor.locked_up());
+        assert!(!processor.state.get_bit(0));
+        assert_eq!(processor.get_pc(), LOCKUP_ADDRESS);
+        assert_eq!(processor.lockup_count, 1);
+
+        let snapshot = processor.lockup_snapshot.unwrap();
+        assert_eq!(snapshot.pc, 0x90);
+        assert_eq!(snapshot.r0_12[3], 0x1234);
+        assert_eq!(snapshot.sp, 0x2000_0400);
+        assert_eq!(snapshot.cfsr, 1 << 16);
+        assert_eq!(snapshot.hfsr, 1 << 30);
+    }
+
+    #[test]
+    fn test_lockup_reset() {
+        // Arrange
+        let mut processor = locked_processor(LockupPolicy::Reset);
+        processor.write32(0x2000_0000, 0xcafe_f00d).unwrap();
+
+        // Act
+        processor.check_lockup();
+
+        // Assert
+        assert!(!processor.locked_up());
+        assert!(processor.state.get_bit(0));
+        assert_eq!(processor.get_pc(), 0x80);
+        assert!(processor.psr.get_t());
+        assert_eq!(processor.cfsr, 0);
+        assert_eq!(processor.hfsr, 0);
+        assert_eq!(processor.lockup_count, 1);
+        assert_eq!(processor.lockup_snapshot.unwrap().pc, 0x90);
+        // memory survives the reset
+        assert_eq!(processor.read32(0x2000_0000).unwrap(), 0xcafe_f00d);
+    }
+}
@@ -4,10 +4,14 @@
 
 pub mod bits;
 pub mod condition;
+pub mod cpu;
+pub mod event;
 pub mod exception;
 pub mod fault;
 pub mod fetch;
+pub mod fpu;
 pub mod instruction;
+pub mod lockup;
 pub mod monitor;
 pub mod operation;
 pub mod register;
@@ -9,8 +9,8 @@ use crate::Processor;
 ///
 pub trait Monitor {
     ///
-    /// check if there has not been changes to given `address` with given bit `size` since
-    /// last time monitors were set.
+    /// check if there has not been changes to given `address` with given byte `size` since
+    /// last time monitors were set. The local monitor is cleared by the check.
     ///
     /// Return
     ///  true if monitor pass (no changes to the given area), false otherwise.
@@ -21,12 +21,96 @@ pub trait Monitor {
     /// sets a monitor for load exclusive operation
     ///
     fn set_exclusive_monitors(&mut self, address: u32, size: usize);
+
+    ///
+    /// clears the local monitor, done by CLREX and on exception entry and return
+    ///
+    fn clear_exclusive_local(&mut self);
+
+    ///
+    /// clears the local monitor if a store to `address` with byte `size`
+    /// overlaps the monitored area
+    ///
+    fn monitor_store(&mut self, address: u32, size: usize);
 }
 
 impl Monitor for Processor {
-    fn exclusive_monitors_pass(&mut self, _address: u32, _size: usize) -> bool {
-        true
+    fn exclusive_monitors_pass(&mut self, address: u32, size: usize) -> bool {
+        let pass = self.exclusive_monitor == Some((address, size));
+        self.exclusive_monitor = None;
+        pass
+    }
+
+    fn set_exclusive_monitors(&mut self, address: u32, size: usize) {
+        self.exclusive_monitor = Some((address, size));
+    }
+
+    fn clear_exclusive_local(&mut self) {
+        self.exclusive_monitor = None;
     }
 
-    fn set_exclusive_monitors(&mut self, _address: u32, _size: usize) {}
+    #[inline(always)]
+    fn monitor_store(&mut self, address: u32, size: usize) {
+        if let Some((tagged, tagged_size)) = self.exclusive_monitor {
+            let start = u64::from(address);
+            let tagged_start = u64::from(tagged);
+            if start < tagged_start + tagged_size as u64 && tagged_start < start + size as u64 {
+                self.exclusive_monitor = None;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_exclusive_monitor_pass() {
+        // Arrange
+        let mut processor = Processor::new();
+        processor.set_exclusive_monitors(0x2000_0000, 4);
+
+        // Act & Assert
+        assert!(processor.exclusive_monitors_pass(0x2000_0000, 4));
+        assert!(!processor.exclusive_monitors_pass(0x2000_0000, 4));
+    }
+
+    #[test]
+    fn test_exclusive_monitor_mismatch() {
+        // Arrange
+        let mut processor = Processor::new();
+
+        // Act & Assert
+        processor.set_exclusive_monitors(0x2000_0000, 4);
+        assert!(!processor.exclusive_monitors_pass(0x2000_0004, 4));
+
+        processor.set_exclusive_monitors(0x2000_0000, 4);
+        assert!(!processor.exclusive_monitors_pass(0x2000_0000, 2));
+
+        processor.set_exclusive_monitors(0x2000_0000, 4);
+        processor.clear_exclusive_local();
+        assert!(!processor.exclusive_monitors_pass(0x2000_0000, 4));
+    }
+
+    #[test]
+    fn test_exclusive_monitor_store() {
+        // Arrange
+        let mut processor = Processor::new();
+        processor.set_exclusive_monitors(0x2000_0004, 4);
+
+        // Act
+        processor.monitor_store(0x2000_0000, 4);
+        processor.monitor_store(0x2000_0008, 1);
+
+        // Assert
+        assert!(processor.exclusive_monitors_pass(0x2000_0004, 4));
+
+        // Act
+        processor.set_exclusive_monitors(0x2000_0004, 4);
+        processor.monitor_store(0x2000_0007, 1);
+
+        // Assert
+        assert!(!processor.exclusive_monitors_pass(0x2000_0004, 4));
+    }
 }
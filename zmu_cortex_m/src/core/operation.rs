@@ -96,6 +96,39 @@ pub fn add_with_carry(x: u32, y: u32, carry_in: bool) -> (u32, bool, bool) {
     (result, carry_out, overflow)
 }
 
+///
+/// Saturate a signed value to a N bit signed integer
+///
+/// return tuple of (result, saturated)
+///
+pub fn signed_sat_q(value: i64, n: usize) -> (i32, bool) {
+    let max = (1_i64 << (n - 1)) - 1;
+    let min = -(1_i64 << (n - 1));
+    if value > max {
+        (max as i32, true)
+    } else if value < min {
+        (min as i32, true)
+    } else {
+        (value as i32, false)
+    }
+}
+
+///
+/// Saturate a signed value to a N bit unsigned integer
+///
+/// return tuple of (result, saturated)
+///
+pub fn unsigned_sat_q(value: i64, n: usize) -> (u32, bool) {
+    let max = (1_i64 << n) - 1;
+    if value > max {
+        (max as u32, true)
+    } else if value < 0 {
+        (0, true)
+    } else {
+        (value as u32, false)
+    }
+}
+
 ///
 /// This function performs the condition test for an instruction, based on:
 /// • the two Thumb conditional branch encodings, encodings T1 and T3 of the B instruction
@@ -470,6 +503,22 @@ mod tests {
         assert_eq!(carry, true);
         assert_eq!(overflow, false);
     }
+
+    #[test]
+    fn test_signed_sat_q() {
+        assert_eq!(signed_sat_q(0x7fff, 16), (0x7fff, false));
+        assert_eq!(signed_sat_q(0x8000, 16), (0x7fff, true));
+        assert_eq!(signed_sat_q(-0x8001, 16), (-0x8000, true));
+        assert_eq!(signed_sat_q(i64::from(i32::MIN), 32), (i32::MIN, false));
+    }
+
+    #[test]
+    fn test_unsigned_sat_q() {
+        assert_eq!(unsigned_sat_q(0xff, 8), (0xff, false));
+        assert_eq!(unsigned_sat_q(0x100, 8), (0xff, true));
+        assert_eq!(unsigned_sat_q(-1, 8), (0, true));
+        assert_eq!(unsigned_sat_q(5, 0), (0, true));
+    }
     #[test]
     fn test_build_imm_6_11() {
         assert_eq!(build_imm_6_11(0xF00080C4), 0xc4 << 1);
@@ -514,25 +514,13 @@ impl Epsr for PSR {
 }
 
 impl Ipsr for PSR {
-    #[cfg(any(armv7m, armv7em))]
     fn get_isr_number(&self) -> usize {
         (*self).value.get_bits(0..9) as usize
     }
 
-    #[cfg(any(armv6m))]
-    fn get_isr_number(&self) -> usize {
-        (*self).value.get_bits(0..6) as usize
-    }
-
-    #[cfg(any(armv7m, armv7em))]
     fn set_isr_number(&mut self, exception_number: usize) {
         self.value.set_bits(0..9, exception_number as u32);
     }
-
-    #[cfg(any(armv6m))]
-    fn set_isr_number(&mut self, exception_number: usize) {
-        self.value.set_bits(0..6, exception_number as u32);
-    }
 }
 
 #[derive(Copy, Clone, PartialEq, Debug)]
@@ -1066,18 +1054,20 @@ impl fmt::Display for SpecialReg {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Copy, Clone)]
 /// CONTROL register parts
 pub struct Control {
     /// Thread mode priviledge level
     pub n_priv: bool,
     /// selection of current active stack pointer, true = PSP, false = MSP
     pub sp_sel: bool,
+    /// floating point context active, always false without the FP extension
+    pub fpca: bool,
 }
 
 impl From<Control> for u8 {
     fn from(control: Control) -> Self {
-        control.n_priv as Self + ((control.sp_sel as Self) << 1)
+        control.n_priv as Self + ((control.sp_sel as Self) << 1) + ((control.fpca as Self) << 2)
     }
 }
 
@@ -1175,7 +1165,7 @@ impl From<u8> for DoubleReg {
 
 impl From<u8> for SingleReg {
     fn from(value: u8) -> Self {
-        match value & 0xf {
+        match value & 0x1f {
             0 => Self::S0,
             1 => Self::S1,
             2 => Self::S2,
@@ -3,9 +3,13 @@
 //!
 
 use crate::bus::Bus;
-use crate::core::exception::ExceptionHandling;
+use crate::core::bits::Bits;
+use crate::core::exception::{Exception, ExceptionHandling};
 use crate::core::fault::Fault;
+use crate::core::monitor::Monitor;
 use crate::core::register::{BaseReg, PSR};
+use crate::peripheral::dcb::{DebugControlBlock, DHCSR_S_RESET_ST};
+use crate::peripheral::scb::ccr_reset;
 use crate::Processor;
 use crate::ProcessorMode;
 
@@ -15,6 +19,12 @@ pub trait Reset {
     /// Reset Processor
     ///
     fn reset(&mut self) -> Result<(), Fault>;
+
+    ///
+    /// Reset the processor together with the system control space, as done
+    /// by a watchdog. Memory contents and debug logic are preserved.
+    ///
+    fn system_reset(&mut self) -> Result<(), Fault>;
 }
 
 impl Reset for Processor {
@@ -42,24 +52,53 @@ impl Reset for Processor {
         self.psr = PSR { value: 0 };
         self.primask = false;
 
-        #[cfg(any(armv7m, armv7em))]
-        {
-            self.faultmask = false;
-        }
+        self.faultmask = false;
         self.basepri = 0;
         self.control.sp_sel = false;
         self.control.n_priv = false;
+        self.control.fpca = false;
 
         //TODO self.scs.reset();
         self.exceptions_reset();
 
-        //self.event_reg.clear();
+        self.event_register = false;
+        self.waiting_for_event = false;
 
         self.itstate = 0;
+        self.clear_exclusive_local();
         self.execution_priority = self.get_execution_priority();
 
         let reset_vector = self.read32(vtor + 4)?;
         self.blx_write_pc(reset_vector);
+
+        // debug logic is not affected by a core reset
+        self.dhcsr |= DHCSR_S_RESET_ST;
+        self.debug_vector_catch(Exception::Reset);
+
+        self.state.set_bit(1, false); // not sleeping
+        self.state.set_bit(3, false); // reset leaves the lockup state
         Ok(())
     }
+
+    fn system_reset(&mut self) -> Result<(), Fault> {
+        self.vtor = 0;
+        self.aircr = 0;
+        self.scr = 0;
+        self.ccr = ccr_reset(self.cpu);
+        self.shcsr = 0;
+        self.cfsr = 0;
+        self.hfsr = 0;
+        self.mmfar = 0;
+        self.bfar = 0;
+
+        self.nvic_interrupt_enabled = [0; 16];
+        self.nvic_interrupt_pending = [0; 16];
+        self.pending_exception_count = 0;
+
+        self.syst_csr = 0;
+        self.syst_rvr = 0;
+        self.syst_cvr = 0;
+
+        self.reset()
+    }
 }
@@ -0,0 +1,68 @@
+use crate::core::bits::Bits;
+use crate::core::instruction::{Instruction, Reg3NoSetFlagsParams};
+
+#[allow(non_snake_case)]
+pub fn decode_SADD16_t1(opcode: u32) -> Instruction {
+    Instruction::SADD16 {
+        params: Reg3NoSetFlagsParams {
+            rd: opcode.get_bits(8..12).into(),
+            rn: opcode.get_bits(16..20).into(),
+            rm: opcode.get_bits(0..4).into(),
+        },
+    }
+}
+
+#[allow(non_snake_case)]
+pub fn decode_QADD16_t1(opcode: u32) -> Instruction {
+    Instruction::QADD16 {
+        params: Reg3NoSetFlagsParams {
+            rd: opcode.get_bits(8..12).into(),
+            rn: opcode.get_bits(16..20).into(),
+            rm: opcode.get_bits(0..4).into(),
+        },
+    }
+}
+
+#[allow(non_snake_case)]
+pub fn decode_SHADD16_t1(opcode: u32) -> Instruction {
+    Instruction::SHADD16 {
+        params: Reg3NoSetFlagsParams {
+            rd: opcode.get_bits(8..12).into(),
+            rn: opcode.get_bits(16..20).into(),
+            rm: opcode.get_bits(0..4).into(),
+        },
+    }
+}
+
+#[allow(non_snake_case)]
+pub fn decode_UADD16_t1(opcode: u32) -> Instruction {
+    Instruction::UADD16 {
+        params: Reg3NoSetFlagsParams {
+            rd: opcode.get_bits(8..12).into(),
+            rn: opcode.get_bits(16..20).into(),
+            rm: opcode.get_bits(0..4).into(),
+        },
+    }
+}
+
+#[allow(non_snake_case)]
+pub fn decode_UQADD16_t1(opcode: u32) -> Instruction {
+    Instruction::UQADD16 {
+        params: Reg3NoSetFlagsParams {
+            rd: opcode.get_bits(8..12).into(),
+            rn: opcode.get_bits(16..20).into(),
+            rm: opcode.get_bits(0..4).into(),
+        },
+    }
+}
+
+#[allow(non_snake_case)]
+pub fn decode_UHADD16_t1(opcode: u32) -> Instruction {
+    Instruction::UHADD16 {
+        params: Reg3NoSetFlagsParams {
+            rd: opcode.get_bits(8..12).into(),
+            rn: opcode.get_bits(16..20).into(),
+            rm: opcode.get_bits(0..4).into(),
+        },
+    }
+}
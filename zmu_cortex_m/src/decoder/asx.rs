@@ -0,0 +1,68 @@
+use crate::core::bits::Bits;
+use crate::core::instruction::{Instruction, Reg3NoSetFlagsParams};
+
+#[allow(non_snake_case)]
+pub fn decode_SASX_t1(opcode: u32) -> Instruction {
+    Instruction::SASX {
+        params: Reg3NoSetFlagsParams {
+            rd: opcode.get_bits(8..12).into(),
+            rn: opcode.get_bits(16..20).into(),
+            rm: opcode.get_bits(0..4).into(),
+        },
+    }
+}
+
+#[allow(non_snake_case)]
+pub fn decode_QASX_t1(opcode: u32) -> Instruction {
+    Instruction::QASX {
+        params: Reg3NoSetFlagsParams {
+            rd: opcode.get_bits(8..12).into(),
+            rn: opcode.get_bits(16..20).into(),
+            rm: opcode.get_bits(0..4).into(),
+        },
+    }
+}
+
+#[allow(non_snake_case)]
+pub fn decode_SHASX_t1(opcode: u32) -> Instruction {
+    Instruction::SHASX {
+        params: Reg3NoSetFlagsParams {
+            rd: opcode.get_bits(8..12).into(),
+            rn: opcode.get_bits(16..20).into(),
+            rm: opcode.get_bits(0..4).into(),
+        },
+    }
+}
+
+#[allow(non_snake_case)]
+pub fn decode_UASX_t1(opcode: u32) -> Instruction {
+    Instruction::UASX {
+        params: Reg3NoSetFlagsParams {
+            rd: opcode.get_bits(8..12).into(),
+            rn: opcode.get_bits(16..20).into(),
+            rm: opcode.get_bits(0..4).into(),
+        },
+    }
+}
+
+#[allow(non_snake_case)]
+pub fn decode_UQASX_t1(opcode: u32) -> Instruction {
+    Instruction::UQASX {
+        params: Reg3NoSetFlagsParams {
+            rd: opcode.get_bits(8..12).into(),
+            rn: opcode.get_bits(16..20).into(),
+            rm: opcode.get_bits(0..4).into(),
+        },
+    }
+}
+
+#[allow(non_snake_case)]
+pub fn decode_UHASX_t1(opcode: u32) -> Instruction {
+    Instruction::UHASX {
+        params: Reg3NoSetFlagsParams {
+            rd: opcode.get_bits(8..12).into(),
+            rn: opcode.get_bits(16..20).into(),
+            rm: opcode.get_bits(0..4).into(),
+        },
+    }
+}
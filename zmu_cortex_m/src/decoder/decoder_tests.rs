@@ -3,13 +3,19 @@ use crate::core::instruction::{
     Reg2DoubleParams, Reg2FullParams, Reg2ImmCarryParams, Reg2ImmParams, Reg2Params,
     Reg2RdRmParams, Reg2RnRmParams, Reg2RtRnImm32Params, Reg2ShiftNParams,
     Reg2ShiftNoSetFlagsParams, Reg2ShiftParams, Reg2UsizeParams, Reg3FullParams, Reg3HighParams,
-    Reg3NoSetFlagsParams, Reg3Params, Reg3RdRtRnImm32Params, Reg3ShiftParams, Reg3UsizeParams,
-    Reg4HighParams, Reg4NoSetFlagsParams, Reg643232Params, RegImm32AddParams,
-    RegImmCarryNoSetFlagsParams, RegImmCarryParams, RegImmParams, SRType, SetFlags, UbfxParams, VLoadAndStoreParams,
+    Reg3NoSetFlagsParams, Reg3Params, Reg3RdRtRnImm32Params, Reg3RoundParams,
+    Reg3ShiftNoSetFlagsParams, Reg3ShiftParams, Reg3SwapParams, Reg3UsizeParams,
+    Reg3WordHighParams, Reg4HighParams, Reg4NoSetFlagsParams, Reg4RoundParams, Reg4SwapParams,
+    Reg643232HighParams, Reg643232Params, Reg643232SwapParams, RegImm32AddParams,
+    RegImmCarryNoSetFlagsParams, RegImmCarryParams, RegImmParams, SRType, Saturate16Params,
+    SaturateParams, SetFlags, UbfxParams,
+    VCmpParams, VCvtFixedParams, VCvtHalfParams, VCvtIntParams, VLoadAndStoreMultipleParams,
+    VLoadAndStoreParams, VMovCr2DpParams, VMovCr2Sp2Params, VMovCrScalarParams, VMovCrSpParams,
+    VMovImmParams, VPushPopParams, VReg2Params, VReg3Params,
 };
 
 use super::*;
-use crate::core::register::{DoubleReg, ExtensionReg, Reg};
+use crate::core::register::{DoubleReg, ExtensionReg, Reg, SingleReg};
 
 #[test]
 fn test_is_thumb32() {
@@ -1124,14 +1130,6 @@ fn test_decode_mrs() {
     );
 }*/
 
-#[cfg(any(armv6m))]
-#[test]
-fn test_decode_cpsid() {
-    // CPSID i
-    assert_eq!(decode_16(0xB672), Instruction::CPS { im: true });
-}
-
-#[cfg(any(armv7m, armv7em))]
 #[test]
 fn test_decode_cpsid() {
     // CPSID i
@@ -2687,14 +2685,14 @@ fn test_decode_sel() {
 }
 
 #[test]
-fn test_decode_tbh() {
-    // e8df f013       tbh     [pc, r3, lsl #1]
-
+fn test_decode_sadd16() {
+    // 0xfa92f103 sadd16 r1, r2, r3
     assert_eq!(
-        decode_32(0xe8dff013),
-        Instruction::TBH {
-            params: Reg2RnRmParams {
-                rn: Reg::PC,
+        decode_32(0xfa92f103),
+        Instruction::SADD16 {
+            params: Reg3NoSetFlagsParams {
+                rd: Reg::R1,
+                rn: Reg::R2,
                 rm: Reg::R3,
             }
         }
@@ -2702,201 +2700,1283 @@ fn test_decode_tbh() {
 }
 
 #[test]
-fn test_decode_movt() {
-    // f2c2 0100       movt    r1, #8192
+fn test_decode_qsub8() {
+    // 0xfac2f113 qsub8 r1, r2, r3
+    assert_eq!(
+        decode_32(0xfac2f113),
+        Instruction::QSUB8 {
+            params: Reg3NoSetFlagsParams {
+                rd: Reg::R1,
+                rn: Reg::R2,
+                rm: Reg::R3,
+            }
+        }
+    );
+}
 
+#[test]
+fn test_decode_uhasx() {
+    // 0xfaa2f163 uhasx r1, r2, r3
     assert_eq!(
-        decode_32(0xf2c20100),
-        Instruction::MOVT {
-            params: MovtParams {
+        decode_32(0xfaa2f163),
+        Instruction::UHASX {
+            params: Reg3NoSetFlagsParams {
                 rd: Reg::R1,
-                imm16: 0x2000
+                rn: Reg::R2,
+                rm: Reg::R3,
             }
         }
     );
 }
 
 #[test]
-fn test_decode_mvn_reg_w() {
-    // ea6f 5507       mvn.w   r5, r7, lsl #20
+fn test_decode_shsax() {
+    // 0xfae2f123 shsax r1, r2, r3
+    assert_eq!(
+        decode_32(0xfae2f123),
+        Instruction::SHSAX {
+            params: Reg3NoSetFlagsParams {
+                rd: Reg::R1,
+                rn: Reg::R2,
+                rm: Reg::R3,
+            }
+        }
+    );
+}
 
+#[test]
+fn test_decode_uqsub16() {
+    // 0xfad2f153 uqsub16 r1, r2, r3
     assert_eq!(
-        decode_32(0xea6f5507),
-        Instruction::MVN_reg {
-            params: Reg2ShiftParams {
-                rd: Reg::R5,
-                rm: Reg::R7,
-                setflags: SetFlags::False,
-                shift_n: 20,
-                shift_t: SRType::LSL,
-            },
-            thumb32: true,
+        decode_32(0xfad2f153),
+        Instruction::UQSUB16 {
+            params: Reg3NoSetFlagsParams {
+                rd: Reg::R1,
+                rn: Reg::R2,
+                rm: Reg::R3,
+            }
         }
     );
 }
 
 #[test]
-fn test_decode_teq_w() {
-    //f090 0f00       teq     r0, #0
+fn test_decode_qdadd() {
+    // 0xfa83f192 qdadd r1, r2, r3
     assert_eq!(
-        decode_32(0xf0900f00),
-        Instruction::TEQ_imm {
-            params: RegImmCarryNoSetFlagsParams {
-                rn: Reg::R0,
-                imm32: Imm32Carry::Carry {
-                    imm32_c0: (0, false),
-                    imm32_c1: (0, true),
-                },
+        decode_32(0xfa83f192),
+        Instruction::QDADD {
+            params: Reg3NoSetFlagsParams {
+                rd: Reg::R1,
+                rn: Reg::R3,
+                rm: Reg::R2,
             }
         }
     );
 }
 
 #[test]
-fn test_decode_mov_rxx_w() {
-    //ea4f 0232       mov.w   r2, r2, rrx
+fn test_decode_qsub() {
+    // 0xfa83f1a2 qsub r1, r2, r3
     assert_eq!(
-        decode_32(0xea4f0232),
-        Instruction::RRX {
-            params: Reg2Params {
-                rd: Reg::R2,
+        decode_32(0xfa83f1a2),
+        Instruction::QSUB {
+            params: Reg3NoSetFlagsParams {
+                rd: Reg::R1,
+                rn: Reg::R3,
                 rm: Reg::R2,
-                setflags: false,
             }
         }
     );
 }
 
 #[test]
-fn test_decode_str_imm_t4() {
-    //f84d cd04       str.w   ip, [sp, #-4]!
-    // => same as PUSH r12
-    match decode_32(0xf84dcd04) {
-        Instruction::PUSH { registers, thumb32 } => {
-            let elems: Vec<_> = registers.iter().collect();
-            assert_eq!(vec![Reg::R12], elems);
-            assert_eq!(thumb32, true);
+fn test_decode_usad8() {
+    // 0xfb72f103 usad8 r1, r2, r3
+    assert_eq!(
+        decode_32(0xfb72f103),
+        Instruction::USAD8 {
+            params: Reg3NoSetFlagsParams {
+                rd: Reg::R1,
+                rn: Reg::R2,
+                rm: Reg::R3,
+            }
         }
-        _ => {
-            assert!(false);
+    );
+}
+
+#[test]
+fn test_decode_smladx() {
+    // 0xfb224113 smladx r1, r2, r3, r4
+    assert_eq!(
+        decode_32(0xfb224113),
+        Instruction::SMLAD {
+            params: Reg4SwapParams {
+                rd: Reg::R1,
+                rn: Reg::R2,
+                rm: Reg::R3,
+                ra: Reg::R4,
+                m_swap: true,
+            }
         }
-    }
+    );
 }
 
 #[test]
-fn test_decode_subw_imm_t4() {
-    // f2a4 4333       subw    r3, r4, #1075   ; 0x433
+fn test_decode_smuad() {
+    // 0xfb22f103 smuad r1, r2, r3
     assert_eq!(
-        decode_32(0xf2a44333),
-        Instruction::SUB_imm {
-            params: Reg2ImmParams {
-                rd: Reg::R3,
-                rn: Reg::R4,
-                imm32: 1075,
-                setflags: SetFlags::False,
-            },
-            thumb32: true,
+        decode_32(0xfb22f103),
+        Instruction::SMUAD {
+            params: Reg3SwapParams {
+                rd: Reg::R1,
+                rn: Reg::R2,
+                rm: Reg::R3,
+                m_swap: false,
+            }
         }
     );
 }
 
 #[test]
-fn test_decode_asrw_reg_t2() {
-    //  fa43 f305       asr.w   r3, r3, r5
+fn test_decode_smusdx() {
+    // 0xfb42f113 smusdx r1, r2, r3
     assert_eq!(
-        decode_32(0xfa43f305),
-        Instruction::ASR_reg {
-            params: Reg3Params {
-                rd: Reg::R3,
+        decode_32(0xfb42f113),
+        Instruction::SMUSD {
+            params: Reg3SwapParams {
+                rd: Reg::R1,
+                rn: Reg::R2,
+                rm: Reg::R3,
+                m_swap: true,
+            }
+        }
+    );
+}
+
+#[test]
+fn test_decode_smlald() {
+    // 0xfbc312c4 smlald r1, r2, r3, r4
+    assert_eq!(
+        decode_32(0xfbc312c4),
+        Instruction::SMLALD {
+            params: Reg643232SwapParams {
+                rdlo: Reg::R1,
+                rdhi: Reg::R2,
                 rn: Reg::R3,
-                rm: Reg::R5,
-                setflags: SetFlags::False,
-            },
-            thumb32: true,
+                rm: Reg::R4,
+                m_swap: false,
+            }
         }
     );
 }
 
 #[test]
-fn test_decode_dmb() {
-    //  f3bf 8f5f       dmb sy
-    assert_eq!(decode_32(0xf3bf8f5f), Instruction::DMB);
+fn test_decode_smlsldx() {
+    // 0xfbd312d4 smlsldx r1, r2, r3, r4
+    assert_eq!(
+        decode_32(0xfbd312d4),
+        Instruction::SMLSLD {
+            params: Reg643232SwapParams {
+                rdlo: Reg::R1,
+                rdhi: Reg::R2,
+                rn: Reg::R3,
+                rm: Reg::R4,
+                m_swap: true,
+            }
+        }
+    );
 }
 
 #[test]
-fn test_decode_ldrex() {
-    //  e850 3f00       ldrex   r3, [r0]
+fn test_decode_smlalbt() {
+    // 0xfbc31294 smlalbt r1, r2, r3, r4
     assert_eq!(
-        decode_32(0xe8503f00),
-        Instruction::LDREX {
-            params: Reg2RtRnImm32Params {
-                rt: Reg::R3,
-                rn: Reg::R0,
-                imm32: 0,
+        decode_32(0xfbc31294),
+        Instruction::SMLAL_hw {
+            params: Reg643232HighParams {
+                rdlo: Reg::R1,
+                rdhi: Reg::R2,
+                rn: Reg::R3,
+                rm: Reg::R4,
+                n_high: false,
+                m_high: true,
             }
         }
     );
 }
 
 #[test]
-fn test_decode_strex() {
-    //  e840 2c00       strex   ip, r2, [r0]
+fn test_decode_smmlar() {
+    // 0xfb524113 smmlar r1, r2, r3, r4
     assert_eq!(
-        decode_32(0xe8402c00),
-        Instruction::STREX {
-            params: Reg3RdRtRnImm32Params {
-                rd: Reg::R12,
-                rt: Reg::R2,
-                rn: Reg::R0,
-                imm32: 0,
+        decode_32(0xfb524113),
+        Instruction::SMMLA {
+            params: Reg4RoundParams {
+                rd: Reg::R1,
+                rn: Reg::R2,
+                rm: Reg::R3,
+                ra: Reg::R4,
+                round: true,
             }
         }
     );
 }
 
 #[test]
-fn test_decode_bfc() {
-    //  f36f 011f       bfc     r1, #0, #32
+fn test_decode_smmls() {
+    // 0xfb624103 smmls r1, r2, r3, r4
     assert_eq!(
-        decode_32(0xf36f011f),
-        Instruction::BFC {
-            params: BfcParams {
+        decode_32(0xfb624103),
+        Instruction::SMMLS {
+            params: Reg4RoundParams {
                 rd: Reg::R1,
-                lsbit: 0,
-                msbit: 31,
+                rn: Reg::R2,
+                rm: Reg::R3,
+                ra: Reg::R4,
+                round: false,
             }
         }
     );
 }
 
 #[test]
-fn test_decode_vldr() {
-    //  ed9f 7b86       vldr    d7, [pc, #536]  ; 448 <_vfprintf_r+0x290>
+fn test_decode_smmul() {
+    // 0xfb52f103 smmul r1, r2, r3
     assert_eq!(
-        decode_32(0xed9f7b86),
-        Instruction::VLDR {
-            params: VLoadAndStoreParams {
-                dd: ExtensionReg::Double { reg: DoubleReg::D7 },
-                rn: Reg::PC,
-                add: true,
-                imm32: 0x86 << 2,
+        decode_32(0xfb52f103),
+        Instruction::SMMUL {
+            params: Reg3RoundParams {
+                rd: Reg::R1,
+                rn: Reg::R2,
+                rm: Reg::R3,
+                round: false,
             }
         }
     );
 }
 
 #[test]
-fn test_decode_vstr() {
-    //250:       ed8d 7b12       vstr    d7, [sp, #72]   ; 0x48
+fn test_decode_smulwb() {
+    // 0xfb32f103 smulwb r1, r2, r3
     assert_eq!(
-        decode_32(0xed8d7b12),
-        Instruction::VSTR {
-            params: VLoadAndStoreParams {
-                dd: ExtensionReg::Double { reg: DoubleReg::D7 },
-                rn: Reg::SP,
-                add: true,
-                imm32: 0x48,
+        decode_32(0xfb32f103),
+        Instruction::SMULW {
+            params: Reg3WordHighParams {
+                rd: Reg::R1,
+                rn: Reg::R2,
+                rm: Reg::R3,
+                m_high: false,
+            }
+        }
+    );
+}
+
+#[test]
+fn test_decode_umaal() {
+    // 0xfbe31264 umaal r1, r2, r3, r4
+    assert_eq!(
+        decode_32(0xfbe31264),
+        Instruction::UMAAL {
+            params: Reg643232Params {
+                rdlo: Reg::R1,
+                rdhi: Reg::R2,
+                rn: Reg::R3,
+                rm: Reg::R4,
+            }
+        }
+    );
+}
+
+#[test]
+fn test_decode_usada8() {
+    // 0xfb724103 usada8 r1, r2, r3, r4
+    assert_eq!(
+        decode_32(0xfb724103),
+        Instruction::USADA8 {
+            params: Reg4NoSetFlagsParams {
+                rd: Reg::R1,
+                rn: Reg::R2,
+                rm: Reg::R3,
+                ra: Reg::R4,
+            }
+        }
+    );
+}
+
+#[test]
+fn test_decode_ssat16() {
+    // 0xf3220104 ssat16 r1, #5, r2
+    assert_eq!(
+        decode_32(0xf3220104),
+        Instruction::SSAT16 {
+            params: Saturate16Params {
+                rd: Reg::R1,
+                rn: Reg::R2,
+                saturate_to: 5,
             }
         }
     );
 }
+
+#[test]
+fn test_decode_usat16() {
+    // 0xf3a20105 usat16 r1, #5, r2
+    assert_eq!(
+        decode_32(0xf3a20105),
+        Instruction::USAT16 {
+            params: Saturate16Params {
+                rd: Reg::R1,
+                rn: Reg::R2,
+                saturate_to: 5,
+            }
+        }
+    );
+}
+
+#[test]
+fn test_decode_ssat() {
+    // 0xf30201c4 ssat r1, #5, r2, lsl #3
+    assert_eq!(
+        decode_32(0xf30201c4),
+        Instruction::SSAT {
+            params: SaturateParams {
+                rd: Reg::R1,
+                rn: Reg::R2,
+                saturate_to: 5,
+                shift_t: SRType::LSL,
+                shift_n: 3,
+            }
+        }
+    );
+}
+
+#[test]
+fn test_decode_usat() {
+    // 0xf3a201c5 usat r1, #5, r2, asr #3
+    assert_eq!(
+        decode_32(0xf3a201c5),
+        Instruction::USAT {
+            params: SaturateParams {
+                rd: Reg::R1,
+                rn: Reg::R2,
+                saturate_to: 5,
+                shift_t: SRType::ASR,
+                shift_n: 3,
+            }
+        }
+    );
+}
+
+#[test]
+fn test_decode_pkhbt() {
+    // 0xeac21103 pkhbt r1, r2, r3, lsl #4
+    assert_eq!(
+        decode_32(0xeac21103),
+        Instruction::PKH {
+            params: Reg3ShiftNoSetFlagsParams {
+                rd: Reg::R1,
+                rn: Reg::R2,
+                rm: Reg::R3,
+                shift_t: SRType::LSL,
+                shift_n: 4,
+            }
+        }
+    );
+}
+
+#[test]
+fn test_decode_pkhtb() {
+    // 0xeac21123 pkhtb r1, r2, r3, asr #4
+    assert_eq!(
+        decode_32(0xeac21123),
+        Instruction::PKH {
+            params: Reg3ShiftNoSetFlagsParams {
+                rd: Reg::R1,
+                rn: Reg::R2,
+                rm: Reg::R3,
+                shift_t: SRType::ASR,
+                shift_n: 4,
+            }
+        }
+    );
+}
+
+#[test]
+fn test_decode_sxtab() {
+    // 0xfa42f193 sxtab r1, r2, r3, ror #8
+    assert_eq!(
+        decode_32(0xfa42f193),
+        Instruction::SXTAB {
+            params: Reg3UsizeParams {
+                rd: Reg::R1,
+                rn: Reg::R2,
+                rm: Reg::R3,
+                rotation: 8,
+            }
+        }
+    );
+}
+
+#[test]
+fn test_decode_sxtah() {
+    // 0xfa02f183 sxtah r1, r2, r3
+    assert_eq!(
+        decode_32(0xfa02f183),
+        Instruction::SXTAH {
+            params: Reg3UsizeParams {
+                rd: Reg::R1,
+                rn: Reg::R2,
+                rm: Reg::R3,
+                rotation: 0,
+            }
+        }
+    );
+}
+
+#[test]
+fn test_decode_uxtah() {
+    // 0xfa12f183 uxtah r1, r2, r3
+    assert_eq!(
+        decode_32(0xfa12f183),
+        Instruction::UXTAH {
+            params: Reg3UsizeParams {
+                rd: Reg::R1,
+                rn: Reg::R2,
+                rm: Reg::R3,
+                rotation: 0,
+            }
+        }
+    );
+}
+
+#[test]
+fn test_decode_sxtab16() {
+    // 0xfa22f183 sxtab16 r1, r2, r3
+    assert_eq!(
+        decode_32(0xfa22f183),
+        Instruction::SXTAB16 {
+            params: Reg3UsizeParams {
+                rd: Reg::R1,
+                rn: Reg::R2,
+                rm: Reg::R3,
+                rotation: 0,
+            }
+        }
+    );
+}
+
+#[test]
+fn test_decode_uxtab16() {
+    // 0xfa32f183 uxtab16 r1, r2, r3
+    assert_eq!(
+        decode_32(0xfa32f183),
+        Instruction::UXTAB16 {
+            params: Reg3UsizeParams {
+                rd: Reg::R1,
+                rn: Reg::R2,
+                rm: Reg::R3,
+                rotation: 0,
+            }
+        }
+    );
+}
+
+#[test]
+fn test_decode_sxtb16() {
+    // 0xfa2ff183 sxtb16 r1, r3
+    assert_eq!(
+        decode_32(0xfa2ff183),
+        Instruction::SXTB16 {
+            params: Reg2UsizeParams {
+                rd: Reg::R1,
+                rm: Reg::R3,
+                rotation: 0,
+            }
+        }
+    );
+}
+
+#[test]
+fn test_decode_uxtb16() {
+    // 0xfa3ff1a3 uxtb16 r1, r3, ror #16
+    assert_eq!(
+        decode_32(0xfa3ff1a3),
+        Instruction::UXTB16 {
+            params: Reg2UsizeParams {
+                rd: Reg::R1,
+                rm: Reg::R3,
+                rotation: 16,
+            }
+        }
+    );
+}
+
+#[test]
+fn test_decode_tbh() {
+    // e8df f013       tbh     [pc, r3, lsl #1]
+
+    assert_eq!(
+        decode_32(0xe8dff013),
+        Instruction::TBH {
+            params: Reg2RnRmParams {
+                rn: Reg::PC,
+                rm: Reg::R3,
+            }
+        }
+    );
+}
+
+#[test]
+fn test_decode_movt() {
+    // f2c2 0100       movt    r1, #8192
+
+    assert_eq!(
+        decode_32(0xf2c20100),
+        Instruction::MOVT {
+            params: MovtParams {
+                rd: Reg::R1,
+                imm16: 0x2000
+            }
+        }
+    );
+}
+
+#[test]
+fn test_decode_mvn_reg_w() {
+    // ea6f 5507       mvn.w   r5, r7, lsl #20
+
+    assert_eq!(
+        decode_32(0xea6f5507),
+        Instruction::MVN_reg {
+            params: Reg2ShiftParams {
+                rd: Reg::R5,
+                rm: Reg::R7,
+                setflags: SetFlags::False,
+                shift_n: 20,
+                shift_t: SRType::LSL,
+            },
+            thumb32: true,
+        }
+    );
+}
+
+#[test]
+fn test_decode_teq_w() {
+    //f090 0f00       teq     r0, #0
+    assert_eq!(
+        decode_32(0xf0900f00),
+        Instruction::TEQ_imm {
+            params: RegImmCarryNoSetFlagsParams {
+                rn: Reg::R0,
+                imm32: Imm32Carry::Carry {
+                    imm32_c0: (0, false),
+                    imm32_c1: (0, true),
+                },
+            }
+        }
+    );
+}
+
+#[test]
+fn test_decode_mov_rxx_w() {
+    //ea4f 0232       mov.w   r2, r2, rrx
+    assert_eq!(
+        decode_32(0xea4f0232),
+        Instruction::RRX {
+            params: Reg2Params {
+                rd: Reg::R2,
+                rm: Reg::R2,
+                setflags: false,
+            }
+        }
+    );
+}
+
+#[test]
+fn test_decode_str_imm_t4() {
+    //f84d cd04       str.w   ip, [sp, #-4]!
+    // => same as PUSH r12
+    match decode_32(0xf84dcd04) {
+        Instruction::PUSH { registers, thumb32 } => {
+            let elems: Vec<_> = registers.iter().collect();
+            assert_eq!(vec![Reg::R12], elems);
+            assert_eq!(thumb32, true);
+        }
+        _ => {
+            assert!(false);
+        }
+    }
+}
+
+#[test]
+fn test_decode_subw_imm_t4() {
+    // f2a4 4333       subw    r3, r4, #1075   ; 0x433
+    assert_eq!(
+        decode_32(0xf2a44333),
+        Instruction::SUB_imm {
+            params: Reg2ImmParams {
+                rd: Reg::R3,
+                rn: Reg::R4,
+                imm32: 1075,
+                setflags: SetFlags::False,
+            },
+            thumb32: true,
+        }
+    );
+}
+
+#[test]
+fn test_decode_asrw_reg_t2() {
+    //  fa43 f305       asr.w   r3, r3, r5
+    assert_eq!(
+        decode_32(0xfa43f305),
+        Instruction::ASR_reg {
+            params: Reg3Params {
+                rd: Reg::R3,
+                rn: Reg::R3,
+                rm: Reg::R5,
+                setflags: SetFlags::False,
+            },
+            thumb32: true,
+        }
+    );
+}
+
+#[test]
+fn test_decode_clrex() {
+    //  f3bf 8f2f       clrex
+    assert_eq!(decode_32(0xf3bf8f2f), Instruction::CLREX);
+}
+
+#[test]
+fn test_decode_dmb() {
+    //  f3bf 8f5f       dmb sy
+    assert_eq!(decode_32(0xf3bf8f5f), Instruction::DMB);
+}
+
+#[test]
+fn test_decode_ldrex() {
+    //  e850 3f00       ldrex   r3, [r0]
+    assert_eq!(
+        decode_32(0xe8503f00),
+        Instruction::LDREX {
+            params: Reg2RtRnImm32Params {
+                rt: Reg::R3,
+                rn: Reg::R0,
+                imm32: 0,
+            }
+        }
+    );
+}
+
+#[test]
+fn test_decode_strex() {
+    //  e840 2c00       strex   ip, r2, [r0]
+    assert_eq!(
+        decode_32(0xe8402c00),
+        Instruction::STREX {
+            params: Reg3RdRtRnImm32Params {
+                rd: Reg::R12,
+                rt: Reg::R2,
+                rn: Reg::R0,
+                imm32: 0,
+            }
+        }
+    );
+}
+
+#[test]
+fn test_decode_bfc() {
+    //  f36f 011f       bfc     r1, #0, #32
+    assert_eq!(
+        decode_32(0xf36f011f),
+        Instruction::BFC {
+            params: BfcParams {
+                rd: Reg::R1,
+                lsbit: 0,
+                msbit: 31,
+            }
+        }
+    );
+}
+
+#[test]
+fn test_decode_vldr() {
+    //  ed9f 7b86       vldr    d7, [pc, #536]  ; 448 <_vfprintf_r+0x290>
+    assert_eq!(
+        decode_32(0xed9f7b86),
+        Instruction::VLDR {
+            params: VLoadAndStoreParams {
+                dd: ExtensionReg::Double { reg: DoubleReg::D7 },
+                rn: Reg::PC,
+                add: true,
+                imm32: 0x86 << 2,
+            }
+        }
+    );
+}
+
+#[test]
+fn test_decode_vstr() {
+    //250:       ed8d 7b12       vstr    d7, [sp, #72]   ; 0x48
+    assert_eq!(
+        decode_32(0xed8d7b12),
+        Instruction::VSTR {
+            params: VLoadAndStoreParams {
+                dd: ExtensionReg::Double { reg: DoubleReg::D7 },
+                rn: Reg::SP,
+                add: true,
+                imm32: 0x48,
+            }
+        }
+    );
+}
+
+#[test]
+fn test_decode_vldr_single() {
+    // vldr s17, [r0, #4]
+    assert_eq!(
+        decode_32(0xedd08a01),
+        Instruction::VLDR {
+            params: VLoadAndStoreParams {
+                dd: ExtensionReg::Single {
+                    reg: SingleReg::S17
+                },
+                rn: Reg::R0,
+                add: true,
+                imm32: 4,
+            }
+        }
+    );
+    // vstr s17, [r0, #-8]
+    assert_eq!(
+        decode_32(0xed408a02),
+        Instruction::VSTR {
+            params: VLoadAndStoreParams {
+                dd: ExtensionReg::Single {
+                    reg: SingleReg::S17
+                },
+                rn: Reg::R0,
+                add: false,
+                imm32: 8,
+            }
+        }
+    );
+}
+
+#[test]
+fn test_decode_vldm_vstm() {
+    // vldmia r1, {s0-s3}
+    assert_eq!(
+        decode_32(0xec910a04),
+        Instruction::VLDM {
+            params: VLoadAndStoreMultipleParams {
+                rn: Reg::R1,
+                dd: ExtensionReg::Single { reg: SingleReg::S0 },
+                regs: 4,
+                add: true,
+                wback: false,
+            }
+        }
+    );
+    // vldmia r1!, {d0-d3}
+    assert_eq!(
+        decode_32(0xecb10b08),
+        Instruction::VLDM {
+            params: VLoadAndStoreMultipleParams {
+                rn: Reg::R1,
+                dd: ExtensionReg::Double { reg: DoubleReg::D0 },
+                regs: 4,
+                add: true,
+                wback: true,
+            }
+        }
+    );
+    // vstmdb r2!, {s5-s6}
+    assert_eq!(
+        decode_32(0xed622a02),
+        Instruction::VSTM {
+            params: VLoadAndStoreMultipleParams {
+                rn: Reg::R2,
+                dd: ExtensionReg::Single { reg: SingleReg::S5 },
+                regs: 2,
+                add: false,
+                wback: true,
+            }
+        }
+    );
+}
+
+#[test]
+fn test_decode_vpush_vpop() {
+    // vpush {s16-s31}
+    assert_eq!(
+        decode_32(0xed2d8a10),
+        Instruction::VPUSH {
+            params: VPushPopParams {
+                dd: ExtensionReg::Single {
+                    reg: SingleReg::S16
+                },
+                regs: 16,
+            }
+        }
+    );
+    // vpush {d8-d15}
+    assert_eq!(
+        decode_32(0xed2d8b10),
+        Instruction::VPUSH {
+            params: VPushPopParams {
+                dd: ExtensionReg::Double { reg: DoubleReg::D8 },
+                regs: 8,
+            }
+        }
+    );
+    // vpop {d8}
+    assert_eq!(
+        decode_32(0xecbd8b02),
+        Instruction::VPOP {
+            params: VPushPopParams {
+                dd: ExtensionReg::Double { reg: DoubleReg::D8 },
+                regs: 1,
+            }
+        }
+    );
+}
+
+#[test]
+fn test_decode_vmov_core_register() {
+    // vmov s3, r4
+    assert_eq!(
+        decode_32(0xee014a90),
+        Instruction::VMOV_cr_sp {
+            params: VMovCrSpParams {
+                rt: Reg::R4,
+                sn: SingleReg::S3,
+                to_arm_register: false,
+            }
+        }
+    );
+    // vmov r4, s3
+    assert_eq!(
+        decode_32(0xee114a90),
+        Instruction::VMOV_cr_sp {
+            params: VMovCrSpParams {
+                rt: Reg::R4,
+                sn: SingleReg::S3,
+                to_arm_register: true,
+            }
+        }
+    );
+    // vmov r5, r6, s3, s4
+    assert_eq!(
+        decode_32(0xec565a31),
+        Instruction::VMOV_cr2_sp2 {
+            params: VMovCr2Sp2Params {
+                rt: Reg::R5,
+                rt2: Reg::R6,
+                sm: SingleReg::S3,
+                to_arm_registers: true,
+            }
+        }
+    );
+    // vmov d3, r5, r6
+    assert_eq!(
+        decode_32(0xec465b13),
+        Instruction::VMOV_cr2_dp {
+            params: VMovCr2DpParams {
+                rt: Reg::R5,
+                rt2: Reg::R6,
+                dm: DoubleReg::D3,
+                to_arm_registers: false,
+            }
+        }
+    );
+    // vmov.32 d3[1], r7
+    assert_eq!(
+        decode_32(0xee237b10),
+        Instruction::VMOV_cr_scalar {
+            params: VMovCrScalarParams {
+                rt: Reg::R7,
+                dd: DoubleReg::D3,
+                x: 1,
+                to_arm_register: false,
+            }
+        }
+    );
+    // vmov.32 r7, d3[0]
+    assert_eq!(
+        decode_32(0xee137b10),
+        Instruction::VMOV_cr_scalar {
+            params: VMovCrScalarParams {
+                rt: Reg::R7,
+                dd: DoubleReg::D3,
+                x: 0,
+                to_arm_register: true,
+            }
+        }
+    );
+}
+
+#[test]
+fn test_decode_vmrs_vmsr() {
+    // vmrs r2, fpscr
+    assert_eq!(decode_32(0xeef12a10), Instruction::VMRS { rt: Reg::R2 });
+    // vmrs APSR_nzcv, fpscr
+    assert_eq!(decode_32(0xeef1fa10), Instruction::VMRS { rt: Reg::PC });
+    // vmsr fpscr, r3
+    assert_eq!(decode_32(0xeee13a10), Instruction::VMSR { rt: Reg::R3 });
+}
+
+#[test]
+fn test_decode_vreg3_arithmetic() {
+    let params = VReg3Params {
+        sd: SingleReg::S1,
+        sn: SingleReg::S2,
+        sm: SingleReg::S3,
+    };
+    // vadd.f32 s0, s1, s2
+    assert_eq!(
+        decode_32(0xee300a81),
+        Instruction::VADD {
+            params: VReg3Params {
+                sd: SingleReg::S0,
+                sn: SingleReg::S1,
+                sm: SingleReg::S2,
+            }
+        }
+    );
+    // vadd.f32 s31, s30, s29
+    assert_eq!(
+        decode_32(0xee7ffa2e),
+        Instruction::VADD {
+            params: VReg3Params {
+                sd: SingleReg::S31,
+                sn: SingleReg::S30,
+                sm: SingleReg::S29,
+            }
+        }
+    );
+    // vsub.f32 s3, s4, s5
+    assert_eq!(
+        decode_32(0xee721a62),
+        Instruction::VSUB {
+            params: VReg3Params {
+                sd: SingleReg::S3,
+                sn: SingleReg::S4,
+                sm: SingleReg::S5,
+            }
+        }
+    );
+    // vmul.f32 s6, s7, s8
+    assert_eq!(
+        decode_32(0xee233a84),
+        Instruction::VMUL {
+            params: VReg3Params {
+                sd: SingleReg::S6,
+                sn: SingleReg::S7,
+                sm: SingleReg::S8,
+            }
+        }
+    );
+    // vnmul.f32 s9, s10, s11
+    assert_eq!(
+        decode_32(0xee654a65),
+        Instruction::VNMUL {
+            params: VReg3Params {
+                sd: SingleReg::S9,
+                sn: SingleReg::S10,
+                sm: SingleReg::S11,
+            }
+        }
+    );
+    // vdiv.f32 s12, s13, s14
+    assert_eq!(
+        decode_32(0xee866a87),
+        Instruction::VDIV {
+            params: VReg3Params {
+                sd: SingleReg::S12,
+                sn: SingleReg::S13,
+                sm: SingleReg::S14,
+            }
+        }
+    );
+    // s1, s2, s3 forms of the multiply accumulate instructions
+    assert_eq!(decode_32(0xee410a21), Instruction::VMLA { params });
+    assert_eq!(decode_32(0xee410a61), Instruction::VMLS { params });
+    assert_eq!(decode_32(0xee510a61), Instruction::VNMLA { params });
+    assert_eq!(decode_32(0xee510a21), Instruction::VNMLS { params });
+    assert_eq!(decode_32(0xeee10a21), Instruction::VFMA { params });
+    assert_eq!(decode_32(0xeee10a61), Instruction::VFMS { params });
+    assert_eq!(decode_32(0xeed10a61), Instruction::VFNMA { params });
+    assert_eq!(decode_32(0xeed10a21), Instruction::VFNMS { params });
+}
+
+#[test]
+fn test_decode_vreg2_arithmetic() {
+    // s4, s5 forms
+    let params = VReg2Params {
+        sd: SingleReg::S4,
+        sm: SingleReg::S5,
+    };
+    assert_eq!(decode_32(0xeeb12ae2), Instruction::VSQRT { params });
+    assert_eq!(decode_32(0xeeb02ae2), Instruction::VABS { params });
+    assert_eq!(decode_32(0xeeb12a62), Instruction::VNEG { params });
+    assert_eq!(decode_32(0xeeb02a62), Instruction::VMOV_reg { params });
+}
+
+#[test]
+fn test_decode_vmov_imm() {
+    // vmov.f32 s7, #1.0
+    assert_eq!(
+        decode_32(0xeef73a00),
+        Instruction::VMOV_imm {
+            params: VMovImmParams {
+                sd: SingleReg::S7,
+                imm32: 0x3f80_0000,
+            }
+        }
+    );
+    // vmov.f32 s7, #-0.125
+    assert_eq!(
+        decode_32(0xeefc3a00),
+        Instruction::VMOV_imm {
+            params: VMovImmParams {
+                sd: SingleReg::S7,
+                imm32: 0xbe00_0000,
+            }
+        }
+    );
+}
+
+#[test]
+fn test_decode_vcmp() {
+    // vcmp.f32 s1, s2
+    assert_eq!(
+        decode_32(0xeef40a41),
+        Instruction::VCMP {
+            params: VCmpParams {
+                sd: SingleReg::S1,
+                sm: Some(SingleReg::S2),
+                quiet_nan_exc: false,
+            }
+        }
+    );
+    // vcmpe.f32 s1, #0
+    assert_eq!(
+        decode_32(0xeef50ac0),
+        Instruction::VCMP {
+            params: VCmpParams {
+                sd: SingleReg::S1,
+                sm: None,
+                quiet_nan_exc: true,
+            }
+        }
+    );
+}
+
+#[test]
+fn test_decode_vcvt() {
+    // vcvt.f32.s32 s1, s2
+    assert_eq!(
+        decode_32(0xeef80ac1),
+        Instruction::VCVT_int {
+            params: VCvtIntParams {
+                sd: SingleReg::S1,
+                sm: SingleReg::S2,
+                to_integer: false,
+                signed: true,
+                round_zero: false,
+            }
+        }
+    );
+    // vcvt.u32.f32 s1, s2
+    assert_eq!(
+        decode_32(0xeefc0ac1),
+        Instruction::VCVT_int {
+            params: VCvtIntParams {
+                sd: SingleReg::S1,
+                sm: SingleReg::S2,
+                to_integer: true,
+                signed: false,
+                round_zero: true,
+            }
+        }
+    );
+    // vcvtr.s32.f32 s1, s2
+    assert_eq!(
+        decode_32(0xeefd0a41),
+        Instruction::VCVT_int {
+            params: VCvtIntParams {
+                sd: SingleReg::S1,
+                sm: SingleReg::S2,
+                to_integer: true,
+                signed: true,
+                round_zero: false,
+            }
+        }
+    );
+    // vcvt.s16.f32 s1, s1, #3
+    assert_eq!(
+        decode_32(0xeefe0a66),
+        Instruction::VCVT_fixed {
+            params: VCvtFixedParams {
+                sd: SingleReg::S1,
+                to_fixed: true,
+                unsigned: false,
+                size: 16,
+                frac_bits: 3,
+            }
+        }
+    );
+    // vcvt.f32.u32 s1, s1, #20
+    assert_eq!(
+        decode_32(0xeefb0ac6),
+        Instruction::VCVT_fixed {
+            params: VCvtFixedParams {
+                sd: SingleReg::S1,
+                to_fixed: false,
+                unsigned: true,
+                size: 32,
+                frac_bits: 20,
+            }
+        }
+    );
+    // vcvtt.f16.f32 s1, s2
+    assert_eq!(
+        decode_32(0xeef30ac1),
+        Instruction::VCVT_half {
+            params: VCvtHalfParams {
+                sd: SingleReg::S1,
+                sm: SingleReg::S2,
+                to_half: true,
+                top: true,
+            }
+        }
+    );
+}
+
+#[test]
+fn test_decode_armv6m_profile() {
+    // Arrange
+    let mut processor = Processor::new();
+    processor.cpu(Cpu::CortexM0);
+
+    // Act & Assert
+    // 32 bit instructions available on armv6m
+    assert_eq!(
+        processor.decode(ThumbCode::from(0xf7ffffbf_u32)),
+        Instruction::BL { imm32: -130 }
+    );
+    assert_eq!(
+        processor.decode(ThumbCode::from(0xf3bf8f5f_u32)),
+        Instruction::DMB
+    );
+    // SDIV R2, R9, R10
+    assert_eq!(
+        processor.decode(ThumbCode::from(0xfb99f2fa_u32)),
+        Instruction::UDF {
+            imm32: 0,
+            opcode: ThumbCode::from(0xfb99f2fa_u32),
+            thumb32: true,
+        }
+    );
+    // CBZ R1, 0x3be4
+    assert_eq!(
+        processor.decode(ThumbCode::from(0xb179_u16)),
+        Instruction::UDF {
+            imm32: 0,
+            opcode: ThumbCode::from(0xb179_u16),
+            thumb32: false,
+        }
+    );
+    // UXTB R1, R1
+    assert!(match processor.decode(ThumbCode::from(0xb2c9_u16)) {
+        Instruction::UXTB { .. } => true,
+        _ => false,
+    });
+    // CPSID i
+    assert!(match processor.decode(ThumbCode::from(0xb672_u16)) {
+        Instruction::CPS { .. } => true,
+        _ => false,
+    });
+    // CPSID f
+    assert_eq!(
+        processor.decode(ThumbCode::from(0xb671_u16)),
+        Instruction::UDF {
+            imm32: 0,
+            opcode: ThumbCode::from(0xb671_u16),
+            thumb32: false,
+        }
+    );
+    // vldr s17, [r0, #4]
+    assert_eq!(
+        processor.decode(ThumbCode::from(0xedd08a01_u32)),
+        Instruction::UDF {
+            imm32: 0,
+            opcode: ThumbCode::from(0xedd08a01_u32),
+            thumb32: true,
+        }
+    );
+}
+
+#[test]
+fn test_decode_armv7m_profile() {
+    // Arrange
+    let mut processor = Processor::new();
+    processor.cpu(Cpu::CortexM3);
+
+    // Act & Assert
+    // SDIV R2, R9, R10
+    assert!(match processor.decode(ThumbCode::from(0xfb99f2fa_u32)) {
+        Instruction::SDIV { .. } => true,
+        _ => false,
+    });
+    // CPSID f
+    assert_eq!(
+        processor.decode(ThumbCode::from(0xb671_u16)),
+        Instruction::CPS {
+            im: true,
+            affect_pri: false,
+            affect_fault: true,
+        }
+    );
+    // smladx r1, r2, r3, r4
+    assert_eq!(
+        processor.decode(ThumbCode::from(0xfb224113_u32)),
+        Instruction::UDF {
+            imm32: 0,
+            opcode: ThumbCode::from(0xfb224113_u32),
+            thumb32: true,
+        }
+    );
+    // vldr s17, [r0, #4] is a coprocessor instruction, NOCP when executed
+    assert!(match processor.decode(ThumbCode::from(0xedd08a01_u32)) {
+        Instruction::VLDR { .. } => true,
+        _ => false,
+    });
+}
+
+#[test]
+fn test_decode_armv7em_profile() {
+    // Arrange
+    let mut m4 = Processor::new();
+    m4.cpu(Cpu::CortexM4);
+    let mut m4f = Processor::new();
+    m4f.cpu(Cpu::CortexM4F);
+
+    // Act & Assert
+    // smladx r1, r2, r3, r4
+    assert!(match m4.decode(ThumbCode::from(0xfb224113_u32)) {
+        Instruction::SMLAD { .. } => true,
+        _ => false,
+    });
+    // vldr s17, [r0, #4]
+    assert!(match m4.decode(ThumbCode::from(0xedd08a01_u32)) {
+        Instruction::VLDR { .. } => true,
+        _ => false,
+    });
+    assert!(match m4f.decode(ThumbCode::from(0xedd08a01_u32)) {
+        Instruction::VLDR { .. } => true,
+        _ => false,
+    });
+}
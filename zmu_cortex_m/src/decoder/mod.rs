@@ -4,7 +4,8 @@
 //!
 
 use crate::core::bits::Bits;
-use crate::core::instruction::Instruction;
+use crate::core::cpu::{Architecture, Cpu};
+use crate::core::instruction::{instruction_size, Instruction};
 
 //#[cfg(test)]
 //use crate::core::register::SpecialReg;
@@ -28,9 +29,12 @@ mod yield_;
 
 mod adc;
 mod add;
+mod add16;
+mod add8;
 mod adr;
 mod and;
 mod asr;
+mod asx;
 
 mod b;
 mod bic;
@@ -76,30 +80,48 @@ mod nop;
 mod orn;
 mod orr;
 
+mod pkh;
 mod pld;
 mod pli;
 mod pop;
 mod push;
 
+mod qadd;
+mod qsub;
+
 mod rbit;
 mod rev;
 mod ror;
 mod rrx;
 mod rsb;
 
+mod sax;
 mod sbc;
 mod sdiv;
 mod sel;
 mod sev;
 mod smla;
+mod smlad;
 mod smlal;
+mod smlald;
+mod smlaw;
+mod smlsd;
+mod smlsld;
+mod smmla;
+mod smmls;
+mod smmul;
+mod smuad;
 mod smul;
 mod smull;
+mod smulw;
+mod smusd;
 mod stc;
 mod stm;
 mod str;
 mod strex;
 mod sub;
+mod sub16;
+mod sub8;
 mod sxt;
 
 mod tbb;
@@ -108,15 +130,36 @@ mod teq;
 mod tst;
 
 mod movt;
-mod uadd8;
 mod udiv;
+mod umaal;
 mod umlal;
 mod umull;
+mod usad8;
 mod uxt;
 mod uxtab;
 
+mod vabs;
+mod vadd;
+mod vcmp;
+mod vcvt;
+mod vdiv;
+mod vfma;
+mod vfnma;
+mod vldm;
 mod vldr;
+mod vmla;
+mod vmov;
+mod vmrs;
+mod vmsr;
+mod vmul;
+mod vneg;
+mod vnmla;
+mod vpop;
+mod vpush;
+mod vsqrt;
+mod vstm;
 mod vstr;
+mod vsub;
 
 use {
     crate::decoder::str::{
@@ -132,9 +175,21 @@ use {
         decode_ADD_imm_t3, decode_ADD_imm_t4, decode_ADD_reg_sp_t1, decode_ADD_reg_sp_t2,
         decode_ADD_reg_t1, decode_ADD_reg_t2, decode_ADD_reg_t3,
     },
+    add16::{
+        decode_QADD16_t1, decode_SADD16_t1, decode_SHADD16_t1, decode_UADD16_t1, decode_UHADD16_t1,
+        decode_UQADD16_t1,
+    },
+    add8::{
+        decode_QADD8_t1, decode_SADD8_t1, decode_SHADD8_t1, decode_UADD8_t1, decode_UHADD8_t1,
+        decode_UQADD8_t1,
+    },
     adr::{decode_ADR_t1, decode_ADR_t2, decode_ADR_t3},
     and::{decode_AND_imm_t1, decode_AND_reg_t1, decode_AND_reg_t2},
     asr::{decode_ASR_imm_t1, decode_ASR_imm_t2, decode_ASR_reg_t1, decode_ASR_reg_t2},
+    asx::{
+        decode_QASX_t1, decode_SASX_t1, decode_SHASX_t1, decode_UASX_t1, decode_UHASX_t1,
+        decode_UQASX_t1,
+    },
     b::{decode_B_t1_SVC_t1, decode_B_t2, decode_B_t3, decode_B_t4},
     bfc::decode_BFC_t1,
     bfi::decode_BFI_t1,
@@ -203,10 +258,13 @@ use {
     nop::{decode_NOP_t1, decode_NOP_t2},
     orn::{decode_ORN_imm_t1, decode_ORN_reg_t1},
     orr::{decode_ORR_imm_t1, decode_ORR_reg_t1, decode_ORR_reg_t2},
+    pkh::decode_PKH_t1,
     pld::{decode_PLD_imm_t1, decode_PLD_imm_t2, decode_PLD_lit_t1, decode_PLD_reg_t1},
     pli::{decode_PLI_lit_imm_t1, decode_PLI_lit_imm_t2, decode_PLI_lit_imm_t3, decode_PLI_reg_t1},
     pop::{decode_POP_reg_t1, decode_POP_t2, decode_POP_t3},
     push::{decode_PUSH_t1, decode_PUSH_t2, decode_PUSH_t3},
+    qadd::{decode_QADD_t1, decode_QDADD_t1},
+    qsub::{decode_QDSUB_t1, decode_QSUB_t1},
     rbit::decode_RBIT_t1,
     rev::{
         decode_REV16_t1, decode_REV16_t2, decode_REVSH_t1, decode_REVSH_t2, decode_REV_t1,
@@ -215,16 +273,31 @@ use {
     ror::{decode_ROR_imm_t1, decode_ROR_reg_t1, decode_ROR_reg_t2},
     rrx::decode_RRX_t1,
     rsb::{decode_RSB_imm_t1, decode_RSB_imm_t2, decode_RSB_reg_t1},
+    sax::{
+        decode_QSAX_t1, decode_SHSAX_t1, decode_SSAX_t1, decode_UHSAX_t1, decode_UQSAX_t1,
+        decode_USAX_t1,
+    },
     sbc::{decode_SBC_imm_t1, decode_SBC_reg_t1, decode_SBC_reg_t2},
     sbfx::decode_SBFX_t1,
     sdiv::decode_SDIV_t1,
     sel::decode_SEL_t1,
     sev::{decode_SEV_t1, decode_SEV_t2},
     smla::decode_SMLA_t1,
-    smlal::decode_SMLAL_t1,
+    smlad::decode_SMLAD_t1,
+    smlal::{decode_SMLAL_hw_t1, decode_SMLAL_t1},
+    smlald::decode_SMLALD_t1,
+    smlaw::decode_SMLAW_t1,
+    smlsd::decode_SMLSD_t1,
+    smlsld::decode_SMLSLD_t1,
+    smmla::decode_SMMLA_t1,
+    smmls::decode_SMMLS_t1,
+    smmul::decode_SMMUL_t1,
+    smuad::decode_SMUAD_t1,
     smul::decode_SMUL_t1,
     smull::decode_SMULL_t1,
-    ssat::decode_SSAT_t1,
+    smulw::decode_SMULW_t1,
+    smusd::decode_SMUSD_t1,
+    ssat::{decode_SSAT16_t1, decode_SSAT_t1},
     stc::{decode_STC2_t2, decode_STC_t1},
     stm::{decode_STMDB_t1, decode_STM_t1, decode_STM_t2},
     strex::{decode_STREXB_t1, decode_STREXH_t1, decode_STREX_t1},
@@ -233,26 +306,66 @@ use {
         decode_SUB_imm_t2, decode_SUB_imm_t3, decode_SUB_imm_t4, decode_SUB_reg_t1,
         decode_SUB_reg_t2,
     },
-    sxt::{decode_SXTB_t1, decode_SXTB_t2, decode_SXTH_t1, decode_SXTH_t2},
+    sub16::{
+        decode_QSUB16_t1, decode_SHSUB16_t1, decode_SSUB16_t1, decode_UHSUB16_t1, decode_UQSUB16_t1,
+        decode_USUB16_t1,
+    },
+    sub8::{
+        decode_QSUB8_t1, decode_SHSUB8_t1, decode_SSUB8_t1, decode_UHSUB8_t1, decode_UQSUB8_t1,
+        decode_USUB8_t1,
+    },
+    sxt::{
+        decode_SXTAB16_t1, decode_SXTAB_t1, decode_SXTAH_t1, decode_SXTB16_t1, decode_SXTB_t1,
+        decode_SXTB_t2, decode_SXTH_t1, decode_SXTH_t2,
+    },
     tbb::decode_TBB_t1,
     tbh::decode_TBH_t1,
     teq::{decode_TEQ_imm_t1, decode_TEQ_reg_t1},
     tst::{decode_TST_imm_t1, decode_TST_reg_t1, decode_TST_reg_t2},
-    uadd8::decode_UADD8_t1,
     ubfx::decode_UBFX_t1,
     udiv::decode_UDIV_t1,
+    umaal::decode_UMAAL_t1,
     umlal::decode_UMLAL_t1,
     umull::decode_UMULL_t1,
-    usat::decode_USAT_t1,
-    uxt::{decode_UXTB_t1, decode_UXTB_t2, decode_UXTH_t1, decode_UXTH_t2},
+    usad8::{decode_USAD8_t1, decode_USADA8_t1},
+    usat::{decode_USAT16_t1, decode_USAT_t1},
+    uxt::{
+        decode_UXTAB16_t1, decode_UXTAH_t1, decode_UXTB16_t1, decode_UXTB_t1, decode_UXTB_t2,
+        decode_UXTH_t1, decode_UXTH_t2,
+    },
     uxtab::decode_UXTAB_t1,
+    vabs::decode_VABS_t1,
+    vadd::decode_VADD_t1,
+    vcmp::{decode_VCMP_t1, decode_VCMP_t2},
+    vcvt::{decode_VCVT_fixed_t1, decode_VCVT_half_t1, decode_VCVT_int_t1},
+    vdiv::decode_VDIV_t1,
+    vfma::{decode_VFMA_t1, decode_VFMS_t1},
+    vfnma::{decode_VFNMA_t1, decode_VFNMS_t1},
+    vldm::{decode_VLDM_t1, decode_VLDM_t2},
     vldr::{decode_VLDR_t1, decode_VLDR_t2},
+    vmla::{decode_VMLA_t1, decode_VMLS_t1},
+    vmov::{
+        decode_VMOV_cr2_dp_t1, decode_VMOV_cr2_sp2_t1, decode_VMOV_cr_scalar_t1,
+        decode_VMOV_cr_sp_t1, decode_VMOV_imm_t1, decode_VMOV_reg_t1, decode_VMOV_scalar_cr_t1,
+    },
+    vmrs::decode_VMRS_t1,
+    vmsr::decode_VMSR_t1,
+    vmul::decode_VMUL_t1,
+    vneg::decode_VNEG_t1,
+    vnmla::{decode_VNMLA_t1, decode_VNMLS_t1, decode_VNMUL_t2},
+    vpop::{decode_VPOP_t1, decode_VPOP_t2},
+    vpush::{decode_VPUSH_t1, decode_VPUSH_t2},
+    vsqrt::decode_VSQRT_t1,
+    vstm::{decode_VSTM_t1, decode_VSTM_t2},
     vstr::{decode_VSTR_t1, decode_VSTR_t2},
+    vsub::decode_VSUB_t1,
     wfe::{decode_WFE_t1, decode_WFE_t2},
     wfi::{decode_WFI_t1, decode_WFI_t2},
     yield_::{decode_YIELD_t1, decode_YIELD_t2},
 };
 
+use crate::core::instruction::{VReg2Params, VReg3Params};
+use crate::core::register::{DoubleReg, SingleReg};
 use crate::core::thumb::ThumbCode;
 use crate::Processor;
 
@@ -268,13 +381,129 @@ pub trait Decoder {
 
 impl Decoder for Processor {
     fn decode(&self, code: ThumbCode) -> Instruction {
-        match code {
+        let instruction = match code {
             ThumbCode::Thumb32 { opcode } => decode_32(opcode),
             ThumbCode::Thumb16 { opcode } => decode_16(opcode),
+        };
+        if is_implemented(self.cpu, &instruction) {
+            instruction
+        } else {
+            Instruction::UDF {
+                imm32: 0,
+                opcode: code,
+                thumb32: instruction_size(&instruction) == 4,
+            }
         }
     }
 }
 
+///
+/// Check if the core implements the decoded instruction.
+///
+/// ARMv6-M has only a few 32 bit instructions and the DSP instructions are
+/// part of ARMv7E-M. The floating point instructions are coprocessor
+/// instructions on ARMv7-M: without the floating point extension they are
+/// decoded normally and fault with NOCP when executed.
+///
+fn is_implemented(cpu: Cpu, instruction: &Instruction) -> bool {
+    match cpu.architecture() {
+        Architecture::ArmV6M => is_armv6m(instruction),
+        Architecture::ArmV7M => !is_dsp(instruction),
+        Architecture::ArmV7EM => true,
+    }
+}
+
+fn is_armv6m(instruction: &Instruction) -> bool {
+    match instruction {
+        Instruction::BL { .. }
+        | Instruction::MSR_reg { .. }
+        | Instruction::MRS { .. }
+        | Instruction::DMB
+        | Instruction::DSB
+        | Instruction::ISB
+        | Instruction::UDF { .. } => true,
+        Instruction::CBZ { .. } | Instruction::CBNZ { .. } | Instruction::IT { .. } => false,
+        // there is no FAULTMASK
+        Instruction::CPS { affect_fault, .. } => !affect_fault,
+        _ => instruction_size(instruction) == 2,
+    }
+}
+
+fn is_dsp(instruction: &Instruction) -> bool {
+    matches!(
+        instruction,
+        Instruction::PKH { .. }
+            | Instruction::QADD { .. }
+            | Instruction::QSUB { .. }
+            | Instruction::QDADD { .. }
+            | Instruction::QDSUB { .. }
+            | Instruction::SADD16 { .. }
+            | Instruction::QADD16 { .. }
+            | Instruction::SHADD16 { .. }
+            | Instruction::UADD16 { .. }
+            | Instruction::UQADD16 { .. }
+            | Instruction::UHADD16 { .. }
+            | Instruction::SASX { .. }
+            | Instruction::QASX { .. }
+            | Instruction::SHASX { .. }
+            | Instruction::UASX { .. }
+            | Instruction::UQASX { .. }
+            | Instruction::UHASX { .. }
+            | Instruction::SSAX { .. }
+            | Instruction::QSAX { .. }
+            | Instruction::SHSAX { .. }
+            | Instruction::USAX { .. }
+            | Instruction::UQSAX { .. }
+            | Instruction::UHSAX { .. }
+            | Instruction::SSUB16 { .. }
+            | Instruction::QSUB16 { .. }
+            | Instruction::SHSUB16 { .. }
+            | Instruction::USUB16 { .. }
+            | Instruction::UQSUB16 { .. }
+            | Instruction::UHSUB16 { .. }
+            | Instruction::SADD8 { .. }
+            | Instruction::QADD8 { .. }
+            | Instruction::SHADD8 { .. }
+            | Instruction::UADD8 { .. }
+            | Instruction::UQADD8 { .. }
+            | Instruction::UHADD8 { .. }
+            | Instruction::SSUB8 { .. }
+            | Instruction::QSUB8 { .. }
+            | Instruction::SHSUB8 { .. }
+            | Instruction::USUB8 { .. }
+            | Instruction::UQSUB8 { .. }
+            | Instruction::UHSUB8 { .. }
+            | Instruction::SEL { .. }
+            | Instruction::USAD8 { .. }
+            | Instruction::USADA8 { .. }
+            | Instruction::SSAT16 { .. }
+            | Instruction::USAT16 { .. }
+            | Instruction::SXTAB { .. }
+            | Instruction::SXTAB16 { .. }
+            | Instruction::SXTAH { .. }
+            | Instruction::SXTB16 { .. }
+            | Instruction::UXTAB { .. }
+            | Instruction::UXTAB16 { .. }
+            | Instruction::UXTAH { .. }
+            | Instruction::UXTB16 { .. }
+            | Instruction::SMUL { .. }
+            | Instruction::SMLA { .. }
+            | Instruction::SMLAL_hw { .. }
+            | Instruction::SMLAD { .. }
+            | Instruction::SMLALD { .. }
+            | Instruction::SMLAW { .. }
+            | Instruction::SMLSD { .. }
+            | Instruction::SMLSLD { .. }
+            | Instruction::SMMLA { .. }
+            | Instruction::SMMLS { .. }
+            | Instruction::SMMUL { .. }
+            | Instruction::SMUAD { .. }
+            | Instruction::SMULW { .. }
+            | Instruction::SMUSD { .. }
+            | Instruction::UMAAL { .. }
+    )
+}
+
 /// determine if 16 bit word is start of 32 thumb value
 pub fn is_thumb32(word: u16) -> bool {
     match word.get_bits(11..16) {
@@ -301,6 +530,35 @@ fn decode_UDF_t2(opcode: u32) -> Instruction {
     }
 }
 
+///
+/// Single precision register number from a 4 bit field and an extra low bit
+///
+fn vreg_single(opcode: u32, field: usize, bit: usize) -> SingleReg {
+    SingleReg::from(((opcode.get_bits(field..field + 4) << 1) | opcode.get_bit(bit) as u32) as u8)
+}
+
+///
+/// Double precision register number from a 4 bit field and an extra high bit
+///
+fn vreg_double(opcode: u32, field: usize, bit: usize) -> DoubleReg {
+    DoubleReg::from((opcode.get_bits(field..field + 4) | ((opcode.get_bit(bit) as u32) << 4)) as u8)
+}
+
+fn vreg3_params(opcode: u32) -> VReg3Params {
+    VReg3Params {
+        sd: vreg_single(opcode, 12, 22),
+        sn: vreg_single(opcode, 16, 7),
+        sm: vreg_single(opcode, 0, 5),
+    }
+}
+
+fn vreg2_params(opcode: u32) -> VReg2Params {
+    VReg2Params {
+        sd: vreg_single(opcode, 12, 22),
+        sm: vreg_single(opcode, 0, 5),
+    }
+}
+
 #[allow(clippy::cognitive_complexity)]
 #[allow(clippy::too_many_lines)]
 ///
@@ -317,7 +575,7 @@ pub fn decode_16(opcode: u16) -> Instruction {
         decode_YIELD_t1(opcode)
     } else if opcode == 0xbf30 {
         decode_WFI_t1(opcode)
-    } else if (opcode & 0xffef) == 0xb662 {
+    } else if (opcode & 0xffec) == 0xb660 {
         decode_CPS_t1(opcode)
     } else if (opcode & 0xff87) == 0x4485 {
         decode_ADD_reg_sp_t2(opcode)
@@ -546,8 +804,136 @@ pub fn decode_32(opcode: u32) -> Instruction {
         decode_STREXB_t1(opcode)
     } else if (opcode & 0xfff0f0f0) == 0xfa90f0a0 {
         decode_RBIT_t1(opcode)
+    } else if (opcode & 0xfff0f0f0) == 0xfa90f000 {
+        decode_SADD16_t1(opcode)
+    } else if (opcode & 0xfff0f0f0) == 0xfa90f010 {
+        decode_QADD16_t1(opcode)
+    } else if (opcode & 0xfff0f0f0) == 0xfa90f020 {
+        decode_SHADD16_t1(opcode)
+    } else if (opcode & 0xfff0f0f0) == 0xfa90f040 {
+        decode_UADD16_t1(opcode)
+    } else if (opcode & 0xfff0f0f0) == 0xfa90f050 {
+        decode_UQADD16_t1(opcode)
+    } else if (opcode & 0xfff0f0f0) == 0xfa90f060 {
+        decode_UHADD16_t1(opcode)
+    } else if (opcode & 0xfff0f0f0) == 0xfaa0f000 {
+        decode_SASX_t1(opcode)
+    } else if (opcode & 0xfff0f0f0) == 0xfaa0f010 {
+        decode_QASX_t1(opcode)
+    } else if (opcode & 0xfff0f0f0) == 0xfaa0f020 {
+        decode_SHASX_t1(opcode)
+    } else if (opcode & 0xfff0f0f0) == 0xfaa0f040 {
+        decode_UASX_t1(opcode)
+    } else if (opcode & 0xfff0f0f0) == 0xfaa0f050 {
+        decode_UQASX_t1(opcode)
+    } else if (opcode & 0xfff0f0f0) == 0xfaa0f060 {
+        decode_UHASX_t1(opcode)
+    } else if (opcode & 0xfff0f0f0) == 0xfae0f000 {
+        decode_SSAX_t1(opcode)
+    } else if (opcode & 0xfff0f0f0) == 0xfae0f010 {
+        decode_QSAX_t1(opcode)
+    } else if (opcode & 0xfff0f0f0) == 0xfae0f020 {
+        decode_SHSAX_t1(opcode)
+    } else if (opcode & 0xfff0f0f0) == 0xfae0f040 {
+        decode_USAX_t1(opcode)
+    } else if (opcode & 0xfff0f0f0) == 0xfae0f050 {
+        decode_UQSAX_t1(opcode)
+    } else if (opcode & 0xfff0f0f0) == 0xfae0f060 {
+        decode_UHSAX_t1(opcode)
+    } else if (opcode & 0xfff0f0f0) == 0xfad0f000 {
+        decode_SSUB16_t1(opcode)
+    } else if (opcode & 0xfff0f0f0) == 0xfad0f010 {
+        decode_QSUB16_t1(opcode)
+    } else if (opcode & 0xfff0f0f0) == 0xfad0f020 {
+        decode_SHSUB16_t1(opcode)
+    } else if (opcode & 0xfff0f0f0) == 0xfad0f040 {
+        decode_USUB16_t1(opcode)
+    } else if (opcode & 0xfff0f0f0) == 0xfad0f050 {
+        decode_UQSUB16_t1(opcode)
+    } else if (opcode & 0xfff0f0f0) == 0xfad0f060 {
+        decode_UHSUB16_t1(opcode)
+    } else if (opcode & 0xfff0f0f0) == 0xfa80f000 {
+        decode_SADD8_t1(opcode)
+    } else if (opcode & 0xfff0f0f0) == 0xfa80f010 {
+        decode_QADD8_t1(opcode)
+    } else if (opcode & 0xfff0f0f0) == 0xfa80f020 {
+        decode_SHADD8_t1(opcode)
     } else if (opcode & 0xfff0f0f0) == 0xfa80f040 {
         decode_UADD8_t1(opcode)
+    } else if (opcode & 0xfff0f0f0) == 0xfa80f050 {
+        decode_UQADD8_t1(opcode)
+    } else if (opcode & 0xfff0f0f0) == 0xfa80f060 {
+        decode_UHADD8_t1(opcode)
+    } else if (opcode & 0xfff0f0f0) == 0xfac0f000 {
+        decode_SSUB8_t1(opcode)
+    } else if (opcode & 0xfff0f0f0) == 0xfac0f010 {
+        decode_QSUB8_t1(opcode)
+    } else if (opcode & 0xfff0f0f0) == 0xfac0f020 {
+        decode_SHSUB8_t1(opcode)
+    } else if (opcode & 0xfff0f0f0) == 0xfac0f040 {
+        decode_USUB8_t1(opcode)
+    } else if (opcode & 0xfff0f0f0) == 0xfac0f050 {
+        decode_UQSUB8_t1(opcode)
+    } else if (opcode & 0xfff0f0f0) == 0xfac0f060 {
+        decode_UHSUB8_t1(opcode)
+    } else if (opcode & 0xfff0f0f0) == 0xfa80f080 {
+        decode_QADD_t1(opcode)
+    } else if (opcode & 0xfff0f0f0) == 0xfa80f0a0 {
+        decode_QSUB_t1(opcode)
+    } else if (opcode & 0xfff0f0f0) == 0xfa80f090 {
+        decode_QDADD_t1(opcode)
+    } else if (opcode & 0xfff0f0f0) == 0xfa80f0b0 {
+        decode_QDSUB_t1(opcode)
+    } else if (opcode & 0xfff0f0e0) == 0xfb20f000 {
+        decode_SMUAD_t1(opcode)
+    } else if (opcode & 0xfff000e0) == 0xfb200000 {
+        decode_SMLAD_t1(opcode)
+    } else if (opcode & 0xfff0f0e0) == 0xfb40f000 {
+        decode_SMUSD_t1(opcode)
+    } else if (opcode & 0xfff000e0) == 0xfb400000 {
+        decode_SMLSD_t1(opcode)
+    } else if (opcode & 0xfff0f0e0) == 0xfb30f000 {
+        decode_SMULW_t1(opcode)
+    } else if (opcode & 0xfff000e0) == 0xfb300000 {
+        decode_SMLAW_t1(opcode)
+    } else if (opcode & 0xfff0f0e0) == 0xfb50f000 {
+        decode_SMMUL_t1(opcode)
+    } else if (opcode & 0xfff000e0) == 0xfb500000 {
+        decode_SMMLA_t1(opcode)
+    } else if (opcode & 0xfff000e0) == 0xfb600000 {
+        decode_SMMLS_t1(opcode)
+    } else if (opcode & 0xfff000e0) == 0xfbc000c0 {
+        decode_SMLALD_t1(opcode)
+    } else if (opcode & 0xfff000e0) == 0xfbd000c0 {
+        decode_SMLSLD_t1(opcode)
+    } else if (opcode & 0xfff000c0) == 0xfbc00080 {
+        decode_SMLAL_hw_t1(opcode)
+    } else if (opcode & 0xfff000f0) == 0xfbe00060 {
+        decode_UMAAL_t1(opcode)
+    } else if (opcode & 0xfff0f0f0) == 0xfb70f000 {
+        decode_USAD8_t1(opcode)
+    } else if (opcode & 0xfff000f0) == 0xfb700000 {
+        decode_USADA8_t1(opcode)
+    } else if (opcode & 0xfff0f0f0) == 0xf3200000 {
+        decode_SSAT16_t1(opcode)
+    } else if (opcode & 0xfff0f0f0) == 0xf3a00000 {
+        decode_USAT16_t1(opcode)
+    } else if (opcode & 0xfff08010) == 0xeac00000 {
+        decode_PKH_t1(opcode)
+    } else if (opcode & 0xfffff0c0) == 0xfa2ff080 {
+        decode_SXTB16_t1(opcode)
+    } else if (opcode & 0xfffff0c0) == 0xfa3ff080 {
+        decode_UXTB16_t1(opcode)
+    } else if (opcode & 0xfff0f0c0) == 0xfa40f080 {
+        decode_SXTAB_t1(opcode)
+    } else if (opcode & 0xfff0f0c0) == 0xfa00f080 {
+        decode_SXTAH_t1(opcode)
+    } else if (opcode & 0xfff0f0c0) == 0xfa10f080 {
+        decode_UXTAH_t1(opcode)
+    } else if (opcode & 0xfff0f0c0) == 0xfa20f080 {
+        decode_SXTAB16_t1(opcode)
+    } else if (opcode & 0xfff0f0c0) == 0xfa30f080 {
+        decode_UXTAB16_t1(opcode)
     } else if (opcode & 0xfff0f0f0) == 0xfab0f080 {
         decode_CLZ_t1(opcode)
     } else if (opcode & 0xfff00ff0) == 0xe8c00f50 {
@@ -668,6 +1054,92 @@ pub fn decode_32(opcode: u32) -> Instruction {
         decode_SUB_SP_imm_t2(opcode)
     } else if (opcode & 0xff7f0000) == 0xf93f0000 {
         decode_LDRSH_lit_t1(opcode)
+    } else if (opcode & 0xffff0fff) == 0xeef10a10 {
+        decode_VMRS_t1(opcode)
+    } else if (opcode & 0xffff0fff) == 0xeee10a10 {
+        decode_VMSR_t1(opcode)
+    } else if (opcode & 0xffbf0f7f) == 0xeeb50a40 {
+        decode_VCMP_t2(opcode)
+    } else if (opcode & 0xffbf0fd0) == 0xeeb00a40 {
+        decode_VMOV_reg_t1(opcode)
+    } else if (opcode & 0xffbf0fd0) == 0xeeb00ac0 {
+        decode_VABS_t1(opcode)
+    } else if (opcode & 0xffbf0fd0) == 0xeeb10a40 {
+        decode_VNEG_t1(opcode)
+    } else if (opcode & 0xffbf0fd0) == 0xeeb10ac0 {
+        decode_VSQRT_t1(opcode)
+    } else if (opcode & 0xffe00f7f) == 0xee000a10 {
+        decode_VMOV_cr_sp_t1(opcode)
+    } else if (opcode & 0xffd00f7f) == 0xee000b10 {
+        decode_VMOV_cr_scalar_t1(opcode)
+    } else if (opcode & 0xffd00f7f) == 0xee100b10 {
+        decode_VMOV_scalar_cr_t1(opcode)
+    } else if (opcode & 0xffbf0f50) == 0xeeb40a40 {
+        decode_VCMP_t1(opcode)
+    } else if (opcode & 0xffbf0f50) == 0xeeb80a40 {
+        decode_VCVT_int_t1(opcode)
+    } else if (opcode & 0xffbe0f50) == 0xeebc0a40 {
+        decode_VCVT_int_t1(opcode)
+    } else if (opcode & 0xffbe0f50) == 0xeeb20a40 {
+        decode_VCVT_half_t1(opcode)
+    } else if (opcode & 0xffba0f50) == 0xeeba0a40 {
+        decode_VCVT_fixed_t1(opcode)
+    } else if (opcode & 0xffbf0f00) == 0xed2d0b00 {
+        decode_VPUSH_t1(opcode)
+    } else if (opcode & 0xffbf0f00) == 0xed2d0a00 {
+        decode_VPUSH_t2(opcode)
+    } else if (opcode & 0xffbf0f00) == 0xecbd0b00 {
+        decode_VPOP_t1(opcode)
+    } else if (opcode & 0xffbf0f00) == 0xecbd0a00 {
+        decode_VPOP_t2(opcode)
+    } else if (opcode & 0xffb00ff0) == 0xeeb00a00 {
+        decode_VMOV_imm_t1(opcode)
+    } else if (opcode & 0xffe00fd0) == 0xec400a10 {
+        decode_VMOV_cr2_sp2_t1(opcode)
+    } else if (opcode & 0xffe00fd0) == 0xec400b10 {
+        decode_VMOV_cr2_dp_t1(opcode)
+    } else if (opcode & 0xffb00f50) == 0xee000a00 {
+        decode_VMLA_t1(opcode)
+    } else if (opcode & 0xffb00f50) == 0xee000a40 {
+        decode_VMLS_t1(opcode)
+    } else if (opcode & 0xffb00f50) == 0xee100a40 {
+        decode_VNMLA_t1(opcode)
+    } else if (opcode & 0xffb00f50) == 0xee100a00 {
+        decode_VNMLS_t1(opcode)
+    } else if (opcode & 0xffb00f50) == 0xee200a00 {
+        decode_VMUL_t1(opcode)
+    } else if (opcode & 0xffb00f50) == 0xee200a40 {
+        decode_VNMUL_t2(opcode)
+    } else if (opcode & 0xffb00f50) == 0xee300a00 {
+        decode_VADD_t1(opcode)
+    } else if (opcode & 0xffb00f50) == 0xee300a40 {
+        decode_VSUB_t1(opcode)
+    } else if (opcode & 0xffb00f50) == 0xee800a00 {
+        decode_VDIV_t1(opcode)
+    } else if (opcode & 0xffb00f50) == 0xee900a40 {
+        decode_VFNMA_t1(opcode)
+    } else if (opcode & 0xffb00f50) == 0xee900a00 {
+        decode_VFNMS_t1(opcode)
+    } else if (opcode & 0xffb00f50) == 0xeea00a00 {
+        decode_VFMA_t1(opcode)
+    } else if (opcode & 0xffb00f50) == 0xeea00a40 {
+        decode_VFMS_t1(opcode)
+    } else if (opcode & 0xffb00f00) == 0xed200b00 {
+        decode_VSTM_t1(opcode)
+    } else if (opcode & 0xffb00f00) == 0xed200a00 {
+        decode_VSTM_t2(opcode)
+    } else if (opcode & 0xffb00f00) == 0xed300b00 {
+        decode_VLDM_t1(opcode)
+    } else if (opcode & 0xffb00f00) == 0xed300a00 {
+        decode_VLDM_t2(opcode)
+    } else if (opcode & 0xff900f00) == 0xec800b00 {
+        decode_VSTM_t1(opcode)
+    } else if (opcode & 0xff900f00) == 0xec800a00 {
+        decode_VSTM_t2(opcode)
+    } else if (opcode & 0xff900f00) == 0xec900b00 {
+        decode_VLDM_t1(opcode)
+    } else if (opcode & 0xff900f00) == 0xec900a00 {
+        decode_VLDM_t2(opcode)
     } else if (opcode & 0xff300f00) == 0xed100a00 {
         decode_VLDR_t2(opcode)
     } else if (opcode & 0xff300f00) == 0xed100b00 {
@@ -680,6 +1152,9 @@ pub fn decode_32(opcode: u32) -> Instruction {
         decode_UBFX_t1(opcode)
     } else if (opcode & 0xff300f00) == 0xed000b00 {
         decode_VSTR_t1(opcode)
+    } else if (opcode & 0xfc000e00) == 0xec000a00 {
+        // unallocated encodings of the floating point extension
+        decode_UDF_t2(opcode)
     } else if (opcode & 0xfff08020) == 0xf3600000 {
         decode_BFI_t1(opcode)
     } else if (opcode & 0xfff08020) == 0xf3400000 {
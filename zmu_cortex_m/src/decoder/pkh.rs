@@ -0,0 +1,22 @@
+use crate::core::bits::Bits;
+use crate::core::instruction::{Instruction, Reg3ShiftNoSetFlagsParams};
+use crate::core::operation::decode_imm_shift;
+
+#[allow(non_snake_case)]
+pub fn decode_PKH_t1(opcode: u32) -> Instruction {
+    let imm3: u8 = opcode.get_bits(12..15) as u8;
+    let imm2: u8 = opcode.get_bits(6..8) as u8;
+    let tb: u8 = opcode.get_bit(5) as u8;
+
+    let (shift_t, shift_n) = decode_imm_shift(tb << 1, (imm3 << 2) + imm2);
+
+    Instruction::PKH {
+        params: Reg3ShiftNoSetFlagsParams {
+            rd: opcode.get_bits(8..12).into(),
+            rn: opcode.get_bits(16..20).into(),
+            rm: opcode.get_bits(0..4).into(),
+            shift_t,
+            shift_n,
+        },
+    }
+}
@@ -1,6 +1,6 @@
 use crate::core::{
     bits::Bits,
-    instruction::{Instruction, Reg643232Params},
+    instruction::{Instruction, Reg643232HighParams, Reg643232Params},
     register::Reg,
 };
 
@@ -19,3 +19,17 @@ pub fn decode_SMLAL_t1(opcode: u32) -> Instruction {
         },
     }
 }
+
+#[allow(non_snake_case)]
+pub fn decode_SMLAL_hw_t1(opcode: u32) -> Instruction {
+    Instruction::SMLAL_hw {
+        params: Reg643232HighParams {
+            rdlo: opcode.get_bits(12..16).into(),
+            rdhi: opcode.get_bits(8..12).into(),
+            rn: opcode.get_bits(16..20).into(),
+            rm: opcode.get_bits(0..4).into(),
+            n_high: opcode.get_bit(5),
+            m_high: opcode.get_bit(4),
+        },
+    }
+}
@@ -0,0 +1,15 @@
+use crate::core::bits::Bits;
+use crate::core::instruction::{Instruction, Reg643232SwapParams};
+
+#[allow(non_snake_case)]
+pub fn decode_SMLALD_t1(opcode: u32) -> Instruction {
+    Instruction::SMLALD {
+        params: Reg643232SwapParams {
+            rdlo: opcode.get_bits(12..16).into(),
+            rdhi: opcode.get_bits(8..12).into(),
+            rn: opcode.get_bits(16..20).into(),
+            rm: opcode.get_bits(0..4).into(),
+            m_swap: opcode.get_bit(4),
+        },
+    }
+}
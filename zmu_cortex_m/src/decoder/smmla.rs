@@ -0,0 +1,15 @@
+use crate::core::bits::Bits;
+use crate::core::instruction::{Instruction, Reg4RoundParams};
+
+#[allow(non_snake_case)]
+pub fn decode_SMMLA_t1(opcode: u32) -> Instruction {
+    Instruction::SMMLA {
+        params: Reg4RoundParams {
+            rd: opcode.get_bits(8..12).into(),
+            rn: opcode.get_bits(16..20).into(),
+            rm: opcode.get_bits(0..4).into(),
+            ra: opcode.get_bits(12..16).into(),
+            round: opcode.get_bit(4),
+        },
+    }
+}
@@ -0,0 +1,14 @@
+use crate::core::bits::Bits;
+use crate::core::instruction::{Instruction, Reg3RoundParams};
+
+#[allow(non_snake_case)]
+pub fn decode_SMMUL_t1(opcode: u32) -> Instruction {
+    Instruction::SMMUL {
+        params: Reg3RoundParams {
+            rd: opcode.get_bits(8..12).into(),
+            rn: opcode.get_bits(16..20).into(),
+            rm: opcode.get_bits(0..4).into(),
+            round: opcode.get_bit(4),
+        },
+    }
+}
@@ -0,0 +1,25 @@
+use crate::core::bits::Bits;
+use crate::core::instruction::{Instruction, Reg3NoSetFlagsParams, Reg4NoSetFlagsParams};
+
+#[allow(non_snake_case)]
+pub fn decode_USAD8_t1(opcode: u32) -> Instruction {
+    Instruction::USAD8 {
+        params: Reg3NoSetFlagsParams {
+            rd: opcode.get_bits(8..12).into(),
+            rn: opcode.get_bits(16..20).into(),
+            rm: opcode.get_bits(0..4).into(),
+        },
+    }
+}
+
+#[allow(non_snake_case)]
+pub fn decode_USADA8_t1(opcode: u32) -> Instruction {
+    Instruction::USADA8 {
+        params: Reg4NoSetFlagsParams {
+            rd: opcode.get_bits(8..12).into(),
+            rn: opcode.get_bits(16..20).into(),
+            rm: opcode.get_bits(0..4).into(),
+            ra: opcode.get_bits(12..16).into(),
+        },
+    }
+}
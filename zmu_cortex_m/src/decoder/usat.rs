@@ -1,10 +1,33 @@
-use crate::core::instruction::Instruction;
+use crate::core::bits::Bits;
+use crate::core::instruction::{Instruction, Saturate16Params, SaturateParams};
+use crate::core::operation::decode_imm_shift;
 
 #[allow(non_snake_case)]
 pub fn decode_USAT_t1(opcode: u32) -> Instruction {
-    Instruction::UDF {
-        imm32: 0,
-        opcode: opcode.into(),
-        thumb32: true,
+    let imm3: u8 = opcode.get_bits(12..15) as u8;
+    let imm2: u8 = opcode.get_bits(6..8) as u8;
+    let sh: u8 = opcode.get_bit(21) as u8;
+
+    let (shift_t, shift_n) = decode_imm_shift(sh << 1, (imm3 << 2) + imm2);
+
+    Instruction::USAT {
+        params: SaturateParams {
+            rd: opcode.get_bits(8..12).into(),
+            rn: opcode.get_bits(16..20).into(),
+            saturate_to: opcode.get_bits(0..5) as u8,
+            shift_t,
+            shift_n,
+        },
+    }
+}
+
+#[allow(non_snake_case)]
+pub fn decode_USAT16_t1(opcode: u32) -> Instruction {
+    Instruction::USAT16 {
+        params: Saturate16Params {
+            rd: opcode.get_bits(8..12).into(),
+            rn: opcode.get_bits(16..20).into(),
+            saturate_to: opcode.get_bits(0..4) as u8,
+        },
     }
 }
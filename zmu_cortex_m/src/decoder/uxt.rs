@@ -1,5 +1,5 @@
 use crate::core::bits::Bits;
-use crate::core::instruction::{Instruction, Reg2UsizeParams};
+use crate::core::instruction::{Instruction, Reg2UsizeParams, Reg3UsizeParams};
 use crate::core::register::Reg;
 
 #[allow(non_snake_case)]
@@ -50,3 +50,38 @@ pub fn decode_UXTH_t2(opcode: u32) -> Instruction {
         thumb32: true,
     }
 }
+
+#[allow(non_snake_case)]
+pub fn decode_UXTAB16_t1(opcode: u32) -> Instruction {
+    Instruction::UXTAB16 {
+        params: Reg3UsizeParams {
+            rd: opcode.get_bits(8..12).into(),
+            rn: opcode.get_bits(16..20).into(),
+            rm: opcode.get_bits(0..4).into(),
+            rotation: (opcode.get_bits(4..6) << 3) as usize,
+        },
+    }
+}
+
+#[allow(non_snake_case)]
+pub fn decode_UXTAH_t1(opcode: u32) -> Instruction {
+    Instruction::UXTAH {
+        params: Reg3UsizeParams {
+            rd: opcode.get_bits(8..12).into(),
+            rn: opcode.get_bits(16..20).into(),
+            rm: opcode.get_bits(0..4).into(),
+            rotation: (opcode.get_bits(4..6) << 3) as usize,
+        },
+    }
+}
+
+#[allow(non_snake_case)]
+pub fn decode_UXTB16_t1(opcode: u32) -> Instruction {
+    Instruction::UXTB16 {
+        params: Reg2UsizeParams {
+            rd: opcode.get_bits(8..12).into(),
+            rm: opcode.get_bits(0..4).into(),
+            rotation: (opcode.get_bits(4..6) << 3) as usize,
+        },
+    }
+}
@@ -0,0 +1,11 @@
+use crate::core::instruction::Instruction;
+
+use super::vreg2_params;
+
+#[allow(non_snake_case)]
+#[inline(always)]
+pub fn decode_VABS_t1(opcode: u32) -> Instruction {
+    Instruction::VABS {
+        params: vreg2_params(opcode),
+    }
+}
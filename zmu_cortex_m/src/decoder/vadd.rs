@@ -0,0 +1,11 @@
+use crate::core::instruction::Instruction;
+
+use super::vreg3_params;
+
+#[allow(non_snake_case)]
+#[inline(always)]
+pub fn decode_VADD_t1(opcode: u32) -> Instruction {
+    Instruction::VADD {
+        params: vreg3_params(opcode),
+    }
+}
@@ -0,0 +1,28 @@
+use crate::core::bits::Bits;
+use crate::core::instruction::{Instruction, VCmpParams};
+
+use super::vreg_single;
+
+#[allow(non_snake_case)]
+#[inline(always)]
+pub fn decode_VCMP_t1(opcode: u32) -> Instruction {
+    Instruction::VCMP {
+        params: VCmpParams {
+            sd: vreg_single(opcode, 12, 22),
+            sm: Some(vreg_single(opcode, 0, 5)),
+            quiet_nan_exc: opcode.get_bit(7),
+        },
+    }
+}
+
+#[allow(non_snake_case)]
+#[inline(always)]
+pub fn decode_VCMP_t2(opcode: u32) -> Instruction {
+    Instruction::VCMP {
+        params: VCmpParams {
+            sd: vreg_single(opcode, 12, 22),
+            sm: None,
+            quiet_nan_exc: opcode.get_bit(7),
+        },
+    }
+}
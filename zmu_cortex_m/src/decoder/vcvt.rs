@@ -0,0 +1,52 @@
+use crate::core::bits::Bits;
+use crate::core::instruction::{Instruction, VCvtFixedParams, VCvtHalfParams, VCvtIntParams};
+
+use super::vreg_single;
+
+#[allow(non_snake_case)]
+#[inline(always)]
+pub fn decode_VCVT_int_t1(opcode: u32) -> Instruction {
+    let to_integer = opcode.get_bit(18);
+    Instruction::VCVT_int {
+        params: VCvtIntParams {
+            sd: vreg_single(opcode, 12, 22),
+            sm: vreg_single(opcode, 0, 5),
+            to_integer,
+            signed: if to_integer {
+                opcode.get_bit(16)
+            } else {
+                opcode.get_bit(7)
+            },
+            round_zero: to_integer && opcode.get_bit(7),
+        },
+    }
+}
+
+#[allow(non_snake_case)]
+#[inline(always)]
+pub fn decode_VCVT_fixed_t1(opcode: u32) -> Instruction {
+    let size = if opcode.get_bit(7) { 32 } else { 16 };
+    let imm5 = (opcode.get_bits(0..4) << 1) as u8 | opcode.get_bit(5) as u8;
+    Instruction::VCVT_fixed {
+        params: VCvtFixedParams {
+            sd: vreg_single(opcode, 12, 22),
+            to_fixed: opcode.get_bit(18),
+            unsigned: opcode.get_bit(16),
+            size,
+            frac_bits: size.wrapping_sub(imm5),
+        },
+    }
+}
+
+#[allow(non_snake_case)]
+#[inline(always)]
+pub fn decode_VCVT_half_t1(opcode: u32) -> Instruction {
+    Instruction::VCVT_half {
+        params: VCvtHalfParams {
+            sd: vreg_single(opcode, 12, 22),
+            sm: vreg_single(opcode, 0, 5),
+            to_half: opcode.get_bit(16),
+            top: opcode.get_bit(7),
+        },
+    }
+}
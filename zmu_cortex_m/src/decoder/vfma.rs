@@ -0,0 +1,19 @@
+use crate::core::instruction::Instruction;
+
+use super::vreg3_params;
+
+#[allow(non_snake_case)]
+#[inline(always)]
+pub fn decode_VFMA_t1(opcode: u32) -> Instruction {
+    Instruction::VFMA {
+        params: vreg3_params(opcode),
+    }
+}
+
+#[allow(non_snake_case)]
+#[inline(always)]
+pub fn decode_VFMS_t1(opcode: u32) -> Instruction {
+    Instruction::VFMS {
+        params: vreg3_params(opcode),
+    }
+}
@@ -0,0 +1,19 @@
+use crate::core::instruction::Instruction;
+
+use super::vreg3_params;
+
+#[allow(non_snake_case)]
+#[inline(always)]
+pub fn decode_VFNMA_t1(opcode: u32) -> Instruction {
+    Instruction::VFNMA {
+        params: vreg3_params(opcode),
+    }
+}
+
+#[allow(non_snake_case)]
+#[inline(always)]
+pub fn decode_VFNMS_t1(opcode: u32) -> Instruction {
+    Instruction::VFNMS {
+        params: vreg3_params(opcode),
+    }
+}
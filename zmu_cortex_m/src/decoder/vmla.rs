@@ -0,0 +1,19 @@
+use crate::core::instruction::Instruction;
+
+use super::vreg3_params;
+
+#[allow(non_snake_case)]
+#[inline(always)]
+pub fn decode_VMLA_t1(opcode: u32) -> Instruction {
+    Instruction::VMLA {
+        params: vreg3_params(opcode),
+    }
+}
+
+#[allow(non_snake_case)]
+#[inline(always)]
+pub fn decode_VMLS_t1(opcode: u32) -> Instruction {
+    Instruction::VMLS {
+        params: vreg3_params(opcode),
+    }
+}
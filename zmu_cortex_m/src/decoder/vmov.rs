@@ -0,0 +1,101 @@
+use crate::core::bits::Bits;
+use crate::core::instruction::{
+    Instruction, VMovCr2DpParams, VMovCr2Sp2Params, VMovCrScalarParams, VMovCrSpParams,
+    VMovImmParams,
+};
+use crate::core::register::Reg;
+
+use super::{vreg2_params, vreg_double, vreg_single};
+
+///
+/// Expand the 8 bit immediate of VMOV into a single precision value
+///
+fn vfp_expand_imm(imm8: u32) -> u32 {
+    let sign = imm8.get_bit(7) as u32;
+    let b = imm8.get_bit(6);
+    let exp = ((!b as u32) << 7) | if b { 0x7c } else { 0 } | imm8.get_bits(4..6);
+    (sign << 31) | (exp << 23) | (imm8.get_bits(0..4) << 19)
+}
+
+#[allow(non_snake_case)]
+#[inline(always)]
+pub fn decode_VMOV_imm_t1(opcode: u32) -> Instruction {
+    Instruction::VMOV_imm {
+        params: VMovImmParams {
+            sd: vreg_single(opcode, 12, 22),
+            imm32: vfp_expand_imm((opcode.get_bits(16..20) << 4) | opcode.get_bits(0..4)),
+        },
+    }
+}
+
+#[allow(non_snake_case)]
+#[inline(always)]
+pub fn decode_VMOV_reg_t1(opcode: u32) -> Instruction {
+    Instruction::VMOV_reg {
+        params: vreg2_params(opcode),
+    }
+}
+
+#[allow(non_snake_case)]
+#[inline(always)]
+pub fn decode_VMOV_cr_sp_t1(opcode: u32) -> Instruction {
+    Instruction::VMOV_cr_sp {
+        params: VMovCrSpParams {
+            rt: Reg::from(opcode.get_bits(12..16) as u8),
+            sn: vreg_single(opcode, 16, 7),
+            to_arm_register: opcode.get_bit(20),
+        },
+    }
+}
+
+#[allow(non_snake_case)]
+#[inline(always)]
+pub fn decode_VMOV_cr2_sp2_t1(opcode: u32) -> Instruction {
+    Instruction::VMOV_cr2_sp2 {
+        params: VMovCr2Sp2Params {
+            rt: Reg::from(opcode.get_bits(12..16) as u8),
+            rt2: Reg::from(opcode.get_bits(16..20) as u8),
+            sm: vreg_single(opcode, 0, 5),
+            to_arm_registers: opcode.get_bit(20),
+        },
+    }
+}
+
+#[allow(non_snake_case)]
+#[inline(always)]
+pub fn decode_VMOV_cr2_dp_t1(opcode: u32) -> Instruction {
+    Instruction::VMOV_cr2_dp {
+        params: VMovCr2DpParams {
+            rt: Reg::from(opcode.get_bits(12..16) as u8),
+            rt2: Reg::from(opcode.get_bits(16..20) as u8),
+            dm: vreg_double(opcode, 0, 5),
+            to_arm_registers: opcode.get_bit(20),
+        },
+    }
+}
+
+#[allow(non_snake_case)]
+#[inline(always)]
+pub fn decode_VMOV_cr_scalar_t1(opcode: u32) -> Instruction {
+    Instruction::VMOV_cr_scalar {
+        params: VMovCrScalarParams {
+            rt: Reg::from(opcode.get_bits(12..16) as u8),
+            dd: vreg_double(opcode, 16, 7),
+            x: opcode.get_bit(21) as u8,
+            to_arm_register: false,
+        },
+    }
+}
+
+#[allow(non_snake_case)]
+#[inline(always)]
+pub fn decode_VMOV_scalar_cr_t1(opcode: u32) -> Instruction {
+    Instruction::VMOV_cr_scalar {
+        params: VMovCrScalarParams {
+            rt: Reg::from(opcode.get_bits(12..16) as u8),
+            dd: vreg_double(opcode, 16, 7),
+            x: opcode.get_bit(21) as u8,
+            to_arm_register: true,
+        },
+    }
+}
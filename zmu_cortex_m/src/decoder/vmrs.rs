@@ -0,0 +1,11 @@
+use crate::core::bits::Bits;
+use crate::core::instruction::Instruction;
+use crate::core::register::Reg;
+
+#[allow(non_snake_case)]
+#[inline(always)]
+pub fn decode_VMRS_t1(opcode: u32) -> Instruction {
+    Instruction::VMRS {
+        rt: Reg::from(opcode.get_bits(12..16) as u8),
+    }
+}
@@ -0,0 +1,11 @@
+use crate::core::bits::Bits;
+use crate::core::instruction::Instruction;
+use crate::core::register::Reg;
+
+#[allow(non_snake_case)]
+#[inline(always)]
+pub fn decode_VMSR_t1(opcode: u32) -> Instruction {
+    Instruction::VMSR {
+        rt: Reg::from(opcode.get_bits(12..16) as u8),
+    }
+}
@@ -0,0 +1,11 @@
+use crate::core::instruction::Instruction;
+
+use super::vreg2_params;
+
+#[allow(non_snake_case)]
+#[inline(always)]
+pub fn decode_VNEG_t1(opcode: u32) -> Instruction {
+    Instruction::VNEG {
+        params: vreg2_params(opcode),
+    }
+}
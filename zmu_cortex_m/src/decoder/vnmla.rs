@@ -0,0 +1,27 @@
+use crate::core::instruction::Instruction;
+
+use super::vreg3_params;
+
+#[allow(non_snake_case)]
+#[inline(always)]
+pub fn decode_VNMLA_t1(opcode: u32) -> Instruction {
+    Instruction::VNMLA {
+        params: vreg3_params(opcode),
+    }
+}
+
+#[allow(non_snake_case)]
+#[inline(always)]
+pub fn decode_VNMLS_t1(opcode: u32) -> Instruction {
+    Instruction::VNMLS {
+        params: vreg3_params(opcode),
+    }
+}
+
+#[allow(non_snake_case)]
+#[inline(always)]
+pub fn decode_VNMUL_t2(opcode: u32) -> Instruction {
+    Instruction::VNMUL {
+        params: vreg3_params(opcode),
+    }
+}
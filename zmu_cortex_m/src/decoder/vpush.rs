@@ -0,0 +1,31 @@
+use crate::core::bits::Bits;
+use crate::core::instruction::{Instruction, VPushPopParams};
+use crate::core::register::ExtensionReg;
+
+use super::{vreg_double, vreg_single};
+
+#[allow(non_snake_case)]
+#[inline(always)]
+pub fn decode_VPUSH_t1(opcode: u32) -> Instruction {
+    Instruction::VPUSH {
+        params: VPushPopParams {
+            dd: ExtensionReg::Double {
+                reg: vreg_double(opcode, 12, 22),
+            },
+            regs: (opcode.get_bits(0..8) / 2) as u8,
+        },
+    }
+}
+
+#[allow(non_snake_case)]
+#[inline(always)]
+pub fn decode_VPUSH_t2(opcode: u32) -> Instruction {
+    Instruction::VPUSH {
+        params: VPushPopParams {
+            dd: ExtensionReg::Single {
+                reg: vreg_single(opcode, 12, 22),
+            },
+            regs: opcode.get_bits(0..8) as u8,
+        },
+    }
+}
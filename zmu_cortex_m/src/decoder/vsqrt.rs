@@ -0,0 +1,11 @@
+use crate::core::instruction::Instruction;
+
+use super::vreg2_params;
+
+#[allow(non_snake_case)]
+#[inline(always)]
+pub fn decode_VSQRT_t1(opcode: u32) -> Instruction {
+    Instruction::VSQRT {
+        params: vreg2_params(opcode),
+    }
+}
@@ -0,0 +1,37 @@
+use crate::core::bits::Bits;
+use crate::core::instruction::{Instruction, VLoadAndStoreMultipleParams};
+use crate::core::register::{ExtensionReg, Reg};
+
+use super::{vreg_double, vreg_single};
+
+#[allow(non_snake_case)]
+#[inline(always)]
+pub fn decode_VSTM_t1(opcode: u32) -> Instruction {
+    Instruction::VSTM {
+        params: VLoadAndStoreMultipleParams {
+            rn: Reg::from(opcode.get_bits(16..20) as u8),
+            dd: ExtensionReg::Double {
+                reg: vreg_double(opcode, 12, 22),
+            },
+            regs: (opcode.get_bits(0..8) / 2) as u8,
+            add: opcode.get_bit(23),
+            wback: opcode.get_bit(21),
+        },
+    }
+}
+
+#[allow(non_snake_case)]
+#[inline(always)]
+pub fn decode_VSTM_t2(opcode: u32) -> Instruction {
+    Instruction::VSTM {
+        params: VLoadAndStoreMultipleParams {
+            rn: Reg::from(opcode.get_bits(16..20) as u8),
+            dd: ExtensionReg::Single {
+                reg: vreg_single(opcode, 12, 22),
+            },
+            regs: opcode.get_bits(0..8) as u8,
+            add: opcode.get_bit(23),
+            wback: opcode.get_bit(21),
+        },
+    }
+}
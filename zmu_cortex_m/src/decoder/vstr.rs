@@ -1,6 +1,8 @@
 use crate::core::bits::Bits;
 use crate::core::instruction::{Instruction, VLoadAndStoreParams};
-use crate::core::register::{DoubleReg, ExtensionReg, Reg, SingleReg};
+use crate::core::register::{ExtensionReg, Reg};
+
+use super::{vreg_double, vreg_single};
 
 #[allow(non_snake_case)]
 #[inline(always)]
@@ -8,9 +10,7 @@ pub fn decode_VSTR_t1(opcode: u32) -> Instruction {
     Instruction::VSTR {
         params: VLoadAndStoreParams {
             dd: ExtensionReg::Double {
-                reg: DoubleReg::from(
-                    opcode.get_bits(12..16) as u8 + ((opcode.get_bit(22) as u8) << 4) as u8,
-                ),
+                reg: vreg_double(opcode, 12, 22),
             },
             rn: Reg::from(opcode.get_bits(16..20) as u8),
             imm32: opcode.get_bits(0..8) << 2,
@@ -25,9 +25,7 @@ pub fn decode_VSTR_t2(opcode: u32) -> Instruction {
     Instruction::VSTR {
         params: VLoadAndStoreParams {
             dd: ExtensionReg::Single {
-                reg: SingleReg::from(
-                    opcode.get_bits(12..16) as u8 + ((opcode.get_bit(22) as u8) << 4) as u8,
-                ),
+                reg: vreg_single(opcode, 12, 22),
             },
             rn: Reg::from(opcode.get_bits(16..20) as u8),
             imm32: opcode.get_bits(0..8) << 2,
@@ -6,6 +6,12 @@
 use crate::bus::Bus;
 use crate::core::fault::Fault;
 
+/// Number of external interrupts, the full NVIC range
+pub const NVIC_NUM_INTERRUPTS: usize = 496;
+
+/// Number of implemented priority bits (`__NVIC_PRIO_BITS`)
+pub const NVIC_PRIO_BITS: u8 = 8;
+
 ///
 ///
 pub struct Device {}
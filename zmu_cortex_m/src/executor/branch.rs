@@ -102,7 +102,9 @@ impl IsaBranch for Processor {
             let rn = self.get_r(params.rn);
             let rm = self.get_r(params.rm);
             let pc = self.get_r(Reg::PC);
-            let halfwords = u32::from(self.read16(rn + (rm << 1))?);
+            let address = rn + (rm << 1);
+            self.check_unaligned_trap(address, 2)?;
+            let halfwords = u32::from(self.read16_unaligned(address)?);
 
             self.branch_write_pc(pc + 2 * halfwords);
 
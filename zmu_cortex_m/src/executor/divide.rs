@@ -54,6 +54,7 @@ impl IsaDivide for Processor {
 mod tests {
     use super::*;
     use crate::core::{instruction::Instruction, register::Reg};
+    use crate::peripheral::scb::CCR_DIV_0_TRP;
 
     #[test]
     fn test_udiv() {
@@ -79,4 +80,36 @@ mod tests {
         assert_eq!(core.get_r(Reg::R0), 0x29a);
         assert_eq!(core.get_r(Reg::R1), 0x3);
     }
+
+    #[test]
+    fn test_udiv_by_zero() {
+        // arrange
+        let mut core = Processor::new();
+        core.set_r(Reg::R0, 0x7d0);
+        core.set_r(Reg::R1, 0);
+
+        let instruction = Instruction::UDIV {
+            params: Reg3NoSetFlagsParams {
+                rd: Reg::R0,
+                rn: Reg::R0,
+                rm: Reg::R1,
+            },
+        };
+
+        // act
+        let result = core.execute_internal(&instruction);
+
+        // assert
+        assert_eq!(result, Ok(ExecuteSuccess::Taken { cycles: 2 }));
+        assert_eq!(core.get_r(Reg::R0), 0);
+
+        // act: trap enabled by CCR.DIV_0_TRP
+        core.ccr |= CCR_DIV_0_TRP;
+        core.set_r(Reg::R0, 0x7d0);
+        let result = core.execute_internal(&instruction);
+
+        // assert
+        assert_eq!(result, Err(Fault::DivByZero));
+        assert_eq!(core.get_r(Reg::R0), 0x7d0);
+    }
 }
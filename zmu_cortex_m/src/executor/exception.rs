@@ -1,8 +1,10 @@
 use crate::Processor;
 
 use crate::{
+    core::exception::ExceptionHandling,
     core::register::{BaseReg, Reg},
     executor::{ExecuteSuccess, ExecutorHelper},
+    peripheral::dcb::{DebugControlBlock, DFSR_BKPT},
     semihosting::{decode_semihostcmd, semihost_return},
 };
 
@@ -10,14 +12,15 @@ use super::ExecuteResult;
 
 /// Branching operations
 pub trait IsaException {
-    fn exec_svc(&self) -> ExecuteResult;
+    fn exec_svc(&mut self) -> ExecuteResult;
     fn exec_bkpt(&mut self, imm32: u32) -> ExecuteResult;
 }
 
 impl IsaException for Processor {
-    fn exec_svc(&self) -> ExecuteResult {
+    fn exec_svc(&mut self) -> ExecuteResult {
         if self.condition_passed() {
-            //TODO
+            // the exception is taken after the instruction, it returns to the next one
+            self.supervisor_call();
             return Ok(ExecuteSuccess::Taken { cycles: 1 });
         }
         Ok(ExecuteSuccess::NotTaken)
@@ -32,8 +35,78 @@ impl IsaException for Processor {
             if let Some(sh_func) = &mut self.semihost_func {
                 let semihost_response = (sh_func)(&semihost_cmd);
                 semihost_return(self, &semihost_response);
+                if self.state & 1 == 0 {
+                    self.exit_status = semihost_cmd.exit_status();
+                }
             }
+        } else if self.halting_debug_enabled() {
+            // halt on the breakpoint instruction itself
+            self.debug_halt(DFSR_BKPT);
+            return Ok(ExecuteSuccess::Branched { cycles: 1 });
         }
         Ok(ExecuteSuccess::Taken { cycles: 1 })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bus::Bus;
+    use crate::core::bits::Bits;
+    use crate::core::exception::Exception;
+    use crate::core::register::{Epsr, Ipsr};
+    use crate::executor::Executor;
+
+    fn svc_processor() -> Processor {
+        // svc #0, nop after the vector table
+        let mut code = [0; 0x104];
+        code[0x100..].copy_from_slice(&[0x00, 0xdf, 0x00, 0xbf]);
+
+        let mut processor = Processor::new();
+        processor.flash_memory(code.len(), &code);
+        processor.cache_instructions();
+        processor.set_msp(0x2000_1000);
+        processor.set_pc(0x100);
+        processor.psr.set_t(true);
+        processor.execution_priority = processor.get_execution_priority();
+        processor
+    }
+
+    #[test]
+    fn test_svc_exception() {
+        // Arrange
+        let mut processor = svc_processor();
+        processor.set_exception_priority(Exception::SVCall, 0x80);
+
+        // Act
+        processor.step();
+
+        // Assert
+        assert_eq!(
+            processor.psr.get_isr_number(),
+            usize::from(Exception::SVCall)
+        );
+        // stacked return address is the instruction after svc
+        assert_eq!(processor.read32(0x2000_1000 - 8).unwrap(), 0x102);
+    }
+
+    #[test]
+    fn test_svc_escalates_to_hardfault() {
+        // Arrange
+        let mut processor = svc_processor();
+        processor.primask = true;
+        processor.execution_priority = processor.get_execution_priority();
+
+        // Act
+        processor.step();
+
+        // Assert
+        assert_eq!(
+            processor.psr.get_isr_number(),
+            usize::from(Exception::HardFault)
+        );
+        assert!(!processor.exception_pending(Exception::SVCall));
+        assert_eq!(processor.read32(0x2000_1000 - 8).unwrap(), 0x102);
+        assert!(processor.hfsr.get_bit(30)); // FORCED
+    }
+}
@@ -0,0 +1,399 @@
+use crate::Processor;
+
+use crate::executor::{ExecuteSuccess, ExecutorHelper};
+
+use super::ExecuteResult;
+use crate::core::{
+    bits::Bits,
+    fpu::{
+        fixed_to_fp, fp_abs, fp_add, fp_compare, fp_div, fp_half_to_single, fp_mul, fp_mul_add,
+        fp_neg, fp_single_to_half, fp_sqrt, fp_sub, fp_to_fixed,
+    },
+    instruction::{
+        VCmpParams, VCvtFixedParams, VCvtHalfParams, VCvtIntParams, VMovImmParams, VReg2Params,
+        VReg3Params,
+    },
+    register::ExtensionRegOperations,
+};
+
+/// Floating point data processing operations
+pub trait IsaFloatingPointDataProcessing {
+    fn exec_vabs(&mut self, params: &VReg2Params) -> ExecuteResult;
+    fn exec_vadd(&mut self, params: &VReg3Params) -> ExecuteResult;
+    fn exec_vcmp(&mut self, params: &VCmpParams) -> ExecuteResult;
+    fn exec_vcvt_int(&mut self, params: &VCvtIntParams) -> ExecuteResult;
+    fn exec_vcvt_fixed(&mut self, params: &VCvtFixedParams) -> ExecuteResult;
+    fn exec_vcvt_half(&mut self, params: &VCvtHalfParams) -> ExecuteResult;
+    fn exec_vdiv(&mut self, params: &VReg3Params) -> ExecuteResult;
+    fn exec_vfma(&mut self, params: &VReg3Params) -> ExecuteResult;
+    fn exec_vfms(&mut self, params: &VReg3Params) -> ExecuteResult;
+    fn exec_vfnma(&mut self, params: &VReg3Params) -> ExecuteResult;
+    fn exec_vfnms(&mut self, params: &VReg3Params) -> ExecuteResult;
+    fn exec_vmla(&mut self, params: &VReg3Params) -> ExecuteResult;
+    fn exec_vmls(&mut self, params: &VReg3Params) -> ExecuteResult;
+    fn exec_vmov_imm(&mut self, params: &VMovImmParams) -> ExecuteResult;
+    fn exec_vmov_reg(&mut self, params: &VReg2Params) -> ExecuteResult;
+    fn exec_vmul(&mut self, params: &VReg3Params) -> ExecuteResult;
+    fn exec_vneg(&mut self, params: &VReg2Params) -> ExecuteResult;
+    fn exec_vnmla(&mut self, params: &VReg3Params) -> ExecuteResult;
+    fn exec_vnmls(&mut self, params: &VReg3Params) -> ExecuteResult;
+    fn exec_vnmul(&mut self, params: &VReg3Params) -> ExecuteResult;
+    fn exec_vsqrt(&mut self, params: &VReg2Params) -> ExecuteResult;
+    fn exec_vsub(&mut self, params: &VReg3Params) -> ExecuteResult;
+}
+
+///
+/// Execute an operation of form Sd = op(Sd, Sn, Sm)
+///
+fn vreg3_operation<F>(
+    processor: &mut Processor,
+    params: &VReg3Params,
+    cycles: u32,
+    operation: F,
+) -> ExecuteResult
+where
+    F: FnOnce(u32, u32, u32, &mut u32) -> u32,
+{
+    if processor.condition_passed() {
+        processor.execute_fp_check()?;
+
+        let d = processor.get_sr(params.sd);
+        let n = processor.get_sr(params.sn);
+        let m = processor.get_sr(params.sm);
+        let result = operation(d, n, m, &mut processor.fpscr);
+        processor.set_sr(params.sd, result);
+
+        return Ok(ExecuteSuccess::Taken { cycles });
+    }
+    Ok(ExecuteSuccess::NotTaken)
+}
+
+///
+/// Execute an operation of form Sd = op(Sm)
+///
+fn vreg2_operation<F>(
+    processor: &mut Processor,
+    params: &VReg2Params,
+    cycles: u32,
+    operation: F,
+) -> ExecuteResult
+where
+    F: FnOnce(u32, &mut u32) -> u32,
+{
+    if processor.condition_passed() {
+        processor.execute_fp_check()?;
+
+        let m = processor.get_sr(params.sm);
+        let result = operation(m, &mut processor.fpscr);
+        processor.set_sr(params.sd, result);
+
+        return Ok(ExecuteSuccess::Taken { cycles });
+    }
+    Ok(ExecuteSuccess::NotTaken)
+}
+
+impl IsaFloatingPointDataProcessing for Processor {
+    fn exec_vabs(&mut self, params: &VReg2Params) -> ExecuteResult {
+        vreg2_operation(self, params, 1, |m, _| fp_abs(m))
+    }
+
+    fn exec_vadd(&mut self, params: &VReg3Params) -> ExecuteResult {
+        vreg3_operation(self, params, 1, |_, n, m, fpscr| fp_add(n, m, fpscr))
+    }
+
+    fn exec_vcmp(&mut self, params: &VCmpParams) -> ExecuteResult {
+        if self.condition_passed() {
+            self.execute_fp_check()?;
+
+            let op1 = self.get_sr(params.sd);
+            let op2 = match params.sm {
+                Some(sm) => self.get_sr(sm),
+                None => 0,
+            };
+            let nzcv = fp_compare(op1, op2, params.quiet_nan_exc, &mut self.fpscr);
+            self.fpscr.set_bits(28..32, nzcv >> 28);
+
+            return Ok(ExecuteSuccess::Taken { cycles: 1 });
+        }
+        Ok(ExecuteSuccess::NotTaken)
+    }
+
+    fn exec_vcvt_int(&mut self, params: &VCvtIntParams) -> ExecuteResult {
+        let unsigned = !params.signed;
+        if params.to_integer {
+            let round_zero = params.round_zero;
+            vreg2_operation(
+                self,
+                &VReg2Params {
+                    sd: params.sd,
+                    sm: params.sm,
+                },
+                1,
+                |m, fpscr| fp_to_fixed(m, 32, 0, unsigned, round_zero, fpscr),
+            )
+        } else {
+            vreg2_operation(
+                self,
+                &VReg2Params {
+                    sd: params.sd,
+                    sm: params.sm,
+                },
+                1,
+                |m, fpscr| fixed_to_fp(m, 32, 0, unsigned, fpscr),
+            )
+        }
+    }
+
+    fn exec_vcvt_fixed(&mut self, params: &VCvtFixedParams) -> ExecuteResult {
+        let size = u32::from(params.size);
+        let frac_bits = u32::from(params.frac_bits);
+        let unsigned = params.unsigned;
+        let operands = VReg2Params {
+            sd: params.sd,
+            sm: params.sd,
+        };
+        if params.to_fixed {
+            vreg2_operation(self, &operands, 1, |d, fpscr| {
+                fp_to_fixed(d, size, frac_bits, unsigned, true, fpscr)
+            })
+        } else {
+            vreg2_operation(self, &operands, 1, |d, fpscr| {
+                fixed_to_fp(d, size, frac_bits, unsigned, fpscr)
+            })
+        }
+    }
+
+    fn exec_vcvt_half(&mut self, params: &VCvtHalfParams) -> ExecuteResult {
+        if self.condition_passed() {
+            self.execute_fp_check()?;
+
+            let m = self.get_sr(params.sm);
+            let half = if params.top { 16..32 } else { 0..16 };
+            if params.to_half {
+                let mut result = self.get_sr(params.sd);
+                result.set_bits(half, u32::from(fp_single_to_half(m, &mut self.fpscr)));
+                self.set_sr(params.sd, result);
+            } else {
+                let result = fp_half_to_single(m.get_bits(half) as u16, &mut self.fpscr);
+                self.set_sr(params.sd, result);
+            }
+
+            return Ok(ExecuteSuccess::Taken { cycles: 1 });
+        }
+        Ok(ExecuteSuccess::NotTaken)
+    }
+
+    fn exec_vdiv(&mut self, params: &VReg3Params) -> ExecuteResult {
+        vreg3_operation(self, params, 14, |_, n, m, fpscr| fp_div(n, m, fpscr))
+    }
+
+    fn exec_vfma(&mut self, params: &VReg3Params) -> ExecuteResult {
+        vreg3_operation(self, params, 3, |d, n, m, fpscr| fp_mul_add(d, n, m, fpscr))
+    }
+
+    fn exec_vfms(&mut self, params: &VReg3Params) -> ExecuteResult {
+        vreg3_operation(self, params, 3, |d, n, m, fpscr| {
+            fp_mul_add(d, fp_neg(n), m, fpscr)
+        })
+    }
+
+    fn exec_vfnma(&mut self, params: &VReg3Params) -> ExecuteResult {
+        vreg3_operation(self, params, 3, |d, n, m, fpscr| {
+            fp_mul_add(fp_neg(d), fp_neg(n), m, fpscr)
+        })
+    }
+
+    fn exec_vfnms(&mut self, params: &VReg3Params) -> ExecuteResult {
+        vreg3_operation(self, params, 3, |d, n, m, fpscr| {
+            fp_mul_add(fp_neg(d), n, m, fpscr)
+        })
+    }
+
+    fn exec_vmla(&mut self, params: &VReg3Params) -> ExecuteResult {
+        vreg3_operation(self, params, 3, |d, n, m, fpscr| {
+            let product = fp_mul(n, m, fpscr);
+            fp_add(d, product, fpscr)
+        })
+    }
+
+    fn exec_vmls(&mut self, params: &VReg3Params) -> ExecuteResult {
+        vreg3_operation(self, params, 3, |d, n, m, fpscr| {
+            let product = fp_mul(n, m, fpscr);
+            fp_add(d, fp_neg(product), fpscr)
+        })
+    }
+
+    fn exec_vmov_imm(&mut self, params: &VMovImmParams) -> ExecuteResult {
+        if self.condition_passed() {
+            self.execute_fp_check()?;
+            self.set_sr(params.sd, params.imm32);
+            return Ok(ExecuteSuccess::Taken { cycles: 1 });
+        }
+        Ok(ExecuteSuccess::NotTaken)
+    }
+
+    fn exec_vmov_reg(&mut self, params: &VReg2Params) -> ExecuteResult {
+        vreg2_operation(self, params, 1, |m, _| m)
+    }
+
+    fn exec_vmul(&mut self, params: &VReg3Params) -> ExecuteResult {
+        vreg3_operation(self, params, 1, |_, n, m, fpscr| fp_mul(n, m, fpscr))
+    }
+
+    fn exec_vneg(&mut self, params: &VReg2Params) -> ExecuteResult {
+        vreg2_operation(self, params, 1, |m, _| fp_neg(m))
+    }
+
+    fn exec_vnmla(&mut self, params: &VReg3Params) -> ExecuteResult {
+        vreg3_operation(self, params, 3, |d, n, m, fpscr| {
+            let product = fp_mul(n, m, fpscr);
+            fp_add(fp_neg(d), fp_neg(product), fpscr)
+        })
+    }
+
+    fn exec_vnmls(&mut self, params: &VReg3Params) -> ExecuteResult {
+        vreg3_operation(self, params, 3, |d, n, m, fpscr| {
+            let product = fp_mul(n, m, fpscr);
+            fp_add(fp_neg(d), product, fpscr)
+        })
+    }
+
+    fn exec_vnmul(&mut self, params: &VReg3Params) -> ExecuteResult {
+        vreg3_operation(self, params, 1, |_, n, m, fpscr| {
+            fp_neg(fp_mul(n, m, fpscr))
+        })
+    }
+
+    fn exec_vsqrt(&mut self, params: &VReg2Params) -> ExecuteResult {
+        vreg2_operation(self, params, 14, fp_sqrt)
+    }
+
+    fn exec_vsub(&mut self, params: &VReg3Params) -> ExecuteResult {
+        vreg3_operation(self, params, 1, |_, n, m, fpscr| fp_sub(n, m, fpscr))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{fault::Fault, instruction::Instruction, register::SingleReg};
+
+    fn fp_enabled_core() -> Processor {
+        let mut core = Processor::new();
+        core.cpacr = 0x00f0_0000;
+        core
+    }
+
+    #[test]
+    fn test_vadd() {
+        // arrange
+        let mut core = fp_enabled_core();
+        core.set_sr(SingleReg::S1, 1.5f32.to_bits());
+        core.set_sr(SingleReg::S2, 2.25f32.to_bits());
+
+        let instruction = Instruction::VADD {
+            params: VReg3Params {
+                sd: SingleReg::S0,
+                sn: SingleReg::S1,
+                sm: SingleReg::S2,
+            },
+        };
+
+        // act
+        let result = core.execute_internal(&instruction);
+
+        // assert
+        assert_eq!(result, Ok(ExecuteSuccess::Taken { cycles: 1 }));
+        assert_eq!(f32::from_bits(core.get_sr(SingleReg::S0)), 3.75);
+    }
+
+    #[test]
+    fn test_vmla() {
+        // arrange
+        let mut core = fp_enabled_core();
+        core.set_sr(SingleReg::S1, 10.0f32.to_bits());
+        core.set_sr(SingleReg::S2, 3.0f32.to_bits());
+        core.set_sr(SingleReg::S3, 0.5f32.to_bits());
+
+        let instruction = Instruction::VMLA {
+            params: VReg3Params {
+                sd: SingleReg::S1,
+                sn: SingleReg::S2,
+                sm: SingleReg::S3,
+            },
+        };
+
+        // act
+        let result = core.execute_internal(&instruction);
+
+        // assert
+        assert_eq!(result, Ok(ExecuteSuccess::Taken { cycles: 3 }));
+        assert_eq!(f32::from_bits(core.get_sr(SingleReg::S1)), 11.5);
+    }
+
+    #[test]
+    fn test_vcmp_sets_fpscr_flags() {
+        // arrange
+        let mut core = fp_enabled_core();
+        core.set_sr(SingleReg::S1, (-1.0f32).to_bits());
+
+        let instruction = Instruction::VCMP {
+            params: VCmpParams {
+                sd: SingleReg::S1,
+                sm: None,
+                quiet_nan_exc: false,
+            },
+        };
+
+        // act
+        let result = core.execute_internal(&instruction);
+
+        // assert
+        assert_eq!(result, Ok(ExecuteSuccess::Taken { cycles: 1 }));
+        assert_eq!(core.fpscr >> 28, 0b1000);
+    }
+
+    #[test]
+    fn test_vcvt_float_to_signed() {
+        // arrange
+        let mut core = fp_enabled_core();
+        core.set_sr(SingleReg::S2, (-7.75f32).to_bits());
+
+        let instruction = Instruction::VCVT_int {
+            params: VCvtIntParams {
+                sd: SingleReg::S1,
+                sm: SingleReg::S2,
+                to_integer: true,
+                signed: true,
+                round_zero: true,
+            },
+        };
+
+        // act
+        let result = core.execute_internal(&instruction);
+
+        // assert
+        assert_eq!(result, Ok(ExecuteSuccess::Taken { cycles: 1 }));
+        assert_eq!(core.get_sr(SingleReg::S1) as i32, -7);
+    }
+
+    #[test]
+    fn test_fp_disabled_is_nocp() {
+        // arrange
+        let mut core = Processor::new();
+
+        let instruction = Instruction::VMOV_imm {
+            params: VMovImmParams {
+                sd: SingleReg::S0,
+                imm32: 0x3f80_0000,
+            },
+        };
+
+        // act
+        let result = core.execute_internal(&instruction);
+
+        // assert
+        assert_eq!(result, Err(Fault::NoCp));
+        assert_eq!(core.get_sr(SingleReg::S0), 0);
+    }
+}
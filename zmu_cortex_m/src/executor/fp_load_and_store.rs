@@ -6,21 +6,118 @@ use super::ExecuteResult;
 use crate::{
     bus::Bus,
     core::{
-        instruction::VLoadAndStoreParams,
+        instruction::{VLoadAndStoreMultipleParams, VLoadAndStoreParams, VPushPopParams},
         register::{BaseReg, ExtensionReg, ExtensionRegOperations, Reg},
     },
 };
 
-/// Multiply operations
+/// Floating point load and store operations
 pub trait IsaFloatingPointLoadAndStore {
+    fn exec_vldm(&mut self, params: &VLoadAndStoreMultipleParams) -> ExecuteResult;
     fn exec_vldr(&mut self, params: &VLoadAndStoreParams) -> ExecuteResult;
+    fn exec_vpop(&mut self, params: &VPushPopParams) -> ExecuteResult;
+    fn exec_vpush(&mut self, params: &VPushPopParams) -> ExecuteResult;
+    fn exec_vstm(&mut self, params: &VLoadAndStoreMultipleParams) -> ExecuteResult;
     fn exec_vstr(&mut self, params: &VLoadAndStoreParams) -> ExecuteResult;
 }
 
+///
+/// Index of the first word in the register file and the number of words
+/// transferred by a register list
+///
+fn vreglist_words(dd: ExtensionReg, regs: u8) -> (usize, usize) {
+    match dd {
+        ExtensionReg::Single { reg } => (usize::from(reg), usize::from(regs)),
+        ExtensionReg::Double { reg } => (usize::from(reg), 2 * usize::from(regs)),
+    }
+}
+
+fn vload_multiple(
+    processor: &mut Processor,
+    rn: Reg,
+    dd: ExtensionReg,
+    regs: u8,
+    add: bool,
+    wback: bool,
+) -> ExecuteResult {
+    processor.execute_fp_check()?;
+
+    let (first, words) = vreglist_words(dd, regs);
+    let imm32 = 4 * words as u32;
+    let base = processor.get_r(rn);
+    let mut address = if add { base } else { base - imm32 };
+    processor.check_alignment(address, 4)?;
+
+    for i in 0..words {
+        let data = processor.read32(address)?;
+        processor.fp_regs[(first + i) % 32] = data;
+        address += 4;
+    }
+
+    if wback {
+        if add {
+            processor.add_r(rn, imm32);
+        } else {
+            processor.sub_r(rn, imm32);
+        }
+    }
+    Ok(ExecuteSuccess::Taken {
+        cycles: 1 + regs as u32,
+    })
+}
+
+fn vstore_multiple(
+    processor: &mut Processor,
+    rn: Reg,
+    dd: ExtensionReg,
+    regs: u8,
+    add: bool,
+    wback: bool,
+) -> ExecuteResult {
+    processor.execute_fp_check()?;
+
+    let (first, words) = vreglist_words(dd, regs);
+    let imm32 = 4 * words as u32;
+    let base = processor.get_r(rn);
+    let mut address = if add { base } else { base - imm32 };
+    processor.check_alignment(address, 4)?;
+
+    for i in 0..words {
+        let value = processor.fp_regs[(first + i) % 32];
+        processor.write32(address, value)?;
+        address += 4;
+    }
+
+    if wback {
+        if add {
+            processor.add_r(rn, imm32);
+        } else {
+            processor.sub_r(rn, imm32);
+        }
+    }
+    Ok(ExecuteSuccess::Taken {
+        cycles: 1 + regs as u32,
+    })
+}
+
 impl IsaFloatingPointLoadAndStore for Processor {
+    fn exec_vldm(&mut self, params: &VLoadAndStoreMultipleParams) -> ExecuteResult {
+        if self.condition_passed() {
+            return vload_multiple(
+                self,
+                params.rn,
+                params.dd,
+                params.regs,
+                params.add,
+                params.wback,
+            );
+        }
+        Ok(ExecuteSuccess::NotTaken)
+    }
+
     fn exec_vldr(&mut self, params: &VLoadAndStoreParams) -> ExecuteResult {
         if self.condition_passed() {
-            //self.execute_fp_check();
+            self.execute_fp_check()?;
 
             let base = match params.rn {
                 Reg::PC => self.get_r(Reg::PC) & 0xffff_fffc,
@@ -32,6 +129,8 @@ impl IsaFloatingPointLoadAndStore for Processor {
             } else {
                 base - params.imm32
             };
+
+            self.check_alignment(address, 4)?;
             match params.dd {
                 ExtensionReg::Single { reg } => {
                     let data = self.read32(address)?;
@@ -48,10 +147,38 @@ impl IsaFloatingPointLoadAndStore for Processor {
         }
         Ok(ExecuteSuccess::NotTaken)
     }
-    
+
+    fn exec_vpop(&mut self, params: &VPushPopParams) -> ExecuteResult {
+        if self.condition_passed() {
+            return vload_multiple(self, Reg::SP, params.dd, params.regs, true, true);
+        }
+        Ok(ExecuteSuccess::NotTaken)
+    }
+
+    fn exec_vpush(&mut self, params: &VPushPopParams) -> ExecuteResult {
+        if self.condition_passed() {
+            return vstore_multiple(self, Reg::SP, params.dd, params.regs, false, true);
+        }
+        Ok(ExecuteSuccess::NotTaken)
+    }
+
+    fn exec_vstm(&mut self, params: &VLoadAndStoreMultipleParams) -> ExecuteResult {
+        if self.condition_passed() {
+            return vstore_multiple(
+                self,
+                params.rn,
+                params.dd,
+                params.regs,
+                params.add,
+                params.wback,
+            );
+        }
+        Ok(ExecuteSuccess::NotTaken)
+    }
+
     fn exec_vstr(&mut self, params: &VLoadAndStoreParams) -> ExecuteResult {
         if self.condition_passed() {
-            //self.execute_fp_check();
+            self.execute_fp_check()?;
 
             let base = self.get_r(params.rn);
 
@@ -60,6 +187,8 @@ impl IsaFloatingPointLoadAndStore for Processor {
             } else {
                 base - params.imm32
             };
+
+            self.check_alignment(address, 4)?;
             match params.dd {
                 ExtensionReg::Single { reg } => {
                     let value = self.get_sr(reg);
@@ -0,0 +1,168 @@
+use crate::Processor;
+
+use crate::executor::{ExecuteSuccess, ExecutorHelper};
+
+use super::ExecuteResult;
+use crate::core::{
+    bits::Bits,
+    fpu::FPSCR_WRITE_MASK,
+    instruction::{VMovCr2DpParams, VMovCr2Sp2Params, VMovCrScalarParams, VMovCrSpParams},
+    register::{BaseReg, ExtensionRegOperations, Reg, SingleReg},
+};
+
+/// Floating point register transfer operations
+pub trait IsaFloatingPointRegisterTransfer {
+    fn exec_vmov_cr_sp(&mut self, params: &VMovCrSpParams) -> ExecuteResult;
+    fn exec_vmov_cr2_sp2(&mut self, params: &VMovCr2Sp2Params) -> ExecuteResult;
+    fn exec_vmov_cr2_dp(&mut self, params: &VMovCr2DpParams) -> ExecuteResult;
+    fn exec_vmov_cr_scalar(&mut self, params: &VMovCrScalarParams) -> ExecuteResult;
+    fn exec_vmrs(&mut self, rt: Reg) -> ExecuteResult;
+    fn exec_vmsr(&mut self, rt: Reg) -> ExecuteResult;
+}
+
+impl IsaFloatingPointRegisterTransfer for Processor {
+    fn exec_vmov_cr_sp(&mut self, params: &VMovCrSpParams) -> ExecuteResult {
+        if self.condition_passed() {
+            self.execute_fp_check()?;
+
+            if params.to_arm_register {
+                let value = self.get_sr(params.sn);
+                self.set_r(params.rt, value);
+            } else {
+                let value = self.get_r(params.rt);
+                self.set_sr(params.sn, value);
+            }
+
+            return Ok(ExecuteSuccess::Taken { cycles: 1 });
+        }
+        Ok(ExecuteSuccess::NotTaken)
+    }
+
+    fn exec_vmov_cr2_sp2(&mut self, params: &VMovCr2Sp2Params) -> ExecuteResult {
+        if self.condition_passed() {
+            self.execute_fp_check()?;
+
+            let sm1 = SingleReg::from(usize::from(params.sm) as u8 + 1);
+            if params.to_arm_registers {
+                let value = self.get_sr(params.sm);
+                self.set_r(params.rt, value);
+                let value = self.get_sr(sm1);
+                self.set_r(params.rt2, value);
+            } else {
+                let value = self.get_r(params.rt);
+                self.set_sr(params.sm, value);
+                let value = self.get_r(params.rt2);
+                self.set_sr(sm1, value);
+            }
+
+            return Ok(ExecuteSuccess::Taken { cycles: 2 });
+        }
+        Ok(ExecuteSuccess::NotTaken)
+    }
+
+    fn exec_vmov_cr2_dp(&mut self, params: &VMovCr2DpParams) -> ExecuteResult {
+        if self.condition_passed() {
+            self.execute_fp_check()?;
+
+            if params.to_arm_registers {
+                let (low_word, high_word) = self.get_dr(params.dm);
+                self.set_r(params.rt, low_word);
+                self.set_r(params.rt2, high_word);
+            } else {
+                let low_word = self.get_r(params.rt);
+                let high_word = self.get_r(params.rt2);
+                self.set_dr(params.dm, low_word, high_word);
+            }
+
+            return Ok(ExecuteSuccess::Taken { cycles: 2 });
+        }
+        Ok(ExecuteSuccess::NotTaken)
+    }
+
+    fn exec_vmov_cr_scalar(&mut self, params: &VMovCrScalarParams) -> ExecuteResult {
+        if self.condition_passed() {
+            self.execute_fp_check()?;
+
+            let (low_word, high_word) = self.get_dr(params.dd);
+            if params.to_arm_register {
+                let value = if params.x == 0 { low_word } else { high_word };
+                self.set_r(params.rt, value);
+            } else {
+                let value = self.get_r(params.rt);
+                if params.x == 0 {
+                    self.set_dr(params.dd, value, high_word);
+                } else {
+                    self.set_dr(params.dd, low_word, value);
+                }
+            }
+
+            return Ok(ExecuteSuccess::Taken { cycles: 1 });
+        }
+        Ok(ExecuteSuccess::NotTaken)
+    }
+
+    fn exec_vmrs(&mut self, rt: Reg) -> ExecuteResult {
+        if self.condition_passed() {
+            self.execute_fp_check()?;
+
+            if rt == Reg::PC {
+                // APSR_nzcv
+                self.psr.value.set_bits(28..32, self.fpscr.get_bits(28..32));
+            } else {
+                self.set_r(rt, self.fpscr);
+            }
+
+            return Ok(ExecuteSuccess::Taken { cycles: 1 });
+        }
+        Ok(ExecuteSuccess::NotTaken)
+    }
+
+    fn exec_vmsr(&mut self, rt: Reg) -> ExecuteResult {
+        if self.condition_passed() {
+            self.execute_fp_check()?;
+            self.fpscr = self.get_r(rt) & FPSCR_WRITE_MASK;
+            return Ok(ExecuteSuccess::Taken { cycles: 1 });
+        }
+        Ok(ExecuteSuccess::NotTaken)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{instruction::Instruction, register::Apsr};
+
+    #[test]
+    fn test_vmrs_apsr_nzcv() {
+        // arrange
+        let mut core = Processor::new();
+        core.cpacr = 0x00f0_0000;
+        core.psr.value = 0;
+        core.fpscr = 0x6000_0000;
+
+        // act
+        let result = core.execute_internal(&Instruction::VMRS { rt: Reg::PC });
+
+        // assert
+        assert_eq!(result, Ok(ExecuteSuccess::Taken { cycles: 1 }));
+        assert!(core.psr.get_z());
+        assert!(core.psr.get_c());
+        assert!(!core.psr.get_n());
+        assert!(!core.psr.get_v());
+    }
+
+    #[test]
+    fn test_vmsr_ignores_reserved_bits() {
+        // arrange
+        let mut core = Processor::new();
+        core.cpacr = 0x00f0_0000;
+        core.set_r(Reg::R3, 0xffff_ffff);
+
+        // act
+        let result = core.execute_internal(&Instruction::VMSR { rt: Reg::R3 });
+
+        // assert
+        assert_eq!(result, Ok(ExecuteSuccess::Taken { cycles: 1 }));
+        assert_eq!(core.fpscr, FPSCR_WRITE_MASK);
+    }
+}
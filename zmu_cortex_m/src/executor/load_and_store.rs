@@ -64,8 +64,9 @@ impl IsaLoadAndStore for Processor {
             let rn = self.get_r(params.rn);
             let (address, offset_address) =
                 resolve_addressing(rn, offset, params.add, params.index);
+            self.check_unaligned_trap(address, 4)?;
 
-            let data = self.read32(address)?;
+            let data = self.read32_unaligned(address)?;
             if params.wback {
                 self.set_r(params.rn, offset_address);
             }
@@ -111,8 +112,9 @@ impl IsaLoadAndStore for Processor {
 
             let (address, offset_address) =
                 resolve_addressing(self.get_r(params.rn), offset, params.add, params.index);
+            self.check_unaligned_trap(address, 2)?;
 
-            let data = u32::from(self.read16(address)?);
+            let data = u32::from(self.read16_unaligned(address)?);
             if params.wback {
                 self.set_r(params.rn, offset_address);
             }
@@ -131,8 +133,9 @@ impl IsaLoadAndStore for Processor {
 
             let (address, offset_address) =
                 resolve_addressing(self.get_r(params.rn), offset, params.add, params.index);
+            self.check_unaligned_trap(address, 2)?;
 
-            let data = u32::from(self.read16(address)?);
+            let data = u32::from(self.read16_unaligned(address)?);
             if params.wback {
                 self.set_r(params.rn, offset_address);
             }
@@ -173,8 +176,9 @@ impl IsaLoadAndStore for Processor {
                 c,
             );
             let address = self.get_r(params.rn) + offset;
+            self.check_unaligned_trap(address, 4)?;
             let value = self.get_r(params.rt);
-            self.write32(address, value)?;
+            self.write32_unaligned(address, value)?;
 
             return Ok(ExecuteSuccess::Taken { cycles: 2 });
         }
@@ -209,8 +213,9 @@ impl IsaLoadAndStore for Processor {
                 c,
             );
             let address = self.get_r(params.rn) + offset;
+            self.check_unaligned_trap(address, 2)?;
             let value = self.get_r(params.rt).get_bits(0..16);
-            self.write16(address, value as u16)?;
+            self.write16_unaligned(address, value as u16)?;
             return Ok(ExecuteSuccess::Taken { cycles: 2 });
         }
         Ok(ExecuteSuccess::NotTaken)
@@ -224,8 +229,9 @@ impl IsaLoadAndStore for Processor {
                 params.add,
                 params.index,
             );
+            self.check_unaligned_trap(address, 4)?;
 
-            let data = self.read32(address)?;
+            let data = self.read32_unaligned(address)?;
             if params.wback {
                 self.set_r(params.rn, offset_address);
             }
@@ -268,8 +274,9 @@ impl IsaLoadAndStore for Processor {
                 params.add,
                 params.index,
             );
+            self.check_unaligned_trap(address, 2)?;
 
-            let data = self.read16(address)?;
+            let data = self.read16_unaligned(address)?;
             if params.wback {
                 self.set_r(params.rn, offset_address);
             }
@@ -307,8 +314,9 @@ impl IsaLoadAndStore for Processor {
                 params.add,
                 params.index,
             );
+            self.check_unaligned_trap(address, 2)?;
 
-            let data = self.read16(address)?;
+            let data = self.read16_unaligned(address)?;
             if params.wback {
                 self.set_r(params.rn, offset_address);
             }
@@ -327,13 +335,14 @@ impl IsaLoadAndStore for Processor {
                 params.add,
                 params.index,
             );
+            self.check_unaligned_trap(address, 4)?;
 
             let value = self.get_r(params.rt);
             if params.wback {
                 self.set_r(params.rn, offset_address);
             }
 
-            self.write32(address, value)?;
+            self.write32_unaligned(address, value)?;
 
             return Ok(ExecuteSuccess::Taken { cycles: 2 });
         }
@@ -368,9 +377,10 @@ impl IsaLoadAndStore for Processor {
                 params.add,
                 params.index,
             );
+            self.check_unaligned_trap(address, 2)?;
 
             let value = self.get_r(params.rt);
-            self.write16(address, value.get_bits(0..16) as u16)?;
+            self.write16_unaligned(address, value.get_bits(0..16) as u16)?;
 
             if params.wback {
                 self.set_r(params.rn, offset_address);
@@ -384,6 +394,7 @@ impl IsaLoadAndStore for Processor {
     fn exec_ldrex(&mut self, params: Reg2RtRnImm32Params) -> ExecuteResult {
         if self.condition_passed() {
             let (address, _) = resolve_addressing(self.get_r(params.rn), params.imm32, true, true);
+            self.check_alignment(address, 4)?;
 
             self.set_exclusive_monitors(address, 4);
 
@@ -414,6 +425,7 @@ impl IsaLoadAndStore for Processor {
     fn exec_ldrexh(&mut self, params: Reg2RtRnParams) -> ExecuteResult {
         if self.condition_passed() {
             let address = self.get_r(params.rn);
+            self.check_alignment(address, 2)?;
             self.set_exclusive_monitors(address, 2);
 
             let data = self.read16(address)?;
@@ -430,6 +442,7 @@ impl IsaLoadAndStore for Processor {
     fn exec_strex(&mut self, params: Reg3RdRtRnImm32Params) -> ExecuteResult {
         if self.condition_passed() {
             let (address, _) = resolve_addressing(self.get_r(params.rn), params.imm32, true, true);
+            self.check_alignment(address, 4)?;
 
             if self.exclusive_monitors_pass(address, 4) {
                 self.write32(address, self.get_r(params.rt))?;
@@ -462,6 +475,7 @@ impl IsaLoadAndStore for Processor {
     fn exec_strexh(&mut self, params: Reg3RdRtRnParams) -> ExecuteResult {
         if self.condition_passed() {
             let address = self.get_r(params.rn);
+            self.check_alignment(address, 2)?;
 
             if self.exclusive_monitors_pass(address, 2) {
                 self.write16(address, self.get_r(params.rt) as u16)?;
@@ -482,6 +496,7 @@ impl IsaLoadAndStore for Processor {
                 params.add,
                 params.index,
             );
+            self.check_alignment(address, 4)?;
 
             let data = self.read32(address)?;
             self.set_r(params.rt, data);
@@ -505,6 +520,7 @@ impl IsaLoadAndStore for Processor {
                 params.add,
                 params.index,
             );
+            self.check_alignment(address, 4)?;
 
             let value1 = self.get_r(params.rt);
             self.write32(address, value1)?;
@@ -528,7 +544,8 @@ impl IsaLoadAndStore for Processor {
             } else {
                 base - params.imm32
             };
-            let data = self.read32(address)?;
+            self.check_unaligned_trap(address, 4)?;
+            let data = self.read32_unaligned(address)?;
 
             if params.rt == Reg::PC {
                 self.load_write_pc(data)?;
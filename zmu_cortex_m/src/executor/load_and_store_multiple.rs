@@ -27,6 +27,8 @@ impl IsaLoadAndStoreMultiple for Processor {
 
             let mut address = self.get_r(params.rn);
 
+            self.check_alignment(address, 4)?;
+
             for reg in params.registers.iter() {
                 let r = self.get_r(reg);
                 self.write32(address, r)?;
@@ -49,6 +51,8 @@ impl IsaLoadAndStoreMultiple for Processor {
 
             let mut address = self.get_r(params.rn) - regs_size;
 
+            self.check_alignment(address, 4)?;
+
             for reg in params.registers.iter() {
                 let r = self.get_r(reg);
                 self.write32(address, r)?;
@@ -71,6 +75,8 @@ impl IsaLoadAndStoreMultiple for Processor {
 
             let mut address = self.get_r(params.rn);
 
+            self.check_alignment(address, 4)?;
+
             let mut branched = false;
             for reg in params.registers.iter() {
                 let value = self.read32(address)?;
@@ -100,6 +106,7 @@ impl IsaLoadAndStoreMultiple for Processor {
             let regs_size = 4 * (registers.len() as u32);
             let sp = self.get_r(Reg::SP);
             let mut address = sp - regs_size;
+            self.check_alignment(address, 4)?;
 
             for reg in registers.iter() {
                 let value = self.get_r(reg);
@@ -120,6 +127,7 @@ impl IsaLoadAndStoreMultiple for Processor {
             let regs_size = 4 * (registers.len() as u32);
             let sp = self.get_r(Reg::SP);
             let mut address = sp;
+            self.check_alignment(address, 4)?;
 
             self.set_r(Reg::SP, sp + regs_size);
 
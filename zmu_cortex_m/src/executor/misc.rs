@@ -1,7 +1,10 @@
 use crate::Processor;
 
 use crate::{
-    core::{bits::Bits, condition::Condition, exception::ExceptionHandling},
+    core::{
+        bits::Bits, condition::Condition, event::EventRegister, exception::ExceptionHandling,
+        monitor::Monitor,
+    },
     executor::{ExecuteSuccess, ExecutorHelper},
 };
 
@@ -9,6 +12,7 @@ use super::ExecuteResult;
 
 /// Branching operations
 pub trait IsaMisc {
+    fn exec_clrex(&mut self) -> ExecuteResult;
     fn exec_dmb(&self) -> ExecuteResult;
     fn exec_dsb(&self) -> ExecuteResult;
     fn exec_isb(&self) -> ExecuteResult;
@@ -16,13 +20,20 @@ pub trait IsaMisc {
     fn exec_pld_imm(&self) -> ExecuteResult;
     fn exec_pld_lit(&self) -> ExecuteResult;
     fn exec_pld_reg(&self) -> ExecuteResult;
-    fn exec_sev(&self) -> ExecuteResult;
-    fn exec_wfe(&self) -> ExecuteResult;
+    fn exec_sev(&mut self) -> ExecuteResult;
+    fn exec_wfe(&mut self) -> ExecuteResult;
     fn exec_yield(&self) -> ExecuteResult;
     fn exec_wfi(&mut self) -> ExecuteResult;
 }
 
 impl IsaMisc for Processor {
+    fn exec_clrex(&mut self) -> ExecuteResult {
+        if self.condition_passed() {
+            self.clear_exclusive_local();
+            return Ok(ExecuteSuccess::Taken { cycles: 1 });
+        }
+        Ok(ExecuteSuccess::NotTaken)
+    }
     fn exec_dmb(&self) -> ExecuteResult {
         if self.condition_passed() {
             return Ok(ExecuteSuccess::Taken { cycles: 4 });
@@ -71,16 +82,18 @@ impl IsaMisc for Processor {
         }
     }
 
-    fn exec_sev(&self) -> ExecuteResult {
+    fn exec_sev(&mut self) -> ExecuteResult {
         if self.condition_passed() {
+            self.set_event();
             Ok(ExecuteSuccess::Taken { cycles: 1 })
         } else {
             Ok(ExecuteSuccess::NotTaken)
         }
     }
 
-    fn exec_wfe(&self) -> ExecuteResult {
+    fn exec_wfe(&mut self) -> ExecuteResult {
         if self.condition_passed() {
+            self.wait_for_event();
             Ok(ExecuteSuccess::Taken { cycles: 1 })
         } else {
             Ok(ExecuteSuccess::NotTaken)
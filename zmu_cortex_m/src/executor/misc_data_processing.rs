@@ -7,7 +7,8 @@ use super::ExecuteResult;
 use crate::core::{
     bits::Bits,
     instruction::{
-        BfcParams, BfiParams, MovtParams, Reg2RdRmParams, Reg3NoSetFlagsParams, UbfxParams,
+        BfcParams, BfiParams, MovtParams, Reg2RdRmParams, Reg3NoSetFlagsParams,
+        Reg4NoSetFlagsParams, UbfxParams,
     },
     operation::sign_extend,
     register::{Apsr, BaseReg},
@@ -25,6 +26,8 @@ pub trait IsaMiscDataProcessing {
     fn exec_rev16(&mut self, params: Reg2RdRmParams) -> ExecuteResult;
     fn exec_revsh(&mut self, params: Reg2RdRmParams) -> ExecuteResult;
     fn exec_sel(&mut self, params: &Reg3NoSetFlagsParams) -> ExecuteResult;
+    fn exec_usad8(&mut self, params: &Reg3NoSetFlagsParams) -> ExecuteResult;
+    fn exec_usada8(&mut self, params: &Reg4NoSetFlagsParams) -> ExecuteResult;
     fn exec_ubfx(&mut self, params: &UbfxParams) -> ExecuteResult;
 }
 
@@ -185,6 +188,40 @@ impl IsaMiscDataProcessing for Processor {
         }
         Ok(ExecuteSuccess::NotTaken)
     }
+
+    fn exec_usad8(&mut self, params: &Reg3NoSetFlagsParams) -> ExecuteResult {
+        if self.condition_passed() {
+            let rn = self.get_r(params.rn);
+            let rm = self.get_r(params.rm);
+
+            self.set_r(params.rd, sum_of_absolute_differences(rn, rm));
+            return Ok(ExecuteSuccess::Taken { cycles: 1 });
+        }
+        Ok(ExecuteSuccess::NotTaken)
+    }
+
+    fn exec_usada8(&mut self, params: &Reg4NoSetFlagsParams) -> ExecuteResult {
+        if self.condition_passed() {
+            let rn = self.get_r(params.rn);
+            let rm = self.get_r(params.rm);
+            let ra = self.get_r(params.ra);
+
+            let result = ra.wrapping_add(sum_of_absolute_differences(rn, rm));
+            self.set_r(params.rd, result);
+            return Ok(ExecuteSuccess::Taken { cycles: 1 });
+        }
+        Ok(ExecuteSuccess::NotTaken)
+    }
+}
+
+fn sum_of_absolute_differences(rn: u32, rm: u32) -> u32 {
+    (0..4)
+        .map(|i| {
+            let byte_n: u32 = rn.get_bits(i * 8..(i + 1) * 8);
+            let byte_m: u32 = rm.get_bits(i * 8..(i + 1) * 8);
+            (byte_n as i32 - byte_m as i32).unsigned_abs()
+        })
+        .sum()
 }
 
 #[cfg(test)]
@@ -239,4 +276,29 @@ mod tests {
         assert_eq!(core.get_r(Reg::R0), 0xe000e400);
         assert_eq!(core.get_r(Reg::R1), 0x00e000e4);
     }
+
+    #[test]
+    fn test_usada8() {
+        // arrange
+        let mut core = Processor::new();
+        core.psr.value = 0;
+        core.set_r(Reg::R2, 0x0110_ff00);
+        core.set_r(Reg::R3, 0x0201_00ff);
+        core.set_r(Reg::R4, 4);
+
+        let instruction = Instruction::USADA8 {
+            params: Reg4NoSetFlagsParams {
+                rd: Reg::R1,
+                rn: Reg::R2,
+                rm: Reg::R3,
+                ra: Reg::R4,
+            },
+        };
+
+        // act
+        core.execute_internal(&instruction).unwrap();
+
+        // assert
+        assert_eq!(core.get_r(Reg::R1), 1 + 15 + 255 + 255 + 4);
+    }
 }
@@ -3,24 +3,42 @@
 //!
 
 
+use crate::bus::Bus;
 use crate::core::bits::Bits;
 use crate::core::condition::Condition;
-use crate::core::exception::{Exception, ExceptionHandling};
+use crate::core::event::EventRegister;
+use crate::core::exception::ExceptionHandling;
 use crate::core::fault::Fault;
-use crate::core::instruction::{Imm32Carry, Instruction, SetFlags};
+use crate::core::fetch::Fetch;
+use crate::core::fpu::FPCCR_ASPEN;
+use crate::core::instruction::{instruction_size, Imm32Carry, Instruction, SetFlags};
+use crate::core::lockup::Lockup;
 
 use crate::core::operation::condition_test;
-use crate::core::register::{Apsr, BaseReg};
+use crate::core::register::{Apsr, BaseReg, Epsr};
+use crate::core::thumb::ThumbCode;
+use crate::decoder::Decoder;
 use crate::memory::map::MapMemory;
-use crate::peripheral::{dwt::Dwt, systick::SysTick};
+use crate::peripheral::{
+    dcb::DebugControlBlock,
+    dwt::Dwt,
+    fpb::{FlashPatchBreakpoint, FpbMatch},
+    itm::InstrumentationTraceMacrocell,
+    scb::{CCR_DIV_0_TRP, CCR_UNALIGN_TRP, SCR_SLEEPDEEP},
+    systick::SysTick,
+};
+use crate::rtt::RealTimeTransfer;
 
 use crate::Processor;
+use crate::ProcessorMode;
 
 mod branch;
 mod coproc;
 mod divide;
 mod exception;
+mod fp_data_processing;
 mod fp_load_and_store;
+mod fp_register_transfer;
 mod load_and_store;
 mod load_and_store_multiple;
 mod misc;
@@ -28,6 +46,8 @@ mod misc_data_processing;
 mod multiply;
 mod packing;
 mod parallel_add;
+mod saturate;
+mod saturating_add_sub;
 mod shift;
 mod signed_multiply;
 mod status_register;
@@ -37,7 +57,9 @@ use branch::IsaBranch;
 use coproc::IsaCoprocessor;
 use divide::IsaDivide;
 use exception::IsaException;
+use fp_data_processing::IsaFloatingPointDataProcessing;
 use fp_load_and_store::IsaFloatingPointLoadAndStore;
+use fp_register_transfer::IsaFloatingPointRegisterTransfer;
 use load_and_store::IsaLoadAndStore;
 use load_and_store_multiple::IsaLoadAndStoreMultiple;
 use misc::IsaMisc;
@@ -45,6 +67,8 @@ use misc_data_processing::IsaMiscDataProcessing;
 use multiply::IsaMultiply;
 use packing::IsaPacking;
 use parallel_add::IsaParallelAddSub;
+use saturate::IsaSaturate;
+use saturating_add_sub::IsaSaturatingAddSub;
 use shift::IsaShift;
 use signed_multiply::IsaSignedMultiply;
 use status_register::IsaStatusRegister;
@@ -91,6 +115,14 @@ trait ExecutorHelper {
     fn condition_passed(&self) -> bool;
     fn condition_passed_b(&self, cond: Condition) -> bool;
     fn integer_zero_divide_trapping_enabled(&self) -> bool;
+    fn check_alignment(&self, address: u32, size: u32) -> Result<(), Fault>;
+    fn check_unaligned_trap(&self, address: u32, size: u32) -> Result<(), Fault>;
+    fn read32_unaligned(&mut self, address: u32) -> Result<u32, Fault>;
+    fn read16_unaligned(&self, address: u32) -> Result<u16, Fault>;
+    fn write32_unaligned(&mut self, address: u32, value: u32) -> Result<(), Fault>;
+    fn write16_unaligned(&mut self, address: u32, value: u16) -> Result<(), Fault>;
+    fn fetch_instruction(&self, pc: u32) -> Result<(Instruction, usize), Fault>;
+    fn execute_fp_check(&mut self) -> Result<(), Fault>;
     fn set_itstate(&mut self, state: u8);
     fn it_advance(&mut self);
     fn in_it_block(&self) -> bool;
@@ -157,7 +189,101 @@ impl ExecutorHelper for Processor {
         self.itstate.get_bits(0..4) == 0b1000
     }
     fn integer_zero_divide_trapping_enabled(&self) -> bool {
-        true
+        self.ccr & CCR_DIV_0_TRP != 0
+    }
+
+    // accesses that must always be aligned (MemA)
+    fn check_alignment(&self, address: u32, size: u32) -> Result<(), Fault> {
+        if address & (size - 1) == 0 {
+            Ok(())
+        } else {
+            Err(Fault::Unaligned)
+        }
+    }
+
+    // accesses that may be unaligned unless trapped by CCR.UNALIGN_TRP (MemU)
+    fn check_unaligned_trap(&self, address: u32, size: u32) -> Result<(), Fault> {
+        if self.ccr & CCR_UNALIGN_TRP != 0 {
+            self.check_alignment(address, size)
+        } else {
+            Ok(())
+        }
+    }
+
+    // unaligned accesses that are not trapped are done as byte accesses
+    fn read32_unaligned(&mut self, address: u32) -> Result<u32, Fault> {
+        if address.trailing_zeros() >= 2 {
+            return self.read32(address);
+        }
+        let mut bytes = [0; 4];
+        for (i, byte) in bytes.iter_mut().enumerate() {
+            *byte = self.read8(address.wrapping_add(i as u32))?;
+        }
+        Ok(u32::from_le_bytes(bytes))
+    }
+
+    fn read16_unaligned(&self, address: u32) -> Result<u16, Fault> {
+        if address & 0b1 == 0 {
+            return self.read16(address);
+        }
+        let low = self.read8(address)?;
+        let high = self.read8(address.wrapping_add(1))?;
+        Ok(u16::from_le_bytes([low, high]))
+    }
+
+    fn write32_unaligned(&mut self, address: u32, value: u32) -> Result<(), Fault> {
+        if address.trailing_zeros() >= 2 {
+            return self.write32(address, value);
+        }
+        for (i, byte) in value.to_le_bytes().iter().enumerate() {
+            self.write8(address.wrapping_add(i as u32), *byte)?;
+        }
+        Ok(())
+    }
+
+    fn write16_unaligned(&mut self, address: u32, value: u16) -> Result<(), Fault> {
+        if address & 0b1 == 0 {
+            return self.write16(address, value);
+        }
+        for (i, byte) in value.to_le_bytes().iter().enumerate() {
+            self.write8(address.wrapping_add(i as u32), *byte)?;
+        }
+        Ok(())
+    }
+
+    fn fetch_instruction(&self, pc: u32) -> Result<(Instruction, usize), Fault> {
+        if let Some(&cached) = self.instruction_cache.get((self.map_address(pc) >> 1) as usize) {
+            return Ok(cached);
+        }
+        // execute never regions of the default memory map
+        if (0x4000_0000..0x6000_0000).contains(&pc) || pc >= 0xA000_0000 {
+            return Err(Fault::IAccViol);
+        }
+        let thumb = self.fetch(pc).map_err(|_| Fault::Ibuserr)?;
+        let instruction = self.decode(thumb);
+        Ok((instruction, instruction_size(&instruction)))
+    }
+
+    fn execute_fp_check(&mut self) -> Result<(), Fault> {
+        // the coprocessor is not present
+        if !self.cpu.has_fpu() {
+            return Err(Fault::NoCp);
+        }
+        // CPACR.CP10 access rights, CP11 must be programmed the same
+        match self.cpacr.get_bits(20..22) {
+            0b11 => (),
+            0b01 if self.mode == ProcessorMode::HandlerMode || !self.control.n_priv => (),
+            _ => return Err(Fault::NoCp),
+        }
+
+        self.preserve_fp_state()?;
+
+        // first floating point instruction of a context
+        if self.fpccr & FPCCR_ASPEN != 0 && !self.control.fpca {
+            self.fpscr.set_bits(22..27, self.fpdscr.get_bits(22..27));
+            self.control.fpca = true;
+        }
+        Ok(())
     }
 
     #[inline(always)]
@@ -301,7 +427,7 @@ impl ExecutorHelper for Processor {
             // Group: Signed multiply instructions (ArmV7-m)
             //
             // --------------------------------------------
-            Instruction::SMLAL { params: _ } => unimplemented!(),
+            Instruction::SMLAL { params: _ } => Err(Fault::UndefInstr),
             Instruction::SMULL { params } => self.exec_smull(params),
 
             // --------------------------------------------
@@ -312,6 +438,13 @@ impl ExecutorHelper for Processor {
             Instruction::UMLAL { params } => self.exec_umlal(params),
             Instruction::UMULL { params } => self.exec_umull(params),
 
+            // --------------------------------------------
+            //
+            // Group: Unsigned Multiply instructions (ARMv7-M DSP extension)
+            //
+            // --------------------------------------------
+            Instruction::UMAAL { params } => self.exec_umaal(params),
+
             // --------------------------------------------
             //
             // Group: Signed Multiply instructions (ARMv7-M DSP extension)
@@ -319,24 +452,44 @@ impl ExecutorHelper for Processor {
             // --------------------------------------------
             Instruction::SMUL { params } => self.exec_smul(params),
             Instruction::SMLA { params } => self.exec_smla(params),
+            Instruction::SMLAL_hw { params } => self.exec_smlal_hw(params),
+            Instruction::SMLAD { params } => self.exec_smlad(params),
+            Instruction::SMLALD { params } => self.exec_smlald(params),
+            Instruction::SMLAW { params } => self.exec_smlaw(params),
+            Instruction::SMLSD { params } => self.exec_smlsd(params),
+            Instruction::SMLSLD { params } => self.exec_smlsld(params),
+            Instruction::SMMLA { params } => self.exec_smmla(params),
+            Instruction::SMMLS { params } => self.exec_smmls(params),
+            Instruction::SMMUL { params } => self.exec_smmul(params),
+            Instruction::SMUAD { params } => self.exec_smuad(params),
+            Instruction::SMULW { params } => self.exec_smulw(params),
+            Instruction::SMUSD { params } => self.exec_smusd(params),
 
             // --------------------------------------------
             //
             // Group: Saturating instructions (ARMv7-M base arch)
             //
             // --------------------------------------------
+            Instruction::SSAT { params } => self.exec_ssat(params),
+            Instruction::USAT { params } => self.exec_usat(params),
 
             // --------------------------------------------
             //
             // Group: Saturating instructions (ARMv7-M DSP extensions)
             //
             // --------------------------------------------
+            Instruction::SSAT16 { params } => self.exec_ssat16(params),
+            Instruction::USAT16 { params } => self.exec_usat16(params),
 
             // --------------------------------------------
             //
             // Group: Saturating add/sub (ARMv7-M DSP extensions)
             //
             // --------------------------------------------
+            Instruction::QADD { params } => self.exec_qadd(params),
+            Instruction::QSUB { params } => self.exec_qsub(params),
+            Instruction::QDADD { params } => self.exec_qdadd(params),
+            Instruction::QDSUB { params } => self.exec_qdsub(params),
 
             // --------------------------------------------
             //
@@ -354,7 +507,15 @@ impl ExecutorHelper for Processor {
             // Group: Packing and unpacking instructions (DSP extensions)
             //
             // --------------------------------------------
+            Instruction::PKH { params } => self.exec_pkh(params),
+            Instruction::SXTAB { params } => self.exec_sxtab(params),
+            Instruction::SXTAB16 { params } => self.exec_sxtab16(params),
+            Instruction::SXTAH { params } => self.exec_sxtah(params),
+            Instruction::SXTB16 { params } => self.exec_sxtb16(params),
             Instruction::UXTAB { params } => self.exec_uxtab(params),
+            Instruction::UXTAB16 { params } => self.exec_uxtab16(params),
+            Instruction::UXTAH { params } => self.exec_uxtah(params),
+            Instruction::UXTB16 { params } => self.exec_uxtb16(params),
 
             // --------------------------------------------
             //
@@ -370,7 +531,42 @@ impl ExecutorHelper for Processor {
             // Group: Parallel add / sub (DSP extension)
             //
             // --------------------------------------------
+            Instruction::SADD16 { params } => self.exec_sadd16(params),
+            Instruction::QADD16 { params } => self.exec_qadd16(params),
+            Instruction::SHADD16 { params } => self.exec_shadd16(params),
+            Instruction::UADD16 { params } => self.exec_uadd16(params),
+            Instruction::UQADD16 { params } => self.exec_uqadd16(params),
+            Instruction::UHADD16 { params } => self.exec_uhadd16(params),
+            Instruction::SASX { params } => self.exec_sasx(params),
+            Instruction::QASX { params } => self.exec_qasx(params),
+            Instruction::SHASX { params } => self.exec_shasx(params),
+            Instruction::UASX { params } => self.exec_uasx(params),
+            Instruction::UQASX { params } => self.exec_uqasx(params),
+            Instruction::UHASX { params } => self.exec_uhasx(params),
+            Instruction::SSAX { params } => self.exec_ssax(params),
+            Instruction::QSAX { params } => self.exec_qsax(params),
+            Instruction::SHSAX { params } => self.exec_shsax(params),
+            Instruction::USAX { params } => self.exec_usax(params),
+            Instruction::UQSAX { params } => self.exec_uqsax(params),
+            Instruction::UHSAX { params } => self.exec_uhsax(params),
+            Instruction::SSUB16 { params } => self.exec_ssub16(params),
+            Instruction::QSUB16 { params } => self.exec_qsub16(params),
+            Instruction::SHSUB16 { params } => self.exec_shsub16(params),
+            Instruction::USUB16 { params } => self.exec_usub16(params),
+            Instruction::UQSUB16 { params } => self.exec_uqsub16(params),
+            Instruction::UHSUB16 { params } => self.exec_uhsub16(params),
+            Instruction::SADD8 { params } => self.exec_sadd8(params),
+            Instruction::QADD8 { params } => self.exec_qadd8(params),
+            Instruction::SHADD8 { params } => self.exec_shadd8(params),
             Instruction::UADD8 { params } => self.exec_uadd8(params),
+            Instruction::UQADD8 { params } => self.exec_uqadd8(params),
+            Instruction::UHADD8 { params } => self.exec_uhadd8(params),
+            Instruction::SSUB8 { params } => self.exec_ssub8(params),
+            Instruction::QSUB8 { params } => self.exec_qsub8(params),
+            Instruction::SHSUB8 { params } => self.exec_shsub8(params),
+            Instruction::USUB8 { params } => self.exec_usub8(params),
+            Instruction::UQSUB8 { params } => self.exec_uqsub8(params),
+            Instruction::UHSUB8 { params } => self.exec_uhsub8(params),
 
             // --------------------------------------------
             //
@@ -396,6 +592,8 @@ impl ExecutorHelper for Processor {
             //
             // --------------------------------------------
             Instruction::SEL { params } => self.exec_sel(params),
+            Instruction::USAD8 { params } => self.exec_usad8(params),
+            Instruction::USADA8 { params } => self.exec_usada8(params),
             // --------------------------------------------
             //
             // Group: Status register access instructions
@@ -404,10 +602,6 @@ impl ExecutorHelper for Processor {
             Instruction::MRS { params } => self.exec_mrs(*params),
             Instruction::MSR_reg { params } => self.exec_msr(*params),
 
-            #[cfg(armv6m)]
-            Instruction::CPS { im } => self.exec_cps(*im),
-
-            #[cfg(any(armv7m, armv7em))]
             Instruction::CPS {
                 im,
                 affect_pri,
@@ -469,6 +663,7 @@ impl ExecutorHelper for Processor {
             // Group: Miscellaneous
             //
             // --------------------------------------------
+            Instruction::CLREX => self.exec_clrex(),
             Instruction::DMB => self.exec_dmb(),
             Instruction::DSB => self.exec_dsb(),
             Instruction::ISB => self.exec_isb(),
@@ -510,8 +705,11 @@ impl ExecutorHelper for Processor {
             // Group: Floating-point load and store instructions
             //
             // --------------------------------------------
+            Instruction::VLDM { params } => self.exec_vldm(params),
             Instruction::VLDR { params } => self.exec_vldr(params),
-
+            Instruction::VPOP { params } => self.exec_vpop(params),
+            Instruction::VPUSH { params } => self.exec_vpush(params),
+            Instruction::VSTM { params } => self.exec_vstm(params),
             Instruction::VSTR { params } => self.exec_vstr(params),
 
             // --------------------------------------------
@@ -519,23 +717,47 @@ impl ExecutorHelper for Processor {
             // Group: Floating-point register transfer instructions
             //
             // --------------------------------------------
+            Instruction::VMOV_cr_sp { params } => self.exec_vmov_cr_sp(params),
+            Instruction::VMOV_cr2_sp2 { params } => self.exec_vmov_cr2_sp2(params),
+            Instruction::VMOV_cr2_dp { params } => self.exec_vmov_cr2_dp(params),
+            Instruction::VMOV_cr_scalar { params } => self.exec_vmov_cr_scalar(params),
+            Instruction::VMRS { rt } => self.exec_vmrs(*rt),
+            Instruction::VMSR { rt } => self.exec_vmsr(*rt),
 
             // --------------------------------------------
             //
             // Group: Floating-point data-processing instructions
             //
             // --------------------------------------------
+            Instruction::VABS { params } => self.exec_vabs(params),
+            Instruction::VADD { params } => self.exec_vadd(params),
+            Instruction::VCMP { params } => self.exec_vcmp(params),
+            Instruction::VCVT_int { params } => self.exec_vcvt_int(params),
+            Instruction::VCVT_fixed { params } => self.exec_vcvt_fixed(params),
+            Instruction::VCVT_half { params } => self.exec_vcvt_half(params),
+            Instruction::VDIV { params } => self.exec_vdiv(params),
+            Instruction::VFMA { params } => self.exec_vfma(params),
+            Instruction::VFMS { params } => self.exec_vfms(params),
+            Instruction::VFNMA { params } => self.exec_vfnma(params),
+            Instruction::VFNMS { params } => self.exec_vfnms(params),
+            Instruction::VMLA { params } => self.exec_vmla(params),
+            Instruction::VMLS { params } => self.exec_vmls(params),
+            Instruction::VMOV_imm { params } => self.exec_vmov_imm(params),
+            Instruction::VMOV_reg { params } => self.exec_vmov_reg(params),
+            Instruction::VMUL { params } => self.exec_vmul(params),
+            Instruction::VNEG { params } => self.exec_vneg(params),
+            Instruction::VNMLA { params } => self.exec_vnmla(params),
+            Instruction::VNMLS { params } => self.exec_vnmls(params),
+            Instruction::VNMUL { params } => self.exec_vnmul(params),
+            Instruction::VSQRT { params } => self.exec_vsqrt(params),
+            Instruction::VSUB { params } => self.exec_vsub(params),
 
             // --------------------------------------------
             //
             // Fallback: unknown instruction
             //
             // --------------------------------------------
-            Instruction::UDF { imm32, opcode, .. } => {
-                println!("UDF {}, {}", imm32, opcode);
-                todo!("undefined")
-                //Err(Fault::UndefInstr)
-            }
+            Instruction::UDF { .. } => Err(Fault::UndefInstr),
         }
     }
 }
@@ -543,21 +765,67 @@ impl ExecutorHelper for Processor {
 impl Executor for Processor {
     #[inline(always)]
     fn step_sleep(&mut self) {
+        if self.scr & SCR_SLEEPDEEP != 0 {
+            self.deep_sleep_cycle_count += 1;
+        }
+        self.sleep_cycle_count += 1;
         self.syst_step(1);
         self.check_exceptions();
-        self.dwt_tick(1);
+        self.check_event();
+        self.check_lockup();
+        self.dwt_tick_sleep(1);
+        self.itm_tick(1);
+        self.rtt_tick(1);
     }
 
     #[inline(always)]
     fn step(&mut self) {
         let pc = self.get_pc();
-        let mapped_pc = (self.map_address(pc) >> 1) as usize;
-        let (instruction, instruction_size) = self.instruction_cache[mapped_pc];
-        let count = self.execute(&instruction, instruction_size);
+        if self.dwt_watch_enabled && self.dwt_match_pc(pc) {
+            return;
+        }
+        let fetched = if !self.psr.get_t() {
+            Err(Fault::Invstate)
+        } else if self.fpb_enabled {
+            match self.fpb_match_fetch(pc) {
+                FpbMatch::None => self.fetch_instruction(pc),
+                FpbMatch::Remap(thumb) => {
+                    let instruction = self.decode(thumb);
+                    Ok((instruction, instruction_size(&instruction)))
+                }
+                FpbMatch::Breakpoint => {
+                    self.debug_breakpoint(pc);
+                    return;
+                }
+            }
+        } else {
+            self.fetch_instruction(pc)
+        };
+        let (executed, count) = match fetched {
+            Ok((instruction, instruction_size)) => {
+                (Some(instruction), self.execute(&instruction, instruction_size))
+            }
+            Err(fault) => {
+                // the instruction is not executed
+                self.fault_entry(fault, pc);
+                //TODO: proper amount of cycles calculation
+                (None, 12)
+            }
+        };
         self.cycle_count += u64::from(count);
+        if self.dwt_watch_enabled {
+            self.dwt_data_step(pc);
+        }
         self.dwt_tick(count);
+        if let Some(instruction) = executed {
+            self.dwt_count_instruction(&instruction, count);
+        }
+        self.itm_tick(count);
+        self.rtt_tick(count);
         self.syst_step(count);
         self.check_exceptions();
+        self.check_lockup();
+        self.debug_step();
         //TODO exception entry also burns cycles that should be accounted for
         //DWT and SYST ticking
     }
@@ -569,14 +837,21 @@ impl Executor for Processor {
         let in_it_block = self.in_it_block();
 
         match self.execute_internal(&instruction) {
-            Err(_fault) => {
+            Err(fault) => {
                 // all faults are mapped to hardfaults on armv6m
                 let new_pc = self.get_pc();
 
-                //TODO: map to correct exception
+                if fault == Fault::UndefInstr && self.strict_decode {
+                    let opcode = self
+                        .fetch(new_pc)
+                        .unwrap_or(ThumbCode::Thumb16 { opcode: 0 });
+                    self.undefined_instruction = Some((new_pc, opcode));
+                    self.state.set_bit(0, false);
+                    return 0;
+                }
+
                 //TODO: cycles not correctly accumulated yet for exception entry
-                self.exception_entry(Exception::HardFault, new_pc)
-                    .expect("error handling on exception entry not implemented");
+                self.fault_entry(fault, new_pc);
                 //TODO: proper amount of cycles calculation
                 12
             }
@@ -604,7 +879,12 @@ impl Executor for Processor {
 mod tests {
     use super::*;
     use crate::core::condition::Condition;
+    use crate::core::cpu::Cpu;
     use crate::core::instruction::instruction_size;
+    use crate::core::exception::Exception;
+    use crate::core::instruction::Reg2FullParams;
+    use crate::core::instruction::{Reg2RtRnImm32Params, Reg3RdRtRnImm32Params};
+    use crate::core::register::Ipsr;
     use crate::core::{register::Reg, instruction::{
         ITCondition, Reg2ShiftNoSetFlagsParams, RegImmCarryParams, SRType, SetFlags,
     }};
@@ -651,4 +931,371 @@ mod tests {
         assert_eq!(core.get_r(Reg::R4), 0x01);
         assert!(!core.in_it_block());
     }
+
+    #[test]
+    fn test_udf_usage_fault() {
+        // arrange
+        let mut core = Processor::new();
+        core.set_msp(0x2000_1000);
+        core.shcsr.set_bit(18, true); // USGFAULTENA
+        core.execution_priority = core.get_execution_priority();
+        let udf = Instruction::UDF {
+            imm32: 0,
+            opcode: ThumbCode::from(0xde00_u16),
+            thumb32: false,
+        };
+
+        // act
+        core.execute(&udf, instruction_size(&udf));
+
+        // assert
+        assert!(core.cfsr.get_bit(16)); // UNDEFINSTR
+        assert!(!core.hfsr.get_bit(30));
+        assert_eq!(
+            core.psr.get_isr_number(),
+            usize::from(Exception::UsageFault)
+        );
+    }
+
+    #[test]
+    fn test_fp_without_fpu_is_nocp() {
+        // arrange
+        let mut core = Processor::new();
+        core.cpu(Cpu::CortexM4);
+        core.set_msp(0x2000_1000);
+        core.shcsr.set_bit(18, true); // USGFAULTENA
+        core.cpacr = 0x00f0_0000;
+        core.execution_priority = core.get_execution_priority();
+        // vldr s17, [r0, #4]
+        let vldr = core.decode(ThumbCode::from(0xedd0_8a01_u32));
+
+        // act
+        core.execute(&vldr, instruction_size(&vldr));
+
+        // assert
+        assert!(core.cfsr.get_bit(19)); // NOCP
+        assert!(!core.cfsr.get_bit(16)); // UNDEFINSTR
+        assert_eq!(
+            core.psr.get_isr_number(),
+            usize::from(Exception::UsageFault)
+        );
+    }
+
+    #[test]
+    fn test_udf_escalated_to_hardfault() {
+        // arrange
+        let mut core = Processor::new();
+        core.set_msp(0x2000_1000);
+        let udf = Instruction::UDF {
+            imm32: 0,
+            opcode: ThumbCode::from(0xf7f0_a000_u32),
+            thumb32: true,
+        };
+
+        // act
+        core.execute(&udf, instruction_size(&udf));
+
+        // assert
+        assert!(core.cfsr.get_bit(16)); // UNDEFINSTR
+        assert!(core.hfsr.get_bit(30)); // FORCED
+        assert_eq!(
+            core.psr.get_isr_number(),
+            usize::from(Exception::HardFault)
+        );
+    }
+
+    #[test]
+    fn test_udf_hardfault() {
+        // arrange
+        let mut core = Processor::new();
+        core.cpu(Cpu::CortexM0);
+        core.set_msp(0x2000_1000);
+        let udf = Instruction::UDF {
+            imm32: 0,
+            opcode: ThumbCode::from(0xde00_u16),
+            thumb32: false,
+        };
+
+        // act
+        core.execute(&udf, instruction_size(&udf));
+
+        // assert
+        assert_eq!(
+            core.psr.get_isr_number(),
+            usize::from(Exception::HardFault)
+        );
+    }
+
+    #[test]
+    fn test_precise_bus_fault() {
+        // arrange
+        let mut core = Processor::new();
+        core.set_msp(0x2000_1000);
+        core.set_pc(0x100);
+        core.set_r(Reg::R1, 0x3000_0000);
+        core.shcsr.set_bit(17, true); // BUSFAULTENA
+        core.execution_priority = core.get_execution_priority();
+        let ldr = Instruction::LDR_imm {
+            params: Reg2FullParams {
+                rt: Reg::R0,
+                rn: Reg::R1,
+                imm32: 0x10,
+                index: true,
+                add: true,
+                wback: false,
+            },
+            thumb32: false,
+        };
+
+        // act
+        core.execute(&ldr, instruction_size(&ldr));
+
+        // assert
+        assert!(core.cfsr.get_bit(9)); // PRECISERR
+        assert!(core.cfsr.get_bit(15)); // BFARVALID
+        assert_eq!(core.bfar, 0x3000_0010);
+        assert_eq!(
+            core.psr.get_isr_number(),
+            usize::from(Exception::BusFault)
+        );
+        // the faulting instruction is the return address
+        assert_eq!(core.read32(0x2000_1000 - 0x20 + 0x18).unwrap(), 0x100);
+    }
+
+    #[test]
+    fn test_unaligned_trap() {
+        // arrange
+        let mut core = Processor::new();
+        core.set_msp(0x2000_1000);
+        core.set_r(Reg::R1, 0x2000_0002);
+        core.shcsr.set_bit(18, true); // USGFAULTENA
+        core.execution_priority = core.get_execution_priority();
+        let ldr = Instruction::LDR_imm {
+            params: Reg2FullParams {
+                rt: Reg::R0,
+                rn: Reg::R1,
+                imm32: 0,
+                index: true,
+                add: true,
+                wback: false,
+            },
+            thumb32: false,
+        };
+
+        // act: unaligned word access is allowed by default
+        core.execute(&ldr, instruction_size(&ldr));
+
+        // assert
+        assert_eq!(core.psr.get_isr_number(), 0);
+
+        // act
+        core.ccr |= CCR_UNALIGN_TRP;
+        core.execute(&ldr, instruction_size(&ldr));
+
+        // assert
+        assert!(core.cfsr.get_bit(24)); // UNALIGNED
+        assert_eq!(
+            core.psr.get_isr_number(),
+            usize::from(Exception::UsageFault)
+        );
+    }
+
+    #[test]
+    fn test_unaligned_access_split() {
+        // arrange
+        let mut core = Processor::new();
+        core.set_msp(0x2000_1000);
+        core.write32(0x2000_0100, 0x0403_0201).unwrap();
+        core.write32(0x2000_0104, 0x0807_0605).unwrap();
+        core.set_r(Reg::R1, 0x2000_0101);
+        core.shcsr.set_bit(17, true); // BUSFAULTENA
+        core.execution_priority = core.get_execution_priority();
+        let ldr = Instruction::LDR_imm {
+            params: Reg2FullParams {
+                rt: Reg::R0,
+                rn: Reg::R1,
+                imm32: 0,
+                index: true,
+                add: true,
+                wback: false,
+            },
+            thumb32: false,
+        };
+
+        // act
+        core.execute(&ldr, instruction_size(&ldr));
+
+        // assert
+        assert_eq!(core.get_r(Reg::R0), 0x0504_0302);
+
+        // act: the word crosses the end of RAM
+        core.set_r(Reg::R1, 0x2001_fffe);
+        core.execute(&ldr, instruction_size(&ldr));
+
+        // assert
+        assert!(core.cfsr.get_bit(9)); // PRECISERR
+        assert_eq!(core.bfar, 0x2002_0000);
+        assert_eq!(
+            core.psr.get_isr_number(),
+            usize::from(Exception::BusFault)
+        );
+    }
+
+    #[test]
+    fn test_unaligned_armv6m() {
+        // arrange
+        let mut core = Processor::new();
+        core.cpu(Cpu::CortexM0);
+        core.set_msp(0x2000_1000);
+        core.set_r(Reg::R1, 0x2000_0102);
+        core.execution_priority = core.get_execution_priority();
+        let ldrh = Instruction::LDRH_imm {
+            params: Reg2FullParams {
+                rt: Reg::R0,
+                rn: Reg::R1,
+                imm32: 1,
+                index: true,
+                add: true,
+                wback: false,
+            },
+            thumb32: false,
+        };
+
+        // act
+        core.execute(&ldrh, instruction_size(&ldrh));
+
+        // assert
+        assert_eq!(
+            core.psr.get_isr_number(),
+            usize::from(Exception::HardFault)
+        );
+    }
+
+    #[test]
+    fn test_strex_fails_after_exception() {
+        // arrange
+        let mut core = Processor::new();
+        core.set_msp(0x2000_1000);
+        core.set_r(Reg::R1, 0x2000_0000);
+        core.set_r(Reg::R2, 5);
+        let ldrex = Instruction::LDREX {
+            params: Reg2RtRnImm32Params {
+                rt: Reg::R0,
+                rn: Reg::R1,
+                imm32: 0,
+            },
+        };
+        let strex = Instruction::STREX {
+            params: Reg3RdRtRnImm32Params {
+                rd: Reg::R3,
+                rt: Reg::R2,
+                rn: Reg::R1,
+                imm32: 0,
+            },
+        };
+
+        // act
+        core.execute(&ldrex, 4);
+        core.execute(&strex, 4);
+
+        // assert
+        assert_eq!(core.get_r(Reg::R3), 0);
+        assert_eq!(core.read32(0x2000_0000).unwrap(), 5);
+
+        // act
+        core.set_r(Reg::R2, 6);
+        core.execute(&ldrex, 4);
+        core.exception_entry(Exception::SysTick, 0).unwrap();
+        core.execute(&strex, 4);
+
+        // assert
+        assert_eq!(core.get_r(Reg::R3), 1);
+        assert_eq!(core.read32(0x2000_0000).unwrap(), 5);
+    }
+
+    #[test]
+    fn test_invalid_thumb_state() {
+        // arrange
+        let mut core = Processor::new();
+        core.cache_instructions();
+        core.set_msp(0x2000_1000);
+        core.set_pc(0x100);
+        core.psr.set_t(false);
+
+        // act
+        core.step();
+
+        // assert
+        assert!(core.cfsr.get_bit(17)); // INVSTATE
+        assert_eq!(core.instruction_count, 0);
+        assert_eq!(
+            core.psr.get_isr_number(),
+            usize::from(Exception::HardFault)
+        );
+    }
+
+    #[test]
+    fn test_udf_strict_decode() {
+        // arrange
+        let mut core = Processor::new();
+        core.set_msp(0x2000_1000);
+        core.flash_memory(4, &[0x00, 0xde, 0x00, 0xbf]);
+        core.set_pc(0);
+        core.strict_decode = true;
+        core.state = 1;
+        let udf = Instruction::UDF {
+            imm32: 0,
+            opcode: ThumbCode::from(0xde00_u16),
+            thumb32: false,
+        };
+
+        // act
+        core.execute(&udf, instruction_size(&udf));
+
+        // assert
+        assert_eq!(core.state, 0);
+        assert_eq!(
+            core.undefined_instruction,
+            Some((0, ThumbCode::Thumb16 { opcode: 0xde00 }))
+        );
+        assert_eq!(core.psr.get_isr_number(), 0);
+        assert_eq!(core.cfsr, 0);
+    }
+
+    #[test]
+    fn test_wfe_sleep() {
+        // arrange
+        let mut core = Processor::new();
+        core.set_msp(0x2000_1000);
+        core.state = 1;
+        let sev = Instruction::SEV { thumb32: false };
+        let wfe = Instruction::WFE { thumb32: false };
+
+        // act
+        core.execute(&sev, 2);
+        core.execute(&wfe, 2);
+
+        // assert
+        assert_eq!(core.state, 0b01); // event was consumed
+
+        // act
+        core.execute(&wfe, 2);
+        core.write32(0xE000_ED10, 0b10100).unwrap(); // SEVONPEND, SLEEPDEEP
+        core.step_sleep();
+        core.step_sleep();
+
+        // assert
+        assert_eq!(core.state, 0b11);
+        assert_eq!(core.sleep_cycle_count, 2);
+        assert_eq!(core.deep_sleep_cycle_count, 2);
+
+        // act
+        core.write32(0xE000_E200, 1).unwrap(); // pend disabled interrupt 0
+        core.step_sleep();
+
+        // assert
+        assert_eq!(core.state, 0b01);
+        assert_eq!(core.psr.get_isr_number(), 0);
+        assert_eq!(core.sleep_cycle_count, 3);
+    }
 }
@@ -16,6 +16,7 @@ pub trait IsaMultiply {
     fn exec_mul(&mut self, params: &Reg3Params) -> ExecuteResult;
     fn exec_umlal(&mut self, params: &Reg643232Params) -> ExecuteResult;
     fn exec_umull(&mut self, params: &Reg643232Params) -> ExecuteResult;
+    fn exec_umaal(&mut self, params: &Reg643232Params) -> ExecuteResult;
 }
 
 impl IsaMultiply for Processor {
@@ -92,6 +93,23 @@ impl IsaMultiply for Processor {
         }
         Ok(ExecuteSuccess::NotTaken)
     }
+
+    fn exec_umaal(&mut self, params: &Reg643232Params) -> ExecuteResult {
+        if self.condition_passed() {
+            let rn = u64::from(self.get_r(params.rn));
+            let rm = u64::from(self.get_r(params.rm));
+            let rdlo = u64::from(self.get_r(params.rdlo));
+            let rdhi = u64::from(self.get_r(params.rdhi));
+
+            // cannot overflow: (2^32 - 1)^2 + 2 * (2^32 - 1) = 2^64 - 1
+            let result = rn * rm + rdhi + rdlo;
+
+            self.set_r(params.rdlo, result.get_bits(0..32) as u32);
+            self.set_r(params.rdhi, result.get_bits(32..64) as u32);
+            return Ok(ExecuteSuccess::Taken { cycles: 1 });
+        }
+        Ok(ExecuteSuccess::NotTaken)
+    }
 }
 
 #[cfg(test)]
@@ -124,4 +142,31 @@ mod tests {
 
         assert_eq!(core.get_r(Reg::R1), 0x20000DD0);
     }
+
+    #[test]
+    fn test_umaal() {
+        // arrange
+        let mut core = Processor::new();
+        core.psr.value = 0;
+        core.set_r(Reg::R1, 0xffff_ffff);
+        core.set_r(Reg::R2, 0xffff_ffff);
+        core.set_r(Reg::R3, 0xffff_ffff);
+        core.set_r(Reg::R4, 0xffff_ffff);
+
+        let instruction = Instruction::UMAAL {
+            params: Reg643232Params {
+                rdlo: Reg::R3,
+                rdhi: Reg::R4,
+                rn: Reg::R1,
+                rm: Reg::R2,
+            },
+        };
+
+        // act
+        core.execute_internal(&instruction).unwrap();
+
+        // assert
+        assert_eq!(core.get_r(Reg::R3), 0xffff_ffff);
+        assert_eq!(core.get_r(Reg::R4), 0xffff_ffff);
+    }
 }
@@ -3,11 +3,12 @@ use crate::Processor;
 use crate::executor::{ExecuteSuccess, ExecutorHelper};
 
 use super::ExecuteResult;
-use crate::core::instruction::{Reg2UsizeParams, Reg3UsizeParams};
+use crate::core::instruction::{Reg2UsizeParams, Reg3ShiftNoSetFlagsParams, Reg3UsizeParams};
 use crate::core::{
     bits::Bits,
-    operation::{ror, sign_extend},
-    register::BaseReg,
+    instruction::SRType,
+    operation::{ror, shift, sign_extend},
+    register::{Apsr, BaseReg},
 };
 
 /// Multiply operations
@@ -17,6 +18,14 @@ pub trait IsaPacking {
     fn exec_uxtb(&mut self, params: &Reg2UsizeParams) -> ExecuteResult;
     fn exec_uxth(&mut self, params: &Reg2UsizeParams) -> ExecuteResult;
     fn exec_uxtab(&mut self, params: &Reg3UsizeParams) -> ExecuteResult;
+    fn exec_uxtab16(&mut self, params: &Reg3UsizeParams) -> ExecuteResult;
+    fn exec_uxtah(&mut self, params: &Reg3UsizeParams) -> ExecuteResult;
+    fn exec_uxtb16(&mut self, params: &Reg2UsizeParams) -> ExecuteResult;
+    fn exec_sxtab(&mut self, params: &Reg3UsizeParams) -> ExecuteResult;
+    fn exec_sxtab16(&mut self, params: &Reg3UsizeParams) -> ExecuteResult;
+    fn exec_sxtah(&mut self, params: &Reg3UsizeParams) -> ExecuteResult;
+    fn exec_sxtb16(&mut self, params: &Reg2UsizeParams) -> ExecuteResult;
+    fn exec_pkh(&mut self, params: &Reg3ShiftNoSetFlagsParams) -> ExecuteResult;
 }
 
 impl IsaPacking for Processor {
@@ -73,4 +82,245 @@ impl IsaPacking for Processor {
         }
         Ok(ExecuteSuccess::NotTaken)
     }
+
+    fn exec_uxtab16(&mut self, params: &Reg3UsizeParams) -> ExecuteResult {
+        if self.condition_passed() {
+            let rotated = ror(self.get_r(params.rm), params.rotation);
+            let rn = self.get_r(params.rn);
+
+            let mut result: u32 = 0;
+            result.set_bits(
+                0..16,
+                rn.get_bits(0..16)
+                    .wrapping_add(rotated.get_bits(0..8))
+                    .get_bits(0..16),
+            );
+            result.set_bits(
+                16..32,
+                rn.get_bits(16..32)
+                    .wrapping_add(rotated.get_bits(16..24))
+                    .get_bits(0..16),
+            );
+            self.set_r(params.rd, result);
+            return Ok(ExecuteSuccess::Taken { cycles: 1 });
+        }
+        Ok(ExecuteSuccess::NotTaken)
+    }
+
+    fn exec_uxtah(&mut self, params: &Reg3UsizeParams) -> ExecuteResult {
+        if self.condition_passed() {
+            let rotated = ror(self.get_r(params.rm), params.rotation);
+            let rn = self.get_r(params.rn);
+            let result = rn.wrapping_add(rotated.get_bits(0..16));
+            self.set_r(params.rd, result);
+            return Ok(ExecuteSuccess::Taken { cycles: 1 });
+        }
+        Ok(ExecuteSuccess::NotTaken)
+    }
+
+    fn exec_uxtb16(&mut self, params: &Reg2UsizeParams) -> ExecuteResult {
+        if self.condition_passed() {
+            let rotated = ror(self.get_r(params.rm), params.rotation);
+
+            let mut result: u32 = 0;
+            result.set_bits(0..16, rotated.get_bits(0..8));
+            result.set_bits(16..32, rotated.get_bits(16..24));
+            self.set_r(params.rd, result);
+            return Ok(ExecuteSuccess::Taken { cycles: 1 });
+        }
+        Ok(ExecuteSuccess::NotTaken)
+    }
+
+    fn exec_sxtab(&mut self, params: &Reg3UsizeParams) -> ExecuteResult {
+        if self.condition_passed() {
+            let rotated = ror(self.get_r(params.rm), params.rotation);
+            let rn = self.get_r(params.rn);
+            let result = rn.wrapping_add(sign_extend(rotated.get_bits(0..8), 7, 32) as u32);
+            self.set_r(params.rd, result);
+            return Ok(ExecuteSuccess::Taken { cycles: 1 });
+        }
+        Ok(ExecuteSuccess::NotTaken)
+    }
+
+    fn exec_sxtab16(&mut self, params: &Reg3UsizeParams) -> ExecuteResult {
+        if self.condition_passed() {
+            let rotated = ror(self.get_r(params.rm), params.rotation);
+            let rn = self.get_r(params.rn);
+
+            let mut result: u32 = 0;
+            result.set_bits(
+                0..16,
+                rn.get_bits(0..16)
+                    .wrapping_add(sign_extend(rotated.get_bits(0..8), 7, 32) as u32)
+                    .get_bits(0..16),
+            );
+            result.set_bits(
+                16..32,
+                rn.get_bits(16..32)
+                    .wrapping_add(sign_extend(rotated.get_bits(16..24), 7, 32) as u32)
+                    .get_bits(0..16),
+            );
+            self.set_r(params.rd, result);
+            return Ok(ExecuteSuccess::Taken { cycles: 1 });
+        }
+        Ok(ExecuteSuccess::NotTaken)
+    }
+
+    fn exec_sxtah(&mut self, params: &Reg3UsizeParams) -> ExecuteResult {
+        if self.condition_passed() {
+            let rotated = ror(self.get_r(params.rm), params.rotation);
+            let rn = self.get_r(params.rn);
+            let result = rn.wrapping_add(sign_extend(rotated.get_bits(0..16), 15, 32) as u32);
+            self.set_r(params.rd, result);
+            return Ok(ExecuteSuccess::Taken { cycles: 1 });
+        }
+        Ok(ExecuteSuccess::NotTaken)
+    }
+
+    fn exec_sxtb16(&mut self, params: &Reg2UsizeParams) -> ExecuteResult {
+        if self.condition_passed() {
+            let rotated = ror(self.get_r(params.rm), params.rotation);
+
+            let mut result: u32 = 0;
+            result.set_bits(
+                0..16,
+                (sign_extend(rotated.get_bits(0..8), 7, 32) as u32).get_bits(0..16),
+            );
+            result.set_bits(
+                16..32,
+                (sign_extend(rotated.get_bits(16..24), 7, 32) as u32).get_bits(0..16),
+            );
+            self.set_r(params.rd, result);
+            return Ok(ExecuteSuccess::Taken { cycles: 1 });
+        }
+        Ok(ExecuteSuccess::NotTaken)
+    }
+
+    fn exec_pkh(&mut self, params: &Reg3ShiftNoSetFlagsParams) -> ExecuteResult {
+        if self.condition_passed() {
+            let operand2 = shift(
+                self.get_r(params.rm),
+                params.shift_t,
+                params.shift_n as usize,
+                self.psr.get_c(),
+            );
+            let rn = self.get_r(params.rn);
+
+            let mut result: u32 = 0;
+            if params.shift_t == SRType::ASR {
+                // PKHTB
+                result.set_bits(0..16, operand2.get_bits(0..16));
+                result.set_bits(16..32, rn.get_bits(16..32));
+            } else {
+                // PKHBT
+                result.set_bits(0..16, rn.get_bits(0..16));
+                result.set_bits(16..32, operand2.get_bits(16..32));
+            }
+            self.set_r(params.rd, result);
+            return Ok(ExecuteSuccess::Taken { cycles: 1 });
+        }
+        Ok(ExecuteSuccess::NotTaken)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{instruction::Instruction, register::Reg};
+
+    #[test]
+    fn test_pkhbt() {
+        // arrange
+        let mut core = Processor::new();
+        core.psr.value = 0;
+        core.set_r(Reg::R2, 0x1111_2222);
+        core.set_r(Reg::R3, 0x0003_3333);
+
+        let instruction = Instruction::PKH {
+            params: Reg3ShiftNoSetFlagsParams {
+                rd: Reg::R1,
+                rn: Reg::R2,
+                rm: Reg::R3,
+                shift_t: SRType::LSL,
+                shift_n: 16,
+            },
+        };
+
+        // act
+        core.execute_internal(&instruction).unwrap();
+
+        // assert
+        assert_eq!(core.get_r(Reg::R1), 0x3333_2222);
+    }
+
+    #[test]
+    fn test_pkhtb_asr_32() {
+        // arrange
+        let mut core = Processor::new();
+        core.psr.value = 0;
+        core.set_r(Reg::R2, 0x1111_2222);
+        core.set_r(Reg::R3, 0x8000_0000);
+
+        let instruction = Instruction::PKH {
+            params: Reg3ShiftNoSetFlagsParams {
+                rd: Reg::R1,
+                rn: Reg::R2,
+                rm: Reg::R3,
+                shift_t: SRType::ASR,
+                shift_n: 32,
+            },
+        };
+
+        // act
+        core.execute_internal(&instruction).unwrap();
+
+        // assert
+        assert_eq!(core.get_r(Reg::R1), 0x1111_ffff);
+    }
+
+    #[test]
+    fn test_sxtab16() {
+        // arrange
+        let mut core = Processor::new();
+        core.psr.value = 0;
+        core.set_r(Reg::R2, 0x0001_0010);
+        core.set_r(Reg::R3, 0x00ff_0080);
+
+        let instruction = Instruction::SXTAB16 {
+            params: Reg3UsizeParams {
+                rd: Reg::R1,
+                rn: Reg::R2,
+                rm: Reg::R3,
+                rotation: 0,
+            },
+        };
+
+        // act
+        core.execute_internal(&instruction).unwrap();
+
+        // assert
+        assert_eq!(core.get_r(Reg::R1), 0x0000_ff90);
+    }
+
+    #[test]
+    fn test_uxtb16_rotated() {
+        // arrange
+        let mut core = Processor::new();
+        core.psr.value = 0;
+        core.set_r(Reg::R3, 0x1122_3344);
+
+        let instruction = Instruction::UXTB16 {
+            params: Reg2UsizeParams {
+                rd: Reg::R1,
+                rm: Reg::R3,
+                rotation: 8,
+            },
+        };
+
+        // act
+        core.execute_internal(&instruction).unwrap();
+
+        // assert
+        assert_eq!(core.get_r(Reg::R1), 0x0011_0033);
+    }
 }
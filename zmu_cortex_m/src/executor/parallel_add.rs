@@ -6,38 +6,469 @@ use super::ExecuteResult;
 use crate::core::instruction::Reg3NoSetFlagsParams;
 use crate::core::{
     bits::Bits,
+    operation::{sign_extend, signed_sat_q, unsigned_sat_q},
     register::{Apsr, BaseReg},
 };
 
-/// Divide operations
-pub trait IsaParallelAddSub {
-    fn exec_uadd8(&mut self, params: &Reg3NoSetFlagsParams) -> ExecuteResult;
+#[derive(PartialEq, Copy, Clone)]
+enum LaneOp {
+    Add,
+    Sub,
 }
 
-impl IsaParallelAddSub for Processor {
-    fn exec_uadd8(&mut self, params: &Reg3NoSetFlagsParams) -> ExecuteResult {
+#[derive(PartialEq, Copy, Clone)]
+enum Mode {
+    Signed,
+    Saturating,
+    Halving,
+    Unsigned,
+    UnsignedSaturating,
+    UnsignedHalving,
+}
+
+// For each result lane: the lane of Rm combined with the same lane of Rn, and the operation.
+const ADD16: [(usize, LaneOp); 2] = [(0, LaneOp::Add), (1, LaneOp::Add)];
+const ASX: [(usize, LaneOp); 2] = [(1, LaneOp::Sub), (0, LaneOp::Add)];
+const SAX: [(usize, LaneOp); 2] = [(1, LaneOp::Add), (0, LaneOp::Sub)];
+const SUB16: [(usize, LaneOp); 2] = [(0, LaneOp::Sub), (1, LaneOp::Sub)];
+const ADD8: [(usize, LaneOp); 4] = [
+    (0, LaneOp::Add),
+    (1, LaneOp::Add),
+    (2, LaneOp::Add),
+    (3, LaneOp::Add),
+];
+const SUB8: [(usize, LaneOp); 4] = [
+    (0, LaneOp::Sub),
+    (1, LaneOp::Sub),
+    (2, LaneOp::Sub),
+    (3, LaneOp::Sub),
+];
+
+trait ParallelHelper {
+    fn parallel_add_sub(
+        &mut self,
+        params: &Reg3NoSetFlagsParams,
+        mode: Mode,
+        lanes: &[(usize, LaneOp)],
+    ) -> ExecuteResult;
+
+    fn set_ge(&mut self, index: usize, bit: bool);
+}
+
+impl ParallelHelper for Processor {
+    fn parallel_add_sub(
+        &mut self,
+        params: &Reg3NoSetFlagsParams,
+        mode: Mode,
+        lanes: &[(usize, LaneOp)],
+    ) -> ExecuteResult {
         if self.condition_passed() {
-            let rm: u32 = self.get_r(params.rm);
             let rn: u32 = self.get_r(params.rn);
+            let rm: u32 = self.get_r(params.rm);
 
-            let sum1: u32 = rn.get_bits(0..8) + rm.get_bits(0..8);
-            let sum2: u32 = rn.get_bits(8..16) + rm.get_bits(8..16);
-            let sum3: u32 = rn.get_bits(16..24) + rm.get_bits(16..24);
-            let sum4: u32 = rn.get_bits(24..32) + rm.get_bits(24..32);
+            let size = 32 / lanes.len();
+            let signed = matches!(mode, Mode::Signed | Mode::Saturating | Mode::Halving);
+            let lane = |value: u32, index: usize| -> i32 {
+                let bits = value.get_bits(index * size..(index + 1) * size);
+                if signed {
+                    sign_extend(bits, size - 1, 32) as i32
+                } else {
+                    bits as i32
+                }
+            };
 
-            let mut result: u32 = sum1.get_bits(0..8);
-            result.set_bits(8..16, sum2.get_bits(0..8));
-            result.set_bits(16..24, sum3.get_bits(0..8));
-            result.set_bits(24..32, sum4.get_bits(0..8));
-            self.set_r(params.rd, result);
+            let mut result: u32 = 0;
+            for (index, (m_index, op)) in lanes.iter().enumerate() {
+                let operand1 = lane(rn, index);
+                let operand2 = lane(rm, *m_index);
+                let value = match op {
+                    LaneOp::Add => operand1 + operand2,
+                    LaneOp::Sub => operand1 - operand2,
+                };
 
-            self.psr.set_ge0(sum1 >= 0x100);
-            self.psr.set_ge1(sum2 >= 0x100);
-            self.psr.set_ge2(sum3 >= 0x100);
-            self.psr.set_ge3(sum4 >= 0x100);
+                let lane_result = match mode {
+                    Mode::Signed | Mode::Unsigned => value as u32,
+                    Mode::Saturating => signed_sat_q(i64::from(value), size).0 as u32,
+                    Mode::UnsignedSaturating => unsigned_sat_q(i64::from(value), size).0,
+                    Mode::Halving | Mode::UnsignedHalving => (value >> 1) as u32,
+                };
+                result.set_bits(index * size..(index + 1) * size, lane_result.get_bits(0..size));
+
+                if mode == Mode::Signed || mode == Mode::Unsigned {
+                    let ge = if mode == Mode::Unsigned && *op == LaneOp::Add {
+                        value >= 1 << size
+                    } else {
+                        value >= 0
+                    };
+                    let ge_bits = 4 / lanes.len();
+                    for ge_index in index * ge_bits..(index + 1) * ge_bits {
+                        self.set_ge(ge_index, ge);
+                    }
+                }
+            }
+            self.set_r(params.rd, result);
 
             return Ok(ExecuteSuccess::Taken { cycles: 1 });
         }
         Ok(ExecuteSuccess::NotTaken)
     }
+
+    fn set_ge(&mut self, index: usize, bit: bool) {
+        match index {
+            0 => self.psr.set_ge0(bit),
+            1 => self.psr.set_ge1(bit),
+            2 => self.psr.set_ge2(bit),
+            _ => self.psr.set_ge3(bit),
+        }
+    }
+}
+
+/// Parallel addition and subtraction operations
+pub trait IsaParallelAddSub {
+    fn exec_sadd16(&mut self, params: &Reg3NoSetFlagsParams) -> ExecuteResult;
+    fn exec_qadd16(&mut self, params: &Reg3NoSetFlagsParams) -> ExecuteResult;
+    fn exec_shadd16(&mut self, params: &Reg3NoSetFlagsParams) -> ExecuteResult;
+    fn exec_uadd16(&mut self, params: &Reg3NoSetFlagsParams) -> ExecuteResult;
+    fn exec_uqadd16(&mut self, params: &Reg3NoSetFlagsParams) -> ExecuteResult;
+    fn exec_uhadd16(&mut self, params: &Reg3NoSetFlagsParams) -> ExecuteResult;
+    fn exec_sasx(&mut self, params: &Reg3NoSetFlagsParams) -> ExecuteResult;
+    fn exec_qasx(&mut self, params: &Reg3NoSetFlagsParams) -> ExecuteResult;
+    fn exec_shasx(&mut self, params: &Reg3NoSetFlagsParams) -> ExecuteResult;
+    fn exec_uasx(&mut self, params: &Reg3NoSetFlagsParams) -> ExecuteResult;
+    fn exec_uqasx(&mut self, params: &Reg3NoSetFlagsParams) -> ExecuteResult;
+    fn exec_uhasx(&mut self, params: &Reg3NoSetFlagsParams) -> ExecuteResult;
+    fn exec_ssax(&mut self, params: &Reg3NoSetFlagsParams) -> ExecuteResult;
+    fn exec_qsax(&mut self, params: &Reg3NoSetFlagsParams) -> ExecuteResult;
+    fn exec_shsax(&mut self, params: &Reg3NoSetFlagsParams) -> ExecuteResult;
+    fn exec_usax(&mut self, params: &Reg3NoSetFlagsParams) -> ExecuteResult;
+    fn exec_uqsax(&mut self, params: &Reg3NoSetFlagsParams) -> ExecuteResult;
+    fn exec_uhsax(&mut self, params: &Reg3NoSetFlagsParams) -> ExecuteResult;
+    fn exec_ssub16(&mut self, params: &Reg3NoSetFlagsParams) -> ExecuteResult;
+    fn exec_qsub16(&mut self, params: &Reg3NoSetFlagsParams) -> ExecuteResult;
+    fn exec_shsub16(&mut self, params: &Reg3NoSetFlagsParams) -> ExecuteResult;
+    fn exec_usub16(&mut self, params: &Reg3NoSetFlagsParams) -> ExecuteResult;
+    fn exec_uqsub16(&mut self, params: &Reg3NoSetFlagsParams) -> ExecuteResult;
+    fn exec_uhsub16(&mut self, params: &Reg3NoSetFlagsParams) -> ExecuteResult;
+    fn exec_sadd8(&mut self, params: &Reg3NoSetFlagsParams) -> ExecuteResult;
+    fn exec_qadd8(&mut self, params: &Reg3NoSetFlagsParams) -> ExecuteResult;
+    fn exec_shadd8(&mut self, params: &Reg3NoSetFlagsParams) -> ExecuteResult;
+    fn exec_uadd8(&mut self, params: &Reg3NoSetFlagsParams) -> ExecuteResult;
+    fn exec_uqadd8(&mut self, params: &Reg3NoSetFlagsParams) -> ExecuteResult;
+    fn exec_uhadd8(&mut self, params: &Reg3NoSetFlagsParams) -> ExecuteResult;
+    fn exec_ssub8(&mut self, params: &Reg3NoSetFlagsParams) -> ExecuteResult;
+    fn exec_qsub8(&mut self, params: &Reg3NoSetFlagsParams) -> ExecuteResult;
+    fn exec_shsub8(&mut self, params: &Reg3NoSetFlagsParams) -> ExecuteResult;
+    fn exec_usub8(&mut self, params: &Reg3NoSetFlagsParams) -> ExecuteResult;
+    fn exec_uqsub8(&mut self, params: &Reg3NoSetFlagsParams) -> ExecuteResult;
+    fn exec_uhsub8(&mut self, params: &Reg3NoSetFlagsParams) -> ExecuteResult;
+}
+
+impl IsaParallelAddSub for Processor {
+    fn exec_sadd16(&mut self, params: &Reg3NoSetFlagsParams) -> ExecuteResult {
+        self.parallel_add_sub(params, Mode::Signed, &ADD16)
+    }
+
+    fn exec_qadd16(&mut self, params: &Reg3NoSetFlagsParams) -> ExecuteResult {
+        self.parallel_add_sub(params, Mode::Saturating, &ADD16)
+    }
+
+    fn exec_shadd16(&mut self, params: &Reg3NoSetFlagsParams) -> ExecuteResult {
+        self.parallel_add_sub(params, Mode::Halving, &ADD16)
+    }
+
+    fn exec_uadd16(&mut self, params: &Reg3NoSetFlagsParams) -> ExecuteResult {
+        self.parallel_add_sub(params, Mode::Unsigned, &ADD16)
+    }
+
+    fn exec_uqadd16(&mut self, params: &Reg3NoSetFlagsParams) -> ExecuteResult {
+        self.parallel_add_sub(params, Mode::UnsignedSaturating, &ADD16)
+    }
+
+    fn exec_uhadd16(&mut self, params: &Reg3NoSetFlagsParams) -> ExecuteResult {
+        self.parallel_add_sub(params, Mode::UnsignedHalving, &ADD16)
+    }
+
+    fn exec_sasx(&mut self, params: &Reg3NoSetFlagsParams) -> ExecuteResult {
+        self.parallel_add_sub(params, Mode::Signed, &ASX)
+    }
+
+    fn exec_qasx(&mut self, params: &Reg3NoSetFlagsParams) -> ExecuteResult {
+        self.parallel_add_sub(params, Mode::Saturating, &ASX)
+    }
+
+    fn exec_shasx(&mut self, params: &Reg3NoSetFlagsParams) -> ExecuteResult {
+        self.parallel_add_sub(params, Mode::Halving, &ASX)
+    }
+
+    fn exec_uasx(&mut self, params: &Reg3NoSetFlagsParams) -> ExecuteResult {
+        self.parallel_add_sub(params, Mode::Unsigned, &ASX)
+    }
+
+    fn exec_uqasx(&mut self, params: &Reg3NoSetFlagsParams) -> ExecuteResult {
+        self.parallel_add_sub(params, Mode::UnsignedSaturating, &ASX)
+    }
+
+    fn exec_uhasx(&mut self, params: &Reg3NoSetFlagsParams) -> ExecuteResult {
+        self.parallel_add_sub(params, Mode::UnsignedHalving, &ASX)
+    }
+
+    fn exec_ssax(&mut self, params: &Reg3NoSetFlagsParams) -> ExecuteResult {
+        self.parallel_add_sub(params, Mode::Signed, &SAX)
+    }
+
+    fn exec_qsax(&mut self, params: &Reg3NoSetFlagsParams) -> ExecuteResult {
+        self.parallel_add_sub(params, Mode::Saturating, &SAX)
+    }
+
+    fn exec_shsax(&mut self, params: &Reg3NoSetFlagsParams) -> ExecuteResult {
+        self.parallel_add_sub(params, Mode::Halving, &SAX)
+    }
+
+    fn exec_usax(&mut self, params: &Reg3NoSetFlagsParams) -> ExecuteResult {
+        self.parallel_add_sub(params, Mode::Unsigned, &SAX)
+    }
+
+    fn exec_uqsax(&mut self, params: &Reg3NoSetFlagsParams) -> ExecuteResult {
+        self.parallel_add_sub(params, Mode::UnsignedSaturating, &SAX)
+    }
+
+    fn exec_uhsax(&mut self, params: &Reg3NoSetFlagsParams) -> ExecuteResult {
+        self.parallel_add_sub(params, Mode::UnsignedHalving, &SAX)
+    }
+
+    fn exec_ssub16(&mut self, params: &Reg3NoSetFlagsParams) -> ExecuteResult {
+        self.parallel_add_sub(params, Mode::Signed, &SUB16)
+    }
+
+    fn exec_qsub16(&mut self, params: &Reg3NoSetFlagsParams) -> ExecuteResult {
+        self.parallel_add_sub(params, Mode::Saturating, &SUB16)
+    }
+
+    fn exec_shsub16(&mut self, params: &Reg3NoSetFlagsParams) -> ExecuteResult {
+        self.parallel_add_sub(params, Mode::Halving, &SUB16)
+    }
+
+    fn exec_usub16(&mut self, params: &Reg3NoSetFlagsParams) -> ExecuteResult {
+        self.parallel_add_sub(params, Mode::Unsigned, &SUB16)
+    }
+
+    fn exec_uqsub16(&mut self, params: &Reg3NoSetFlagsParams) -> ExecuteResult {
+        self.parallel_add_sub(params, Mode::UnsignedSaturating, &SUB16)
+    }
+
+    fn exec_uhsub16(&mut self, params: &Reg3NoSetFlagsParams) -> ExecuteResult {
+        self.parallel_add_sub(params, Mode::UnsignedHalving, &SUB16)
+    }
+
+    fn exec_sadd8(&mut self, params: &Reg3NoSetFlagsParams) -> ExecuteResult {
+        self.parallel_add_sub(params, Mode::Signed, &ADD8)
+    }
+
+    fn exec_qadd8(&mut self, params: &Reg3NoSetFlagsParams) -> ExecuteResult {
+        self.parallel_add_sub(params, Mode::Saturating, &ADD8)
+    }
+
+    fn exec_shadd8(&mut self, params: &Reg3NoSetFlagsParams) -> ExecuteResult {
+        self.parallel_add_sub(params, Mode::Halving, &ADD8)
+    }
+
+    fn exec_uadd8(&mut self, params: &Reg3NoSetFlagsParams) -> ExecuteResult {
+        self.parallel_add_sub(params, Mode::Unsigned, &ADD8)
+    }
+
+    fn exec_uqadd8(&mut self, params: &Reg3NoSetFlagsParams) -> ExecuteResult {
+        self.parallel_add_sub(params, Mode::UnsignedSaturating, &ADD8)
+    }
+
+    fn exec_uhadd8(&mut self, params: &Reg3NoSetFlagsParams) -> ExecuteResult {
+        self.parallel_add_sub(params, Mode::UnsignedHalving, &ADD8)
+    }
+
+    fn exec_ssub8(&mut self, params: &Reg3NoSetFlagsParams) -> ExecuteResult {
+        self.parallel_add_sub(params, Mode::Signed, &SUB8)
+    }
+
+    fn exec_qsub8(&mut self, params: &Reg3NoSetFlagsParams) -> ExecuteResult {
+        self.parallel_add_sub(params, Mode::Saturating, &SUB8)
+    }
+
+    fn exec_shsub8(&mut self, params: &Reg3NoSetFlagsParams) -> ExecuteResult {
+        self.parallel_add_sub(params, Mode::Halving, &SUB8)
+    }
+
+    fn exec_usub8(&mut self, params: &Reg3NoSetFlagsParams) -> ExecuteResult {
+        self.parallel_add_sub(params, Mode::Unsigned, &SUB8)
+    }
+
+    fn exec_uqsub8(&mut self, params: &Reg3NoSetFlagsParams) -> ExecuteResult {
+        self.parallel_add_sub(params, Mode::UnsignedSaturating, &SUB8)
+    }
+
+    fn exec_uhsub8(&mut self, params: &Reg3NoSetFlagsParams) -> ExecuteResult {
+        self.parallel_add_sub(params, Mode::UnsignedHalving, &SUB8)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{instruction::Instruction, register::Reg};
+
+    fn run(core: &mut Processor, instruction: Instruction, rn: u32, rm: u32) -> u32 {
+        core.psr.value = 0;
+        core.set_r(Reg::R2, rn);
+        core.set_r(Reg::R3, rm);
+        core.execute_internal(&instruction).unwrap();
+        core.get_r(Reg::R1)
+    }
+
+    fn params() -> Reg3NoSetFlagsParams {
+        Reg3NoSetFlagsParams {
+            rd: Reg::R1,
+            rn: Reg::R2,
+            rm: Reg::R3,
+        }
+    }
+
+    #[test]
+    fn test_sadd16() {
+        // arrange
+        let mut core = Processor::new();
+
+        // act
+        let result = run(
+            &mut core,
+            Instruction::SADD16 { params: params() },
+            0x0002_0001,
+            0xfffd_0003,
+        );
+
+        // assert
+        assert_eq!(result, 0xffff_0004);
+        assert_eq!(core.psr.value.get_bits(16..20), 0b0011);
+    }
+
+    #[test]
+    fn test_uadd8() {
+        // arrange
+        let mut core = Processor::new();
+
+        // act
+        let result = run(
+            &mut core,
+            Instruction::UADD8 { params: params() },
+            0xff01_8000,
+            0x0101_8000,
+        );
+
+        // assert
+        assert_eq!(result, 0x0002_0000);
+        assert_eq!(core.psr.value.get_bits(16..20), 0b1010);
+    }
+
+    #[test]
+    fn test_uadd16() {
+        // arrange
+        let mut core = Processor::new();
+
+        // act
+        let result = run(
+            &mut core,
+            Instruction::UADD16 { params: params() },
+            0xffff_0001,
+            0x0002_0001,
+        );
+
+        // assert
+        assert_eq!(result, 0x0001_0002);
+        assert_eq!(core.psr.value.get_bits(16..20), 0b1100);
+    }
+
+    #[test]
+    fn test_usub8() {
+        // arrange
+        let mut core = Processor::new();
+
+        // act
+        let result = run(
+            &mut core,
+            Instruction::USUB8 { params: params() },
+            0x0102_0304,
+            0x0202_0202,
+        );
+
+        // assert
+        assert_eq!(result, 0xff00_0102);
+        assert_eq!(core.psr.value.get_bits(16..20), 0b0111);
+    }
+
+    #[test]
+    fn test_qsub8() {
+        // arrange
+        let mut core = Processor::new();
+
+        // act
+        let result = run(
+            &mut core,
+            Instruction::QSUB8 { params: params() },
+            0x807f_1005,
+            0x01ff_2003,
+        );
+
+        // assert
+        assert_eq!(result, 0x807f_f002);
+        assert_eq!(core.psr.value.get_bits(16..20), 0);
+    }
+
+    #[test]
+    fn test_uqadd16() {
+        // arrange
+        let mut core = Processor::new();
+
+        // act
+        let result = run(
+            &mut core,
+            Instruction::UQADD16 { params: params() },
+            0xfff0_0010,
+            0x0020_0010,
+        );
+
+        // assert
+        assert_eq!(result, 0xffff_0020);
+    }
+
+    #[test]
+    fn test_uhasx() {
+        // arrange
+        let mut core = Processor::new();
+
+        // act
+        let result = run(
+            &mut core,
+            Instruction::UHASX { params: params() },
+            0x0001_0002,
+            0x0004_fffe,
+        );
+
+        // assert
+        assert_eq!(result, 0x7fff_ffff);
+    }
+
+    #[test]
+    fn test_shsax() {
+        // arrange
+        let mut core = Processor::new();
+
+        // act
+        let result = run(
+            &mut core,
+            Instruction::SHSAX { params: params() },
+            0x8000_7fff,
+            0x0001_7fff,
+        );
+
+        // assert
+        // lane 0: (0x7fff + 0x0001) / 2, lane 1: (-0x8000 - 0x7fff) / 2
+        assert_eq!(result, 0x8000_4000);
+    }
 }
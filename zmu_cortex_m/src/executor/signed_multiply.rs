@@ -3,17 +3,72 @@ use crate::Processor;
 use crate::executor::{ExecuteSuccess, ExecutorHelper};
 
 use super::ExecuteResult;
-use crate::core::instruction::{Reg3HighParams, Reg4HighParams, Reg643232Params};
+use crate::core::instruction::{
+    Reg3HighParams, Reg3RoundParams, Reg3SwapParams, Reg3WordHighParams, Reg4HighParams,
+    Reg4RoundParams, Reg4SwapParams, Reg4WordHighParams, Reg643232HighParams, Reg643232Params,
+    Reg643232SwapParams,
+};
 use crate::core::{
     bits::Bits,
-    register::{Apsr, BaseReg},
+    operation::ror,
+    register::{Apsr, BaseReg, Reg},
 };
 
+fn halfword(value: u32, high: bool) -> i64 {
+    if high {
+        i64::from(value.get_bits(16..32) as i16)
+    } else {
+        i64::from(value.get_bits(0..16) as i16)
+    }
+}
+
+/// Products of the bottom and the top halfwords, Rm halfwords optionally swapped
+fn dual_products(rn: u32, rm: u32, m_swap: bool) -> (i64, i64) {
+    let operand2 = if m_swap { ror(rm, 16) } else { rm };
+    (
+        halfword(rn, false) * halfword(operand2, false),
+        halfword(rn, true) * halfword(operand2, true),
+    )
+}
+
+fn overflows_i32(value: i64) -> bool {
+    value != i64::from(value as i32)
+}
+
 /// Multiply operations
 pub trait IsaSignedMultiply {
     fn exec_smull(&mut self, params: &Reg643232Params) -> ExecuteResult;
     fn exec_smul(&mut self, params: &Reg3HighParams) -> ExecuteResult;
     fn exec_smla(&mut self, params: &Reg4HighParams) -> ExecuteResult;
+    fn exec_smlal_hw(&mut self, params: &Reg643232HighParams) -> ExecuteResult;
+    fn exec_smlad(&mut self, params: &Reg4SwapParams) -> ExecuteResult;
+    fn exec_smlsd(&mut self, params: &Reg4SwapParams) -> ExecuteResult;
+    fn exec_smuad(&mut self, params: &Reg3SwapParams) -> ExecuteResult;
+    fn exec_smusd(&mut self, params: &Reg3SwapParams) -> ExecuteResult;
+    fn exec_smlald(&mut self, params: &Reg643232SwapParams) -> ExecuteResult;
+    fn exec_smlsld(&mut self, params: &Reg643232SwapParams) -> ExecuteResult;
+    fn exec_smmla(&mut self, params: &Reg4RoundParams) -> ExecuteResult;
+    fn exec_smmls(&mut self, params: &Reg4RoundParams) -> ExecuteResult;
+    fn exec_smmul(&mut self, params: &Reg3RoundParams) -> ExecuteResult;
+    fn exec_smlaw(&mut self, params: &Reg4WordHighParams) -> ExecuteResult;
+    fn exec_smulw(&mut self, params: &Reg3WordHighParams) -> ExecuteResult;
+}
+
+trait SignedMultiplyHelper {
+    fn get_r64(&self, rdlo: Reg, rdhi: Reg) -> i64;
+    fn set_r64(&mut self, rdlo: Reg, rdhi: Reg, value: i64);
+}
+
+impl SignedMultiplyHelper for Processor {
+    fn get_r64(&self, rdlo: Reg, rdhi: Reg) -> i64 {
+        ((u64::from(self.get_r(rdhi)) << 32) | u64::from(self.get_r(rdlo))) as i64
+    }
+
+    fn set_r64(&mut self, rdlo: Reg, rdhi: Reg, value: i64) {
+        let value = value as u64;
+        self.set_r(rdlo, value.get_bits(0..32) as u32);
+        self.set_r(rdhi, value.get_bits(32..64) as u32);
+    }
 }
 
 impl IsaSignedMultiply for Processor {
@@ -73,15 +128,182 @@ impl IsaSignedMultiply for Processor {
                 op as i16
             });
 
-            let result = operand1
-                .wrapping_mul(operand2)
-                .wrapping_add(self.get_r(params.ra) as i32);
+            let result =
+                i64::from(operand1 * operand2) + i64::from(self.get_r(params.ra) as i32);
+
+            self.set_r(params.rd, result as u32);
+            if overflows_i32(result) {
+                self.psr.set_q(true);
+            }
+
+            return Ok(ExecuteSuccess::Taken { cycles: 1 });
+        }
+        Ok(ExecuteSuccess::NotTaken)
+    }
+
+    fn exec_smlal_hw(&mut self, params: &Reg643232HighParams) -> ExecuteResult {
+        if self.condition_passed() {
+            let product = halfword(self.get_r(params.rn), params.n_high)
+                * halfword(self.get_r(params.rm), params.m_high);
+            let result = product.wrapping_add(self.get_r64(params.rdlo, params.rdhi));
+
+            self.set_r64(params.rdlo, params.rdhi, result);
+            return Ok(ExecuteSuccess::Taken { cycles: 1 });
+        }
+        Ok(ExecuteSuccess::NotTaken)
+    }
+
+    fn exec_smlad(&mut self, params: &Reg4SwapParams) -> ExecuteResult {
+        if self.condition_passed() {
+            let (product1, product2) =
+                dual_products(self.get_r(params.rn), self.get_r(params.rm), params.m_swap);
+            let result = product1 + product2 + i64::from(self.get_r(params.ra) as i32);
+
+            self.set_r(params.rd, result as u32);
+            if overflows_i32(result) {
+                self.psr.set_q(true);
+            }
+            return Ok(ExecuteSuccess::Taken { cycles: 1 });
+        }
+        Ok(ExecuteSuccess::NotTaken)
+    }
+
+    fn exec_smlsd(&mut self, params: &Reg4SwapParams) -> ExecuteResult {
+        if self.condition_passed() {
+            let (product1, product2) =
+                dual_products(self.get_r(params.rn), self.get_r(params.rm), params.m_swap);
+            let result = product1 - product2 + i64::from(self.get_r(params.ra) as i32);
 
             self.set_r(params.rd, result as u32);
-            if result != result as i32 {
+            if overflows_i32(result) {
                 self.psr.set_q(true);
             }
+            return Ok(ExecuteSuccess::Taken { cycles: 1 });
+        }
+        Ok(ExecuteSuccess::NotTaken)
+    }
+
+    fn exec_smuad(&mut self, params: &Reg3SwapParams) -> ExecuteResult {
+        if self.condition_passed() {
+            let (product1, product2) =
+                dual_products(self.get_r(params.rn), self.get_r(params.rm), params.m_swap);
+            let result = product1 + product2;
 
+            self.set_r(params.rd, result as u32);
+            if overflows_i32(result) {
+                self.psr.set_q(true);
+            }
+            return Ok(ExecuteSuccess::Taken { cycles: 1 });
+        }
+        Ok(ExecuteSuccess::NotTaken)
+    }
+
+    fn exec_smusd(&mut self, params: &Reg3SwapParams) -> ExecuteResult {
+        if self.condition_passed() {
+            let (product1, product2) =
+                dual_products(self.get_r(params.rn), self.get_r(params.rm), params.m_swap);
+            let result = product1 - product2;
+
+            self.set_r(params.rd, result as u32);
+            return Ok(ExecuteSuccess::Taken { cycles: 1 });
+        }
+        Ok(ExecuteSuccess::NotTaken)
+    }
+
+    fn exec_smlald(&mut self, params: &Reg643232SwapParams) -> ExecuteResult {
+        if self.condition_passed() {
+            let (product1, product2) =
+                dual_products(self.get_r(params.rn), self.get_r(params.rm), params.m_swap);
+            let result = (product1 + product2).wrapping_add(self.get_r64(params.rdlo, params.rdhi));
+
+            self.set_r64(params.rdlo, params.rdhi, result);
+            return Ok(ExecuteSuccess::Taken { cycles: 1 });
+        }
+        Ok(ExecuteSuccess::NotTaken)
+    }
+
+    fn exec_smlsld(&mut self, params: &Reg643232SwapParams) -> ExecuteResult {
+        if self.condition_passed() {
+            let (product1, product2) =
+                dual_products(self.get_r(params.rn), self.get_r(params.rm), params.m_swap);
+            let result = (product1 - product2).wrapping_add(self.get_r64(params.rdlo, params.rdhi));
+
+            self.set_r64(params.rdlo, params.rdhi, result);
+            return Ok(ExecuteSuccess::Taken { cycles: 1 });
+        }
+        Ok(ExecuteSuccess::NotTaken)
+    }
+
+    fn exec_smmla(&mut self, params: &Reg4RoundParams) -> ExecuteResult {
+        if self.condition_passed() {
+            let rn = i64::from(self.get_r(params.rn) as i32);
+            let rm = i64::from(self.get_r(params.rm) as i32);
+            let ra = i64::from(self.get_r(params.ra) as i32);
+
+            let mut result = (ra << 32).wrapping_add(rn * rm);
+            if params.round {
+                result = result.wrapping_add(0x8000_0000);
+            }
+            self.set_r(params.rd, (result >> 32) as u32);
+            return Ok(ExecuteSuccess::Taken { cycles: 1 });
+        }
+        Ok(ExecuteSuccess::NotTaken)
+    }
+
+    fn exec_smmls(&mut self, params: &Reg4RoundParams) -> ExecuteResult {
+        if self.condition_passed() {
+            let rn = i64::from(self.get_r(params.rn) as i32);
+            let rm = i64::from(self.get_r(params.rm) as i32);
+            let ra = i64::from(self.get_r(params.ra) as i32);
+
+            let mut result = (ra << 32).wrapping_sub(rn * rm);
+            if params.round {
+                result = result.wrapping_add(0x8000_0000);
+            }
+            self.set_r(params.rd, (result >> 32) as u32);
+            return Ok(ExecuteSuccess::Taken { cycles: 1 });
+        }
+        Ok(ExecuteSuccess::NotTaken)
+    }
+
+    fn exec_smmul(&mut self, params: &Reg3RoundParams) -> ExecuteResult {
+        if self.condition_passed() {
+            let rn = i64::from(self.get_r(params.rn) as i32);
+            let rm = i64::from(self.get_r(params.rm) as i32);
+
+            let mut result = rn * rm;
+            if params.round {
+                result += 0x8000_0000;
+            }
+            self.set_r(params.rd, (result >> 32) as u32);
+            return Ok(ExecuteSuccess::Taken { cycles: 1 });
+        }
+        Ok(ExecuteSuccess::NotTaken)
+    }
+
+    fn exec_smlaw(&mut self, params: &Reg4WordHighParams) -> ExecuteResult {
+        if self.condition_passed() {
+            let rn = i64::from(self.get_r(params.rn) as i32);
+            let operand2 = halfword(self.get_r(params.rm), params.m_high);
+            let ra = i64::from(self.get_r(params.ra) as i32);
+
+            let result = (rn * operand2 + (ra << 16)) >> 16;
+            self.set_r(params.rd, result as u32);
+            if overflows_i32(result) {
+                self.psr.set_q(true);
+            }
+            return Ok(ExecuteSuccess::Taken { cycles: 1 });
+        }
+        Ok(ExecuteSuccess::NotTaken)
+    }
+
+    fn exec_smulw(&mut self, params: &Reg3WordHighParams) -> ExecuteResult {
+        if self.condition_passed() {
+            let rn = i64::from(self.get_r(params.rn) as i32);
+            let operand2 = halfword(self.get_r(params.rm), params.m_high);
+
+            let result = (rn * operand2) >> 16;
+            self.set_r(params.rd, result as u32);
             return Ok(ExecuteSuccess::Taken { cycles: 1 });
         }
         Ok(ExecuteSuccess::NotTaken)
@@ -91,7 +313,7 @@ impl IsaSignedMultiply for Processor {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::core::{instruction::Instruction, register::Reg};
+    use crate::core::instruction::Instruction;
 
     #[test]
     fn test_smlabb() {
@@ -121,4 +343,218 @@ mod tests {
 
         assert_eq!(core.get_r(Reg::R12), 0xFFD4F24B);
     }
+
+    #[test]
+    fn test_smlabb_overflow_sets_q() {
+        // arrange
+        let mut core = Processor::new();
+        core.psr.value = 0;
+        core.set_r(Reg::R1, 0x7fff);
+        core.set_r(Reg::R2, 0x7fff);
+        core.set_r(Reg::R3, 0x7fff_ffff);
+
+        let instruction = Instruction::SMLA {
+            params: Reg4HighParams {
+                rd: Reg::R0,
+                rn: Reg::R1,
+                rm: Reg::R2,
+                ra: Reg::R3,
+                n_high: false,
+                m_high: false,
+            },
+        };
+
+        // act
+        core.execute_internal(&instruction).unwrap();
+
+        // assert
+        assert_eq!(core.get_r(Reg::R0), 0xbfff_0000);
+        assert!(core.psr.get_q());
+    }
+
+    #[test]
+    fn test_smlad() {
+        // arrange
+        let mut core = Processor::new();
+        core.psr.value = 0;
+        core.set_r(Reg::R1, 0x0002_0003);
+        core.set_r(Reg::R2, 0x0004_0005);
+        core.set_r(Reg::R3, 10);
+
+        let mut params = Reg4SwapParams {
+            rd: Reg::R0,
+            rn: Reg::R1,
+            rm: Reg::R2,
+            ra: Reg::R3,
+            m_swap: false,
+        };
+
+        // act
+        core.execute_internal(&Instruction::SMLAD { params }).unwrap();
+        let result = core.get_r(Reg::R0);
+        params.m_swap = true;
+        core.execute_internal(&Instruction::SMLAD { params }).unwrap();
+        let result_x = core.get_r(Reg::R0);
+
+        // assert
+        assert_eq!(result, 3 * 5 + 2 * 4 + 10);
+        assert_eq!(result_x, 3 * 4 + 2 * 5 + 10);
+        assert!(!core.psr.get_q());
+    }
+
+    #[test]
+    fn test_smuad_overflow_sets_q() {
+        // arrange
+        let mut core = Processor::new();
+        core.psr.value = 0;
+        core.set_r(Reg::R1, 0x8000_8000);
+        core.set_r(Reg::R2, 0x8000_8000);
+
+        let instruction = Instruction::SMUAD {
+            params: Reg3SwapParams {
+                rd: Reg::R0,
+                rn: Reg::R1,
+                rm: Reg::R2,
+                m_swap: false,
+            },
+        };
+
+        // act
+        core.execute_internal(&instruction).unwrap();
+
+        // assert
+        assert_eq!(core.get_r(Reg::R0), 0x8000_0000);
+        assert!(core.psr.get_q());
+    }
+
+    #[test]
+    fn test_smusd() {
+        // arrange
+        let mut core = Processor::new();
+        core.psr.value = 0;
+        core.set_r(Reg::R1, 0x0002_0003);
+        core.set_r(Reg::R2, 0x0004_0005);
+
+        let instruction = Instruction::SMUSD {
+            params: Reg3SwapParams {
+                rd: Reg::R0,
+                rn: Reg::R1,
+                rm: Reg::R2,
+                m_swap: false,
+            },
+        };
+
+        // act
+        core.execute_internal(&instruction).unwrap();
+
+        // assert
+        assert_eq!(core.get_r(Reg::R0), 7);
+    }
+
+    #[test]
+    fn test_smlald() {
+        // arrange
+        let mut core = Processor::new();
+        core.psr.value = 0;
+        core.set_r(Reg::R1, 0x0002_0003);
+        core.set_r(Reg::R2, 0x0004_0005);
+        core.set_r(Reg::R3, 0xffff_fff0);
+        core.set_r(Reg::R4, 0x0000_0001);
+
+        let instruction = Instruction::SMLALD {
+            params: Reg643232SwapParams {
+                rdlo: Reg::R3,
+                rdhi: Reg::R4,
+                rn: Reg::R1,
+                rm: Reg::R2,
+                m_swap: false,
+            },
+        };
+
+        // act
+        core.execute_internal(&instruction).unwrap();
+
+        // assert
+        assert_eq!(core.get_r(Reg::R3), 0x0000_0007);
+        assert_eq!(core.get_r(Reg::R4), 0x0000_0002);
+    }
+
+    #[test]
+    fn test_smmul_round() {
+        // arrange
+        let mut core = Processor::new();
+        core.psr.value = 0;
+        core.set_r(Reg::R1, 0x4000_0000);
+        core.set_r(Reg::R2, 3);
+
+        let mut params = Reg3RoundParams {
+            rd: Reg::R0,
+            rn: Reg::R1,
+            rm: Reg::R2,
+            round: false,
+        };
+
+        // act
+        core.execute_internal(&Instruction::SMMUL { params }).unwrap();
+        let truncated = core.get_r(Reg::R0);
+        params.round = true;
+        core.execute_internal(&Instruction::SMMUL { params }).unwrap();
+        let rounded = core.get_r(Reg::R0);
+
+        // assert
+        assert_eq!(truncated, 0);
+        assert_eq!(rounded, 1);
+    }
+
+    #[test]
+    fn test_smmls() {
+        // arrange
+        let mut core = Processor::new();
+        core.psr.value = 0;
+        core.set_r(Reg::R1, 0x0001_0000);
+        core.set_r(Reg::R2, 0x0001_0000);
+        core.set_r(Reg::R3, 5);
+
+        let instruction = Instruction::SMMLS {
+            params: Reg4RoundParams {
+                rd: Reg::R0,
+                rn: Reg::R1,
+                rm: Reg::R2,
+                ra: Reg::R3,
+                round: false,
+            },
+        };
+
+        // act
+        core.execute_internal(&instruction).unwrap();
+
+        // assert
+        assert_eq!(core.get_r(Reg::R0), 4);
+    }
+
+    #[test]
+    fn test_smlawb() {
+        // arrange
+        let mut core = Processor::new();
+        core.psr.value = 0;
+        core.set_r(Reg::R1, 0x0001_0000);
+        core.set_r(Reg::R2, 0x7777_0003);
+        core.set_r(Reg::R3, 2);
+
+        let instruction = Instruction::SMLAW {
+            params: Reg4WordHighParams {
+                rd: Reg::R0,
+                rn: Reg::R1,
+                rm: Reg::R2,
+                ra: Reg::R3,
+                m_high: false,
+            },
+        };
+
+        // act
+        core.execute_internal(&instruction).unwrap();
+
+        // assert
+        assert_eq!(core.get_r(Reg::R0), 5);
+    }
 }
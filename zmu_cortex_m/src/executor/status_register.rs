@@ -11,6 +11,7 @@ use crate::{
         instruction::{MrsParams, MsrParams},
         register::BaseReg,
     },
+    peripheral::nvic::NVIC,
     ProcessorMode,
 };
 
@@ -19,10 +20,6 @@ pub trait IsaStatusRegister {
     fn exec_mrs(&mut self, params: MrsParams) -> ExecuteResult;
     fn exec_msr(&mut self, params: MsrParams) -> ExecuteResult;
 
-    #[cfg(armv6m)]
-    fn exec_cps(&mut self, im: bool) -> ExecuteResult;
-
-    #[cfg(any(armv7m, armv7em))]
     fn exec_cps(&mut self, im: bool, affect_pri: bool, affect_fault: bool) -> ExecuteResult;
 }
 
@@ -62,14 +59,11 @@ impl IsaStatusRegister for Processor {
                     0b010 => {
                         value.set_bits(0..8, u32::from(self.basepri));
                     }
-                    #[cfg(any(armv7m, armv7em))]
-                    0b011 => {
+                    0b011 if self.cpu.armv7m() => {
                         value.set_bit(0, self.faultmask);
                     }
                     0b100 => {
-                        //let ctrl = u8::from(self.control) as u32;
-                        //value.set_bits(0..2, ctrl);
-                        todo!("unimplemented CONTROL");
+                        value.set_bits(0..3, u32::from(u8::from(self.control)));
                     }
                     _ => (),
                 },
@@ -109,18 +103,17 @@ impl IsaStatusRegister for Processor {
                         self.execution_priority = self.get_execution_priority();
                     }
                     0b001 => {
-                        self.basepri = r_n.get_bits(0..8) as u8;
+                        self.basepri = r_n.get_bits(0..8) as u8 & self.nvic_priority_mask();
                         self.execution_priority = self.get_execution_priority();
                     }
                     0b010 => {
-                        let low_rn = r_n.get_bits(0..8) as u8;
+                        let low_rn = r_n.get_bits(0..8) as u8 & self.nvic_priority_mask();
                         if low_rn != 0 && low_rn < self.basepri || self.basepri == 0 {
                             self.basepri = low_rn;
                             self.execution_priority = self.get_execution_priority();
                         }
                     }
-                    #[cfg(any(armv7m, armv7em))]
-                    0b011 => {
+                    0b011 if self.cpu.armv7m() => {
                         if self.execution_priority > -1 {
                             self.faultmask = r_n.get_bit(0);
                             self.execution_priority = self.get_execution_priority();
@@ -131,6 +124,9 @@ impl IsaStatusRegister for Processor {
                         if self.mode == ProcessorMode::ThreadMode {
                             self.control.sp_sel = r_n.get_bit(1);
                         }
+                        if self.cpu.has_fpu() {
+                            self.control.fpca = r_n.get_bit(2);
+                        }
                     }
                     _ => (),
                 },
@@ -142,19 +138,10 @@ impl IsaStatusRegister for Processor {
         Ok(ExecuteSuccess::NotTaken)
     }
 
-    #[cfg(armv6m)]
-    fn exec_cps(&mut self, im: bool) -> ExecuteResult {
-        if im {
-            self.primask = true;
-        } else {
-            self.primask = false;
-        }
-        self.execution_priority = self.get_execution_priority();
-        Ok(ExecuteSuccess::Taken { cycles: 1 })
-    }
-
-    #[cfg(any(armv7m, armv7em))]
     fn exec_cps(&mut self, im: bool, affect_pri: bool, affect_fault: bool) -> ExecuteResult {
+        // armv6m only has PRIMASK, which CPS always changes
+        let affect_pri = affect_pri || self.cpu.armv6m();
+        let affect_fault = affect_fault && self.cpu.armv7m();
         if im {
             if affect_pri {
                 self.primask = true;
@@ -32,32 +32,46 @@ pub mod device;
 pub mod executor;
 pub mod memory;
 pub mod peripheral;
+pub mod rtt;
 pub mod semihosting;
 pub mod system;
 
 use crate::core::instruction::instruction_size;
 
+use crate::core::cpu::Cpu;
 use crate::core::exception::Exception;
 use crate::core::fetch::Fetch;
+use crate::core::fpu::{FPCCR_ASPEN, FPCCR_LSPEN};
 use crate::core::instruction::Instruction;
+use crate::core::lockup::{LockupPolicy, LockupSnapshot};
 use crate::core::register::{Apsr, BaseReg, Control, Reg, PSR};
+use crate::core::thumb::ThumbCode;
 
 use crate::memory::flash::FlashMemory;
+use crate::peripheral::dwt::{dwt_ctrl_reset, DwtDataMatch, DWT_NUM_COMPARATORS};
+use crate::peripheral::fpb::FPB_NUM_COMPARATORS;
+use crate::peripheral::itm::InstrumentationTraceMacrocell;
+use crate::peripheral::nvic::{NVIC_MAX_INTERRUPTS, NVIC_MAX_INTERRUPTS_ARMV6M};
+use crate::peripheral::scb::ccr_reset;
+use crate::peripheral::tpiu::{TPIU_FFCR_RESET, TPIU_SPPR_SWO_MANCHESTER};
 use crate::memory::map::MemoryMapConfig;
 use crate::memory::ram::RAM;
+use crate::rtt::Rtt;
+use crate::semihosting::ExitStatus;
 use crate::semihosting::SemihostingCommand;
 use crate::semihosting::SemihostingResponse;
 
 use crate::core::exception::ExceptionState;
+use std::cell::{Cell, RefCell};
 use std::collections::HashMap;
 use std::fmt;
 use std::io;
 
 #[cfg(feature = "stm32f103")]
-use crate::device::stm32f1xx::Device;
+use crate::device::stm32f1xx::{Device, NVIC_NUM_INTERRUPTS, NVIC_PRIO_BITS};
 
 #[cfg(feature = "generic-device")]
-use crate::device::generic::Device;
+use crate::device::generic::{Device, NVIC_NUM_INTERRUPTS, NVIC_PRIO_BITS};
 use decoder::Decoder;
 
 #[derive(PartialEq, Debug, Copy, Clone)]
@@ -80,6 +94,9 @@ pub struct Processor {
     /// 32 of 32-bit floating pointer registers
     pub fp_regs: [u32; 32],
 
+    /// Floating point status and control register
+    pub fpscr: u32,
+
     /// MSP, virtual reg r[13]
     pub msp: u32,
     /// PSP, virtual reg r[13]
@@ -90,6 +107,10 @@ pub struct Processor {
     /// Total number of processor clock cycles run
     pub cycle_count: u64,
     pub instruction_count: u64,
+    /// Number of clock cycles the processor has been sleeping
+    pub sleep_cycle_count: u64,
+    /// Number of the sleeping clock cycles spent in deep sleep
+    pub deep_sleep_cycle_count: u64,
 
     /// Processor state register, status flags.
     pub psr: PSR,
@@ -103,7 +124,6 @@ pub struct Processor {
     /// interrupt fault mask, a 1 bit mask register for
     /// global interrupt masking
     ///
-    #[cfg(any(armv7m, armv7em))]
     faultmask: bool,
     ///
     /// basepri for selection of executed interrupt priorities
@@ -125,8 +145,45 @@ pub struct Processor {
     ///
     /// bit 0 : 1= simulation running, 0 : simulation terminating
     /// bit 1 : 1= processor sleeping, 0 : processor awake
+    /// bit 2 : 1= processor halted in debug state, 0 : processor not halted
+    /// bit 3 : 1= processor in lockup state, 0 : processor not locked up
     pub state: u32,
 
+    ///
+    /// simulated core type
+    ///
+    cpu: Cpu,
+
+    ///
+    /// exit status given by the program when it terminated the simulation
+    ///
+    pub exit_status: Option<ExitStatus>,
+
+    ///
+    /// stop the simulation on undefined instructions instead of taking a fault
+    ///
+    pub strict_decode: bool,
+
+    ///
+    /// address and opcode of the undefined instruction that stopped the simulation
+    ///
+    pub undefined_instruction: Option<(u32, ThumbCode)>,
+
+    ///
+    /// action taken when the processor enters lockup state
+    ///
+    pub lockup_policy: LockupPolicy,
+
+    ///
+    /// number of times the processor has entered lockup state
+    ///
+    pub lockup_count: u64,
+
+    ///
+    /// registers of the processor when it last entered lockup state
+    ///
+    pub lockup_snapshot: Option<LockupSnapshot>,
+
     ///
     /// lookup table for exceptions and their states
     ///
@@ -137,6 +194,13 @@ pub struct Processor {
     ///
     pub pending_exception_count: u32,
 
+    ///
+    /// bitmaps of the pending and active exceptions indexed by exception
+    /// number, mirror the states in `exceptions` for fast lookup
+    ///
+    exceptions_pending: [u32; 16],
+    exceptions_active: [u32; 16],
+
     ///
     /// cached current execution priority, used for optimization
     /// of exception activation rule resolving
@@ -145,6 +209,22 @@ pub struct Processor {
 
     itstate: u8,
 
+    ///
+    /// address and byte size tagged by the local exclusive monitor,
+    /// None when the monitor is in open access state
+    ///
+    exclusive_monitor: Option<(u32, usize)>,
+
+    ///
+    /// event register for WFE
+    ///
+    event_register: bool,
+
+    ///
+    /// processor is sleeping in WFE until an event
+    ///
+    waiting_for_event: bool,
+
     ///
     /// flash memory data
     ///
@@ -168,6 +248,8 @@ pub struct Processor {
     pub bfar: u32,
     pub afsr: u32,
     pub cpacr: u32,
+    /// address of the last failed bus access, for MMFAR and BFAR
+    fault_address: Cell<u32>,
 
     pub fpccr: u32,
     pub fpcar: u32,
@@ -183,8 +265,63 @@ pub struct Processor {
     pub nvic_interrupt_enabled: [u32; 16],
     pub nvic_interrupt_pending: [u32; 16],
 
+    ///
+    /// number of external interrupts implemented by the NVIC
+    ///
+    nvic_num_interrupts: usize,
+
+    ///
+    /// number of implemented priority bits
+    ///
+    nvic_priority_bits: u8,
+
     pub dwt_ctrl: u32,
     pub dwt_cyccnt: u32,
+    pub dwt_cpicnt: u32,
+    pub dwt_exccnt: u32,
+    pub dwt_sleepcnt: u32,
+    pub dwt_lsucnt: u32,
+    pub dwt_foldcnt: u32,
+    /// down counter for periodic PC sample and cycle count event packets
+    pub dwt_postcnt: u32,
+    pub dwt_comp: [u32; DWT_NUM_COMPARATORS],
+    pub dwt_mask: [u32; DWT_NUM_COMPARATORS],
+    pub dwt_function: [u32; DWT_NUM_COMPARATORS],
+    /// true if any of the DWT comparators can match
+    pub dwt_watch_enabled: bool,
+    /// data accesses matched by DWT comparators during current instruction
+    dwt_data_matches: RefCell<Vec<DwtDataMatch>>,
+
+    pub dhcsr: u32,
+    pub dcrdr: u32,
+    pub demcr: u32,
+    /// instruction count at the last read of DHCSR, for S_RETIRE_ST
+    pub dhcsr_retire_mark: u64,
+
+    pub fpb_ctrl: u32,
+    pub fpb_remap: u32,
+    pub fpb_comp: [u32; FPB_NUM_COMPARATORS],
+    /// true if any of the flash patch comparators can match
+    pub fpb_enabled: bool,
+
+    pub itm_ter: u32,
+    pub itm_tpr: u32,
+    pub itm_tcr: u32,
+    /// ITM registers locked by the lock access register
+    pub itm_locked: bool,
+    /// number of bytes waiting in the ITM output FIFO
+    pub itm_fifo_level: u32,
+    /// ITM packets were lost since last packet
+    pub itm_overflow: bool,
+    /// local timestamp value at last timestamp packet
+    pub itm_timestamp: u64,
+
+    pub tpiu_cspsr: u32,
+    pub tpiu_acpr: u32,
+    pub tpiu_sppr: u32,
+    pub tpiu_ffcr: u32,
+    /// number of formatter frames written
+    pub tpiu_frame_count: u32,
 
     pub syst_rvr: u32,
     pub syst_cvr: u32,
@@ -195,6 +332,11 @@ pub struct Processor {
     ///
     pub itm_file: Option<Box<dyn io::Write + 'static>>,
 
+    ///
+    /// SEGGER RTT channels, polled during simulation
+    ///
+    rtt: Option<Rtt>,
+
     ///
     /// semihosting plug
     ///
@@ -209,7 +351,7 @@ pub struct Processor {
     pub device: Device,
 }
 
-fn make_default_exception_priorities() -> HashMap<usize, ExceptionState> {
+fn make_default_exception_priorities(num_interrupts: usize) -> HashMap<usize, ExceptionState> {
     let mut priorities = HashMap::new();
 
     priorities.insert(
@@ -260,7 +402,7 @@ fn make_default_exception_priorities() -> HashMap<usize, ExceptionState> {
         ExceptionState::new(Exception::SysTick, 0),
     );
 
-    for irqn in 0..32 {
+    for irqn in 0..num_interrupts {
         let irq = Exception::Interrupt { n: irqn };
         priorities.insert(irq.into(), ExceptionState::new(irq, 0));
     }
@@ -273,20 +415,21 @@ impl Processor {
     /// Create processor with default data
     ///
     pub fn new() -> Self {
-        Self {
+        let mut processor = Self {
             mode: ProcessorMode::ThreadMode,
             vtor: 0,
             psr: PSR { value: 0 },
             primask: false,
-            #[cfg(any(armv7m, armv7em))]
             faultmask: false,
             basepri: 0,
             control: Control {
                 n_priv: false,
                 sp_sel: false,
+                fpca: false,
             },
             r0_12: [0; 13],
             fp_regs: [0; 32],
+            fpscr: 0,
             pc: 0,
             msp: 0,
             psp: 0,
@@ -295,13 +438,28 @@ impl Processor {
             // TODO make RAM size configurable
             sram: RAM::new_with_fill(0x2000_0000, 128 * 1024, 0xcd),
             itm_file: None,
+            rtt: None,
             state: 0,
+            cpu: Cpu::CortexM4F,
+            exit_status: None,
+            strict_decode: false,
+            undefined_instruction: None,
+            lockup_policy: LockupPolicy::Stop,
+            lockup_count: 0,
+            lockup_snapshot: None,
             cycle_count: 0,
             instruction_count: 0,
-            exceptions: make_default_exception_priorities(),
+            sleep_cycle_count: 0,
+            deep_sleep_cycle_count: 0,
+            exceptions: HashMap::new(),
             execution_priority: 0,
             pending_exception_count: 0,
+            exceptions_pending: [0; 16],
+            exceptions_active: [0; 16],
             itstate: 0,
+            exclusive_monitor: None,
+            event_register: false,
+            waiting_for_event: false,
             semihost_func: None,
             cpuid: 0,
             icsr: 0,
@@ -316,6 +474,7 @@ impl Processor {
             bfar: 0,
             afsr: 0,
             cpacr: 0,
+            fault_address: Cell::new(0),
 
             fpccr: 0,
             fpcar: 0,
@@ -327,11 +486,48 @@ impl Processor {
             ictr: 0,
             actlr: 0,
 
-            dwt_ctrl: 0x4000_0000,
+            dwt_ctrl: 0,
             dwt_cyccnt: 0,
+            dwt_cpicnt: 0,
+            dwt_exccnt: 0,
+            dwt_sleepcnt: 0,
+            dwt_lsucnt: 0,
+            dwt_foldcnt: 0,
+            dwt_postcnt: 0,
+            dwt_comp: [0; DWT_NUM_COMPARATORS],
+            dwt_mask: [0; DWT_NUM_COMPARATORS],
+            dwt_function: [0; DWT_NUM_COMPARATORS],
+            dwt_watch_enabled: false,
+            dwt_data_matches: RefCell::new(Vec::new()),
+
+            dhcsr: 0,
+            dcrdr: 0,
+            demcr: 0,
+            dhcsr_retire_mark: 0,
+
+            fpb_ctrl: 0,
+            fpb_remap: 0,
+            fpb_comp: [0; FPB_NUM_COMPARATORS],
+            fpb_enabled: false,
+
+            itm_ter: 0,
+            itm_tpr: 0,
+            itm_tcr: 0,
+            itm_locked: true,
+            itm_fifo_level: 0,
+            itm_overflow: false,
+            itm_timestamp: 0,
+
+            tpiu_cspsr: 1,
+            tpiu_acpr: 0,
+            tpiu_sppr: TPIU_SPPR_SWO_MANCHESTER,
+            tpiu_ffcr: TPIU_FFCR_RESET,
+            tpiu_frame_count: 0,
 
             nvic_interrupt_enabled: [0; 16],
             nvic_interrupt_pending: [0; 16],
+            nvic_num_interrupts: 0,
+            nvic_priority_bits: 0,
             syst_rvr: 0,
             syst_cvr: 0,
             syst_csr: 0,
@@ -339,7 +535,61 @@ impl Processor {
             last_pc: 0,
             mem_map: None,
             device: Device::new(),
+        };
+        processor.cpu(Cpu::CortexM4F);
+        processor
+    }
+
+    /// Configure the simulated core type and the registers whose reset
+    /// values depend on it. The NVIC gets the device configuration limited
+    /// to what the core supports.
+    pub fn cpu(&mut self, cpu: Cpu) -> &mut Self {
+        self.cpu = cpu;
+        self.cpuid = cpu.cpuid();
+        self.ccr = ccr_reset(cpu);
+        self.dwt_ctrl = dwt_ctrl_reset(cpu);
+        self.fpb_remap = if cpu.armv7m() { 1 << 29 } else { 0 };
+        if cpu.has_fpu() {
+            self.fpccr = FPCCR_ASPEN | FPCCR_LSPEN;
+            self.mvfr0 = 0x1011_0021;
+            self.mvfr1 = 0x1100_0011;
+        } else {
+            self.fpccr = 0;
+            self.mvfr0 = 0;
+            self.mvfr1 = 0;
         }
+        self.nvic(NVIC_NUM_INTERRUPTS, NVIC_PRIO_BITS)
+    }
+
+    /// Simulated core type
+    pub fn get_cpu(&self) -> Cpu {
+        self.cpu
+    }
+
+    /// Configure the number of external interrupts and the number of
+    /// implemented priority bits of the NVIC. The values are clamped to the
+    /// range of the core: 1-32 interrupts with 2 priority bits on ARMv6-M,
+    /// 1-496 interrupts with 3-8 priority bits on ARMv7-M. Call after `cpu()`,
+    /// which restores the device defaults.
+    pub fn nvic(&mut self, num_interrupts: usize, priority_bits: u8) -> &mut Self {
+        let (max_interrupts, min_bits, max_bits) = if self.cpu.armv6m() {
+            (NVIC_MAX_INTERRUPTS_ARMV6M, 2, 2)
+        } else {
+            (NVIC_MAX_INTERRUPTS, 3, 8)
+        };
+        let num_interrupts = num_interrupts.max(1).min(max_interrupts);
+        let priority_bits = priority_bits.max(min_bits).min(max_bits);
+        self.nvic_num_interrupts = num_interrupts;
+        self.nvic_priority_bits = priority_bits;
+        // INTLINESNUM: number of implemented 32 interrupt blocks minus one
+        self.ictr = ((num_interrupts + 31) / 32 - 1) as u32;
+        self.exceptions = make_default_exception_priorities(num_interrupts);
+        self.pending_exception_count = 0;
+        self.exceptions_pending = [0; 16];
+        self.exceptions_active = [0; 16];
+        self.nvic_interrupt_enabled = [0; 16];
+        self.nvic_interrupt_pending = [0; 16];
+        self
     }
 
     /// Configure flash memory
@@ -354,12 +604,22 @@ impl Processor {
         self
     }
 
-    /// Configure itm output file
+    /// Configure itm output file. When a file is given, the ITM and all of
+    /// its stimulus ports are enabled as if configured by a trace probe.
     pub fn itm<'a>(&'a mut self, file: Option<Box<dyn io::Write + 'static>>) -> &'a mut Self {
+        if file.is_some() {
+            self.itm_enable_all();
+        }
         self.itm_file = file;
         self
     }
 
+    /// Configure RTT channels
+    pub fn rtt(&mut self, rtt: Option<Rtt>) -> &mut Self {
+        self.rtt = rtt;
+        self
+    }
+
     /// Configure semihosting
     pub fn semihost<'a>(
         &'a mut self,
@@ -50,14 +50,14 @@ impl Bus for FlashMemory {
     }
 
     fn write32(&mut self, _addr: u32, _value: u32) -> Result<(), Fault> {
-        Err(Fault::DAccViol)
+        Err(Fault::Preciserr)
     }
 
     fn write16(&mut self, _addr: u32, _value: u16) -> Result<(), Fault> {
-        Err(Fault::DAccViol)
+        Err(Fault::Preciserr)
     }
     fn write8(&mut self, _addr: u32, _value: u8) -> Result<(), Fault> {
-        Err(Fault::DAccViol)
+        Err(Fault::Preciserr)
     }
 
     fn in_range(&self, addr: u32) -> bool {
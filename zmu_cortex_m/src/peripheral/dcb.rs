@@ -0,0 +1,542 @@
+//!
+//! Cortex Debug Control Block simulation
+//!
+//! Halting debug support: entering and leaving debug state, single stepping,
+//! core register transfers and vector catch.
+//!
+
+use crate::core::bits::Bits;
+use crate::core::exception::{Exception, ExceptionHandling};
+use crate::core::fault::Fault;
+use crate::core::fpu::FPSCR_WRITE_MASK;
+use crate::core::lockup::Lockup;
+use crate::core::register::{BaseReg, Reg};
+use crate::Processor;
+
+/// Key that has to be present in DHCSR\[31:16\] for a write to take effect
+pub const DHCSR_DBGKEY: u32 = 0xA05F_0000;
+/// Halting debug enable
+pub const DHCSR_C_DEBUGEN: u32 = 1;
+/// Request the core to halt
+pub const DHCSR_C_HALT: u32 = 1 << 1;
+/// Single step the core when leaving debug state
+pub const DHCSR_C_STEP: u32 = 1 << 2;
+/// Mask PendSV, SysTick and external interrupts while debugging
+pub const DHCSR_C_MASKINTS: u32 = 1 << 3;
+/// Break a stalled load/store (accepted, has no effect in simulation)
+pub const DHCSR_C_SNAPSTALL: u32 = 1 << 5;
+/// Register transfer via DCRSR has completed
+pub const DHCSR_S_REGRDY: u32 = 1 << 16;
+/// Core is in debug state
+pub const DHCSR_S_HALT: u32 = 1 << 17;
+/// Core is sleeping
+pub const DHCSR_S_SLEEP: u32 = 1 << 18;
+/// Core is locked up after an unrecoverable fault
+pub const DHCSR_S_LOCKUP: u32 = 1 << 19;
+/// An instruction has retired since the last read of DHCSR
+pub const DHCSR_S_RETIRE_ST: u32 = 1 << 24;
+/// Core has been reset since the last read of DHCSR
+pub const DHCSR_S_RESET_ST: u32 = 1 << 25;
+
+const DHCSR_CONTROL_MASK: u32 =
+    DHCSR_C_DEBUGEN | DHCSR_C_HALT | DHCSR_C_STEP | DHCSR_C_MASKINTS | DHCSR_C_SNAPSTALL;
+
+const DCRSR_REGWNR: u32 = 1 << 16;
+
+/// Halt on core reset
+pub const DEMCR_VC_CORERESET: u32 = 1;
+/// Halt on MemManage exception
+pub const DEMCR_VC_MMERR: u32 = 1 << 4;
+/// Halt on UsageFault caused by an access to a disabled coprocessor
+pub const DEMCR_VC_NOCPERR: u32 = 1 << 5;
+/// Halt on UsageFault caused by a checking error (alignment, divide by zero)
+pub const DEMCR_VC_CHKERR: u32 = 1 << 6;
+/// Halt on UsageFault caused by a state information error
+pub const DEMCR_VC_STATERR: u32 = 1 << 7;
+/// Halt on BusFault exception
+pub const DEMCR_VC_BUSERR: u32 = 1 << 8;
+/// Halt on a fault during exception entry or return
+pub const DEMCR_VC_INTERR: u32 = 1 << 9;
+/// Halt on HardFault exception
+pub const DEMCR_VC_HARDERR: u32 = 1 << 10;
+/// DebugMonitor exception enable
+pub const DEMCR_MON_EN: u32 = 1 << 16;
+/// DebugMonitor exception pending
+pub const DEMCR_MON_PEND: u32 = 1 << 17;
+/// DebugMonitor single stepping
+pub const DEMCR_MON_STEP: u32 = 1 << 18;
+/// DebugMonitor semaphore bit
+pub const DEMCR_MON_REQ: u32 = 1 << 19;
+/// Global enable for DWT and ITM
+pub const DEMCR_TRCENA: u32 = 1 << 24;
+
+const DEMCR_WRITE_MASK_V6M: u32 = DEMCR_VC_CORERESET | DEMCR_VC_HARDERR | DEMCR_TRCENA;
+
+const DEMCR_WRITE_MASK: u32 = DEMCR_VC_CORERESET
+    | DEMCR_VC_MMERR
+    | DEMCR_VC_NOCPERR
+    | DEMCR_VC_CHKERR
+    | DEMCR_VC_STATERR
+    | DEMCR_VC_BUSERR
+    | DEMCR_VC_INTERR
+    | DEMCR_VC_HARDERR
+    | DEMCR_MON_EN
+    | DEMCR_MON_PEND
+    | DEMCR_MON_STEP
+    | DEMCR_MON_REQ
+    | DEMCR_TRCENA;
+
+/// Halt requested by C_HALT or C_STEP
+pub const DFSR_HALTED: u32 = 1;
+/// Halt caused by a breakpoint
+pub const DFSR_BKPT: u32 = 1 << 1;
+/// Halt caused by a DWT match
+pub const DFSR_DWTTRAP: u32 = 1 << 2;
+/// Halt caused by a vector catch
+pub const DFSR_VCATCH: u32 = 1 << 3;
+/// Halt requested by an external debug request
+pub const DFSR_EXTERNAL: u32 = 1 << 4;
+
+/// Register API to Debug Control Block
+pub trait DebugControlBlock {
+    ///
+    /// Read Debug Halting Control and Status Register.
+    /// Reading clears the sticky S_RETIRE_ST and S_RESET_ST bits.
+    ///
+    fn read_dhcsr(&mut self) -> u32;
+
+    ///
+    /// Write Debug Halting Control and Status Register from software
+    /// running on the processor. C_DEBUGEN can not be changed this way.
+    ///
+    fn write_dhcsr(&mut self, value: u32);
+
+    ///
+    /// Write Debug Halting Control and Status Register via the debug port,
+    /// as done by an external debugger.
+    ///
+    fn dap_write_dhcsr(&mut self, value: u32);
+
+    ///
+    /// Write Debug Core Register Selector Register, starting a register transfer
+    ///
+    fn write_dcrsr(&mut self, value: u32);
+
+    ///
+    /// Read Debug Core Register Data Register
+    ///
+    fn read_dcrdr(&self) -> u32;
+
+    ///
+    /// Write Debug Core Register Data Register
+    ///
+    fn write_dcrdr(&mut self, value: u32);
+
+    ///
+    /// Read Debug Exception and Monitor Control Register
+    ///
+    fn read_demcr(&self) -> u32;
+
+    ///
+    /// Write Debug Exception and Monitor Control Register
+    ///
+    fn write_demcr(&mut self, value: u32);
+
+    ///
+    /// Write Debug Fault Status Register, bits are cleared by writing one
+    ///
+    fn write_dfsr(&mut self, value: u32);
+
+    ///
+    /// Check if halting debug has been enabled by a debugger
+    ///
+    fn halting_debug_enabled(&self) -> bool;
+
+    ///
+    /// Check if the processor is halted in debug state
+    ///
+    fn debug_halted(&self) -> bool;
+
+    ///
+    /// Enter debug state, `reason` is a combination of `DFSR_*` bits
+    ///
+    fn debug_halt(&mut self, reason: u32);
+
+    ///
+    /// Complete a single step requested with C_STEP. Called after each instruction.
+    ///
+    fn debug_step(&mut self);
+
+    ///
+    /// Halt if the vector catch for given exception is enabled.
+    /// Called after the exception has been entered.
+    ///
+    fn debug_vector_catch(&mut self, exception: Exception);
+
+    ///
+    /// Handle a breakpoint debug event for the instruction at `pc`: halt if
+    /// halting debug is enabled, otherwise raise DebugMonitor exception if
+    /// the monitor is enabled and has sufficient priority. Any other
+    /// breakpoint escalates to HardFault with HFSR.DEBUGEVT set.
+    ///
+    fn debug_breakpoint(&mut self, pc: u32);
+
+    ///
+    /// Handle a DWT watchpoint debug event: halt if halting debug is enabled or
+    /// pend the DebugMonitor exception if the monitor is enabled.
+    /// Returns true if the core halted.
+    ///
+    fn debug_watchpoint(&mut self) -> bool;
+}
+
+fn update_dhcsr_control(processor: &mut Processor, control: u32) {
+    processor
+        .dhcsr
+        .set_bits(0..6, (control & DHCSR_CONTROL_MASK).get_bits(0..6));
+
+    if processor.dhcsr & DHCSR_C_DEBUGEN == 0 {
+        // halting debug disabled, other control bits have no effect
+        processor.dhcsr.set_bits(1..6, 0);
+        processor.state.set_bit(2, false);
+    } else if processor.dhcsr & DHCSR_C_HALT != 0 {
+        if !processor.debug_halted() {
+            processor.debug_halt(DFSR_HALTED);
+        }
+    } else {
+        processor.state.set_bit(2, false);
+    }
+}
+
+fn read_core_register(processor: &mut Processor, regsel: u32) -> Option<u32> {
+    let value = match regsel {
+        0..=12 => processor.r0_12[regsel as usize],
+        13 => processor.get_r(Reg::SP),
+        14 => processor.lr,
+        15 => processor.get_pc(),
+        16 => processor.psr.value,
+        17 => processor.get_msp(),
+        18 => processor.get_psp(),
+        20 => {
+            let mut value = 0;
+            value.set_bit(0, processor.primask);
+            value.set_bits(8..16, u32::from(processor.basepri));
+            value.set_bit(16, processor.faultmask);
+            value.set_bit(24, processor.control.n_priv);
+            value.set_bit(25, processor.control.sp_sel);
+            value.set_bit(26, processor.control.fpca);
+            value
+        }
+        0b010_0001 => processor.fpscr,
+        0b100_0000..=0b101_1111 => processor.fp_regs[(regsel - 0b100_0000) as usize],
+        _ => return None,
+    };
+    Some(value)
+}
+
+fn write_core_register(processor: &mut Processor, regsel: u32, value: u32) {
+    match regsel {
+        0..=12 => processor.r0_12[regsel as usize] = value,
+        13 => processor.set_r(Reg::SP, value),
+        14 => processor.lr = value,
+        15 => processor.set_pc(value & 0xffff_fffe),
+        16 => processor.psr.value = value,
+        17 => processor.set_msp(value),
+        18 => processor.set_psp(value),
+        20 => {
+            processor.primask = value.get_bit(0);
+            processor.basepri = value.get_bits(8..16) as u8;
+            if processor.cpu.armv7m() {
+                processor.faultmask = value.get_bit(16);
+            }
+            processor.control.n_priv = value.get_bit(24);
+            processor.control.sp_sel = value.get_bit(25);
+            if processor.cpu.has_fpu() {
+                processor.control.fpca = value.get_bit(26);
+            }
+            processor.execution_priority = processor.get_execution_priority();
+        }
+        0b010_0001 => processor.fpscr = value & FPSCR_WRITE_MASK,
+        0b100_0000..=0b101_1111 => processor.fp_regs[(regsel - 0b100_0000) as usize] = value,
+        _ => (),
+    }
+}
+
+impl DebugControlBlock for Processor {
+    fn read_dhcsr(&mut self) -> u32 {
+        let mut value = self.dhcsr & (DHCSR_CONTROL_MASK | DHCSR_S_REGRDY | DHCSR_S_RESET_ST);
+        value.set_bit(17, self.state.get_bit(2));
+        value.set_bit(18, self.state.get_bit(1));
+        value.set_bit(19, self.locked_up());
+        value.set_bit(24, self.instruction_count != self.dhcsr_retire_mark);
+
+        self.dhcsr &= !DHCSR_S_RESET_ST;
+        self.dhcsr_retire_mark = self.instruction_count;
+        value
+    }
+
+    fn write_dhcsr(&mut self, value: u32) {
+        if value & 0xffff_0000 != DHCSR_DBGKEY || !self.halting_debug_enabled() {
+            return;
+        }
+        update_dhcsr_control(self, value | DHCSR_C_DEBUGEN);
+    }
+
+    fn dap_write_dhcsr(&mut self, value: u32) {
+        if value & 0xffff_0000 != DHCSR_DBGKEY {
+            return;
+        }
+        update_dhcsr_control(self, value);
+    }
+
+    fn write_dcrsr(&mut self, value: u32) {
+        // transfers are only defined in debug state
+        if !self.debug_halted() {
+            return;
+        }
+        let regsel = value.get_bits(0..7);
+        if value & DCRSR_REGWNR == 0 {
+            if let Some(data) = read_core_register(self, regsel) {
+                self.dcrdr = data;
+            }
+        } else {
+            write_core_register(self, regsel, self.dcrdr);
+        }
+        self.dhcsr |= DHCSR_S_REGRDY;
+    }
+
+    fn read_dcrdr(&self) -> u32 {
+        self.dcrdr
+    }
+
+    fn write_dcrdr(&mut self, value: u32) {
+        self.dcrdr = value;
+    }
+
+    fn read_demcr(&self) -> u32 {
+        let mut value = self.demcr;
+        if self.cpu.armv7m() {
+            value.set_bit(17, self.exception_pending(Exception::DebugMonitor));
+        }
+        value
+    }
+
+    fn write_demcr(&mut self, value: u32) {
+        if self.cpu.armv6m() {
+            self.demcr = value & DEMCR_WRITE_MASK_V6M;
+            return;
+        }
+        self.demcr = value & DEMCR_WRITE_MASK & !DEMCR_MON_PEND;
+
+        if value & DEMCR_MON_PEND == 0 {
+            self.clear_pending_exception(Exception::DebugMonitor);
+        } else {
+            self.set_exception_pending(Exception::DebugMonitor);
+        }
+    }
+
+    fn write_dfsr(&mut self, value: u32) {
+        self.dfsr &= !value;
+    }
+
+    fn halting_debug_enabled(&self) -> bool {
+        self.dhcsr & DHCSR_C_DEBUGEN != 0
+    }
+
+    fn debug_halted(&self) -> bool {
+        self.state.get_bit(2)
+    }
+
+    fn debug_halt(&mut self, reason: u32) {
+        self.state.set_bit(2, true);
+        self.dhcsr |= DHCSR_C_HALT;
+        self.dfsr |= reason;
+    }
+
+    #[inline(always)]
+    fn debug_step(&mut self) {
+        if self.dhcsr & (DHCSR_C_DEBUGEN | DHCSR_C_STEP) == (DHCSR_C_DEBUGEN | DHCSR_C_STEP) {
+            self.debug_halt(DFSR_HALTED);
+        }
+    }
+
+    fn debug_vector_catch(&mut self, exception: Exception) {
+        if !self.halting_debug_enabled() {
+            return;
+        }
+        let catch = match exception {
+            Exception::Reset => DEMCR_VC_CORERESET,
+            Exception::HardFault => DEMCR_VC_HARDERR,
+            Exception::MemoryManagementFault => DEMCR_VC_MMERR,
+            Exception::BusFault => DEMCR_VC_BUSERR,
+            Exception::UsageFault => {
+                let mut catch = 0;
+                if self.cfsr.get_bit(19) {
+                    catch |= DEMCR_VC_NOCPERR;
+                }
+                if self.cfsr.get_bits(16..19) != 0 {
+                    catch |= DEMCR_VC_STATERR;
+                }
+                if self.cfsr.get_bits(24..26) != 0 {
+                    catch |= DEMCR_VC_CHKERR;
+                }
+                catch
+            }
+            _ => 0,
+        };
+        if self.demcr & catch != 0 {
+            self.debug_halt(DFSR_VCATCH);
+        }
+    }
+
+    fn debug_breakpoint(&mut self, pc: u32) {
+        if self.halting_debug_enabled() {
+            self.debug_halt(DFSR_BKPT);
+            return;
+        }
+
+        self.dfsr |= DFSR_BKPT;
+        if self.demcr & DEMCR_MON_EN != 0
+            && self.get_exception_priority(Exception::DebugMonitor) < self.execution_priority
+        {
+            if let Err(fault) = self.exception_entry(Exception::DebugMonitor, pc) {
+                self.fault_entry(fault, pc);
+            }
+        } else {
+            self.fault_entry(Fault::DebugEvent, pc);
+        }
+    }
+
+    fn debug_watchpoint(&mut self) -> bool {
+        if self.halting_debug_enabled() {
+            self.debug_halt(DFSR_DWTTRAP);
+            return true;
+        }
+
+        if self.demcr & DEMCR_MON_EN != 0 {
+            self.dfsr |= DFSR_DWTTRAP;
+            self.set_exception_pending(Exception::DebugMonitor);
+        }
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::register::Epsr;
+    use crate::core::reset::Reset;
+    use crate::executor::Executor;
+
+    fn halted_processor() -> Processor {
+        let mut processor = Processor::new();
+        processor.cache_instructions();
+        processor.reset().unwrap();
+        // the blank reset vector does not select thumb state
+        processor.psr.set_t(true);
+        processor.dap_write_dhcsr(DHCSR_DBGKEY | DHCSR_C_DEBUGEN | DHCSR_C_HALT);
+        processor
+    }
+
+    #[test]
+    fn test_dhcsr_software_cannot_enable_debug() {
+        // Arrange
+        let mut processor = Processor::new();
+        processor.reset().unwrap();
+
+        // Act
+        processor.write_dhcsr(DHCSR_DBGKEY | DHCSR_C_DEBUGEN | DHCSR_C_HALT);
+
+        // Assert
+        assert!(!processor.halting_debug_enabled());
+        assert!(!processor.debug_halted());
+        assert_eq!(processor.read_dhcsr() & DHCSR_S_HALT, 0);
+    }
+
+    #[test]
+    fn test_dhcsr_key_required() {
+        // Arrange
+        let mut processor = Processor::new();
+        processor.reset().unwrap();
+
+        // Act
+        processor.dap_write_dhcsr(DHCSR_C_DEBUGEN | DHCSR_C_HALT);
+
+        // Assert
+        assert!(!processor.debug_halted());
+    }
+
+    #[test]
+    fn test_halt_and_step() {
+        // Arrange
+        let mut processor = halted_processor();
+        let dhcsr = processor.read_dhcsr();
+        assert_eq!(dhcsr & DHCSR_S_HALT, DHCSR_S_HALT);
+        assert_eq!(dhcsr & DHCSR_S_RESET_ST, DHCSR_S_RESET_ST);
+        assert_eq!(processor.dfsr, DFSR_HALTED);
+
+        // Act
+        processor.dap_write_dhcsr(DHCSR_DBGKEY | DHCSR_C_DEBUGEN | DHCSR_C_STEP);
+        assert!(!processor.debug_halted());
+        processor.step();
+
+        // Assert
+        assert!(processor.debug_halted());
+        assert_eq!(processor.instruction_count, 1);
+        assert_eq!(
+            processor.read_dhcsr() & DHCSR_S_RETIRE_ST,
+            DHCSR_S_RETIRE_ST
+        );
+
+        // sticky bits are cleared on read
+        let dhcsr = processor.read_dhcsr();
+        assert_eq!(dhcsr & (DHCSR_S_RETIRE_ST | DHCSR_S_RESET_ST), 0);
+    }
+
+    #[test]
+    fn test_register_transfer() {
+        // Arrange
+        let mut processor = halted_processor();
+
+        // Act
+        processor.write_dcrdr(0x1234_5678);
+        processor.write_dcrsr(DCRSR_REGWNR | 3);
+        processor.write_dcrsr(17);
+
+        // Assert
+        assert_eq!(processor.r0_12[3], 0x1234_5678);
+        assert_eq!(processor.read_dcrdr(), processor.msp);
+        assert_eq!(processor.read_dhcsr() & DHCSR_S_REGRDY, DHCSR_S_REGRDY);
+    }
+
+    #[test]
+    fn test_vector_catch_hardfault() {
+        // Arrange
+        let mut processor = Processor::new();
+        processor.reset().unwrap();
+        processor.set_msp(0x2000_1000);
+        processor.dap_write_dhcsr(DHCSR_DBGKEY | DHCSR_C_DEBUGEN);
+        processor.write_demcr(DEMCR_VC_HARDERR);
+
+        // Act
+        processor.exception_entry(Exception::HardFault, 0).unwrap();
+
+        // Assert
+        assert!(processor.debug_halted());
+        assert_eq!(processor.dfsr & DFSR_VCATCH, DFSR_VCATCH);
+    }
+
+    #[test]
+    fn test_vector_catch_core_reset() {
+        // Arrange
+        let mut processor = Processor::new();
+        processor.dap_write_dhcsr(DHCSR_DBGKEY | DHCSR_C_DEBUGEN);
+        processor.write_demcr(DEMCR_VC_CORERESET);
+
+        // Act
+        processor.reset().unwrap();
+
+        // Assert
+        assert!(processor.debug_halted());
+        assert_eq!(processor.dfsr & DFSR_VCATCH, DFSR_VCATCH);
+    }
+}
@@ -1,10 +1,87 @@
 //!
 //! Cortex Debug and Trace unit simulation
 //!
+//! Comparators match instruction fetch addresses, data access addresses
+//! and (v7-M, comparator 1) data values. A match can generate a watchpoint
+//! debug event, or on v7-M emit data trace packets via the ITM.
+//!
+//! On v7-M the unit also generates exception trace, periodic PC sample and
+//! event counter packets, and drives the ITM synchronization packets.
+//!
 
 use crate::core::bits::Bits;
+use crate::core::cpu::Cpu;
+use crate::core::instruction::Instruction;
+use crate::peripheral::dcb::DebugControlBlock;
+use crate::peripheral::itm::InstrumentationTraceMacrocell;
 use crate::Processor;
 
+/// Maximum number of comparators in the unit
+pub const DWT_NUM_COMPARATORS: usize = 4;
+
+/// Number of comparators in the unit of `cpu`
+pub fn dwt_num_comparators(cpu: Cpu) -> usize {
+    if cpu.armv6m() {
+        2
+    } else {
+        DWT_NUM_COMPARATORS
+    }
+}
+
+/// Reset value of the control register of `cpu`, NUMCOMP and the feature bits
+pub fn dwt_ctrl_reset(cpu: Cpu) -> u32 {
+    let numcomp = (dwt_num_comparators(cpu) as u32) << 28;
+    if cpu.armv6m() {
+        numcomp | (1 << 27) | (1 << 24)
+    } else {
+        numcomp
+    }
+}
+
+const DWT_CTRL_CYCCNTENA: u32 = 1;
+const DWT_CTRL_CYCTAP: u32 = 1 << 9;
+const DWT_CTRL_PCSAMPLENA: u32 = 1 << 12;
+const DWT_CTRL_EXCTRCENA: u32 = 1 << 16;
+const DWT_CTRL_CPIEVTENA: u32 = 1 << 17;
+const DWT_CTRL_EXCEVTENA: u32 = 1 << 18;
+const DWT_CTRL_SLEEPEVTENA: u32 = 1 << 19;
+const DWT_CTRL_LSUEVTENA: u32 = 1 << 20;
+const DWT_CTRL_FOLDEVTENA: u32 = 1 << 21;
+const DWT_CTRL_CYCEVTENA: u32 = 1 << 22;
+// features driven by the cycle counter taps: PCSAMPLENA, CYCEVTENA and SYNCTAP
+const DWT_CTRL_TAP_USERS: u32 = DWT_CTRL_PCSAMPLENA | DWT_CTRL_CYCEVTENA | (0b11 << 10);
+
+/// Exception trace function: exception entered
+pub const EXCEPTION_TRACE_ENTERED: u32 = 1;
+/// Exception trace function: exception exited
+pub const EXCEPTION_TRACE_EXITED: u32 = 2;
+/// Exception trace function: returned to exception
+pub const EXCEPTION_TRACE_RETURNED: u32 = 3;
+
+const DWT_FUNCTION_MATCHED: u32 = 1 << 24;
+const DWT_FUNCTION_EMITRANGE: u32 = 1 << 5;
+const DWT_FUNCTION_DATAVMATCH: u32 = 1 << 8;
+const DWT_FUNCTION_LNK1ENA: u32 = 1 << 9;
+
+///
+/// A data access that matched a comparator during the current instruction
+///
+#[derive(Debug, Copy, Clone)]
+pub struct DwtDataMatch {
+    comparator: usize,
+    address: u32,
+    value: u32,
+    size: usize,
+    write: bool,
+}
+
+#[derive(PartialEq, Debug, Copy, Clone)]
+enum DwtAction {
+    None,
+    Watchpoint,
+    Trace,
+}
+
 /// Register API to Debug and Trace peripheral
 pub trait Dwt {
     ///
@@ -17,37 +94,534 @@ pub trait Dwt {
     ///
     fn dwt_write_cyccnt(&mut self, value: u32);
 
+    ///
+    /// write CPI counter value
+    ///
+    fn dwt_write_cpicnt(&mut self, value: u32);
+
+    ///
+    /// write exception overhead counter value
+    ///
+    fn dwt_write_exccnt(&mut self, value: u32);
+
+    ///
+    /// write sleep counter value
+    ///
+    fn dwt_write_sleepcnt(&mut self, value: u32);
+
+    ///
+    /// write load store unit counter value
+    ///
+    fn dwt_write_lsucnt(&mut self, value: u32);
+
+    ///
+    /// write folded instruction counter value
+    ///
+    fn dwt_write_foldcnt(&mut self, value: u32);
+
+    ///
+    /// read program counter sample register
+    ///
+    fn dwt_read_pcsr(&self) -> u32;
+
+    ///
+    /// read comparator register `n`
+    ///
+    fn dwt_read_comp(&self, n: usize) -> u32;
+
+    ///
+    /// write comparator register `n`
+    ///
+    fn dwt_write_comp(&mut self, n: usize, value: u32);
+
+    ///
+    /// read comparator mask register `n`
+    ///
+    fn dwt_read_mask(&self, n: usize) -> u32;
+
+    ///
+    /// write comparator mask register `n`
+    ///
+    fn dwt_write_mask(&mut self, n: usize, value: u32);
+
+    ///
+    /// read comparator function register `n`, clears the MATCHED bit
+    ///
+    fn dwt_read_function(&mut self, n: usize) -> u32;
+
+    ///
+    /// write comparator function register `n`
+    ///
+    fn dwt_write_function(&mut self, n: usize, value: u32);
+
     ///
     /// Clock dwt block ```cycles```.
     ///
     ///
     fn dwt_tick(&mut self, cycles: u32);
+
+    ///
+    /// Clock dwt block ```cycles``` while the processor is sleeping.
+    ///
+    fn dwt_tick_sleep(&mut self, cycles: u32);
+
+    ///
+    /// Update profiling counters after executing ```instruction``` in ```cycles```.
+    ///
+    fn dwt_count_instruction(&mut self, instruction: &Instruction, cycles: u32);
+
+    ///
+    /// Account ```cycles``` of exception entry or return overhead.
+    ///
+    fn dwt_exception_overhead(&mut self, cycles: u32);
+
+    ///
+    /// Emit exception trace packet, ```function``` is one of the
+    /// `EXCEPTION_TRACE_*` values.
+    ///
+    fn dwt_exception_trace(&mut self, exception_number: usize, function: u32);
+
+    ///
+    /// Match instruction address against the comparators before execution.
+    /// Returns true if the core halted on a watchpoint.
+    ///
+    fn dwt_match_pc(&mut self, pc: u32) -> bool;
+
+    ///
+    /// Record a data access for comparator matching. Called by the bus.
+    ///
+    fn dwt_data_access(&self, address: u32, size: usize, value: u32, write: bool);
+
+    ///
+    /// Act on the data accesses that matched while executing the instruction at ```pc```.
+    ///
+    fn dwt_data_step(&mut self, pc: u32);
 }
 
-const DWT_CTRL_CYCCNTENA: u32 = 1;
+fn update_dwt_watch_enabled(processor: &mut Processor) {
+    processor.dwt_watch_enabled = processor
+        .dwt_function
+        .iter()
+        .any(|function| function.get_bits(0..4) != 0);
+}
+
+// Returns (read, write) access directions the comparator function matches, or
+// None if function does not match data accesses.
+fn data_directions(cpu: Cpu, function: u32) -> Option<(bool, bool)> {
+    match function.get_bits(0..4) {
+        0b0101 => Some((true, false)),
+        0b0110 => Some((false, true)),
+        0b0111 => Some((true, true)),
+        0b0001..=0b0011 | 0b1011 if cpu.armv7m() => Some((true, true)),
+        0b1001 | 0b1100 | 0b1110 if cpu.armv7m() => Some((true, false)),
+        0b1010 | 0b1101 | 0b1111 if cpu.armv7m() => Some((false, true)),
+        _ => None,
+    }
+}
+
+fn action(cpu: Cpu, function: u32) -> DwtAction {
+    match function.get_bits(0..4) {
+        0b0100..=0b0111 => DwtAction::Watchpoint,
+        0b0001..=0b0011 | 0b1100..=0b1111 if cpu.armv7m() => DwtAction::Trace,
+        _ => DwtAction::None,
+    }
+}
+
+// Does the access overlap with the address range of comparator `n`
+fn address_match(processor: &Processor, n: usize, address: u32, size: usize) -> bool {
+    let ignore = (1u64 << processor.dwt_mask[n]) - 1;
+    let base = u64::from(processor.dwt_comp[n]) & !ignore;
+    let start = u64::from(address);
+    start <= base + ignore && base < start + size as u64
+}
+
+fn value_match(processor: &Processor, n: usize, address: u32, size: usize, value: u32) -> bool {
+    let function = processor.dwt_function[n];
+    let value_size = 1 << function.get_bits(10..12);
+    if size != value_size {
+        return false;
+    }
+    let value_mask = if size == 4 {
+        0xffff_ffff
+    } else {
+        (1 << (8 * size)) - 1
+    };
+    if value & value_mask != processor.dwt_comp[n] & value_mask {
+        return false;
+    }
+
+    // restrict to addresses of linked comparators that are not otherwise in use
+    let linked: Vec<usize> = [function.get_bits(12..16), function.get_bits(16..20)]
+        .iter()
+        .map(|link| *link as usize)
+        .filter(|link| {
+            *link != n
+                && *link < dwt_num_comparators(processor.cpu)
+                && processor.dwt_function[*link].get_bits(0..4) == 0
+        })
+        .collect();
+    linked.is_empty()
+        || linked
+            .iter()
+            .any(|link| address_match(processor, *link, address, size))
+}
+
+fn is_load_store(instruction: &Instruction) -> bool {
+    matches!(
+        instruction,
+        Instruction::LDM { .. }
+            | Instruction::LDR_imm { .. }
+            | Instruction::LDR_lit { .. }
+            | Instruction::LDR_reg { .. }
+            | Instruction::LDRB_imm { .. }
+            | Instruction::LDRB_reg { .. }
+            | Instruction::LDRH_imm { .. }
+            | Instruction::LDRH_reg { .. }
+            | Instruction::LDRSB_imm { .. }
+            | Instruction::LDRSB_reg { .. }
+            | Instruction::LDRSH_imm { .. }
+            | Instruction::LDRSH_reg { .. }
+            | Instruction::LDRD_imm { .. }
+            | Instruction::LDREX { .. }
+            | Instruction::LDREXB { .. }
+            | Instruction::LDREXH { .. }
+            | Instruction::POP { .. }
+            | Instruction::PUSH { .. }
+            | Instruction::STM { .. }
+            | Instruction::STMDB { .. }
+            | Instruction::STR_imm { .. }
+            | Instruction::STR_reg { .. }
+            | Instruction::STRB_imm { .. }
+            | Instruction::STRB_reg { .. }
+            | Instruction::STRH_imm { .. }
+            | Instruction::STRH_reg { .. }
+            | Instruction::STRD_imm { .. }
+            | Instruction::STREX { .. }
+            | Instruction::STREXB { .. }
+            | Instruction::STREXH { .. }
+            | Instruction::VLDM { .. }
+            | Instruction::VLDR { .. }
+            | Instruction::VPOP { .. }
+            | Instruction::VPUSH { .. }
+            | Instruction::VSTM { .. }
+            | Instruction::VSTR { .. }
+    )
+}
+
+//
+// Emit data trace packets for a trace comparator match
+//
+fn emit_data_trace(processor: &mut Processor, pc: u32, access: &DwtDataMatch) {
+    let function = processor.dwt_function[access.comparator];
+    let id = (access.comparator as u8) << 1;
+
+    let (address_or_pc, data) = match function.get_bits(0..4) {
+        0b0001 => (true, false),
+        0b0010 | 0b1100 | 0b1101 => (function & DWT_FUNCTION_EMITRANGE != 0, true),
+        _ => (true, true),
+    };
+
+    if address_or_pc {
+        if function & DWT_FUNCTION_EMITRANGE == 0 {
+            // PC value packet
+            processor.write_hardware_packet(0b01000 | id, &pc.to_le_bytes());
+        } else {
+            // data address offset packet
+            let offset = access.address as u16;
+            processor.write_hardware_packet(0b01001 | id, &offset.to_le_bytes());
+        }
+    }
+    if data {
+        // data value packet
+        let bytes = access.value.to_le_bytes();
+        processor.write_hardware_packet(0b10000 | id | access.write as u8, &bytes[..access.size]);
+    }
+}
+
+// Add to an 8 bit profiling counter, returns true if the counter wrapped
+fn counter_add(counter: &mut u32, value: u32) -> bool {
+    let sum = *counter + value;
+    *counter = sum & 0xff;
+    sum > 0xff
+}
+
+// Event counter packet, bits: CPI, EXC, SLEEP, LSU, FOLD, CYC
+fn emit_counter_events(processor: &mut Processor, events: u8) {
+    if events != 0 {
+        processor.write_hardware_packet(0, &[events]);
+    }
+}
+
+//
+// Act on the cycle counter taps that toggled since ```previous``` value of the cycle counter
+//
+fn cycle_counter_taps(processor: &mut Processor, previous: u32) {
+    let current = processor.dwt_cyccnt;
+    let toggles = |bit: u32| (current >> bit).wrapping_sub(previous >> bit) & (u32::MAX >> bit);
+
+    let synctap = processor.dwt_ctrl.get_bits(10..12);
+    if synctap != 0 && toggles(22 + 2 * synctap) != 0 {
+        processor.itm_sync();
+    }
+
+    if processor.dwt_ctrl & (DWT_CTRL_PCSAMPLENA | DWT_CTRL_CYCEVTENA) == 0 {
+        return;
+    }
+    let tap = if processor.dwt_ctrl & DWT_CTRL_CYCTAP == 0 {
+        6
+    } else {
+        10
+    };
+    for _ in 0..toggles(tap) {
+        if processor.dwt_postcnt > 0 {
+            processor.dwt_postcnt -= 1;
+            continue;
+        }
+        processor.dwt_postcnt = processor.dwt_ctrl.get_bits(1..5);
+        if processor.dwt_ctrl & DWT_CTRL_PCSAMPLENA != 0 {
+            if processor.state.get_bit(1) {
+                // sleeping, idle packet
+                processor.write_hardware_packet(2, &[0]);
+            } else {
+                let pc = processor.pc;
+                processor.write_hardware_packet(2, &pc.to_le_bytes());
+            }
+        } else {
+            emit_counter_events(processor, 1 << 5);
+        }
+    }
+}
 
 impl Dwt for Processor {
     fn dwt_write_ctrl(&mut self, value: u32) {
-        self.dwt_ctrl.set_bits(16..23, value.get_bits(16..23));
+        if self.cpu.armv7m() {
+            // counters reset when enabled
+            let enabled = value & !self.dwt_ctrl;
+            if enabled & DWT_CTRL_CPIEVTENA != 0 {
+                self.dwt_cpicnt = 0;
+            }
+            if enabled & DWT_CTRL_EXCEVTENA != 0 {
+                self.dwt_exccnt = 0;
+            }
+            if enabled & DWT_CTRL_SLEEPEVTENA != 0 {
+                self.dwt_sleepcnt = 0;
+            }
+            if enabled & DWT_CTRL_LSUEVTENA != 0 {
+                self.dwt_lsucnt = 0;
+            }
+            if enabled & DWT_CTRL_FOLDEVTENA != 0 {
+                self.dwt_foldcnt = 0;
+            }
+            self.dwt_ctrl.set_bits(16..23, value.get_bits(16..23));
+        }
         self.dwt_ctrl.set_bits(0..13, value.get_bits(0..13));
+        self.dwt_postcnt = value.get_bits(5..9);
     }
 
     fn dwt_write_cyccnt(&mut self, value: u32) {
         self.dwt_cyccnt = value;
     }
 
+    fn dwt_write_cpicnt(&mut self, value: u32) {
+        self.dwt_cpicnt = value & 0xff;
+    }
+
+    fn dwt_write_exccnt(&mut self, value: u32) {
+        self.dwt_exccnt = value & 0xff;
+    }
+
+    fn dwt_write_sleepcnt(&mut self, value: u32) {
+        self.dwt_sleepcnt = value & 0xff;
+    }
+
+    fn dwt_write_lsucnt(&mut self, value: u32) {
+        self.dwt_lsucnt = value & 0xff;
+    }
+
+    fn dwt_write_foldcnt(&mut self, value: u32) {
+        self.dwt_foldcnt = value & 0xff;
+    }
+
+    fn dwt_read_pcsr(&self) -> u32 {
+        if self.debug_halted() {
+            0xffff_ffff
+        } else {
+            self.pc
+        }
+    }
+
+    fn dwt_read_comp(&self, n: usize) -> u32 {
+        self.dwt_comp[n]
+    }
+
+    fn dwt_write_comp(&mut self, n: usize, value: u32) {
+        self.dwt_comp[n] = value;
+    }
+
+    fn dwt_read_mask(&self, n: usize) -> u32 {
+        self.dwt_mask[n]
+    }
+
+    fn dwt_write_mask(&mut self, n: usize, value: u32) {
+        self.dwt_mask[n] = value & 0x1f;
+    }
+
+    fn dwt_read_function(&mut self, n: usize) -> u32 {
+        let value = self.dwt_function[n];
+        self.dwt_function[n] &= !DWT_FUNCTION_MATCHED;
+        if self.cpu.armv7m() && n == 1 {
+            return value | DWT_FUNCTION_LNK1ENA;
+        }
+        value
+    }
+
+    fn dwt_write_function(&mut self, n: usize, value: u32) {
+        let writable = if self.cpu.armv6m() {
+            0xf
+        } else if n == 1 {
+            0x000f_fd2f
+        } else {
+            0x0000_0c2f
+        };
+        self.dwt_function[n] = (self.dwt_function[n] & DWT_FUNCTION_MATCHED) | (value & writable);
+        update_dwt_watch_enabled(self);
+    }
+
     #[inline(always)]
     fn dwt_tick(&mut self, cycles: u32) {
-        self.dwt_cyccnt = self
-            .dwt_cyccnt
-            .wrapping_add(cycles * (self.dwt_ctrl & DWT_CTRL_CYCCNTENA));
+        let previous = self.dwt_cyccnt;
+        self.dwt_cyccnt = previous.wrapping_add(cycles * (self.dwt_ctrl & DWT_CTRL_CYCCNTENA));
+        if self.cpu.armv7m()
+            && self.dwt_ctrl & DWT_CTRL_TAP_USERS != 0
+            && self.dwt_cyccnt != previous
+        {
+            cycle_counter_taps(self, previous);
+        }
+    }
+
+    fn dwt_tick_sleep(&mut self, cycles: u32) {
+        self.dwt_tick(cycles);
+        if self.cpu.armv7m()
+            && self.dwt_ctrl & DWT_CTRL_SLEEPEVTENA != 0
+            && counter_add(&mut self.dwt_sleepcnt, cycles)
+        {
+            emit_counter_events(self, 1 << 2);
+        }
+    }
+
+    #[inline(always)]
+    fn dwt_count_instruction(&mut self, instruction: &Instruction, cycles: u32) {
+        if self.cpu.armv6m() || self.dwt_ctrl.get_bits(17..22) == 0 {
+            return;
+        }
+        let extra = cycles.saturating_sub(1);
+        let mut events = 0;
+        if is_load_store(instruction) {
+            if self.dwt_ctrl & DWT_CTRL_LSUEVTENA != 0 && counter_add(&mut self.dwt_lsucnt, extra) {
+                events |= 1 << 3;
+            }
+        } else if self.dwt_ctrl & DWT_CTRL_CPIEVTENA != 0
+            && counter_add(&mut self.dwt_cpicnt, extra)
+        {
+            events |= 1;
+        }
+        if let Instruction::IT { .. } = instruction {
+            if self.dwt_ctrl & DWT_CTRL_FOLDEVTENA != 0 && counter_add(&mut self.dwt_foldcnt, 1) {
+                events |= 1 << 4;
+            }
+        }
+        emit_counter_events(self, events);
+    }
+
+    fn dwt_exception_overhead(&mut self, cycles: u32) {
+        if self.cpu.armv7m()
+            && self.dwt_ctrl & DWT_CTRL_EXCEVTENA != 0
+            && counter_add(&mut self.dwt_exccnt, cycles)
+        {
+            emit_counter_events(self, 1 << 1);
+        }
+    }
+
+    fn dwt_exception_trace(&mut self, exception_number: usize, function: u32) {
+        if self.cpu.armv7m() && self.dwt_ctrl & DWT_CTRL_EXCTRCENA != 0 {
+            let payload = (exception_number as u16 & 0x1ff) | ((function as u16) << 12);
+            self.write_hardware_packet(1, &payload.to_le_bytes());
+        }
+    }
+
+    fn dwt_match_pc(&mut self, pc: u32) -> bool {
+        // clear accesses made by the debugger while halted
+        self.dwt_data_matches.borrow_mut().clear();
+
+        let mut watchpoint = false;
+        for n in 0..dwt_num_comparators(self.cpu) {
+            let function = self.dwt_function[n];
+            if function.get_bits(0..4) == 0b0100 && address_match(self, n, pc, 2) {
+                self.dwt_function[n] |= DWT_FUNCTION_MATCHED;
+                watchpoint |= action(self.cpu, function) == DwtAction::Watchpoint;
+            }
+        }
+        watchpoint && self.debug_watchpoint()
+    }
+
+    fn dwt_data_access(&self, address: u32, size: usize, value: u32, write: bool) {
+        for n in 0..dwt_num_comparators(self.cpu) {
+            let function = self.dwt_function[n];
+            let hit = match data_directions(self.cpu, function) {
+                Some((read_match, write_match))
+                    if (write && write_match) || (!write && read_match) =>
+                {
+                    if self.cpu.armv7m() && function & DWT_FUNCTION_DATAVMATCH != 0 {
+                        value_match(self, n, address, size, value)
+                    } else {
+                        address_match(self, n, address, size)
+                    }
+                }
+                _ => false,
+            };
+            if hit {
+                self.dwt_data_matches.borrow_mut().push(DwtDataMatch {
+                    comparator: n,
+                    address,
+                    value,
+                    size,
+                    write,
+                });
+            }
+        }
+    }
+
+    fn dwt_data_step(&mut self, pc: u32) {
+        if self.dwt_data_matches.borrow().is_empty() {
+            return;
+        }
+        let matches: Vec<DwtDataMatch> = self.dwt_data_matches.borrow_mut().drain(..).collect();
+
+        let mut watchpoint = false;
+        for access in &matches {
+            let function = self.dwt_function[access.comparator];
+            self.dwt_function[access.comparator] |= DWT_FUNCTION_MATCHED;
+            match action(self.cpu, function) {
+                DwtAction::Watchpoint => watchpoint = true,
+                DwtAction::Trace => emit_data_trace(self, pc, access),
+                DwtAction::None => {}
+            }
+        }
+        if watchpoint {
+            self.debug_watchpoint();
+        }
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::bus::Bus;
+    use crate::core::register::{BaseReg, Reg};
     use crate::core::reset::Reset;
+    use crate::executor::Executor;
+    use crate::peripheral::dcb::{DFSR_DWTTRAP, DHCSR_C_DEBUGEN, DHCSR_C_HALT, DHCSR_DBGKEY};
+    use enum_set::EnumSet;
 
     #[test]
     fn test_dwt_tick() {
@@ -66,4 +640,230 @@ mod tests {
         // Act
         assert_eq!(processor.dwt_cyccnt, 42);
     }
+
+    #[test]
+    fn test_dwt_ctrl_numcomp() {
+        // Arrange
+        let mut processor = Processor::new();
+        processor.reset().unwrap();
+
+        // Act
+        let ctrl = processor.read32(0xE000_1000).unwrap();
+        processor.cpu(Cpu::CortexM0Plus);
+        let ctrl_v6m = processor.read32(0xE000_1000).unwrap();
+
+        // Assert
+        assert_eq!(ctrl >> 28, DWT_NUM_COMPARATORS as u32);
+        assert_eq!(ctrl_v6m >> 28, 2);
+        // the registers of the missing comparators are not present
+        assert!(processor.read32(0xE000_1040).is_err());
+    }
+
+    #[test]
+    fn test_write_watchpoint_halts() {
+        // Arrange
+        let mut processor = Processor::new();
+        processor.reset().unwrap();
+        processor.dap_write_dhcsr(DHCSR_DBGKEY | DHCSR_C_DEBUGEN);
+        processor.write32(0xE000_1020, 0x2000_0100).unwrap();
+        processor.write32(0xE000_1024, 2).unwrap();
+        processor.write32(0xE000_1028, 0b0110).unwrap();
+
+        // Act
+        processor.read32(0x2000_0100).unwrap();
+        processor.dwt_data_step(0);
+        let halted_on_read = processor.debug_halted();
+        processor.write8(0x2000_0103, 0x55).unwrap();
+        processor.dwt_data_step(0);
+
+        // Assert
+        assert!(!halted_on_read);
+        assert!(processor.debug_halted());
+        assert_eq!(processor.dfsr & DFSR_DWTTRAP, DFSR_DWTTRAP);
+        assert_eq!(
+            processor.read32(0xE000_1028).unwrap() & DWT_FUNCTION_MATCHED,
+            DWT_FUNCTION_MATCHED
+        );
+        assert_eq!(
+            processor.read32(0xE000_1028).unwrap() & DWT_FUNCTION_MATCHED,
+            0
+        );
+    }
+
+    #[test]
+    fn test_pc_watchpoint_halts_before_execution() {
+        // Arrange
+        let mut code = [0u8; 64];
+        // vector table: initial SP, reset handler
+        code[0..4].copy_from_slice(&0x2000_1000u32.to_le_bytes());
+        code[4..8].copy_from_slice(&9u32.to_le_bytes());
+        // movs r0, #1; movs r0, #2; b .
+        code[8..14].copy_from_slice(&[0x01, 0x20, 0x02, 0x20, 0xfe, 0xe7]);
+        let mut processor = Processor::new();
+        processor.flash_memory(64, &code);
+        processor.cache_instructions();
+        processor.reset().unwrap();
+        processor.dap_write_dhcsr(DHCSR_DBGKEY | DHCSR_C_DEBUGEN);
+        processor.write32(0xE000_1020, 10).unwrap();
+        processor.write32(0xE000_1028, 0b0100).unwrap();
+
+        // Act
+        processor.step();
+        processor.step();
+        processor.step();
+
+        // Assert
+        assert!(processor.debug_halted());
+        assert_eq!(processor.get_pc(), 10);
+        assert_eq!(processor.get_r(Reg::R0), 1);
+    }
+
+    #[test]
+    fn test_pc_match_comparators() {
+        // Arrange
+        let mut processor = Processor::new();
+        processor.cpu(Cpu::CortexM0Plus);
+        processor.reset().unwrap();
+        processor.dap_write_dhcsr(DHCSR_DBGKEY | DHCSR_C_DEBUGEN);
+        // comparator 2 is not implemented on ARMv6-M
+        processor.dwt_comp[2] = 0x100;
+        processor.dwt_function[2] = 0b0100;
+
+        // Act & Assert
+        assert!(!processor.dwt_match_pc(0x100));
+        assert_eq!(processor.dwt_function[2] & DWT_FUNCTION_MATCHED, 0);
+
+        // Arrange: data address read trace is not a PC match
+        processor.cpu(Cpu::CortexM4);
+        processor.dwt_function[2] = 0;
+        processor.dwt_comp[0] = 0x100;
+        processor.dwt_function[0] = 0b1100;
+
+        // Act & Assert
+        assert!(!processor.dwt_match_pc(0x100));
+        assert_eq!(processor.dwt_function[0] & DWT_FUNCTION_MATCHED, 0);
+    }
+
+    #[test]
+    fn test_data_value_match() {
+        // Arrange
+        let mut processor = Processor::new();
+        processor.reset().unwrap();
+        processor.dap_write_dhcsr(DHCSR_DBGKEY | DHCSR_C_DEBUGEN);
+        // comparator 0 provides the linked address
+        processor.write32(0xE000_1020, 0x2000_0200).unwrap();
+        // comparator 1 matches word writes of 0xdeadbeef
+        processor.write32(0xE000_1030, 0xdead_beef).unwrap();
+        processor
+            .write32(0xE000_1038, 0b0110 | DWT_FUNCTION_DATAVMATCH | (2 << 10))
+            .unwrap();
+
+        // Act
+        processor.write32(0x2000_0100, 0xdead_beef).unwrap();
+        processor.write32(0x2000_0200, 0x1234_5678).unwrap();
+        processor.dwt_data_step(0);
+        let halted_on_mismatch = processor.debug_halted();
+        processor.write32(0x2000_0200, 0xdead_beef).unwrap();
+        processor.dwt_data_step(0);
+
+        // Assert
+        assert!(!halted_on_mismatch);
+        assert!(processor.debug_halted());
+        assert_eq!(
+            processor.read32(0xE000_1038).unwrap() & DWT_FUNCTION_LNK1ENA,
+            DWT_FUNCTION_LNK1ENA
+        );
+    }
+
+    #[test]
+    fn test_profiling_counters() {
+        // Arrange
+        let mut processor = Processor::new();
+        processor.reset().unwrap();
+        processor.dwt_cpicnt = 0x55;
+
+        // Act
+        processor.dwt_write_ctrl(
+            DWT_CTRL_CPIEVTENA | DWT_CTRL_LSUEVTENA | DWT_CTRL_SLEEPEVTENA | DWT_CTRL_EXCEVTENA,
+        );
+        processor.dwt_count_instruction(&Instruction::NOP { thumb32: false }, 3);
+        processor.dwt_count_instruction(
+            &Instruction::PUSH {
+                registers: EnumSet::new(),
+                thumb32: false,
+            },
+            255,
+        );
+        processor.dwt_tick_sleep(10);
+        processor.dwt_exception_overhead(12);
+
+        // Assert
+        assert_eq!(processor.dwt_cpicnt, 2);
+        assert_eq!(processor.dwt_lsucnt, 254);
+        assert_eq!(processor.dwt_sleepcnt, 10);
+        assert_eq!(processor.dwt_exccnt, 12);
+    }
+
+    #[test]
+    fn test_exception_trace_and_pc_sampling() {
+        // Arrange
+        use crate::peripheral::itm::{ITM_TCR_DWTENA, ITM_TCR_ITMENA};
+        use std::cell::RefCell;
+        use std::io;
+        use std::rc::Rc;
+
+        struct SharedBuffer(Rc<RefCell<Vec<u8>>>);
+        impl io::Write for SharedBuffer {
+            fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+                self.0.borrow_mut().extend_from_slice(buf);
+                Ok(buf.len())
+            }
+            fn flush(&mut self) -> io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let output = Rc::new(RefCell::new(Vec::new()));
+        let mut processor = Processor::new();
+        processor.itm(Some(Box::new(SharedBuffer(output.clone()))));
+        processor.reset().unwrap();
+        processor.itm_write_lar_u32(0xC5AC_CE55);
+        processor.itm_write_tcr(ITM_TCR_ITMENA | ITM_TCR_DWTENA);
+        // POSTPRESET = 1, POSTINIT = 1, sample every 2 * 64 cycles
+        processor.dwt_write_ctrl(
+            DWT_CTRL_CYCCNTENA | DWT_CTRL_PCSAMPLENA | DWT_CTRL_EXCTRCENA | (1 << 5) | (1 << 1),
+        );
+        processor.set_pc(0x0800_0100);
+
+        // Act
+        processor.dwt_exception_trace(15, EXCEPTION_TRACE_ENTERED);
+        processor.itm_tick(64);
+        processor.dwt_tick(127);
+        let before_sample = output.borrow().len();
+        processor.dwt_tick(1);
+
+        // Assert
+        assert_eq!(before_sample, 3);
+        assert_eq!(
+            *output.borrow(),
+            vec![0x0e, 0x0f, 0x10, 0x17, 0x00, 0x01, 0x00, 0x08]
+        );
+    }
+
+    #[test]
+    fn test_pcsr() {
+        // Arrange
+        let mut processor = Processor::new();
+        processor.reset().unwrap();
+        processor.set_pc(0x100);
+
+        // Act
+        let running = processor.read32(0xE000_101C).unwrap();
+        processor.dap_write_dhcsr(DHCSR_DBGKEY | DHCSR_C_DEBUGEN | DHCSR_C_HALT);
+        let halted = processor.read32(0xE000_101C).unwrap();
+
+        // Assert
+        assert_eq!(running, 0x100);
+        assert_eq!(halted, 0xffff_ffff);
+    }
 }
@@ -0,0 +1,467 @@
+//!
+//! Cortex Flash Patch and Breakpoint unit simulation
+//!
+//! Revision 1 comparators (Cortex-M0/M3/M4) match a word address in the code
+//! region and either remap it to SRAM (v7-M) or generate a breakpoint on the
+//! lower and/or upper halfword. Revision 2 comparators only generate
+//! breakpoints, but at any halfword address.
+//!
+
+use crate::bus::Bus;
+use crate::core::bits::Bits;
+use crate::core::cpu::Cpu;
+use crate::core::thumb::ThumbCode;
+use crate::decoder::is_thumb32;
+use crate::Processor;
+
+const FP_CTRL_ENABLE: u32 = 1;
+const FP_CTRL_KEY: u32 = 1 << 1;
+
+const FP_COMP_ENABLE: u32 = 1;
+
+// number of instruction address comparators
+fn fpb_num_code(cpu: Cpu) -> usize {
+    if cpu.armv6m() {
+        4
+    } else {
+        6
+    }
+}
+
+// number of literal address comparators
+fn fpb_num_lit(cpu: Cpu) -> usize {
+    if cpu.armv6m() {
+        0
+    } else {
+        2
+    }
+}
+
+/// Maximum number of comparators in the unit
+pub const FPB_NUM_COMPARATORS: usize = 8;
+
+const FP_REMAP_RMPSPT: u32 = 1 << 29;
+
+///
+/// Result of matching an instruction fetch address against the comparators
+///
+#[derive(PartialEq, Debug, Copy, Clone)]
+pub enum FpbMatch {
+    /// Fetch address is not affected by the FPB
+    None,
+    /// Fetch address matched a breakpoint comparator
+    Breakpoint,
+    /// Instruction at the fetch address is patched
+    Remap(ThumbCode),
+}
+
+/// Register API to Flash Patch and Breakpoint unit
+pub trait FlashPatchBreakpoint {
+    ///
+    /// Select FPB architecture revision, 1 or 2. Other values are clamped
+    /// to this range. Must be called after the core is selected with
+    /// ```cpu()```, which restores the revision 1 reset state.
+    ///
+    fn fpb_set_revision(&mut self, revision: u32);
+
+    ///
+    /// Read Flash Patch Control Register
+    ///
+    fn read_fp_ctrl(&self) -> u32;
+
+    ///
+    /// Write Flash Patch Control Register
+    ///
+    fn write_fp_ctrl(&mut self, value: u32);
+
+    ///
+    /// Read Flash Patch Remap Register
+    ///
+    fn read_fp_remap(&self) -> u32;
+
+    ///
+    /// Write Flash Patch Remap Register
+    ///
+    fn write_fp_remap(&mut self, value: u32);
+
+    ///
+    /// Read Flash Patch Comparator Register `n`
+    ///
+    fn read_fp_comp(&self, n: usize) -> u32;
+
+    ///
+    /// Write Flash Patch Comparator Register `n`
+    ///
+    fn write_fp_comp(&mut self, n: usize, value: u32);
+
+    ///
+    /// Match instruction fetch address against the code comparators
+    ///
+    fn fpb_match_fetch(&self, pc: u32) -> FpbMatch;
+
+    ///
+    /// Resolve the remapped address of a literal load from the code region,
+    /// if a literal comparator matches the address
+    ///
+    fn fpb_remap_literal(&self, address: u32) -> Option<u32>;
+}
+
+fn fpb_revision2(processor: &Processor) -> bool {
+    processor.fpb_ctrl.get_bits(28..32) == 1
+}
+
+fn update_fpb_enabled(processor: &mut Processor) {
+    processor.fpb_enabled = processor.fpb_ctrl & FP_CTRL_ENABLE != 0
+        && processor.fpb_comp[..fpb_num_code(processor.cpu) + fpb_num_lit(processor.cpu)]
+            .iter()
+            .any(|comp| comp & FP_COMP_ENABLE != 0);
+}
+
+//
+// Revision 1 comparator match for a word in the code region, returns the REPLACE field
+//
+fn comparator_match_word(comp: u32, address: u32) -> Option<u32> {
+    if comp & FP_COMP_ENABLE != 0
+        && address.get_bits(29..32) == 0
+        && comp.get_bits(2..29) == address.get_bits(2..29)
+    {
+        Some(comp.get_bits(30..32))
+    } else {
+        None
+    }
+}
+
+fn remap_address(processor: &Processor, n: usize, address: u32) -> u32 {
+    0x2000_0000 | (processor.fpb_remap.get_bits(5..29) << 5) | ((n as u32) << 2) | (address & 3)
+}
+
+// Resolve address of a halfword fetched via the code comparators, taking remap into account.
+fn fetch_address(processor: &Processor, address: u32) -> u32 {
+    if processor.fpb_remap & FP_REMAP_RMPSPT != 0 && !fpb_revision2(processor) {
+        for n in 0..fpb_num_code(processor.cpu) {
+            if comparator_match_word(processor.fpb_comp[n], address) == Some(0b00) {
+                return remap_address(processor, n, address);
+            }
+        }
+    }
+    address
+}
+
+impl FlashPatchBreakpoint for Processor {
+    fn fpb_set_revision(&mut self, revision: u32) {
+        let revision = revision.clamp(1, 2);
+        self.fpb_ctrl.set_bits(28..32, revision.saturating_sub(1));
+        if self.cpu.armv7m() {
+            self.fpb_remap.set_bit(29, revision == 1);
+        }
+    }
+
+    fn read_fp_ctrl(&self) -> u32 {
+        let mut value = self.fpb_ctrl & !FP_CTRL_KEY;
+        value.set_bits(4..8, fpb_num_code(self.cpu) as u32);
+        value.set_bits(8..12, fpb_num_lit(self.cpu) as u32);
+        value
+    }
+
+    fn write_fp_ctrl(&mut self, value: u32) {
+        if value & FP_CTRL_KEY != 0 {
+            self.fpb_ctrl.set_bit(0, value.get_bit(0));
+            update_fpb_enabled(self);
+        }
+    }
+
+    fn read_fp_remap(&self) -> u32 {
+        self.fpb_remap
+    }
+
+    fn write_fp_remap(&mut self, value: u32) {
+        if self.fpb_remap & FP_REMAP_RMPSPT != 0 {
+            self.fpb_remap.set_bits(5..29, value.get_bits(5..29));
+        }
+    }
+
+    fn read_fp_comp(&self, n: usize) -> u32 {
+        if n < fpb_num_code(self.cpu) + fpb_num_lit(self.cpu) {
+            self.fpb_comp[n]
+        } else {
+            0
+        }
+    }
+
+    fn write_fp_comp(&mut self, n: usize, value: u32) {
+        if n < fpb_num_code(self.cpu) + fpb_num_lit(self.cpu) {
+            self.fpb_comp[n] = if fpb_revision2(self) {
+                value
+            } else {
+                // bit 29 and bit 1 are reserved
+                value & 0xdfff_fffd
+            };
+            update_fpb_enabled(self);
+        }
+    }
+
+    fn fpb_match_fetch(&self, pc: u32) -> FpbMatch {
+        if fpb_revision2(self) {
+            let hit = self.fpb_comp[..fpb_num_code(self.cpu)]
+                .iter()
+                .any(|comp| comp & FP_COMP_ENABLE != 0 && comp & 0xffff_fffe == pc);
+            return if hit {
+                FpbMatch::Breakpoint
+            } else {
+                FpbMatch::None
+            };
+        }
+
+        for comp in &self.fpb_comp[..fpb_num_code(self.cpu)] {
+            let hit = match comparator_match_word(*comp, pc) {
+                Some(0b01) => !pc.get_bit(1),
+                Some(0b10) => pc.get_bit(1),
+                Some(0b11) => true,
+                _ => false,
+            };
+            if hit {
+                return FpbMatch::Breakpoint;
+            }
+        }
+
+        let first = fetch_address(self, pc);
+        let second = fetch_address(self, pc.wrapping_add(2));
+        if first == pc && second == pc.wrapping_add(2) {
+            return FpbMatch::None;
+        }
+
+        let hw = match self.read16(first) {
+            Ok(hw) => hw,
+            Err(_) => return FpbMatch::None,
+        };
+        if is_thumb32(hw) {
+            match self.read16(second) {
+                Ok(hw2) => FpbMatch::Remap(ThumbCode::Thumb32 {
+                    opcode: (u32::from(hw) << 16) + u32::from(hw2),
+                }),
+                Err(_) => FpbMatch::None,
+            }
+        } else if first == pc {
+            FpbMatch::None
+        } else {
+            FpbMatch::Remap(ThumbCode::Thumb16 { opcode: hw })
+        }
+    }
+
+    fn fpb_remap_literal(&self, address: u32) -> Option<u32> {
+        if self.fpb_remap & FP_REMAP_RMPSPT == 0 || fpb_revision2(self) {
+            return None;
+        }
+        let num_code = fpb_num_code(self.cpu);
+        for n in num_code..num_code + fpb_num_lit(self.cpu) {
+            if comparator_match_word(self.fpb_comp[n], address).is_some() {
+                return Some(remap_address(self, n, address));
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::register::BaseReg;
+    use crate::core::register::Epsr;
+    use crate::core::reset::Reset;
+    use crate::executor::Executor;
+    use crate::peripheral::dcb::{DebugControlBlock, DFSR_BKPT, DHCSR_C_DEBUGEN, DHCSR_DBGKEY};
+    use crate::system::simulation::{make_processor, SimulationConfig};
+    use crate::{
+        core::exception::{Exception, ExceptionHandling},
+        peripheral::dcb::DEMCR_MON_EN,
+    };
+
+    fn enabled_processor() -> Processor {
+        let mut processor = Processor::new();
+        processor.reset().unwrap();
+        // the blank reset vector does not select thumb state
+        processor.psr.set_t(true);
+        processor.write_fp_ctrl(FP_CTRL_KEY | FP_CTRL_ENABLE);
+        processor
+    }
+
+    #[test]
+    fn test_fp_ctrl_requires_key() {
+        // Arrange
+        let mut processor = Processor::new();
+
+        // Act
+        processor.write_fp_ctrl(FP_CTRL_ENABLE);
+
+        // Assert
+        assert_eq!(processor.read_fp_ctrl() & FP_CTRL_ENABLE, 0);
+        assert_eq!(
+            processor.read_fp_ctrl().get_bits(4..8),
+            fpb_num_code(processor.cpu) as u32
+        );
+    }
+
+    #[test]
+    fn test_rev1_breakpoint_halfwords() {
+        // Arrange
+        let mut processor = enabled_processor();
+
+        // Act
+        processor.write_fp_comp(0, (0b01 << 30) | 0x100 | FP_COMP_ENABLE);
+        processor.write_fp_comp(1, (0b10 << 30) | 0x200 | FP_COMP_ENABLE);
+
+        // Assert
+        assert!(processor.fpb_enabled);
+        assert_eq!(processor.fpb_match_fetch(0x100), FpbMatch::Breakpoint);
+        assert_eq!(processor.fpb_match_fetch(0x102), FpbMatch::None);
+        assert_eq!(processor.fpb_match_fetch(0x200), FpbMatch::None);
+        assert_eq!(processor.fpb_match_fetch(0x202), FpbMatch::Breakpoint);
+    }
+
+    #[test]
+    fn test_rev2_breakpoint() {
+        // Arrange
+        let mut processor = enabled_processor();
+        processor.fpb_set_revision(2);
+
+        // Act
+        processor.write_fp_comp(0, 0x2000_0102 | FP_COMP_ENABLE);
+
+        // Assert
+        assert_eq!(processor.fpb_match_fetch(0x2000_0102), FpbMatch::Breakpoint);
+        assert_eq!(processor.fpb_match_fetch(0x2000_0100), FpbMatch::None);
+        assert_eq!(processor.fpb_remap_literal(0x2000_0100), None);
+    }
+
+    #[test]
+    fn test_revision_config() {
+        // Arrange
+        let configured = |fpb_revision| {
+            let config = SimulationConfig {
+                fpb_revision,
+                ..SimulationConfig::new(Cpu::CortexM3, 0x100)
+            };
+            make_processor(&[0; 0x100], config).unwrap_or_else(|_| panic!("processor not created"))
+        };
+
+        // Act
+        let rev1 = configured(1);
+        let rev2 = configured(2);
+        let clamped = configured(7);
+
+        // Assert
+        assert_eq!(rev1.read_fp_ctrl().get_bits(28..32), 0);
+        assert_eq!(rev1.read_fp_remap() & FP_REMAP_RMPSPT, FP_REMAP_RMPSPT);
+        assert_eq!(rev2.read_fp_ctrl().get_bits(28..32), 1);
+        assert_eq!(rev2.read_fp_remap() & FP_REMAP_RMPSPT, 0);
+        assert_eq!(clamped.read_fp_ctrl().get_bits(28..32), 1);
+    }
+
+    #[test]
+    fn test_rev1_instruction_remap() {
+        // Arrange
+        let mut processor = enabled_processor();
+        processor.write_fp_remap(0x2000_0100);
+        // comparator 1 remaps to 0x2000_0104
+        processor.write16(0x2000_0104, 0xbf00).unwrap(); // nop
+        processor.write16(0x2000_0106, 0x2001).unwrap(); // movs r0, #1
+
+        // Act
+        processor.write_fp_comp(1, 0x40 | FP_COMP_ENABLE);
+
+        // Assert
+        assert_eq!(
+            processor.fpb_match_fetch(0x40),
+            FpbMatch::Remap(ThumbCode::Thumb16 { opcode: 0xbf00 })
+        );
+        assert_eq!(
+            processor.fpb_match_fetch(0x42),
+            FpbMatch::Remap(ThumbCode::Thumb16 { opcode: 0x2001 })
+        );
+        assert_eq!(processor.fpb_match_fetch(0x44), FpbMatch::None);
+    }
+
+    #[test]
+    fn test_rev1_literal_remap() {
+        // Arrange
+        let mut processor = enabled_processor();
+        processor.write_fp_remap(0x2000_0100);
+        processor.write32(0x2000_0118, 0x1234_5678).unwrap();
+
+        // Act
+        processor.write_fp_comp(fpb_num_code(processor.cpu), 0x80 | FP_COMP_ENABLE);
+
+        // Assert
+        assert_eq!(processor.fpb_remap_literal(0x82), Some(0x2000_011a));
+        assert_eq!(processor.read32(0x80).unwrap(), 0x1234_5678);
+        assert_eq!(processor.read16(0x82).unwrap(), 0x1234);
+        assert_eq!(processor.fpb_remap_literal(0x84), None);
+    }
+
+    #[test]
+    fn test_breakpoint_halts_before_execution() {
+        // Arrange
+        let mut processor = enabled_processor();
+        processor.cache_instructions();
+        processor.dap_write_dhcsr(DHCSR_DBGKEY | DHCSR_C_DEBUGEN);
+        let pc = processor.get_pc();
+
+        // Act
+        processor.write_fp_comp(0, (0b11 << 30) | pc | FP_COMP_ENABLE);
+        processor.step();
+
+        // Assert
+        assert!(processor.debug_halted());
+        assert_eq!(processor.dfsr & DFSR_BKPT, DFSR_BKPT);
+        assert_eq!(processor.instruction_count, 0);
+        assert_eq!(processor.get_pc(), pc);
+    }
+
+    #[test]
+    fn test_breakpoint_debug_monitor() {
+        // Arrange
+        let mut processor = enabled_processor();
+        processor.cache_instructions();
+        processor.set_msp(0x2000_1000);
+        processor.write_demcr(DEMCR_MON_EN);
+        let pc = processor.get_pc();
+
+        // Act
+        processor.write_fp_comp(0, (0b11 << 30) | pc | FP_COMP_ENABLE);
+        processor.step();
+
+        // Assert
+        assert!(!processor.debug_halted());
+        assert!(processor.exception_active(Exception::DebugMonitor));
+        assert_eq!(processor.instruction_count, 0);
+    }
+
+    #[test]
+    fn test_breakpoint_escalates_to_hardfault() {
+        // Arrange
+        let mut processor = enabled_processor();
+        processor.cache_instructions();
+        processor.set_msp(0x2000_1000);
+        let pc = processor.get_pc();
+
+        // Act
+        processor.write_fp_comp(0, (0b11 << 30) | pc | FP_COMP_ENABLE);
+        processor.step();
+
+        // Assert
+        assert!(!processor.debug_halted());
+        assert!(processor.exception_active(Exception::HardFault));
+        assert!(processor.hfsr.get_bit(31)); // DEBUGEVT
+        assert_eq!(processor.dfsr & DFSR_BKPT, DFSR_BKPT);
+        assert_eq!(processor.instruction_count, 0);
+    }
+
+    #[test]
+    fn test_match_fetch_top_of_address_space() {
+        // Arrange
+        let processor = enabled_processor();
+
+        // Act & Assert
+        assert_eq!(processor.fpb_match_fetch(0xffff_fffe), FpbMatch::None);
+    }
+}
@@ -1,17 +1,45 @@
 //!
 //! Cortex Instruction Trace Macrocell simulation
 //!
+//! Packets are buffered in a small FIFO that drains one byte per cycle. Packets
+//! that do not fit are lost and reported with an overflow packet.
+//!
 
+use crate::core::bits::Bits;
+use crate::peripheral::dcb::DEMCR_TRCENA;
+use crate::peripheral::tpiu::TracePortInterface;
 use crate::Processor;
+use crate::ProcessorMode;
+
+/// ITM enable
+pub const ITM_TCR_ITMENA: u32 = 1;
+/// Local timestamp generation enable
+pub const ITM_TCR_TSENA: u32 = 1 << 1;
+/// Synchronization packet transmission enable
+pub const ITM_TCR_SYNCENA: u32 = 1 << 2;
+/// Forwarding of DWT hardware source packets enable
+pub const ITM_TCR_DWTENA: u32 = 1 << 3;
+/// ITM is busy processing packets
+pub const ITM_TCR_BUSY: u32 = 1 << 23;
+
+const ITM_TCR_WRITE_MASK: u32 = 0x007f_0f1f;
+
+const ITM_LAR_KEY: u32 = 0xC5AC_CE55;
+
+/// Size of the ITM output FIFO in bytes
+pub const ITM_FIFO_SIZE: u32 = 32;
+
+const SYNC_PACKET: [u8; 6] = [0, 0, 0, 0, 0, 0x80];
+const OVERFLOW_PACKET: [u8; 1] = [0x70];
 
 ///
 /// ITM peripheral API via register access
 ///
 pub trait InstrumentationTraceMacrocell {
     ///
-    /// read value of stim0 register
+    /// read value of STIM register of given port
     ///
-    fn read_stim0(&self) -> u32;
+    fn read_stim(&self, port: u8) -> u32;
 
     ///
     /// write value with 32 bit width to given STIM port
@@ -36,10 +64,71 @@ pub trait InstrumentationTraceMacrocell {
     /// Value of 0xC5ACCE55 unlocks the access to debug registers.
     ///
     fn itm_write_lar_u32(&mut self, value: u32);
+
+    ///
+    /// read value of LSR register. (Lock Status Register)
+    ///
+    fn itm_read_lsr(&self) -> u32;
+
+    ///
+    /// read value of TER register. (Trace Enable Register)
+    ///
+    fn itm_read_ter(&self) -> u32;
+
+    ///
+    /// write value of TER register. Ignored while the registers are locked.
+    ///
+    fn itm_write_ter(&mut self, value: u32);
+
+    ///
+    /// read value of TPR register. (Trace Privilege Register)
+    ///
+    fn itm_read_tpr(&self) -> u32;
+
+    ///
+    /// write value of TPR register. Ignored while the registers are locked.
+    ///
+    fn itm_write_tpr(&mut self, value: u32);
+
+    ///
+    /// read value of TCR register. (Trace Control Register)
+    ///
+    fn itm_read_tcr(&self) -> u32;
+
+    ///
+    /// write value of TCR register. Ignored while the registers are locked.
+    ///
+    fn itm_write_tcr(&mut self, value: u32);
+
+    ///
+    /// Configure ITM like a trace probe would: enable the unit and all stimulus ports,
+    /// output via SWO without formatting
+    ///
+    fn itm_enable_all(&mut self);
+
+    ///
+    /// write a hardware source packet with given discriminator,
+    /// payload is 1, 2 or 4 bytes in little endian order.
+    /// Dropped unless forwarding of DWT packets is enabled.
+    ///
+    fn write_hardware_packet(&mut self, discriminator: u8, payload: &[u8]);
+
+    ///
+    /// write synchronization packet, if enabled
+    ///
+    fn itm_sync(&mut self);
+
+    ///
+    /// Clock ITM ```cycles```, drains the output FIFO.
+    ///
+    fn itm_tick(&mut self, cycles: u32);
 }
 
 trait InstrumentationTraceMacrocellHelper {
-    fn write_itm_packet(&mut self, packet: Vec<u8>);
+    fn write_itm_packet(&mut self, packet: &[u8]);
+    fn write_itm_bytes(&mut self, bytes: &[u8]);
+    fn itm_enabled(&self) -> bool;
+    fn write_stim(&mut self, port: u8, payload: &[u8]);
 }
 
 fn make_header(port: u8, payload_size: usize) -> u8 {
@@ -67,40 +156,308 @@ fn make_instrumentation_packet(port: u8, payload: &[u8]) -> Vec<u8> {
     packet
 }
 
+fn make_hardware_packet(discriminator: u8, payload: &[u8]) -> Vec<u8> {
+    // header is single byte
+    // ----------------------
+    // bits 0..=1 : SS, payload size = (1 << (SS -1)) bytes
+    // bit 2 = 1
+    // bits 3..=7: discriminator, packet type, 0-31
+    let mut packet = Vec::new();
+    packet.push(make_header(discriminator, payload.len()) | 0b100);
+    packet.extend(payload);
+    packet
+}
+
+fn make_local_timestamp_packet(delta: u64) -> Vec<u8> {
+    if delta < 7 {
+        // single byte format 2
+        return vec![(delta as u8) << 4];
+    }
+
+    if delta >= 1 << 28 {
+        // timestamp counter overflowed
+        return OVERFLOW_PACKET.to_vec();
+    }
+
+    // format 1, TC = 0b00: timestamp is synchronous to the data
+    // followed by 1-4 bytes of continuation encoded timestamp
+    let mut packet = vec![0b1100_0000];
+    let mut value = delta;
+    loop {
+        let byte = (value & 0x7f) as u8;
+        value >>= 7;
+        if value == 0 {
+            packet.push(byte);
+            break;
+        }
+        packet.push(byte | 0x80);
+    }
+    packet
+}
+
 impl InstrumentationTraceMacrocellHelper for Processor {
-    fn write_itm_packet(&mut self, packet: Vec<u8>) {
-        if let Some(f) = &mut self.itm_file {
-            f.write_all(packet.as_slice()).unwrap();
-            f.flush().unwrap();
+    fn write_itm_bytes(&mut self, bytes: &[u8]) {
+        self.itm_fifo_level += bytes.len() as u32;
+        let trace_id = self.itm_tcr.get_bits(16..23) as u8;
+        self.tpiu_write(trace_id, bytes);
+    }
+
+    fn write_itm_packet(&mut self, packet: &[u8]) {
+        if self.itm_fifo_level + packet.len() as u32 > ITM_FIFO_SIZE {
+            self.itm_overflow = true;
+            return;
+        }
+        if self.itm_overflow {
+            self.itm_overflow = false;
+            self.write_itm_bytes(&OVERFLOW_PACKET);
+        }
+        self.write_itm_bytes(packet);
+
+        if self.itm_tcr & ITM_TCR_TSENA != 0 {
+            let prescale = 2 * self.itm_tcr.get_bits(8..10);
+            let timestamp = self.cycle_count >> prescale;
+            let delta = timestamp - self.itm_timestamp;
+            if delta > 0 {
+                self.itm_timestamp = timestamp;
+                self.write_itm_bytes(&make_local_timestamp_packet(delta));
+            }
+        }
+    }
+
+    fn itm_enabled(&self) -> bool {
+        self.demcr & DEMCR_TRCENA != 0 && self.itm_tcr & ITM_TCR_ITMENA != 0
+    }
+
+    fn write_stim(&mut self, port: u8, payload: &[u8]) {
+        let port = port & 0b11111;
+        if !self.itm_enabled() || !self.itm_ter.get_bit(port as usize) {
+            return;
         }
+        let privileged = self.mode == ProcessorMode::HandlerMode || !self.control.n_priv;
+        if !privileged && self.itm_tpr.get_bit((port >> 3) as usize) {
+            return;
+        }
+        self.write_itm_packet(&make_instrumentation_packet(port, payload));
     }
 }
 
 impl InstrumentationTraceMacrocell for Processor {
-    fn read_stim0(&self) -> u32 {
+    fn read_stim(&self, _port: u8) -> u32 {
         // return 0 if fifo is full, 1 otherwise
-        1
+        if self.itm_fifo_level + 5 > ITM_FIFO_SIZE {
+            0
+        } else {
+            1
+        }
     }
 
     fn write_stim_u32(&mut self, port: u8, value: u32) {
-        let payload: [u8; 4] = [
-            (value & 0xff) as u8,
-            ((value & 0xff00) >> 8) as u8,
-            ((value & 0xff_0000) >> 16) as u8,
-            ((value & 0xff00_0000) >> 24) as u8,
-        ];
-        self.write_itm_packet(make_instrumentation_packet(port, &payload));
+        self.write_stim(port, &value.to_le_bytes());
+    }
+
+    fn itm_write_lar_u32(&mut self, value: u32) {
+        self.itm_locked = value != ITM_LAR_KEY;
+    }
+
+    fn itm_read_lsr(&self) -> u32 {
+        // SLI: lock implemented, SLK: locked
+        0b01 | (u32::from(self.itm_locked) << 1)
+    }
+
+    fn itm_read_ter(&self) -> u32 {
+        self.itm_ter
+    }
+
+    fn itm_write_ter(&mut self, value: u32) {
+        if !self.itm_locked {
+            self.itm_ter = value;
+        }
+    }
+
+    fn itm_read_tpr(&self) -> u32 {
+        self.itm_tpr
+    }
+
+    fn itm_write_tpr(&mut self, value: u32) {
+        if !self.itm_locked {
+            self.itm_tpr = value & 0xf;
+        }
+    }
+
+    fn itm_read_tcr(&self) -> u32 {
+        if self.itm_fifo_level > 0 {
+            self.itm_tcr | ITM_TCR_BUSY
+        } else {
+            self.itm_tcr
+        }
+    }
+
+    fn itm_write_tcr(&mut self, value: u32) {
+        if !self.itm_locked {
+            if value & !self.itm_tcr & ITM_TCR_TSENA != 0 {
+                // timestamp counter restarts
+                let prescale = 2 * value.get_bits(8..10);
+                self.itm_timestamp = self.cycle_count >> prescale;
+            }
+            self.itm_tcr = value & ITM_TCR_WRITE_MASK;
+        }
     }
 
-    fn itm_write_lar_u32(&mut self, _value: u32) {}
+    fn itm_enable_all(&mut self) {
+        self.demcr |= DEMCR_TRCENA;
+        self.itm_tcr |= ITM_TCR_ITMENA;
+        self.itm_ter = 0xffff_ffff;
+        self.tpiu_configure_swo();
+    }
 
     fn write_stim_u16(&mut self, port: u8, value: u16) {
-        let payload: [u8; 2] = [(value & 0xff) as u8, ((value & 0xff00) >> 8) as u8];
-        self.write_itm_packet(make_instrumentation_packet(port, &payload));
+        self.write_stim(port, &value.to_le_bytes());
     }
 
     fn write_stim_u8(&mut self, port: u8, value: u8) {
-        let payload: [u8; 1] = [value];
-        self.write_itm_packet(make_instrumentation_packet(port, &payload));
+        self.write_stim(port, &[value]);
+    }
+
+    fn write_hardware_packet(&mut self, discriminator: u8, payload: &[u8]) {
+        if self.itm_enabled() && self.itm_tcr & ITM_TCR_DWTENA != 0 {
+            self.write_itm_packet(&make_hardware_packet(discriminator, payload));
+        }
+    }
+
+    fn itm_sync(&mut self) {
+        if self.itm_enabled() && self.itm_tcr & ITM_TCR_SYNCENA != 0 {
+            self.itm_overflow = false;
+            self.write_itm_bytes(&SYNC_PACKET);
+        }
+    }
+
+    #[inline(always)]
+    fn itm_tick(&mut self, cycles: u32) {
+        self.itm_fifo_level = self.itm_fifo_level.saturating_sub(cycles);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::reset::Reset;
+    use std::cell::RefCell;
+    use std::io;
+    use std::rc::Rc;
+
+    #[derive(Clone)]
+    struct SharedBuffer(Rc<RefCell<Vec<u8>>>);
+
+    impl io::Write for SharedBuffer {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.borrow_mut().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn itm_processor() -> (Processor, Rc<RefCell<Vec<u8>>>) {
+        let output = Rc::new(RefCell::new(Vec::new()));
+        let mut processor = Processor::new();
+        processor.itm(Some(Box::new(SharedBuffer(output.clone()))));
+        processor.reset().unwrap();
+        (processor, output)
+    }
+
+    #[test]
+    fn test_stimulus_packets() {
+        // Arrange
+        let (mut processor, output) = itm_processor();
+
+        // Act
+        processor.write_stim_u8(0, 0x41);
+        processor.write_stim_u16(1, 0x1234);
+        processor.write_stim_u32(31, 0xdead_beef);
+
+        // Assert
+        assert_eq!(
+            *output.borrow(),
+            vec![0x01, 0x41, 0x0a, 0x34, 0x12, 0xfb, 0xef, 0xbe, 0xad, 0xde]
+        );
+    }
+
+    #[test]
+    fn test_trace_enable_and_lock() {
+        // Arrange
+        let (mut processor, output) = itm_processor();
+
+        // Act
+        // ignored, registers are locked
+        processor.itm_write_ter(0);
+        processor.write_stim_u8(0, 1);
+        processor.itm_write_lar_u32(ITM_LAR_KEY);
+        processor.itm_write_ter(0b10);
+        processor.write_stim_u8(0, 2);
+        processor.write_stim_u8(1, 3);
+
+        // Assert
+        assert_eq!(*output.borrow(), vec![0x01, 1, 0x09, 3]);
+        assert_eq!(processor.itm_read_lsr(), 0b01);
+    }
+
+    #[test]
+    fn test_local_timestamps() {
+        // Arrange
+        let (mut processor, output) = itm_processor();
+        processor.itm_write_lar_u32(ITM_LAR_KEY);
+        processor.itm_write_tcr(ITM_TCR_ITMENA | ITM_TCR_TSENA);
+
+        // Act
+        processor.cycle_count += 3;
+        processor.write_stim_u8(0, 1);
+        processor.itm_tick(100);
+        processor.cycle_count += 200;
+        processor.write_stim_u8(0, 2);
+
+        // Assert
+        assert_eq!(
+            *output.borrow(),
+            vec![0x01, 1, 0x30, 0x01, 2, 0xc0, 0xc8, 0x01]
+        );
+    }
+
+    #[test]
+    fn test_overflow() {
+        // Arrange
+        let (mut processor, output) = itm_processor();
+
+        // Act
+        for _ in 0..7 {
+            processor.write_stim_u32(0, 0);
+        }
+        let full = processor.read_stim(0);
+        processor.itm_tick(ITM_FIFO_SIZE);
+        let ready = processor.read_stim(0);
+        processor.write_stim_u8(0, 0x55);
+
+        // Assert
+        assert_eq!(full, 0);
+        assert_eq!(ready, 1);
+        assert_eq!(output.borrow().len(), 6 * 5 + 3);
+        assert_eq!(output.borrow()[30..], [0x70, 0x01, 0x55]);
+    }
+
+    #[test]
+    fn test_hardware_packets_need_dwtena() {
+        // Arrange
+        let (mut processor, output) = itm_processor();
+
+        // Act
+        processor.write_hardware_packet(2, &[0]);
+        processor.itm_write_lar_u32(ITM_LAR_KEY);
+        processor.itm_write_tcr(ITM_TCR_ITMENA | ITM_TCR_DWTENA | ITM_TCR_SYNCENA);
+        processor.itm_sync();
+        processor.write_hardware_packet(2, &[0]);
+
+        // Assert
+        assert_eq!(*output.borrow(), vec![0, 0, 0, 0, 0, 0x80, 0x15, 0x00]);
     }
 }
@@ -2,8 +2,11 @@
 //! Cortex Core Peripherals simulation
 //!
 
+pub mod dcb;
 pub mod dwt;
+pub mod fpb;
 pub mod itm;
 pub mod nvic;
 pub mod scb;
 pub mod systick;
+pub mod tpiu;
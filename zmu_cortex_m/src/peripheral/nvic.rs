@@ -3,11 +3,19 @@
 //!
 
 use crate::core::bits::Bits;
+use crate::core::event::EventRegister;
 use crate::core::exception::Exception;
 use crate::core::exception::ExceptionHandling;
 use crate::Processor;
+use std::ops::Range;
 use Exception::Interrupt;
 
+/// Maximum number of external interrupts supported by the NVIC
+pub const NVIC_MAX_INTERRUPTS: usize = 496;
+
+/// Maximum number of external interrupts supported by the ARMv6-M NVIC
+pub const NVIC_MAX_INTERRUPTS_ARMV6M: usize = 32;
+
 ///
 /// Register API for NVIC
 ///
@@ -92,33 +100,39 @@ pub trait NVIC {
     /// Mark interrupt no longer pending in NVIC point of view.
     ///
     fn nvic_unpend_interrupt(&mut self, irqn: usize);
+
+    ///
+    /// Mask of the implemented bits of a priority value, the unimplemented
+    /// low order bits read as zero.
+    ///
+    fn nvic_priority_mask(&self) -> u8;
 }
 
 trait NVICHelper {
+    fn nvic_implemented(&self, index: usize) -> u32;
+    fn nvic_irqns(&self, index: usize) -> Range<usize>;
     fn nvic_set_pending_exceptions(&mut self, index: usize);
     fn nvic_clear_unpended_exceptions(&mut self, index: usize);
 }
 
-fn set_bits_array(array: &mut [u32; 16], index: usize, value: u32) {
-    if index == 15 {
-        array[index] |= value & 0xffff;
-    } else {
-        array[index] |= value;
+impl NVICHelper for Processor {
+    // bit mask of the implemented interrupts in the 32 interrupt block ```index```
+    fn nvic_implemented(&self, index: usize) -> u32 {
+        match self.nvic_irqns(index).len() {
+            32 => 0xffff_ffff,
+            count => (1 << count) - 1,
+        }
     }
-}
 
-fn clear_bits_array(array: &mut [u32; 16], index: usize, value: u32) {
-    if index == 15 {
-        array[index] &= (value & 0xffff) ^ 0xFFFF_FFFF;
-    } else {
-        array[index] &= value ^ 0xFFFF_FFFF;
+    // implemented interrupt numbers of the 32 interrupt block ```index```
+    fn nvic_irqns(&self, index: usize) -> Range<usize> {
+        let first_irqn = (index * 32).min(self.nvic_num_interrupts);
+        first_irqn..(first_irqn + 32).min(self.nvic_num_interrupts)
     }
-}
 
-impl NVICHelper for Processor {
     fn nvic_set_pending_exceptions(&mut self, index: usize) {
         let mut active = self.nvic_interrupt_pending[index] & self.nvic_interrupt_enabled[index];
-        let mut irqn = index * 4;
+        let mut irqn = index * 32;
         while active != 0 {
             if active & 1 != 0 {
                 self.set_exception_pending(Interrupt { n: irqn });
@@ -130,7 +144,7 @@ impl NVICHelper for Processor {
 
     fn nvic_clear_unpended_exceptions(&mut self, index: usize) {
         let mut active = self.nvic_interrupt_pending[index] & self.nvic_interrupt_enabled[index];
-        for irqn in (index * 4)..(index * 4) + 32 {
+        for irqn in self.nvic_irqns(index) {
             if active & 1 == 0 {
                 self.clear_pending_exception(Interrupt { n: irqn });
             }
@@ -141,7 +155,7 @@ impl NVICHelper for Processor {
 
 impl NVIC for Processor {
     fn nvic_write_iser(&mut self, index: usize, value: u32) {
-        set_bits_array(&mut self.nvic_interrupt_enabled, index, value);
+        self.nvic_interrupt_enabled[index] |= value & self.nvic_implemented(index);
         self.nvic_set_pending_exceptions(index);
     }
 
@@ -150,14 +164,12 @@ impl NVIC for Processor {
     }
 
     fn nvic_write_icer(&mut self, index: usize, value: u32) {
-        clear_bits_array(&mut self.nvic_interrupt_enabled, index, value);
+        self.nvic_interrupt_enabled[index] &= !value;
         self.nvic_clear_unpended_exceptions(index);
     }
 
     fn nvic_unpend_interrupt(&mut self, irqn: usize) {
-        let index = irqn / 32;
-        let bit = irqn % 32;
-        clear_bits_array(&mut self.nvic_interrupt_pending, index, 1 << bit);
+        self.nvic_interrupt_pending[irqn / 32] &= !(1 << (irqn % 32));
     }
 
     fn nvic_read_icer(&self, index: usize) -> u32 {
@@ -165,7 +177,11 @@ impl NVIC for Processor {
     }
 
     fn nvic_write_ispr(&mut self, index: usize, value: u32) {
-        set_bits_array(&mut self.nvic_interrupt_pending, index, value);
+        let pending = self.nvic_interrupt_pending[index];
+        self.nvic_interrupt_pending[index] |= value & self.nvic_implemented(index);
+        if self.nvic_interrupt_pending[index] != pending {
+            self.set_event_on_pend();
+        }
         self.nvic_set_pending_exceptions(index);
     }
 
@@ -174,7 +190,7 @@ impl NVIC for Processor {
     }
 
     fn nvic_write_icpr(&mut self, index: usize, value: u32) {
-        clear_bits_array(&mut self.nvic_interrupt_pending, index, value);
+        self.nvic_interrupt_pending[index] &= !value;
         self.nvic_clear_unpended_exceptions(index);
     }
 
@@ -183,10 +199,9 @@ impl NVIC for Processor {
     }
 
     fn nvic_read_iabr(&self, index: usize) -> u32 {
-        let first_irqn = index * 32;
         let mut active = 0;
         let mut mask = 1;
-        for irqn in first_irqn..first_irqn + 32 {
+        for irqn in self.nvic_irqns(index) {
             if self.exception_active(Interrupt { n: irqn }) {
                 active |= mask;
             }
@@ -220,19 +235,30 @@ impl NVIC for Processor {
     }
 
     fn nvic_read_ipr_u8(&self, index: usize) -> u8 {
+        if index >= self.nvic_num_interrupts {
+            return 0;
+        }
         let priority = self.get_exception_priority(Interrupt { n: index });
         assert!(priority >= 0 && priority < 256);
         priority as u8
     }
 
     fn nvic_write_ipr_u8(&mut self, index: usize, value: u8) {
-        self.set_exception_priority(Interrupt { n: index }, value);
+        if index < self.nvic_num_interrupts {
+            self.set_exception_priority(Interrupt { n: index }, value);
+        }
+    }
+
+    fn nvic_priority_mask(&self) -> u8 {
+        0xff << (8 - self.nvic_priority_bits)
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::bus::Bus;
+    use crate::core::cpu::Cpu;
     use crate::core::exception::ExceptionHandling;
     use crate::core::instruction::Instruction;
     use crate::core::reset::Reset;
@@ -443,4 +469,111 @@ mod tests {
             assert_eq!(processor.nvic_read_ipr_u16(n), value);
         }
     }
+
+    #[test]
+    fn test_nvic_ipr_bus_u8() {
+        // Arrange
+        let mut processor = Processor::new();
+        processor.reset().unwrap();
+
+        // Act
+        processor.write8(0xE000_E401, 0xa0).unwrap(); // IRQ 1
+        processor.write8(0xE000_E5EF, 0xc0).unwrap(); // IRQ 495
+
+        // Assert
+        assert_eq!(processor.read8(0xE000_E400).unwrap(), 0);
+        assert_eq!(processor.read8(0xE000_E401).unwrap(), 0xa0);
+        assert_eq!(processor.read8(0xE000_E5EF).unwrap(), 0xc0);
+        assert_eq!(processor.get_exception_priority(Interrupt { n: 1 }), 0xa0);
+        assert_eq!(processor.get_exception_priority(Interrupt { n: 495 }), 0xc0);
+    }
+
+    #[test]
+    fn test_nvic_ipr_bus_u16() {
+        // Arrange
+        let mut processor = Processor::new();
+        processor.reset().unwrap();
+
+        // Act
+        processor.write16(0xE000_E400, 0xa000).unwrap(); // IRQ 0 and 1
+        processor.write16(0xE000_E5EE, 0xc060).unwrap(); // IRQ 494 and 495
+
+        // Assert
+        assert_eq!(processor.read16(0xE000_E400).unwrap(), 0xa000);
+        assert_eq!(processor.read16(0xE000_E5EE).unwrap(), 0xc060);
+        assert_eq!(processor.get_exception_priority(Interrupt { n: 1 }), 0xa0);
+        assert_eq!(processor.get_exception_priority(Interrupt { n: 494 }), 0x60);
+        assert_eq!(processor.get_exception_priority(Interrupt { n: 495 }), 0xc0);
+    }
+
+    #[test]
+    fn test_nvic_high_interrupts() {
+        // Arrange
+        let mut processor = Processor::new();
+        processor.reset().unwrap();
+
+        // Act
+        processor.write32(0xE000_E104, 1 << 1).unwrap(); // ISER1: irq 33
+        processor.write32(0xE000_E204, 1 << 1).unwrap(); // ISPR1
+        processor.write32(0xE000_EF00, 495).unwrap(); // STIR
+
+        // Assert
+        assert_eq!(processor.read32(0xE000_E004).unwrap(), 15);
+        assert_eq!(processor.nvic_read_iser(0), 0);
+        assert_eq!(processor.nvic_read_ispr(1), 1 << 1);
+        assert_eq!(processor.nvic_read_ispr(15), 1 << 15);
+        assert!(processor.exception_pending(Interrupt { n: 33 }));
+        assert!(!processor.exception_pending(Interrupt { n: 495 }));
+        assert_eq!(processor.get_pending_exception(), Some(Interrupt { n: 33 }));
+    }
+
+    #[test]
+    fn test_nvic_configured_interrupts() {
+        // Arrange
+        let mut processor = Processor::new();
+        processor.nvic(40, 4);
+
+        // Act
+        processor.write32(0xE000_E104, 0xffff_ffff).unwrap();
+        processor.write32(0xE000_E108, 0xffff_ffff).unwrap();
+        processor.nvic_write_ipr_u8(39, 0xff);
+        processor.nvic_write_ipr_u8(40, 0xff);
+
+        // Assert
+        assert_eq!(processor.read32(0xE000_E004).unwrap(), 1);
+        assert_eq!(processor.nvic_read_iser(1), 0xff);
+        assert_eq!(processor.nvic_read_iser(2), 0);
+        assert_eq!(processor.nvic_read_ipr_u8(39), 0xf0);
+        assert_eq!(processor.nvic_read_ipr_u8(40), 0);
+    }
+
+    #[test]
+    fn test_nvic_armv6m_limits() {
+        // Arrange
+        let mut processor = Processor::new();
+        processor.cpu(Cpu::CortexM0);
+
+        // Act
+        processor.nvic(NVIC_MAX_INTERRUPTS, 8);
+        processor.write32(0xE000_E100, 0xffff_ffff).unwrap();
+        processor.write32(0xE000_E104, 0xffff_ffff).unwrap();
+        processor.write32(0xE000_E400, 0xffff_ffff).unwrap();
+        processor.nvic_write_ipr_u8(32, 0xff);
+
+        // Assert
+        assert_eq!(processor.ictr, 0);
+        assert_eq!(processor.nvic_read_iser(0), 0xffff_ffff);
+        assert_eq!(processor.nvic_read_iser(1), 0);
+        assert_eq!(processor.nvic_read_ipr(0), 0xc0c0_c0c0);
+        assert_eq!(processor.nvic_read_ipr_u8(32), 0);
+
+        // Act: the ARMv7-M limits apply again
+        processor.cpu(Cpu::CortexM3);
+        processor.nvic(NVIC_MAX_INTERRUPTS + 1, 1);
+        processor.nvic_write_ipr_u8(32, 0xff);
+
+        // Assert
+        assert_eq!(processor.ictr, 15);
+        assert_eq!(processor.nvic_read_ipr_u8(32), 0xe0);
+    }
 }
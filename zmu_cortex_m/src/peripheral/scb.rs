@@ -3,12 +3,48 @@
 //!
 
 use crate::core::bits::Bits;
+use crate::core::cpu::Cpu;
 use crate::core::exception::Exception;
 use crate::core::exception::ExceptionHandling;
+use crate::peripheral::nvic::NVIC;
 use crate::Processor;
 
 use crate::core::register::Ipsr;
 
+/// SCR: sleep on return from handler to thread mode
+pub const SCR_SLEEPONEXIT: u32 = 1 << 1;
+/// SCR: sleep is deep sleep
+pub const SCR_SLEEPDEEP: u32 = 1 << 2;
+/// SCR: interrupts becoming pending are wakeup events for WFE
+pub const SCR_SEVONPEND: u32 = 1 << 4;
+
+/// AIRCR: key that must be written to the upper halfword for the write to take effect
+pub const AIRCR_VECTKEY: u32 = 0x05FA;
+/// AIRCR: value read from the upper halfword
+pub const AIRCR_VECTKEYSTAT: u32 = 0xFA05;
+
+/// CCR: thread mode can be entered with exceptions active
+pub const CCR_NONBASETHRDENA: u32 = 1;
+/// CCR: unprivileged software can write STIR
+pub const CCR_USERSETMPEND: u32 = 1 << 1;
+/// CCR: trap unaligned halfword and word accesses
+pub const CCR_UNALIGN_TRP: u32 = 1 << 3;
+/// CCR: trap integer divide by zero
+pub const CCR_DIV_0_TRP: u32 = 1 << 4;
+/// CCR: ignore precise bus faults in handlers running at priority -1 or -2
+pub const CCR_BFHFNMIGN: u32 = 1 << 8;
+/// CCR: align the stack to 8 bytes on exception entry
+pub const CCR_STKALIGN: u32 = 1 << 9;
+
+/// CCR: reset value of `cpu`, armv6m always traps unaligned accesses
+pub fn ccr_reset(cpu: Cpu) -> u32 {
+    if cpu.armv6m() {
+        CCR_STKALIGN | CCR_UNALIGN_TRP
+    } else {
+        CCR_STKALIGN
+    }
+}
+
 ///
 /// Register based API to SCB
 ///
@@ -28,112 +64,99 @@ pub trait SystemControlBlock {
     ///
     fn write_vtor(&mut self, value: u32);
 
+    ///
+    /// Write Application Interrupt and Reset Control Register
+    ///
+    fn write_aircr(&mut self, value: u32);
+
     ///
     /// Write System Handler Priority Register 1
     ///
-    #[cfg(any(armv7m, armv7em))]
     fn write_shpr1(&mut self, value: u32);
 
     ///
     /// Write System Handler Priority Register 2
     ///
-    #[cfg(any(armv7m, armv7em))]
     fn write_shpr2(&mut self, value: u32);
 
     ///
     /// Write System Handler Priority Register 3
     ///
-    #[cfg(any(armv7m, armv7em))]
     fn write_shpr3(&mut self, value: u32);
 
     ///
     /// Write System Handler Priority Register 1, 8-bit access
     ///
-    #[cfg(any(armv7m, armv7em))]
     fn write_shpr1_u8(&mut self, offset: usize, value: u8);
 
     ///
     /// Write System Handler Priority Register 2, 8-bit access
     ///
-    #[cfg(any(armv7m, armv7em))]
     fn write_shpr2_u8(&mut self, offset: usize, value: u8);
 
     ///
     /// Write System Handler Priority Register 3, 8-bit access
     ///
-    #[cfg(any(armv7m, armv7em))]
     fn write_shpr3_u8(&mut self, offset: usize, value: u8);
 
     ///
     /// Write System Handler Priority Register 1, 16-bit access
     ///
-    #[cfg(any(armv7m, armv7em))]
     fn write_shpr1_u16(&mut self, offset: usize, value: u16);
 
     ///
     /// Write System Handler Priority Register 2, 16-bit access
     ///
-    #[cfg(any(armv7m, armv7em))]
     fn write_shpr2_u16(&mut self, offset: usize, value: u16);
 
     ///
     /// Write System Handler Priority Register 3, 16-bit access
     ///
-    #[cfg(any(armv7m, armv7em))]
     fn write_shpr3_u16(&mut self, offset: usize, value: u16);
 
     ///
     /// Read System Handler Priority Register 1
     ///
-    #[cfg(any(armv7m, armv7em))]
     fn read_shpr1(&self) -> u32;
 
     ///
     /// Read System Handler Priority Register 2
     ///
-    #[cfg(any(armv7m, armv7em))]
     fn read_shpr2(&self) -> u32;
 
     ///
     /// Read System Handler Priority Register 3
     ///
-    #[cfg(any(armv7m, armv7em))]
     fn read_shpr3(&self) -> u32;
 
     ///
     /// Read System Handler Priority Register 1, 8-bit access
     ///
-    #[cfg(any(armv7m, armv7em))]
     fn read_shpr1_u8(&self, offset: usize) -> u8;
 
     ///
     /// Read System Handler Priority Register 2, 8-bit access
     ///
-    #[cfg(any(armv7m, armv7em))]
     fn read_shpr2_u8(&self, offset: usize) -> u8;
 
     ///
     /// Read System Handler Priority Register 3, 8-bit access
     ///
-    #[cfg(any(armv7m, armv7em))]
     fn read_shpr3_u8(&self, offset: usize) -> u8;
 
     ///
     /// Read System Handler Priority Register 1, 16-bit access
     ///
-    #[cfg(any(armv7m, armv7em))]
     fn read_shpr1_u16(&self, offset: usize) -> u16;
 
     ///
     /// Read System Handler Priority Register 2, 16-bit access
     ///
-    #[cfg(any(armv7m, armv7em))]
     fn read_shpr2_u16(&self, offset: usize) -> u16;
 
     ///
     /// Read System Handler Priority Register 3, 16-bit access
     ///
-    #[cfg(any(armv7m, armv7em))]
     fn read_shpr3_u16(&self, offset: usize) -> u16;
 
     ///
@@ -142,20 +165,35 @@ pub trait SystemControlBlock {
     fn write_scr(&mut self, value: u32);
 
     ///
-    /// Write Debug Exception and Monitor Control Register
+    /// Write System Handler Control and State Register
+    ///
+    fn write_shcsr(&mut self, value: u32);
+
+    ///
+    /// Write Configuration and Control Register
+    ///
+    fn write_ccr(&mut self, value: u32);
+
+    ///
+    /// Write Configurable Fault Status Register, status bits are cleared by writing one
     ///
-    fn write_demcr(&mut self, value: u32);
+    fn write_cfsr(&mut self, value: u32);
 
     ///
-    /// Read Debug Exception and Monitor Control Register
+    /// Write HardFault Status Register, status bits are cleared by writing one
     ///
-    fn read_demcr(&self) -> u32;
+    fn write_hfsr(&mut self, value: u32);
 
     ///
     /// Read Vector Table Offset
     ///
     fn read_vtor(&self) -> u32;
 
+    ///
+    /// Read Application Interrupt and Reset Control Register
+    ///
+    fn read_aircr(&self) -> u32;
+
     ///
     /// Read System Control Register
     ///
@@ -164,7 +202,6 @@ pub trait SystemControlBlock {
     ///
     /// Write "Software Triggered Interrupt Register"
     ///
-    #[cfg(any(armv7m, armv7em))]
     fn write_stir(&mut self, value: u32);
 }
 
@@ -201,7 +238,17 @@ impl SystemControlBlock for Processor {
         self.vtor = value
     }
 
-    #[cfg(any(armv7m, armv7em))]
+    fn write_aircr(&mut self, value: u32) {
+        if value.get_bits(16..32) != AIRCR_VECTKEY {
+            return;
+        }
+        // priority grouping is not part of armv6m
+        if self.cpu.armv7m() {
+            self.aircr.set_bits(8..11, value.get_bits(8..11));
+            self.execution_priority = self.get_execution_priority();
+        }
+    }
+
     fn write_shpr1(&mut self, value: u32) {
         self.write_shpr1_u8(0, value.get_bits(0..8) as u8);
         self.write_shpr1_u8(1, value.get_bits(8..16) as u8);
@@ -209,7 +256,6 @@ impl SystemControlBlock for Processor {
         self.write_shpr1_u8(3, value.get_bits(24..32) as u8);
     }
 
-    #[cfg(any(armv7m, armv7em))]
     fn write_shpr2(&mut self, value: u32) {
         self.write_shpr2_u8(0, value.get_bits(0..8) as u8);
         self.write_shpr2_u8(1, value.get_bits(8..16) as u8);
@@ -217,7 +263,6 @@ impl SystemControlBlock for Processor {
         self.write_shpr2_u8(3, value.get_bits(24..32) as u8);
     }
 
-    #[cfg(any(armv7m, armv7em))]
     fn write_shpr3(&mut self, value: u32) {
         self.write_shpr3_u8(0, value.get_bits(0..8) as u8);
         self.write_shpr3_u8(1, value.get_bits(8..16) as u8);
@@ -225,7 +270,6 @@ impl SystemControlBlock for Processor {
         self.write_shpr3_u8(3, value.get_bits(24..32) as u8);
     }
 
-    #[cfg(any(armv7m, armv7em))]
     fn write_shpr1_u16(&mut self, offset: usize, value: u16) {
         match offset {
             0 | 1 => {
@@ -237,7 +281,6 @@ impl SystemControlBlock for Processor {
         }
     }
 
-    #[cfg(any(armv7m, armv7em))]
     fn write_shpr2_u16(&mut self, offset: usize, value: u16) {
         match offset {
             0 | 1 => {
@@ -249,7 +292,6 @@ impl SystemControlBlock for Processor {
         }
     }
 
-    #[cfg(any(armv7m, armv7em))]
     fn write_shpr3_u16(&mut self, offset: usize, value: u16) {
         match offset {
             0 | 1 => {
@@ -261,7 +303,6 @@ impl SystemControlBlock for Processor {
         }
     }
 
-    #[cfg(any(armv7m, armv7em))]
     fn write_shpr1_u8(&mut self, offset: usize, value: u8) {
         match offset {
             0 => self.set_exception_priority(Exception::MemoryManagementFault, value),
@@ -271,14 +312,12 @@ impl SystemControlBlock for Processor {
         }
     }
 
-    #[cfg(any(armv7m, armv7em))]
     fn write_shpr2_u8(&mut self, offset: usize, value: u8) {
         if 3 == offset {
             self.set_exception_priority(Exception::SVCall, value);
         }
     }
 
-    #[cfg(any(armv7m, armv7em))]
     fn write_shpr3_u8(&mut self, offset: usize, value: u8) {
         match offset {
             0 => self.set_exception_priority(Exception::DebugMonitor, value),
@@ -289,12 +328,32 @@ impl SystemControlBlock for Processor {
     }
 
     fn write_scr(&mut self, value: u32) {
-        self.scr = value;
+        self.scr = value & (SCR_SLEEPONEXIT | SCR_SLEEPDEEP | SCR_SEVONPEND);
     }
 
-    fn write_demcr(&mut self, _value: u32) {}
+    fn write_shcsr(&mut self, value: u32) {
+        // only the MemManage, BusFault and UsageFault enable bits are modeled
+        self.shcsr.set_bits(16..19, value.get_bits(16..19));
+    }
+
+    fn write_ccr(&mut self, value: u32) {
+        let mask = CCR_NONBASETHRDENA
+            | CCR_USERSETMPEND
+            | CCR_UNALIGN_TRP
+            | CCR_DIV_0_TRP
+            | CCR_BFHFNMIGN
+            | CCR_STKALIGN;
+        self.ccr = value & mask;
+    }
+
+    fn write_cfsr(&mut self, value: u32) {
+        self.cfsr &= !value;
+    }
+
+    fn write_hfsr(&mut self, value: u32) {
+        self.hfsr &= !value;
+    }
 
-    #[cfg(any(armv7m, armv7em))]
     fn read_shpr1(&self) -> u32 {
         (u32::from(self.read_shpr1_u8(3)) << 24)
             + (u32::from(self.read_shpr1_u8(2)) << 16)
@@ -302,7 +361,6 @@ impl SystemControlBlock for Processor {
             + u32::from(self.read_shpr1_u8(0))
     }
 
-    #[cfg(any(armv7m, armv7em))]
     fn read_shpr2(&self) -> u32 {
         (u32::from(self.read_shpr2_u8(3)) << 24)
             + (u32::from(self.read_shpr2_u8(2)) << 16)
@@ -310,7 +368,6 @@ impl SystemControlBlock for Processor {
             + u32::from(self.read_shpr2_u8(0))
     }
 
-    #[cfg(any(armv7m, armv7em))]
     fn read_shpr3(&self) -> u32 {
         (u32::from(self.read_shpr3_u8(3)) << 24)
             + (u32::from(self.read_shpr3_u8(2)) << 16)
@@ -318,7 +375,6 @@ impl SystemControlBlock for Processor {
             + u32::from(self.read_shpr3_u8(0))
     }
 
-    #[cfg(any(armv7m, armv7em))]
     fn read_shpr1_u8(&self, offset: usize) -> u8 {
         match offset {
             0 => self.get_exception_priority(Exception::MemoryManagementFault) as u8,
@@ -328,7 +384,6 @@ impl SystemControlBlock for Processor {
         }
     }
 
-    #[cfg(any(armv7m, armv7em))]
     fn read_shpr2_u8(&self, offset: usize) -> u8 {
         match offset {
             3 => self.get_exception_priority(Exception::SVCall) as u8,
@@ -336,7 +391,6 @@ impl SystemControlBlock for Processor {
         }
     }
 
-    #[cfg(any(armv7m, armv7em))]
     fn read_shpr3_u8(&self, offset: usize) -> u8 {
         match offset {
             0 => self.get_exception_priority(Exception::DebugMonitor) as u8,
@@ -346,7 +400,6 @@ impl SystemControlBlock for Processor {
         }
     }
 
-    #[cfg(any(armv7m, armv7em))]
     fn read_shpr1_u16(&self, offset: usize) -> u16 {
         match offset {
             0 | 1 => {
@@ -357,7 +410,6 @@ impl SystemControlBlock for Processor {
         }
     }
 
-    #[cfg(any(armv7m, armv7em))]
     fn read_shpr2_u16(&self, offset: usize) -> u16 {
         match offset {
             0 | 1 => {
@@ -368,7 +420,6 @@ impl SystemControlBlock for Processor {
         }
     }
 
-    #[cfg(any(armv7m, armv7em))]
     fn read_shpr3_u16(&self, offset: usize) -> u16 {
         match offset {
             0 | 1 => {
@@ -379,34 +430,53 @@ impl SystemControlBlock for Processor {
         }
     }
 
+    fn read_aircr(&self) -> u32 {
+        (AIRCR_VECTKEYSTAT << 16) | self.aircr
+    }
+
     fn read_scr(&self) -> u32 {
-        0
+        self.scr
     }
     fn read_vtor(&self) -> u32 {
         self.vtor
     }
 
-    fn read_demcr(&self) -> u32 {
-        0
-    }
-
-    #[cfg(any(armv7m, armv7em))]
     fn write_stir(&mut self, value: u32) {
-        self.set_exception_pending(Exception::Interrupt {
-            n: value.get_bits(0..9) as usize,
-        });
+        // same as setting the pending bit in NVIC ISPR
+        let irqn = value.get_bits(0..9) as usize;
+        self.nvic_write_ispr(irqn / 32, 1 << (irqn % 32));
     }
 }
 
 #[cfg(test)]
-#[cfg(any(armv7m, armv7em))]
 mod tests {
     use super::*;
+    use crate::bus::Bus;
     use crate::core::exception::Exception;
     use crate::core::exception::ExceptionHandling;
 
     #[test]
-    #[cfg(any(armv7m, armv7em))]
+    fn test_cfsr_write_one_to_clear() {
+        // Arrange
+        let mut processor = Processor::new();
+        processor.cfsr = 0x0102_8200;
+
+        // Act
+        processor.write8(0xE000_ED29, 0x02).unwrap();
+
+        // Assert
+        assert_eq!(processor.cfsr, 0x0102_8000);
+        assert_eq!(processor.read8(0xE000_ED29).unwrap(), 0x80);
+        assert_eq!(processor.read16(0xE000_ED2A).unwrap(), 0x0102);
+
+        // Act
+        processor.write32(0xE000_ED28, 0xffff_ffff).unwrap();
+
+        // Assert
+        assert_eq!(processor.cfsr, 0);
+    }
+
+    #[test]
     fn test_shpr_read_write_32() {
         // Arrange
         let mut processor = Processor::new();
@@ -443,7 +513,6 @@ mod tests {
     }
 
     #[test]
-    #[cfg(any(armv7m, armv7em))]
     fn test_shpr_read_write_16() {
         // Arrange
         let mut processor = Processor::new();
@@ -0,0 +1,309 @@
+//!
+//! Cortex Trace Port Interface Unit simulation
+//!
+//! Trace data from the ITM is written to the trace output either as is
+//! (SWO with formatter bypassed) or wrapped in 16 byte formatter frames.
+//! The output is not rate limited by the SWO prescaler.
+//!
+
+use crate::core::bits::Bits;
+use crate::Processor;
+
+/// Selected pin protocol: parallel trace port
+pub const TPIU_SPPR_PARALLEL: u32 = 0;
+/// Selected pin protocol: SWO, Manchester encoding
+pub const TPIU_SPPR_SWO_MANCHESTER: u32 = 1;
+/// Selected pin protocol: SWO, NRZ encoding
+pub const TPIU_SPPR_SWO_NRZ: u32 = 2;
+
+/// Continuous formatting enable
+pub const TPIU_FFCR_ENFCONT: u32 = 1 << 1;
+/// Trigger on trigger in
+pub const TPIU_FFCR_TRIGIN: u32 = 1 << 8;
+
+/// Reset value of the formatter and flush control register
+pub const TPIU_FFCR_RESET: u32 = TPIU_FFCR_TRIGIN | TPIU_FFCR_ENFCONT;
+
+// formatter frame synchronization packet
+const FRAME_SYNC: [u8; 4] = [0xff, 0xff, 0xff, 0x7f];
+
+// a frame synchronization packet is emitted every this many frames
+const FRAME_SYNC_INTERVAL: u32 = 32;
+
+// trace source id for padding
+const NULL_ID: u8 = 0;
+
+/// Register API to Trace Port Interface Unit
+pub trait TracePortInterface {
+    ///
+    /// read supported parallel port sizes register
+    ///
+    fn tpiu_read_sspsr(&self) -> u32;
+
+    ///
+    /// read current parallel port size register
+    ///
+    fn tpiu_read_cspsr(&self) -> u32;
+
+    ///
+    /// write current parallel port size register
+    ///
+    fn tpiu_write_cspsr(&mut self, value: u32);
+
+    ///
+    /// read asynchronous clock prescaler register
+    ///
+    fn tpiu_read_acpr(&self) -> u32;
+
+    ///
+    /// write asynchronous clock prescaler register
+    ///
+    fn tpiu_write_acpr(&mut self, value: u32);
+
+    ///
+    /// read selected pin protocol register
+    ///
+    fn tpiu_read_sppr(&self) -> u32;
+
+    ///
+    /// write selected pin protocol register
+    ///
+    fn tpiu_write_sppr(&mut self, value: u32);
+
+    ///
+    /// read formatter and flush status register
+    ///
+    fn tpiu_read_ffsr(&self) -> u32;
+
+    ///
+    /// read formatter and flush control register
+    ///
+    fn tpiu_read_ffcr(&self) -> u32;
+
+    ///
+    /// write formatter and flush control register
+    ///
+    fn tpiu_write_ffcr(&mut self, value: u32);
+
+    ///
+    /// Configure the port like a debug probe capturing SWO would:
+    /// NRZ encoding and formatter bypassed
+    ///
+    fn tpiu_configure_swo(&mut self);
+
+    ///
+    /// Output trace ```data``` from trace source ```id```
+    ///
+    fn tpiu_write(&mut self, id: u8, data: &[u8]);
+}
+
+fn formatter_enabled(processor: &Processor) -> bool {
+    processor.tpiu_sppr == TPIU_SPPR_PARALLEL || processor.tpiu_ffcr & TPIU_FFCR_ENFCONT != 0
+}
+
+//
+// Encode a formatter frame of 1-14 data bytes from trace source `id`.
+//
+// Even bytes are either an ID change (lsb set) or data with the lsb stored
+// in the last byte of the frame, odd bytes are always data. Unused space
+// is padded with data for the null trace source.
+//
+fn encode_frame(id: u8, data: &[u8]) -> [u8; 16] {
+    assert!(!data.is_empty() && data.len() <= 14);
+
+    // (is_id, value, delayed) for positions 0..15
+    let mut items = vec![(true, id, false)];
+    items.extend(data.iter().map(|d| (false, *d, false)));
+
+    if items.len() < 15 {
+        if items.len() % 2 == 0 {
+            items.push((true, NULL_ID, false));
+        } else {
+            // id change must be at an even position, it takes effect after
+            // the next data byte which still belongs to `id`
+            let last = items.pop().unwrap();
+            items.push((true, NULL_ID, true));
+            items.push(last);
+        }
+        items.resize(15, (false, 0, false));
+    }
+
+    let mut frame = [0u8; 16];
+    for (position, (is_id, value, delayed)) in items.iter().enumerate() {
+        if position % 2 == 1 {
+            frame[position] = *value;
+        } else if *is_id {
+            frame[position] = (value << 1) | 1;
+            frame[15].set_bit(position / 2, *delayed);
+        } else {
+            frame[position] = value & 0xfe;
+            frame[15].set_bit(position / 2, value & 1 == 1);
+        }
+    }
+    frame
+}
+
+fn write_output(processor: &mut Processor, bytes: &[u8]) {
+    if let Some(f) = &mut processor.itm_file {
+        f.write_all(bytes).unwrap();
+        f.flush().unwrap();
+    }
+}
+
+impl TracePortInterface for Processor {
+    fn tpiu_read_sspsr(&self) -> u32 {
+        // port sizes 1, 2 and 4 bits
+        0b1011
+    }
+
+    fn tpiu_read_cspsr(&self) -> u32 {
+        self.tpiu_cspsr
+    }
+
+    fn tpiu_write_cspsr(&mut self, value: u32) {
+        // only a single supported port size can be selected
+        let value = value & self.tpiu_read_sspsr();
+        if value.count_ones() == 1 {
+            self.tpiu_cspsr = value;
+        }
+    }
+
+    fn tpiu_read_acpr(&self) -> u32 {
+        self.tpiu_acpr
+    }
+
+    fn tpiu_write_acpr(&mut self, value: u32) {
+        self.tpiu_acpr = value & 0xffff;
+    }
+
+    fn tpiu_read_sppr(&self) -> u32 {
+        self.tpiu_sppr
+    }
+
+    fn tpiu_write_sppr(&mut self, value: u32) {
+        if value & 0b11 != 0b11 {
+            self.tpiu_sppr = value & 0b11;
+        }
+    }
+
+    fn tpiu_read_ffsr(&self) -> u32 {
+        // FtNonStop: formatter cannot be stopped
+        1 << 3
+    }
+
+    fn tpiu_read_ffcr(&self) -> u32 {
+        self.tpiu_ffcr
+    }
+
+    fn tpiu_write_ffcr(&mut self, value: u32) {
+        if value & !self.tpiu_ffcr & TPIU_FFCR_ENFCONT != 0 {
+            // start with a synchronization packet
+            self.tpiu_frame_count = 0;
+        }
+        self.tpiu_ffcr = value & (TPIU_FFCR_TRIGIN | TPIU_FFCR_ENFCONT);
+    }
+
+    fn tpiu_configure_swo(&mut self) {
+        self.tpiu_sppr = TPIU_SPPR_SWO_NRZ;
+        self.tpiu_ffcr = TPIU_FFCR_TRIGIN;
+    }
+
+    fn tpiu_write(&mut self, id: u8, data: &[u8]) {
+        if !formatter_enabled(self) {
+            write_output(self, data);
+            return;
+        }
+
+        for chunk in data.chunks(14) {
+            if self.tpiu_frame_count % FRAME_SYNC_INTERVAL == 0 {
+                write_output(self, &FRAME_SYNC);
+            }
+            self.tpiu_frame_count = self.tpiu_frame_count.wrapping_add(1);
+            let frame = encode_frame(id, chunk);
+            write_output(self, &frame);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Returns the data bytes of each trace source id in the frame
+    fn decode_frame(frame: &[u8; 16], mut id: u8) -> (u8, Vec<(u8, u8)>) {
+        let mut out = Vec::new();
+        let mut delayed_id = None;
+        for position in 0..15 {
+            let byte = frame[position];
+            if position % 2 == 0 && byte & 1 == 1 {
+                if frame[15].get_bit(position / 2) {
+                    delayed_id = Some(byte >> 1);
+                } else {
+                    id = byte >> 1;
+                }
+                continue;
+            }
+            let value = if position % 2 == 0 {
+                byte | u8::from(frame[15].get_bit(position / 2))
+            } else {
+                byte
+            };
+            out.push((id, value));
+            if let Some(new_id) = delayed_id.take() {
+                id = new_id;
+            }
+        }
+        (id, out)
+    }
+
+    #[test]
+    fn test_encode_full_frame() {
+        // Arrange
+        let data: Vec<u8> = (1..=14).collect();
+
+        // Act
+        let frame = encode_frame(1, &data);
+        let (_, decoded) = decode_frame(&frame, 0);
+
+        // Assert
+        assert_eq!(frame[0], 0x03);
+        let expected: Vec<(u8, u8)> = data.iter().map(|d| (1, *d)).collect();
+        assert_eq!(decoded, expected);
+    }
+
+    #[test]
+    fn test_encode_padded_frames() {
+        for length in 1..14 {
+            // Arrange
+            let data: Vec<u8> = (0..length).map(|n| 0x81 + n as u8).collect();
+
+            // Act
+            let frame = encode_frame(5, &data);
+            let (last_id, decoded) = decode_frame(&frame, 0);
+
+            // Assert
+            let payload: Vec<u8> = decoded
+                .iter()
+                .filter(|(id, _)| *id == 5)
+                .map(|(_, value)| *value)
+                .collect();
+            assert_eq!(payload, data);
+            assert_eq!(last_id, NULL_ID);
+        }
+    }
+
+    #[test]
+    fn test_formatter_bypass() {
+        // Arrange
+        let mut processor = Processor::new();
+
+        // Act
+        let formatted = formatter_enabled(&processor);
+        processor.tpiu_write_sppr(TPIU_SPPR_SWO_NRZ);
+        processor.tpiu_write_ffcr(TPIU_FFCR_TRIGIN);
+
+        // Assert
+        assert!(formatted);
+        assert!(!formatter_enabled(&processor));
+    }
+}
@@ -0,0 +1,354 @@
+//!
+//! SEGGER Real Time Transfer (RTT) host side simulation
+//!
+//! The RTT control block in target memory is polled periodically like a
+//! debug probe would do: data in the up-buffers is written to the host side
+//! outputs and host input is copied to down-buffer 0. The host input is
+//! opened only once the target declares a down-buffer.
+//!
+
+use crate::bus::Bus;
+use crate::Processor;
+use std::collections::HashMap;
+use std::collections::VecDeque;
+use std::io;
+use std::sync::mpsc::Receiver;
+
+/// Identifier at the start of an initialized control block
+pub const RTT_ID: &[u8] = b"SEGGER RTT\0";
+
+// offset of the first buffer descriptor in the control block
+const CB_BUFFERS_OFFSET: u32 = 24;
+
+// size of a buffer descriptor
+const BUFFER_DESC_SIZE: u32 = 24;
+
+// offsets of buffer descriptor fields
+const BUFFER_PTR: u32 = 4;
+const BUFFER_SIZE: u32 = 8;
+const BUFFER_WR_OFF: u32 = 12;
+const BUFFER_RD_OFF: u32 = 16;
+
+// upper limit for buffer counts, protects against a corrupted control block
+const MAX_BUFFERS: u32 = 32;
+
+/// Number of cycles between polls of the control block
+pub const RTT_POLL_INTERVAL: u32 = 1024;
+
+///
+/// Host side configuration and state of RTT channels
+///
+pub struct Rtt {
+    control_block: u32,
+    up_channels: HashMap<u32, Box<dyn io::Write + 'static>>,
+    down_start: Option<Box<dyn FnOnce() -> Receiver<u8> + 'static>>,
+    down_input: Option<Receiver<u8>>,
+    down_pending: VecDeque<u8>,
+    countdown: u32,
+}
+
+impl Rtt {
+    ///
+    /// RTT with control block located at address ```control_block```
+    ///
+    pub fn new(control_block: u32) -> Self {
+        Self {
+            control_block,
+            up_channels: HashMap::new(),
+            down_start: None,
+            down_input: None,
+            down_pending: VecDeque::new(),
+            countdown: RTT_POLL_INTERVAL,
+        }
+    }
+
+    ///
+    /// Write data of up-buffer ```channel``` to ```output```
+    ///
+    pub fn up_channel(&mut self, channel: u32, output: Box<dyn io::Write + 'static>) -> &mut Self {
+        self.up_channels.insert(channel, output);
+        self
+    }
+
+    ///
+    /// Feed bytes received from the input returned by ```start``` to
+    /// down-buffer 0. ```start``` is called when the target first declares
+    /// a down-buffer, so that no input is consumed for targets without one.
+    ///
+    pub fn down_channel(
+        &mut self,
+        start: Box<dyn FnOnce() -> Receiver<u8> + 'static>,
+    ) -> &mut Self {
+        self.down_start = Some(start);
+        self
+    }
+
+    fn initialized(&self, processor: &Processor) -> bool {
+        RTT_ID
+            .iter()
+            .enumerate()
+            .all(|(i, c)| processor.read8(self.control_block + i as u32) == Ok(*c))
+    }
+
+    fn read_up_buffer(&mut self, processor: &mut Processor, desc: u32, channel: u32) {
+        let output = match self.up_channels.get_mut(&channel) {
+            Some(output) => output,
+            None => return,
+        };
+
+        let (buffer, size, wr_off, rd_off) = match (
+            processor.read32(desc + BUFFER_PTR),
+            processor.read32(desc + BUFFER_SIZE),
+            processor.read32(desc + BUFFER_WR_OFF),
+            processor.read32(desc + BUFFER_RD_OFF),
+        ) {
+            (Ok(buffer), Ok(size), Ok(wr_off), Ok(rd_off)) => (buffer, size, wr_off, rd_off),
+            _ => return,
+        };
+        if wr_off >= size || rd_off >= size || wr_off == rd_off {
+            return;
+        }
+
+        let mut data = Vec::new();
+        let mut offset = rd_off;
+        while offset != wr_off {
+            match processor.read8(buffer + offset) {
+                Ok(byte) => data.push(byte),
+                Err(_) => return,
+            }
+            offset = (offset + 1) % size;
+        }
+
+        output.write_all(&data).unwrap();
+        output.flush().unwrap();
+        let _ = processor.write32(desc + BUFFER_RD_OFF, wr_off);
+    }
+
+    fn write_down_buffer(&mut self, processor: &mut Processor, desc: u32) {
+        if let Some(start) = self.down_start.take() {
+            self.down_input = Some(start());
+        }
+        if let Some(input) = &self.down_input {
+            self.down_pending.extend(input.try_iter());
+        }
+        if self.down_pending.is_empty() {
+            return;
+        }
+
+        let (buffer, size, wr_off, rd_off) = match (
+            processor.read32(desc + BUFFER_PTR),
+            processor.read32(desc + BUFFER_SIZE),
+            processor.read32(desc + BUFFER_WR_OFF),
+            processor.read32(desc + BUFFER_RD_OFF),
+        ) {
+            (Ok(buffer), Ok(size), Ok(wr_off), Ok(rd_off)) => (buffer, size, wr_off, rd_off),
+            _ => return,
+        };
+        if wr_off >= size || rd_off >= size {
+            return;
+        }
+
+        // one byte is always left unused to tell a full buffer from an empty one
+        let mut offset = wr_off;
+        while (offset + 1) % size != rd_off {
+            let byte = match self.down_pending.pop_front() {
+                Some(byte) => byte,
+                None => break,
+            };
+            if processor.write8(buffer + offset, byte).is_err() {
+                return;
+            }
+            offset = (offset + 1) % size;
+        }
+        let _ = processor.write32(desc + BUFFER_WR_OFF, offset);
+    }
+
+    fn poll(&mut self, processor: &mut Processor) {
+        if !self.initialized(processor) {
+            return;
+        }
+
+        let (num_up, num_down) = match (
+            processor.read32(self.control_block + 16),
+            processor.read32(self.control_block + 20),
+        ) {
+            (Ok(num_up), Ok(num_down)) if num_up <= MAX_BUFFERS && num_down <= MAX_BUFFERS => {
+                (num_up, num_down)
+            }
+            _ => return,
+        };
+
+        let buffers = self.control_block + CB_BUFFERS_OFFSET;
+        for channel in 0..num_up {
+            self.read_up_buffer(processor, buffers + channel * BUFFER_DESC_SIZE, channel);
+        }
+        if num_down > 0 {
+            self.write_down_buffer(processor, buffers + num_up * BUFFER_DESC_SIZE);
+        }
+    }
+}
+
+///
+/// Polling of the RTT control block during simulation
+///
+pub trait RealTimeTransfer {
+    ///
+    /// Advance time by ```cycles```, polls the control block at regular intervals
+    ///
+    fn rtt_tick(&mut self, cycles: u32);
+
+    ///
+    /// Transfer all pending data immediately
+    ///
+    fn rtt_poll(&mut self);
+}
+
+impl RealTimeTransfer for Processor {
+    fn rtt_tick(&mut self, cycles: u32) {
+        let poll = match &mut self.rtt {
+            Some(rtt) if rtt.countdown <= cycles => {
+                rtt.countdown = RTT_POLL_INTERVAL;
+                true
+            }
+            Some(rtt) => {
+                rtt.countdown -= cycles;
+                false
+            }
+            None => false,
+        };
+        if poll {
+            self.rtt_poll();
+        }
+    }
+
+    fn rtt_poll(&mut self) {
+        if let Some(mut rtt) = self.rtt.take() {
+            rtt.poll(self);
+            self.rtt = Some(rtt);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::reset::Reset;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+    use std::sync::mpsc::channel;
+
+    #[derive(Clone)]
+    struct SharedBuffer(Rc<RefCell<Vec<u8>>>);
+
+    impl io::Write for SharedBuffer {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.borrow_mut().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    const CB: u32 = 0x2000_0100;
+    const UP_BUFFER: u32 = 0x2000_0200;
+    const DOWN_BUFFER: u32 = 0x2000_0300;
+
+    // control block with one up and one down buffer of 16 bytes
+    fn write_control_block(processor: &mut Processor) {
+        for (i, c) in RTT_ID.iter().enumerate() {
+            processor.write8(CB + i as u32, *c).unwrap();
+        }
+        processor.write32(CB + 16, 1).unwrap();
+        processor.write32(CB + 20, 1).unwrap();
+        for (desc, buffer) in [(CB + 24, UP_BUFFER), (CB + 48, DOWN_BUFFER)].iter() {
+            processor.write32(desc + BUFFER_PTR, *buffer).unwrap();
+            processor.write32(desc + BUFFER_SIZE, 16).unwrap();
+            processor.write32(desc + BUFFER_WR_OFF, 0).unwrap();
+            processor.write32(desc + BUFFER_RD_OFF, 0).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_up_buffer_wrap_around() {
+        // Arrange
+        let output = Rc::new(RefCell::new(Vec::new()));
+        let mut processor = Processor::new();
+        let mut rtt = Rtt::new(CB);
+        rtt.up_channel(0, Box::new(SharedBuffer(output.clone())));
+        processor.rtt(Some(rtt));
+        processor.reset().unwrap();
+        write_control_block(&mut processor);
+        for (i, c) in b"Hello, world!".iter().enumerate() {
+            processor.write8(UP_BUFFER + (11 + i as u32) % 16, *c).unwrap();
+        }
+        processor.write32(CB + 24 + BUFFER_RD_OFF, 11).unwrap();
+        processor.write32(CB + 24 + BUFFER_WR_OFF, 8).unwrap();
+
+        // Act
+        processor.rtt_tick(RTT_POLL_INTERVAL - 1);
+        let before_poll = output.borrow().len();
+        processor.rtt_tick(1);
+
+        // Assert
+        assert_eq!(before_poll, 0);
+        assert_eq!(*output.borrow(), b"Hello, world!".to_vec());
+        assert_eq!(processor.read32(CB + 24 + BUFFER_RD_OFF).unwrap(), 8);
+    }
+
+    #[test]
+    fn test_down_buffer_input() {
+        // Arrange
+        let (sender, receiver) = channel();
+        let mut processor = Processor::new();
+        let mut rtt = Rtt::new(CB);
+        rtt.down_channel(Box::new(move || receiver));
+        processor.rtt(Some(rtt));
+        processor.reset().unwrap();
+        write_control_block(&mut processor);
+        for byte in 0..20 {
+            sender.send(byte).unwrap();
+        }
+
+        // Act
+        processor.rtt_poll();
+        let first_wr_off = processor.read32(CB + 48 + BUFFER_WR_OFF).unwrap();
+        processor.write32(CB + 48 + BUFFER_RD_OFF, 15).unwrap();
+        processor.rtt_poll();
+
+        // Assert
+        assert_eq!(first_wr_off, 15);
+        assert_eq!(processor.read32(CB + 48 + BUFFER_WR_OFF).unwrap(), 4);
+        assert_eq!(processor.read8(DOWN_BUFFER + 14).unwrap(), 14);
+        assert_eq!(processor.read8(DOWN_BUFFER + 15).unwrap(), 15);
+        assert_eq!(processor.read8(DOWN_BUFFER + 3).unwrap(), 19);
+    }
+
+    #[test]
+    fn test_down_channel_started_on_use() {
+        // Arrange
+        let started = Rc::new(RefCell::new(false));
+        let flag = started.clone();
+        let mut processor = Processor::new();
+        let mut rtt = Rtt::new(CB);
+        rtt.down_channel(Box::new(move || {
+            *flag.borrow_mut() = true;
+            channel().1
+        }));
+        processor.rtt(Some(rtt));
+        processor.reset().unwrap();
+        write_control_block(&mut processor);
+        processor.write32(CB + 20, 0).unwrap();
+
+        // Act
+        processor.rtt_poll();
+        let started_without_down_buffer = *started.borrow();
+        processor.write32(CB + 20, 1).unwrap();
+        processor.rtt_poll();
+
+        // Assert
+        assert!(!started_without_down_buffer);
+        assert!(*started.borrow());
+    }
+}
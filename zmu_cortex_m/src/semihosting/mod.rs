@@ -62,6 +62,45 @@ impl SysExceptionReason {
     }
 }
 
+/// Exit code of a program that failed without an exit code of its own
+pub const EXIT_FAILURE_CODE: i32 = 1;
+
+/// Exit code reserved for a simulation stopped by a fault trap or a lockup,
+/// never given for an exit status of the program
+pub const FAULT_TRAP_EXIT_CODE: i32 = 254;
+
+///
+/// Exit status of the program, given with SYS_EXIT or SYS_EXIT_EXTENDED
+///
+#[derive(PartialEq, Debug, Copy, Clone)]
+pub struct ExitStatus {
+    /// reason code of the exit
+    pub reason: SysExceptionReason,
+    /// subcode of the exit, the exit code of an application exit
+    pub subcode: u32,
+}
+
+impl ExitStatus {
+    ///
+    /// Exit code for the host process, limited to 8 bits: the low byte of
+    /// the subcode of an application exit, for other reasons
+    /// ```EXIT_FAILURE_CODE```. A failing exit never gives 0 or
+    /// ```FAULT_TRAP_EXIT_CODE```, these map to ```EXIT_FAILURE_CODE```.
+    ///
+    pub fn code(&self) -> i32 {
+        match (self.reason, self.subcode) {
+            (SysExceptionReason::ADPStoppedApplicationExit, 0) => 0,
+            (SysExceptionReason::ADPStoppedApplicationExit, subcode) => {
+                match (subcode & 0xff) as i32 {
+                    0 | FAULT_TRAP_EXIT_CODE => EXIT_FAILURE_CODE,
+                    code => code,
+                }
+            }
+            _ => EXIT_FAILURE_CODE,
+        }
+    }
+}
+
 #[derive(PartialEq, Debug)]
 ///
 /// Semihosting commands
@@ -137,6 +176,39 @@ pub enum SemihostingCommand {
         len: u32,
     },
     ///
+    /// Get a temporary file name
+    ///
+    SysTmpnam {
+        ///
+        /// location in memory to which the name is written
+        ///
+        memoryptr: u32,
+        ///
+        /// target identifier for the name, 0..255
+        ///
+        id: u32,
+        ///
+        /// size of the name buffer in bytes
+        ///
+        len: u32,
+    },
+    ///
+    /// Remove a file
+    ///
+    SysRemove {
+        /// name of the file to remove
+        name: String,
+    },
+    ///
+    /// Rename a file
+    ///
+    SysRename {
+        /// current name of the file
+        from: String,
+        /// new name of the file
+        to: String,
+    },
+    ///
     /// Trigger an exception
     ///
     SysException {
@@ -160,6 +232,82 @@ pub enum SemihostingCommand {
     /// Get the value of errno
     ///
     SysErrno,
+    ///
+    /// Write a character to the debug console
+    ///
+    SysWritec {
+        /// character to write
+        c: u8,
+    },
+    ///
+    /// Write a null terminated string to the debug console
+    ///
+    SysWrite0 {
+        /// string to write, without the terminating null
+        data: Vec<u8>,
+    },
+    ///
+    /// Read a character from the debug console
+    ///
+    SysReadc,
+    ///
+    /// Check if a return code is an error
+    ///
+    SysIserror {
+        /// return code to check
+        status: u32,
+    },
+    ///
+    /// Get the number of seconds since 00:00 January 1, 1970
+    ///
+    SysTime,
+    ///
+    /// Get the number of elapsed target ticks since execution started
+    ///
+    SysElapsed {
+        /// location in memory to which the 64 bit tick count is written
+        memoryptr: u32,
+    },
+    ///
+    /// Get the tick frequency used by SysElapsed
+    ///
+    SysTickfreq,
+    ///
+    /// Get the system stack and heap parameters
+    ///
+    SysHeapinfo {
+        /// location in memory to which the four parameter words are written
+        memoryptr: u32,
+    },
+    ///
+    /// Get the command line used to call the executable
+    ///
+    SysGetCmdline {
+        /// location in memory to which the command line is written
+        memoryptr: u32,
+        /// size of the command line buffer in bytes
+        len: u32,
+    },
+    ///
+    /// Operation that is not supported by the simulator
+    ///
+    SysUnsupported {
+        /// operation number given in r0
+        operation: u32,
+    },
+}
+
+impl SemihostingCommand {
+    ///
+    /// Exit status requested by the command, None if it does not exit
+    ///
+    pub fn exit_status(&self) -> Option<ExitStatus> {
+        match *self {
+            Self::SysException { reason } => Some(ExitStatus { reason, subcode: 0 }),
+            Self::SysExitExtended { reason, subcode } => Some(ExitStatus { reason, subcode }),
+            _ => None,
+        }
+    }
 }
 
 #[derive(PartialEq, Debug, Clone)]
@@ -202,6 +350,21 @@ pub enum SemihostingResponse {
         /// result Ok = data, Err = error code
         result: Result<(u32, Vec<u8>, u32), i32>,
     },
+    /// systmpnam command response
+    SysTmpnam {
+        /// result Ok = (memory location, zero terminated name), Err = error code
+        result: Result<(u32, Vec<u8>), i32>,
+    },
+    /// sysremove command response
+    SysRemove {
+        /// result Ok = removed, Err = host error code
+        result: Result<(), i32>,
+    },
+    /// sysrename command response
+    SysRename {
+        /// result Ok = renamed, Err = host error code
+        result: Result<(), i32>,
+    },
     /// sysexception command response
     SysException {
         /// result
@@ -226,19 +389,85 @@ pub enum SemihostingResponse {
         /// result
         result: u32,
     },
+    /// syswritec command response
+    SysWritec,
+    /// syswrite0 command response
+    SysWrite0,
+    /// sysreadc command response
+    SysReadc {
+        /// result Ok = character, Err = error code
+        result: Result<u32, i32>,
+    },
+    /// sysiserror command response
+    SysIserror {
+        /// result, nonzero if the status was an error
+        result: u32,
+    },
+    /// systime command response
+    SysTime {
+        /// result Ok = seconds since the epoch, Err = error code
+        result: Result<u32, i32>,
+    },
+    /// syselapsed command response
+    SysElapsed {
+        /// result Ok = (memory location, tick count), Err = error code
+        result: Result<(u32, u64), i32>,
+    },
+    /// systickfreq command response
+    SysTickfreq {
+        /// result Ok = ticks per second, Err = error code
+        result: Result<u32, i32>,
+    },
+    /// sysheapinfo command response
+    SysHeapinfo {
+        /// result Ok = (memory location, [heap base, heap limit, stack base, stack limit])
+        result: Result<(u32, [u32; 4]), i32>,
+    },
+    /// sysgetcmdline command response
+    SysGetCmdline {
+        /// result Ok = (memory location, zero terminated command line), Err = error code
+        result: Result<(u32, Vec<u8>), i32>,
+    },
+    /// response to an unsupported operation, always fails
+    SysUnsupported,
 }
 
 const SYS_OPEN: u32 = 0x01;
 const SYS_CLOSE: u32 = 0x02;
+const SYS_WRITEC: u32 = 0x03;
+const SYS_WRITE0: u32 = 0x04;
 const SYS_WRITE: u32 = 0x05;
 const SYS_READ: u32 = 0x06;
+const SYS_READC: u32 = 0x07;
+const SYS_ISERROR: u32 = 0x08;
 const SYS_ISTTY: u32 = 0x09;
 const SYS_SEEK: u32 = 0x0a;
 const SYS_FLEN: u32 = 0x0c;
+const SYS_TMPNAM: u32 = 0x0d;
+const SYS_REMOVE: u32 = 0x0e;
+const SYS_RENAME: u32 = 0x0f;
 const SYS_CLOCK: u32 = 0x10;
+const SYS_TIME: u32 = 0x11;
 const SYS_ERRNO: u32 = 0x13;
+const SYS_GET_CMDLINE: u32 = 0x15;
+const SYS_HEAPINFO: u32 = 0x16;
 const SYS_EXIT: u32 = 0x18;
 const SYS_EXIT_EXTENDED: u32 = 0x20;
+const SYS_ELAPSED: u32 = 0x30;
+const SYS_TICKFREQ: u32 = 0x31;
+
+// Read a string of given length from memory
+fn read_string(processor: &mut Processor, mut ptr: u32, mut len: u32) -> Result<String, Fault> {
+    let mut string_bytes: Vec<u8> = Vec::new();
+
+    while len > 0 {
+        string_bytes.push(processor.read8(ptr)?);
+        ptr += 1;
+        len -= 1;
+    }
+
+    Ok(String::from_utf8_lossy(&string_bytes).into_owned())
+}
 
 ///
 /// Decode semihosting command based on register values
@@ -252,19 +481,12 @@ pub fn decode_semihostcmd(
         SYS_OPEN => {
             let argument_block = r1;
 
-            let mut string_ptr = processor.read32(argument_block)?;
+            let string_ptr = processor.read32(argument_block)?;
             let mode = processor.read32(argument_block + 4)?;
-            let mut filename_len = processor.read32(argument_block + 8)?;
-            let mut string_bytes: Vec<u8> = Vec::new();
-
-            while filename_len > 0 {
-                string_bytes.push(processor.read8(string_ptr)?);
-                string_ptr += 1;
-                filename_len -= 1;
-            }
+            let filename_len = processor.read32(argument_block + 8)?;
 
             SemihostingCommand::SysOpen {
-                name: String::from_utf8(string_bytes).unwrap(),
+                name: read_string(processor, string_ptr, filename_len)?,
                 mode,
             }
         }
@@ -320,8 +542,77 @@ pub fn decode_semihostcmd(
 
             SemihostingCommand::SysSeek { handle, position }
         }
+        SYS_TMPNAM => {
+            let params_ptr = r1;
+            let memoryptr = processor.read32(params_ptr)?;
+            let id = processor.read32(params_ptr + 4)?;
+            let len = processor.read32(params_ptr + 8)?;
+
+            SemihostingCommand::SysTmpnam { memoryptr, id, len }
+        }
+        SYS_REMOVE => {
+            let params_ptr = r1;
+            let string_ptr = processor.read32(params_ptr)?;
+            let string_len = processor.read32(params_ptr + 4)?;
+
+            SemihostingCommand::SysRemove {
+                name: read_string(processor, string_ptr, string_len)?,
+            }
+        }
+        SYS_RENAME => {
+            let params_ptr = r1;
+            let from_ptr = processor.read32(params_ptr)?;
+            let from_len = processor.read32(params_ptr + 4)?;
+            let to_ptr = processor.read32(params_ptr + 8)?;
+            let to_len = processor.read32(params_ptr + 12)?;
+
+            SemihostingCommand::SysRename {
+                from: read_string(processor, from_ptr, from_len)?,
+                to: read_string(processor, to_ptr, to_len)?,
+            }
+        }
         SYS_CLOCK => SemihostingCommand::SysClock,
+        SYS_TIME => SemihostingCommand::SysTime,
         SYS_ERRNO => SemihostingCommand::SysErrno,
+        SYS_WRITEC => SemihostingCommand::SysWritec {
+            c: processor.read8(r1)?,
+        },
+        SYS_WRITE0 => {
+            let mut string_ptr = r1;
+            let mut data: Vec<u8> = Vec::new();
+
+            loop {
+                let c = processor.read8(string_ptr)?;
+                if c == 0 {
+                    break;
+                }
+                data.push(c);
+                string_ptr += 1;
+            }
+            SemihostingCommand::SysWrite0 { data }
+        }
+        SYS_READC => SemihostingCommand::SysReadc,
+        SYS_ISERROR => {
+            let params_ptr = r1;
+            let status = processor.read32(params_ptr)?;
+
+            SemihostingCommand::SysIserror { status }
+        }
+        SYS_ELAPSED => SemihostingCommand::SysElapsed { memoryptr: r1 },
+        SYS_TICKFREQ => SemihostingCommand::SysTickfreq,
+        SYS_HEAPINFO => {
+            let params_ptr = r1;
+            let memoryptr = processor.read32(params_ptr)?;
+
+            SemihostingCommand::SysHeapinfo { memoryptr }
+        }
+        SYS_GET_CMDLINE => {
+            let params_ptr = r1;
+            let memoryptr = processor.read32(params_ptr)?;
+            let len = processor.read32(params_ptr + 4)?;
+
+            SemihostingCommand::SysGetCmdline { memoryptr, len }
+        }
         SYS_EXIT_EXTENDED => {
             let params_ptr = r1;
             let reason = SysExceptionReason::from_u32(processor.read32(params_ptr)?);
@@ -332,9 +623,7 @@ pub fn decode_semihostcmd(
         SYS_EXIT => SemihostingCommand::SysException {
             reason: SysExceptionReason::from_u32(r1),
         },
-        _ => {
-            todo!("unknown semihosting command {}", r0);
-        }
+        operation => SemihostingCommand::SysUnsupported { operation },
     };
     Ok(result)
 }
@@ -385,6 +674,23 @@ pub fn semihost_return(processor: &mut Processor, response: &SemihostingResponse
             }
             Err(error_code) => processor.set_r(Reg::R0, *error_code as u32),
         },
+        SemihostingResponse::SysTmpnam { ref result } => match result {
+            Ok((memoryptr, name)) => {
+                let mut addr = *memoryptr;
+                for x in name {
+                    processor.write8(addr, *x);
+                    addr += 1;
+                }
+                processor.set_r(Reg::R0, 0);
+            }
+            Err(error_code) => processor.set_r(Reg::R0, *error_code as u32),
+        },
+        SemihostingResponse::SysRemove { result } | SemihostingResponse::SysRename { result } => {
+            match result {
+                Ok(()) => processor.set_r(Reg::R0, 0),
+                Err(error_code) => processor.set_r(Reg::R0, error_code as u32),
+            }
+        }
         SemihostingResponse::SysClock { result } => match result {
             Ok(centiseconds) => processor.set_r(Reg::R0, centiseconds),
             Err(error_code) => processor.set_r(Reg::R0, error_code as u32),
@@ -392,5 +698,223 @@ pub fn semihost_return(processor: &mut Processor, response: &SemihostingResponse
         SemihostingResponse::SysErrno { result } => {
             processor.set_r(Reg::R0, result);
         }
+        SemihostingResponse::SysWritec | SemihostingResponse::SysWrite0 => {}
+        SemihostingResponse::SysReadc { result }
+        | SemihostingResponse::SysTime { result }
+        | SemihostingResponse::SysTickfreq { result } => match result {
+            Ok(value) => processor.set_r(Reg::R0, value),
+            Err(error_code) => processor.set_r(Reg::R0, error_code as u32),
+        },
+        SemihostingResponse::SysIserror { result } => {
+            processor.set_r(Reg::R0, result);
+        }
+        SemihostingResponse::SysElapsed { result } => match result {
+            Ok((memoryptr, ticks)) => {
+                processor.write32(memoryptr, ticks as u32);
+                processor.write32(memoryptr + 4, (ticks >> 32) as u32);
+                processor.set_r(Reg::R0, 0);
+            }
+            Err(error_code) => processor.set_r(Reg::R0, error_code as u32),
+        },
+        SemihostingResponse::SysHeapinfo { result } => match result {
+            Ok((memoryptr, info)) => {
+                let mut addr = memoryptr;
+                for x in &info {
+                    processor.write32(addr, *x);
+                    addr += 4;
+                }
+            }
+            Err(error_code) => processor.set_r(Reg::R0, error_code as u32),
+        },
+        SemihostingResponse::SysGetCmdline { ref result } => match result {
+            Ok((memoryptr, cmdline)) => {
+                let mut addr = *memoryptr;
+                for x in cmdline {
+                    processor.write8(addr, *x);
+                    addr += 1;
+                }
+                // length of the command line, without the terminating null,
+                // is written back to the argument block
+                let params_ptr = processor.get_r(Reg::R1);
+                processor.write32(params_ptr + 4, cmdline.len() as u32 - 1);
+                processor.set_r(Reg::R0, 0);
+            }
+            Err(error_code) => processor.set_r(Reg::R0, *error_code as u32),
+        },
+        SemihostingResponse::SysUnsupported => processor.set_r(Reg::R0, (-1_i32) as u32),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::reset::Reset;
+
+    const BLOCK: u32 = 0x2000_0100;
+    const STRINGS: u32 = 0x2000_0200;
+
+    fn write_bytes(processor: &mut Processor, address: u32, bytes: &[u8]) {
+        for (i, b) in bytes.iter().enumerate() {
+            processor.write8(address + i as u32, *b).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_decode_rename() {
+        // Arrange
+        let mut processor = Processor::new();
+        processor.reset().unwrap();
+        write_bytes(&mut processor, STRINGS, b"old.txtnew.bin");
+        processor.write32(BLOCK, STRINGS).unwrap();
+        processor.write32(BLOCK + 4, 7).unwrap();
+        processor.write32(BLOCK + 8, STRINGS + 7).unwrap();
+        processor.write32(BLOCK + 12, 7).unwrap();
+
+        // Act
+        let command = decode_semihostcmd(SYS_RENAME, BLOCK, &mut processor).unwrap();
+
+        // Assert
+        assert_eq!(
+            command,
+            SemihostingCommand::SysRename {
+                from: "old.txt".to_string(),
+                to: "new.bin".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_tmpnam_return() {
+        // Arrange
+        let mut processor = Processor::new();
+        processor.reset().unwrap();
+        processor.write32(BLOCK, STRINGS).unwrap();
+        processor.write32(BLOCK + 4, 3).unwrap();
+        processor.write32(BLOCK + 8, 16).unwrap();
+        let command = decode_semihostcmd(SYS_TMPNAM, BLOCK, &mut processor).unwrap();
+
+        // Act
+        semihost_return(
+            &mut processor,
+            &SemihostingResponse::SysTmpnam {
+                result: Ok((STRINGS, b"tmp003\0".to_vec())),
+            },
+        );
+
+        // Assert
+        assert_eq!(
+            command,
+            SemihostingCommand::SysTmpnam {
+                memoryptr: STRINGS,
+                id: 3,
+                len: 16,
+            }
+        );
+        assert_eq!(processor.get_r(Reg::R0), 0);
+        assert_eq!(processor.read8(STRINGS + 5).unwrap(), b'3');
+        assert_eq!(processor.read8(STRINGS + 6).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_get_cmdline_return() {
+        // Arrange
+        let mut processor = Processor::new();
+        processor.reset().unwrap();
+        processor.write32(BLOCK, STRINGS).unwrap();
+        processor.write32(BLOCK + 4, 64).unwrap();
+        processor.set_r(Reg::R1, BLOCK);
+        let command = decode_semihostcmd(SYS_GET_CMDLINE, BLOCK, &mut processor).unwrap();
+
+        // Act
+        semihost_return(
+            &mut processor,
+            &SemihostingResponse::SysGetCmdline {
+                result: Ok((STRINGS, b"app -v\0".to_vec())),
+            },
+        );
+
+        // Assert
+        assert_eq!(
+            command,
+            SemihostingCommand::SysGetCmdline {
+                memoryptr: STRINGS,
+                len: 64,
+            }
+        );
+        assert_eq!(processor.get_r(Reg::R0), 0);
+        assert_eq!(processor.read32(BLOCK + 4).unwrap(), 6);
+        assert_eq!(processor.read8(STRINGS + 5).unwrap(), b'v');
+        assert_eq!(processor.read8(STRINGS + 6).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_unsupported_operation() {
+        // Arrange
+        let mut processor = Processor::new();
+        processor.reset().unwrap();
+        processor.set_r(Reg::R0, 0x12); // SYS_SYSTEM
+
+        // Act
+        let command = decode_semihostcmd(0x12, BLOCK, &mut processor).unwrap();
+        semihost_return(&mut processor, &SemihostingResponse::SysUnsupported);
+
+        // Assert
+        assert_eq!(
+            command,
+            SemihostingCommand::SysUnsupported { operation: 0x12 }
+        );
+        assert_eq!(processor.get_r(Reg::R0), 0xffff_ffff);
+    }
+
+    #[test]
+    fn test_exit_status_code() {
+        // Arrange
+        let exit = |reason, subcode| ExitStatus { reason, subcode };
+
+        // Act
+        let success = exit(SysExceptionReason::ADPStoppedApplicationExit, 0).code();
+        let failure = exit(SysExceptionReason::ADPStoppedApplicationExit, 3).code();
+        let error = exit(SysExceptionReason::ADPStoppedRunTimeErrorUnknown, 0).code();
+        let error_subcode = exit(SysExceptionReason::ADPStoppedInternalError, 7).code();
+        let truncated = exit(SysExceptionReason::ADPStoppedApplicationExit, 0x1_0003).code();
+        let minus_one = exit(SysExceptionReason::ADPStoppedApplicationExit, 0xffff_ffff).code();
+
+        // Assert
+        assert_eq!(success, 0);
+        assert_eq!(failure, 3);
+        assert_eq!(error, EXIT_FAILURE_CODE);
+        assert_eq!(error_subcode, EXIT_FAILURE_CODE);
+        assert_eq!(truncated, 3);
+        assert_eq!(minus_one, 255);
+    }
+
+    #[test]
+    fn test_exit_status_code_low_byte_zero() {
+        // Arrange
+        let exit = |subcode| ExitStatus {
+            reason: SysExceptionReason::ADPStoppedApplicationExit,
+            subcode,
+        };
+
+        // Act & Assert
+        assert_eq!(exit(256).code(), EXIT_FAILURE_CODE);
+        assert_eq!(exit(512).code(), EXIT_FAILURE_CODE);
+    }
+
+    #[test]
+    fn test_exit_status_code_fault_trap_reserved() {
+        // Arrange
+        let exit = |subcode| ExitStatus {
+            reason: SysExceptionReason::ADPStoppedApplicationExit,
+            subcode,
+        };
+
+        // Act & Assert
+        assert_eq!(exit(FAULT_TRAP_EXIT_CODE as u32).code(), EXIT_FAILURE_CODE);
+        assert_eq!(
+            exit(0x100 + FAULT_TRAP_EXIT_CODE as u32).code(),
+            EXIT_FAILURE_CODE
+        );
+        assert_eq!(exit(2).code(), 2);
     }
 }
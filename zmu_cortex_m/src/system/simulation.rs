@@ -3,10 +3,16 @@
 //!
 
 use crate::core::bits::Bits;
+use crate::core::cpu::Cpu;
 use crate::core::fault::Fault;
+use crate::core::lockup::{Lockup, LockupPolicy, LockupSnapshot};
 use crate::core::register::BaseReg;
 use crate::core::reset::Reset;
+use crate::core::thumb::ThumbCode;
 use crate::executor::Executor;
+use crate::peripheral::fpb::FlashPatchBreakpoint;
+use crate::rtt::{RealTimeTransfer, Rtt};
+use crate::semihosting::ExitStatus;
 use crate::semihosting::SemihostingCommand;
 use crate::semihosting::SemihostingResponse;
 use crate::MemoryMapConfig;
@@ -23,6 +29,16 @@ pub enum SimulationError {
     /// A fault was triggered and escalated to stop the simulation
     ///
     FaultTrap,
+
+    ///
+    /// An undefined instruction was executed in strict decode mode
+    ///
+    UndefinedInstruction {
+        /// address of the instruction
+        pc: u32,
+        /// opcode of the instruction
+        opcode: ThumbCode,
+    },
 }
 
 ///
@@ -40,10 +56,106 @@ pub struct SimulationStatistics {
     ///
     pub cycle_count: u64,
 
+    ///
+    /// Number of system clock cycles the processor was sleeping.
+    ///
+    pub sleep_cycle_count: u64,
+
+    ///
+    /// Number of the sleeping clock cycles spent in deep sleep.
+    ///
+    pub deep_sleep_cycle_count: u64,
+
     ///
     /// Wallclock time spent for the simulation
     ///
     pub duration: Duration,
+
+    ///
+    /// Exit status given by the program, None if it did not exit via semihosting
+    ///
+    pub exit_status: Option<ExitStatus>,
+
+    ///
+    /// Number of times the processor entered lockup state
+    ///
+    pub lockup_count: u64,
+
+    ///
+    /// Registers of the processor when a lockup stopped the simulation, None if
+    /// the simulation was not stopped by a lockup
+    ///
+    pub lockup: Option<LockupSnapshot>,
+}
+
+///
+/// Configuration of the simulated system
+///
+pub struct SimulationConfig {
+    ///
+    /// Simulated core type
+    ///
+    pub cpu: Cpu,
+
+    ///
+    /// Size of the flash memory holding the code
+    ///
+    pub flash_size: usize,
+
+    ///
+    /// Memory map, None for the default map with flash at address 0
+    ///
+    pub map: Option<MemoryMapConfig>,
+
+    ///
+    /// Handler for the semihosting requests
+    ///
+    pub semihost_func: Option<Box<dyn FnMut(&SemihostingCommand) -> SemihostingResponse + 'static>>,
+
+    ///
+    /// Output of the ITM trace data
+    ///
+    pub itm_file: Option<Box<dyn io::Write + 'static>>,
+
+    ///
+    /// SEGGER RTT channels of the program
+    ///
+    pub rtt: Option<Rtt>,
+
+    ///
+    /// Stop the simulation at undefined instructions instead of raising UsageFault
+    ///
+    pub strict_decode: bool,
+
+    ///
+    /// Action taken when the processor locks up
+    ///
+    pub lockup_policy: LockupPolicy,
+
+    ///
+    /// Architecture revision of the Flash Patch and Breakpoint unit, 1 or 2
+    ///
+    pub fpb_revision: u32,
+}
+
+impl SimulationConfig {
+    ///
+    /// Configuration of a core and flash size without semihosting, trace
+    /// outputs or RTT, using the default memory map.
+    ///
+    pub fn new(cpu: Cpu, flash_size: usize) -> Self {
+        Self {
+            cpu,
+            flash_size,
+            map: None,
+            semihost_func: None,
+            itm_file: None,
+            rtt: None,
+            strict_decode: false,
+            lockup_policy: LockupPolicy::Stop,
+            fpb_revision: 1,
+        }
+    }
 }
 
 impl From<Fault> for SimulationError {
@@ -53,27 +165,41 @@ impl From<Fault> for SimulationError {
 }
 
 ///
-/// Run simulation until processing gets terminated
+/// Construct a processor with the given code loaded and bring it out of reset.
 ///
-pub fn simulate(
-    code: &[u8],
-    semihost_func: Box<dyn FnMut(&SemihostingCommand) -> SemihostingResponse + 'static>,
-    itm_file: Option<Box<dyn io::Write + 'static>>,
-    map: Option<MemoryMapConfig>,
-    flash_size: usize,
-) -> Result<SimulationStatistics, SimulationError> {
+/// The processor is left halted at the reset vector: the caller is
+/// responsible for marking it running and stepping it forward.
+///
+pub fn make_processor(code: &[u8], config: SimulationConfig) -> Result<Processor, SimulationError> {
     let mut processor = Processor::new();
 
-    processor.itm(itm_file);
-    processor.semihost(Some(semihost_func));
-    processor.memory_map(map);
-    processor.flash_memory(flash_size, code);
+    processor.cpu(config.cpu);
+    processor.fpb_set_revision(config.fpb_revision);
+    processor.strict_decode = config.strict_decode;
+    processor.lockup_policy = config.lockup_policy;
+    processor.itm(config.itm_file);
+    processor.rtt(config.rtt);
+    processor.semihost(config.semihost_func);
+    processor.memory_map(config.map);
+    processor.flash_memory(config.flash_size, code);
     //processor.ram_memory(ram_size);
 
     processor.cache_instructions();
+    processor.reset()?;
+
+    Ok(processor)
+}
+
+///
+/// Run simulation until processing gets terminated
+///
+pub fn simulate(
+    code: &[u8],
+    config: SimulationConfig,
+) -> Result<SimulationStatistics, SimulationError> {
+    let mut processor = make_processor(code, config)?;
 
     let start = Instant::now();
-    processor.reset()?;
     processor.state.set_bit(0, true); // running
 
     while processor.state & 1 == 1 {
@@ -87,12 +213,26 @@ pub fn simulate(
             processor.step_sleep();
         }
     }
+    // transfer the remaining RTT output
+    processor.rtt_poll();
+    if let Some((pc, opcode)) = processor.undefined_instruction {
+        return Err(SimulationError::UndefinedInstruction { pc, opcode });
+    }
     let end = Instant::now();
 
     Ok(SimulationStatistics {
         instruction_count: processor.instruction_count,
         cycle_count: processor.cycle_count,
+        sleep_cycle_count: processor.sleep_cycle_count,
+        deep_sleep_cycle_count: processor.deep_sleep_cycle_count,
         duration: end.duration_since(start),
+        exit_status: processor.exit_status,
+        lockup_count: processor.lockup_count,
+        lockup: if processor.locked_up() {
+            processor.lockup_snapshot
+        } else {
+            None
+        },
     })
 }
 
@@ -102,24 +242,14 @@ pub fn simulate(
 pub fn simulate_trace<F>(
     code: &[u8],
     mut trace_func: F,
-    semihost_func: Box<dyn FnMut(&SemihostingCommand) -> SemihostingResponse + 'static>,
-    itm_file: Option<Box<dyn io::Write + 'static>>,
-    map: Option<MemoryMapConfig>,
-    flash_size: usize,
+    config: SimulationConfig,
 ) -> Result<SimulationStatistics, SimulationError>
 where
     F: FnMut(&Processor),
 {
-    let mut processor = Processor::new();
-    processor.itm(itm_file);
-    processor.semihost(Some(semihost_func));
-    processor.memory_map(map);
-    processor.flash_memory(flash_size, code);
-    processor.cache_instructions();
+    let mut processor = make_processor(code, config)?;
 
     let start = Instant::now();
-
-    processor.reset().unwrap();
     processor.state.set_bit(0, true); // running
 
     while processor.state & 1 == 1 {
@@ -135,12 +265,25 @@ where
             processor.step_sleep();
         }
     }
+    processor.rtt_poll();
+    if let Some((pc, opcode)) = processor.undefined_instruction {
+        return Err(SimulationError::UndefinedInstruction { pc, opcode });
+    }
 
     let end = Instant::now();
 
     Ok(SimulationStatistics {
         instruction_count: processor.instruction_count,
         cycle_count: processor.cycle_count,
+        sleep_cycle_count: processor.sleep_cycle_count,
+        deep_sleep_cycle_count: processor.deep_sleep_cycle_count,
         duration: end.duration_since(start),
+        exit_status: processor.exit_status,
+        lockup_count: processor.lockup_count,
+        lockup: if processor.locked_up() {
+            processor.lockup_snapshot
+        } else {
+            None
+        },
     })
 }